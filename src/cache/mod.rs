@@ -0,0 +1,463 @@
+//! Persistent local hash cache: `lock verify --cache` and `lock cache`.
+//!
+//! The cache maps a file's identity — (device, inode, size, mtime,
+//! algorithm) — to the digest `lock` last computed for it. While that
+//! identity is unchanged the file is assumed unchanged, so verify can reuse
+//! the digest instead of rereading the bytes. Entries also remember the path
+//! they were hashed at, which is what `lock cache prune` re-stats.
+//!
+//! The cache is a plain JSON file written atomically (temp file + rename).
+//! It is local state, not an artifact: it is never witnessed and never
+//! referenced from lockfiles.
+
+use std::collections::BTreeMap;
+use std::fs::Metadata;
+use std::path::{Path, PathBuf};
+use std::sync::Mutex;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+
+use serde::{Deserialize, Serialize};
+use serde_json::json;
+
+use crate::cli::CacheAction;
+use crate::verify::{LockfileRefusal, VerifyRefusalCode};
+
+/// On-disk format version of the cache file.
+pub const CACHE_VERSION: &str = "lock-cache.v0";
+
+/// Files modified this recently are hashed but not cached: a second write
+/// within the same mtime tick would leave the identity unchanged.
+const RACY_WINDOW: Duration = Duration::from_secs(2);
+
+/// Identity of a file on disk, as far as the cache is concerned.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+struct Stamp {
+    device: u64,
+    inode: u64,
+    size: u64,
+    mtime_secs: u64,
+    mtime_nanos: u32,
+}
+
+impl Stamp {
+    /// `None` where the platform exposes no device/inode pair, or for
+    /// pre-epoch mtimes; such files are simply never cached.
+    fn of(metadata: &Metadata) -> Option<Self> {
+        let (device, inode) = file_id(metadata)?;
+        let mtime = metadata.modified().ok()?.duration_since(UNIX_EPOCH).ok()?;
+        Some(Self {
+            device,
+            inode,
+            size: metadata.len(),
+            mtime_secs: mtime.as_secs(),
+            mtime_nanos: mtime.subsec_nanos(),
+        })
+    }
+}
+
+#[cfg(unix)]
+fn file_id(metadata: &Metadata) -> Option<(u64, u64)> {
+    use std::os::unix::fs::MetadataExt;
+    Some((metadata.dev(), metadata.ino()))
+}
+
+#[cfg(not(unix))]
+fn file_id(_metadata: &Metadata) -> Option<(u64, u64)> {
+    None
+}
+
+/// One cached digest, as stored in the cache file.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct CacheEntry {
+    pub path: String,
+    pub device: u64,
+    pub inode: u64,
+    pub size: u64,
+    pub mtime_secs: u64,
+    pub mtime_nanos: u32,
+    pub algorithm: String,
+    pub digest: String,
+}
+
+impl CacheEntry {
+    fn stamp(&self) -> Stamp {
+        Stamp {
+            device: self.device,
+            inode: self.inode,
+            size: self.size,
+            mtime_secs: self.mtime_secs,
+            mtime_nanos: self.mtime_nanos,
+        }
+    }
+
+    fn key(&self) -> (Stamp, String) {
+        (self.stamp(), self.algorithm.clone())
+    }
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+struct CacheFile {
+    version: String,
+    entries: Vec<CacheEntry>,
+}
+
+/// In-memory cache, shared by verify's worker threads.
+#[derive(Debug, Default)]
+pub struct HashCache {
+    entries: Mutex<BTreeMap<(Stamp, String), CacheEntry>>,
+    dirty: AtomicBool,
+}
+
+impl HashCache {
+    /// Read the cache at `path`; a missing file is an empty cache.
+    pub fn load(path: &Path) -> Result<Self, LockfileRefusal> {
+        let content = match std::fs::read_to_string(path) {
+            Ok(content) => content,
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => return Ok(Self::default()),
+            Err(e) => return Err(cache_refusal(path, &e.to_string())),
+        };
+        let file: CacheFile =
+            serde_json::from_str(&content).map_err(|e| cache_refusal(path, &e.to_string()))?;
+        if file.version != CACHE_VERSION {
+            return Err(cache_refusal(
+                path,
+                &format!("unsupported cache version: {}", file.version),
+            ));
+        }
+        let entries = file
+            .entries
+            .into_iter()
+            .map(|entry| (entry.key(), entry))
+            .collect();
+        Ok(Self {
+            entries: Mutex::new(entries),
+            dirty: AtomicBool::new(false),
+        })
+    }
+
+    /// Cached digest for the file `metadata` describes, if its identity is
+    /// unchanged since it was hashed with `algorithm`.
+    pub fn lookup(&self, metadata: &Metadata, algorithm: &str) -> Option<String> {
+        let key = (Stamp::of(metadata)?, algorithm.to_string());
+        self.lock().get(&key).map(|entry| entry.digest.clone())
+    }
+
+    /// Remember `digest` for the file at `path`.
+    pub fn insert(&self, path: &Path, metadata: &Metadata, algorithm: &str, digest: &str) {
+        let Some(stamp) = Stamp::of(metadata) else {
+            return;
+        };
+        let recent = metadata
+            .modified()
+            .ok()
+            .and_then(|modified| SystemTime::now().duration_since(modified).ok())
+            .is_none_or(|age| age < RACY_WINDOW);
+        if recent {
+            return;
+        }
+
+        let path = std::path::absolute(path).unwrap_or_else(|_| path.to_path_buf());
+        let entry = CacheEntry {
+            path: path.display().to_string(),
+            device: stamp.device,
+            inode: stamp.inode,
+            size: stamp.size,
+            mtime_secs: stamp.mtime_secs,
+            mtime_nanos: stamp.mtime_nanos,
+            algorithm: algorithm.to_string(),
+            digest: digest.to_string(),
+        };
+        self.lock().insert(entry.key(), entry);
+        self.dirty.store(true, Ordering::Relaxed);
+    }
+
+    /// Number of cached digests.
+    pub fn len(&self) -> usize {
+        self.lock().len()
+    }
+
+    /// Whether the cache holds no digests.
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    /// Drop entries whose path is gone or no longer has the cached identity.
+    /// Returns how many were removed.
+    pub fn prune(&self) -> usize {
+        let mut entries = self.lock();
+        let before = entries.len();
+        entries.retain(|(stamp, _), entry| {
+            std::fs::metadata(&entry.path)
+                .ok()
+                .and_then(|metadata| Stamp::of(&metadata))
+                .is_some_and(|current| current == *stamp)
+        });
+        let removed = before - entries.len();
+        if removed > 0 {
+            self.dirty.store(true, Ordering::Relaxed);
+        }
+        removed
+    }
+
+    /// Write the cache back to `path` if anything changed.
+    pub fn save_if_dirty(&self, path: &Path) -> std::io::Result<()> {
+        if !self.dirty.load(Ordering::Relaxed) {
+            return Ok(());
+        }
+        self.save(path)
+    }
+
+    /// Write the cache to `path` atomically.
+    pub fn save(&self, path: &Path) -> std::io::Result<()> {
+        let file = CacheFile {
+            version: CACHE_VERSION.to_string(),
+            entries: self.lock().values().cloned().collect(),
+        };
+        let json = serde_json::to_string(&file).expect("cache file is serializable");
+
+        let mut temp = path.as_os_str().to_owned();
+        temp.push(format!(".tmp.{}", std::process::id()));
+        let temp = PathBuf::from(temp);
+        std::fs::write(&temp, json)?;
+        std::fs::rename(&temp, path).inspect_err(|_| {
+            let _ = std::fs::remove_file(&temp);
+        })
+    }
+
+    /// Cached digests per algorithm.
+    fn by_algorithm(&self) -> BTreeMap<String, usize> {
+        let mut counts = BTreeMap::new();
+        for (_, algorithm) in self.lock().keys() {
+            *counts.entry(algorithm.clone()).or_insert(0) += 1;
+        }
+        counts
+    }
+
+    fn lock(&self) -> std::sync::MutexGuard<'_, BTreeMap<(Stamp, String), CacheEntry>> {
+        self.entries.lock().expect("hash cache lock poisoned")
+    }
+}
+
+fn cache_refusal(path: &Path, error: &str) -> LockfileRefusal {
+    cache_io_refusal("read", path, error)
+}
+
+fn cache_io_refusal(action: &str, path: &Path, error: &str) -> LockfileRefusal {
+    LockfileRefusal::new(
+        VerifyRefusalCode::Io,
+        format!("cannot {action} hash cache: {}", path.display()),
+        json!({ "path": path.display().to_string(), "error": error }),
+    )
+}
+
+// ---------------------------------------------------------------------------
+// `lock cache` subcommands
+// ---------------------------------------------------------------------------
+
+/// Run a `lock cache` subcommand. Returns the exit code: `0` on success,
+/// `2` when the cache cannot be read or written.
+pub fn dispatch(action: &CacheAction) -> u8 {
+    match action {
+        CacheAction::Stats { cache, json } => run_stats(cache, *json),
+        CacheAction::Prune { cache, json } => run_prune(cache, *json),
+        CacheAction::Clear { cache, json } => run_clear(cache, *json),
+    }
+}
+
+/// Report a refusal: a `lock-cache.v0` envelope on stdout under `--json`,
+/// a message on stderr otherwise. Returns exit code `2`.
+fn refuse(refusal: &LockfileRefusal, json_output: bool) -> u8 {
+    if json_output {
+        print!("{}", refusal.to_json(CACHE_VERSION));
+    } else {
+        eprintln!("lock: cache error: {}", refusal.message);
+    }
+    2
+}
+
+fn run_stats(path: &Path, json_output: bool) -> u8 {
+    let cache = match HashCache::load(path) {
+        Ok(cache) => cache,
+        Err(refusal) => return refuse(&refusal, json_output),
+    };
+    let bytes = std::fs::metadata(path).map_or(0, |metadata| metadata.len());
+    let by_algorithm = cache.by_algorithm();
+
+    if json_output {
+        println!(
+            "{}",
+            json!({
+                "version": CACHE_VERSION,
+                "cache": path.display().to_string(),
+                "entries": cache.len(),
+                "bytes": bytes,
+                "by_algorithm": by_algorithm,
+            })
+        );
+    } else {
+        let algorithms: Vec<String> = by_algorithm
+            .iter()
+            .map(|(algorithm, count)| format!("{algorithm} {count}"))
+            .collect();
+        println!(
+            "{}: {} entries, {bytes} bytes{}",
+            path.display(),
+            cache.len(),
+            if algorithms.is_empty() {
+                String::new()
+            } else {
+                format!(" ({})", algorithms.join(", "))
+            }
+        );
+    }
+    0
+}
+
+fn run_prune(path: &Path, json_output: bool) -> u8 {
+    let cache = match HashCache::load(path) {
+        Ok(cache) => cache,
+        Err(refusal) => return refuse(&refusal, json_output),
+    };
+    let removed = cache.prune();
+    if let Err(e) = cache.save_if_dirty(path) {
+        return refuse(
+            &cache_io_refusal("write", path, &e.to_string()),
+            json_output,
+        );
+    }
+
+    if json_output {
+        println!(
+            "{}",
+            json!({
+                "version": CACHE_VERSION,
+                "cache": path.display().to_string(),
+                "removed": removed,
+                "entries": cache.len(),
+            })
+        );
+    } else {
+        println!(
+            "{}: removed {removed} stale entries, {} left",
+            path.display(),
+            cache.len()
+        );
+    }
+    0
+}
+
+fn run_clear(path: &Path, json_output: bool) -> u8 {
+    let cleared = match std::fs::remove_file(path) {
+        Ok(()) => true,
+        Err(e) if e.kind() == std::io::ErrorKind::NotFound => false,
+        Err(e) => {
+            return refuse(
+                &cache_io_refusal("remove", path, &e.to_string()),
+                json_output,
+            );
+        }
+    };
+
+    if json_output {
+        println!(
+            "{}",
+            json!({
+                "version": CACHE_VERSION,
+                "cache": path.display().to_string(),
+                "cleared": cleared,
+            })
+        );
+    } else if cleared {
+        println!("{}: cleared", path.display());
+    } else {
+        println!("{}: already empty", path.display());
+    }
+    0
+}
+
+// ---------------------------------------------------------------------------
+// Tests
+// ---------------------------------------------------------------------------
+
+#[cfg(all(test, unix))]
+mod tests {
+    use super::*;
+
+    /// Write `content` with an mtime old enough to be cacheable.
+    fn old_file(dir: &Path, name: &str, content: &[u8]) -> PathBuf {
+        let path = dir.join(name);
+        std::fs::write(&path, content).unwrap();
+        let file = std::fs::File::options().write(true).open(&path).unwrap();
+        file.set_modified(SystemTime::now() - Duration::from_secs(60))
+            .unwrap();
+        path
+    }
+
+    #[test]
+    fn round_trips_and_keys_on_algorithm() {
+        let dir = tempfile::tempdir().unwrap();
+        let file = old_file(dir.path(), "a.csv", b"a");
+        let metadata = std::fs::metadata(&file).unwrap();
+
+        let cache = HashCache::default();
+        cache.insert(&file, &metadata, "sha256", "sha256:aa");
+        let cache_path = dir.path().join("hash.cache");
+        cache.save_if_dirty(&cache_path).unwrap();
+
+        let loaded = HashCache::load(&cache_path).unwrap();
+        assert_eq!(
+            loaded.lookup(&metadata, "sha256").as_deref(),
+            Some("sha256:aa")
+        );
+        assert_eq!(loaded.lookup(&metadata, "blake3"), None);
+    }
+
+    #[test]
+    fn changed_file_misses() {
+        let dir = tempfile::tempdir().unwrap();
+        let file = old_file(dir.path(), "a.csv", b"a");
+        let cache = HashCache::default();
+        cache.insert(
+            &file,
+            &std::fs::metadata(&file).unwrap(),
+            "sha256",
+            "sha256:aa",
+        );
+
+        let file = old_file(dir.path(), "a.csv", b"ab");
+        assert_eq!(
+            cache.lookup(&std::fs::metadata(&file).unwrap(), "sha256"),
+            None
+        );
+        assert_eq!(cache.prune(), 1);
+        assert!(cache.is_empty());
+    }
+
+    #[test]
+    fn recently_modified_files_are_not_cached() {
+        let dir = tempfile::tempdir().unwrap();
+        let file = dir.path().join("fresh.csv");
+        std::fs::write(&file, b"fresh").unwrap();
+
+        let cache = HashCache::default();
+        cache.insert(
+            &file,
+            &std::fs::metadata(&file).unwrap(),
+            "sha256",
+            "sha256:ff",
+        );
+        assert!(cache.is_empty());
+    }
+
+    #[test]
+    fn missing_cache_is_empty_and_garbage_refuses() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("hash.cache");
+        assert!(HashCache::load(&path).unwrap().is_empty());
+
+        std::fs::write(&path, "not json").unwrap();
+        let refusal = HashCache::load(&path).unwrap_err();
+        assert_eq!(refusal.code, VerifyRefusalCode::Io);
+    }
+}
@@ -0,0 +1,520 @@
+//! BagIt (RFC 8493) bags: `export --format bagit` and `import --format bagit`.
+//!
+//! A bag is a directory holding the payload under `data/` plus tag files:
+//! `bagit.txt`, one `manifest-<algorithm>.txt` per digest algorithm,
+//! `bag-info.txt` metadata, and `tagmanifest-<algorithm>.txt` over the other
+//! tag files. Export writes only the tag files, never the payload.
+
+use std::collections::{BTreeMap, BTreeSet};
+use std::path::Path;
+
+use serde_json::json;
+
+use super::{
+    ChecksumLine, hex, import_record, is_member_path, member_digest, mismatch_refusal,
+    no_digest_refusal, seal_records,
+};
+use crate::cli::ImportArgs;
+use crate::digest::Hasher;
+use crate::input::InputRecord;
+use crate::refusal::LOCK_VERSION;
+use crate::update::BaseLockfile;
+use crate::verify::{LockfileRefusal, VerifyRefusalCode};
+use crate::{OrchestrationOutput, lockfile_refusal_output, refusal_output};
+
+/// Algorithms with BagIt manifests, in order of preference on import.
+const BAG_ALGORITHMS: &[&str] = &["sha256", "sha512"];
+
+const BAGIT_TXT: &str = "BagIt-Version: 1.0\nTag-File-Character-Encoding: UTF-8\n";
+
+/// `bag-info.txt` label that carries the lockfile's `as_of`; BagIt reserves
+/// no label for it.
+const AS_OF_LABEL: &str = "Lock-As-Of";
+
+// ---------------------------------------------------------------------------
+// Export
+// ---------------------------------------------------------------------------
+
+/// Manifest paths percent-encode `%`, CR, and LF (RFC 8493 §2.1.3).
+fn encode_path(path: &str) -> String {
+    path.replace('%', "%25")
+        .replace('\r', "%0D")
+        .replace('\n', "%0A")
+}
+
+fn decode_path(path: &str) -> String {
+    path.replace("%0A", "\n")
+        .replace("%0a", "\n")
+        .replace("%0D", "\r")
+        .replace("%0d", "\r")
+        .replace("%25", "%")
+}
+
+fn digest_of(algorithm: &str, bytes: &[u8]) -> String {
+    let mut hasher = Hasher::new(algorithm).expect("BagIt algorithms are registered");
+    hasher.update(bytes);
+    hasher.finish()
+}
+
+/// `bag-info.txt` contents. Multi-line values continue on indented lines.
+fn bag_info(base: &BaseLockfile) -> String {
+    let bytes: u64 = base.members.iter().map(|member| member.size).sum();
+    let mut fields = vec![
+        (
+            "Bag-Software-Agent",
+            format!("lock {}", env!("CARGO_PKG_VERSION")),
+        ),
+        (
+            "Bagging-Date",
+            chrono::Utc::now().format("%Y-%m-%d").to_string(),
+        ),
+    ];
+    if let Some(dataset_id) = &base.dataset_id {
+        fields.push(("External-Identifier", dataset_id.clone()));
+    }
+    if let Some(note) = &base.note {
+        fields.push(("External-Description", note.clone()));
+    }
+    if let Some(as_of) = &base.as_of {
+        fields.push((AS_OF_LABEL, as_of.clone()));
+    }
+    fields.push(("Lock-Hash", base.lock_hash.clone()));
+    fields.push(("Payload-Oxum", format!("{bytes}.{}", base.members.len())));
+
+    fields
+        .into_iter()
+        .map(|(label, value)| format!("{label}: {}\n", value.replace('\n', "\n  ")))
+        .collect()
+}
+
+/// Write the tag files for `base` into `root`, creating it if needed. The
+/// payload is expected under `root/data/` and is not touched.
+///
+/// A manifest is written for each BagIt algorithm every member has a digest
+/// under; with none, the refusal names the members lacking sha256. Returns
+/// the sha256 tag manifest, which covers everything written.
+pub(super) fn write_bag(base: &BaseLockfile, root: &Path) -> Result<String, LockfileRefusal> {
+    let mut tag_files: BTreeMap<String, String> = BTreeMap::new();
+    for algorithm in BAG_ALGORITHMS {
+        let manifest: Option<String> = base
+            .members
+            .iter()
+            .map(|member| {
+                let digest = member_digest(member, algorithm)?;
+                Some(format!(
+                    "{}  data/{}\n",
+                    hex(digest),
+                    encode_path(&member.path)
+                ))
+            })
+            .collect();
+        if let Some(manifest) = manifest {
+            tag_files.insert(format!("manifest-{algorithm}.txt"), manifest);
+        }
+    }
+    if tag_files.is_empty() {
+        let lacking = base
+            .members
+            .iter()
+            .filter(|member| member_digest(member, "sha256").is_none())
+            .map(|member| member.path.clone())
+            .collect();
+        return Err(no_digest_refusal("bagit", "sha256", lacking));
+    }
+    tag_files.insert("bagit.txt".to_string(), BAGIT_TXT.to_string());
+    tag_files.insert("bag-info.txt".to_string(), bag_info(base));
+
+    let algorithms: Vec<&str> = BAG_ALGORITHMS
+        .iter()
+        .copied()
+        .filter(|algorithm| tag_files.contains_key(&format!("manifest-{algorithm}.txt")))
+        .collect();
+    let tag_manifests: Vec<(String, String)> = algorithms
+        .iter()
+        .map(|algorithm| {
+            let listing = tag_files
+                .iter()
+                .map(|(name, contents)| {
+                    format!(
+                        "{}  {name}\n",
+                        hex(&digest_of(algorithm, contents.as_bytes()))
+                    )
+                })
+                .collect();
+            (format!("tagmanifest-{algorithm}.txt"), listing)
+        })
+        .collect();
+    let payload = tag_manifests[0].1.clone();
+    tag_files.extend(tag_manifests);
+
+    let written = std::fs::create_dir_all(root).and_then(|()| {
+        tag_files
+            .iter()
+            .try_for_each(|(name, contents)| std::fs::write(root.join(name), contents))
+    });
+    written.map_err(|e| {
+        LockfileRefusal::new(
+            VerifyRefusalCode::Io,
+            format!("cannot write bag — {e}"),
+            json!({
+                "root": root.display().to_string(),
+                "error": e.to_string(),
+            }),
+        )
+    })?;
+    Ok(payload)
+}
+
+// ---------------------------------------------------------------------------
+// Import
+// ---------------------------------------------------------------------------
+
+/// Parse `bag-info.txt` into labels and values, joining continuation lines.
+/// Later repeats of a label replace earlier ones.
+fn parse_bag_info(text: &str) -> BTreeMap<String, String> {
+    let mut fields: BTreeMap<String, String> = BTreeMap::new();
+    let mut last: Option<String> = None;
+    for line in text.lines() {
+        let line = line.strip_suffix('\r').unwrap_or(line);
+        if line.starts_with([' ', '\t']) {
+            if let Some(value) = last.as_ref().and_then(|label| fields.get_mut(label)) {
+                value.push('\n');
+                value.push_str(line.trim_start());
+            }
+        } else if let Some((label, value)) = line.split_once(':') {
+            let label = label.trim().to_ascii_lowercase();
+            fields.insert(label.clone(), value.trim().to_string());
+            last = Some(label);
+        }
+    }
+    fields
+}
+
+/// Parse one `<hex> <path>` manifest line; the path must be under `data/`
+/// and stay there once the prefix is removed.
+fn parse_manifest_line(line: &str, algorithm: &str) -> Option<ChecksumLine> {
+    let (hex, path) = line.split_once([' ', '\t'])?;
+    let hex_len = hex_len(algorithm);
+    if hex.len() != hex_len || !hex.chars().all(|c| c.is_ascii_hexdigit()) {
+        return None;
+    }
+    let path = decode_path(path.trim_start_matches([' ', '\t']));
+    let path = path
+        .strip_prefix("data/")
+        .filter(|path| !path.is_empty() && is_member_path(path))?;
+    Some(ChecksumLine {
+        digest: format!("{algorithm}:{}", hex.to_ascii_lowercase()),
+        path: path.to_owned(),
+        hashes: BTreeMap::new(),
+    })
+}
+
+fn hex_len(algorithm: &str) -> usize {
+    hex(&digest_of(algorithm, b"")).len()
+}
+
+fn bad_bag(bag: &Path, error: String) -> OrchestrationOutput {
+    refusal_output(crate::refusal::bad_bag(&bag.display().to_string(), &error))
+}
+
+/// Read every BagIt manifest in `bag`. The first found is primary and sets
+/// `bytes_hash`; the others add to `hashes` and must list exactly the same
+/// paths, each once.
+fn read_manifests(bag: &Path) -> Result<Vec<(usize, ChecksumLine)>, OrchestrationOutput> {
+    let mut lines: Vec<(usize, ChecksumLine)> = Vec::new();
+    let mut index: BTreeMap<String, usize> = BTreeMap::new();
+    let mut primary: Option<&str> = None;
+    for algorithm in BAG_ALGORITHMS {
+        let name = format!("manifest-{algorithm}.txt");
+        let Ok(text) = std::fs::read_to_string(bag.join(&name)) else {
+            continue;
+        };
+        let mut listed = BTreeSet::new();
+        for (number, line) in text.lines().enumerate() {
+            let line = line.strip_suffix('\r').unwrap_or(line);
+            if line.trim().is_empty() {
+                continue;
+            }
+            let Some(parsed) = parse_manifest_line(line, algorithm) else {
+                let expected = format!("`<{algorithm} hex> data/<path>` in {name}");
+                return Err(refusal_output(crate::refusal::bad_checksum_line(
+                    "bagit",
+                    number + 1,
+                    &expected,
+                )));
+            };
+            if !listed.insert(parsed.path.clone()) {
+                return Err(bad_bag(
+                    bag,
+                    format!("{name} lists data/{} more than once", parsed.path),
+                ));
+            }
+            match (primary, index.get(&parsed.path)) {
+                (None, _) => {
+                    index.insert(parsed.path.clone(), lines.len());
+                    lines.push((number + 1, parsed));
+                }
+                (Some(_), Some(&at)) => {
+                    let entry = &mut lines[at].1;
+                    if entry.hashes.is_empty() {
+                        let own = crate::digest::prefix(&entry.digest).to_owned();
+                        entry.hashes.insert(own, entry.digest.clone());
+                    }
+                    entry.hashes.insert(algorithm.to_string(), parsed.digest);
+                }
+                (Some(first), None) => {
+                    return Err(bad_bag(
+                        bag,
+                        format!(
+                            "{name} lists data/{} but manifest-{first}.txt does not",
+                            parsed.path
+                        ),
+                    ));
+                }
+            }
+        }
+        if let Some(first) = primary
+            && let Some((_, missing)) = lines.iter().find(|(_, line)| !listed.contains(&line.path))
+        {
+            return Err(bad_bag(
+                bag,
+                format!(
+                    "manifest-{first}.txt lists data/{} but {name} does not",
+                    missing.path
+                ),
+            ));
+        }
+        primary.get_or_insert(algorithm);
+    }
+    if primary.is_none() {
+        return Err(bad_bag(
+            bag,
+            "no manifest-sha256.txt or manifest-sha512.txt".to_string(),
+        ));
+    }
+    Ok(lines)
+}
+
+/// Refuse when `Payload-Oxum` (`<octets>.<count>`) disagrees with the
+/// payload files found: their summed size and how many could be read, which
+/// become the lockfile's member sizes and `member_count`.
+fn check_oxum(bag: &Path, declared: &str, records: &[InputRecord]) -> Result<(), LockfileRefusal> {
+    let sizes: Vec<u64> = records
+        .iter()
+        .filter_map(|record| record.value.get("size").and_then(|size| size.as_u64()))
+        .collect();
+    let actual = format!("{}.{}", sizes.iter().sum::<u64>(), sizes.len());
+    if declared == actual {
+        return Ok(());
+    }
+    Err(LockfileRefusal::new(
+        VerifyRefusalCode::OxumMismatch,
+        format!("Payload-Oxum {declared} does not match the payload ({actual})"),
+        json!({
+            "bag": bag.display().to_string(),
+            "declared": declared,
+            "actual": actual,
+        }),
+    ))
+}
+
+/// Build a `lock.v0` lockfile from the bag at `args.manifest`.
+///
+/// Digests come from the manifests and are checked against the files under
+/// `data/`, which also give the sizes.
+/// `bag-info.txt` supplies `dataset_id`, `note`, and `as_of` unless the
+/// flags set them, and its `Payload-Oxum`, when present, must agree with the
+/// payload.
+pub(super) fn import_bag(args: &ImportArgs) -> OrchestrationOutput {
+    let bag = args.manifest.as_path();
+    if args.root.is_some() {
+        // A bag's payload is always its own `data/`, so `--root` could only
+        // be ignored; refuse rather than let the caller think it was used.
+        return bad_bag(
+            bag,
+            "--root is not used with --format bagit; the payload is read from data/".to_string(),
+        );
+    }
+    if let Err(e) = std::fs::read_to_string(bag.join("bagit.txt")) {
+        return bad_bag(bag, format!("cannot read bagit.txt — {e}"));
+    }
+    let info = match std::fs::read_to_string(bag.join("bag-info.txt")) {
+        Ok(text) => parse_bag_info(&text),
+        Err(e) if e.kind() == std::io::ErrorKind::NotFound => BTreeMap::new(),
+        Err(e) => {
+            return lockfile_refusal_output(super::manifest_unreadable(bag, &e), LOCK_VERSION);
+        }
+    };
+    let lines = match read_manifests(bag) {
+        Ok(lines) => lines,
+        Err(refusal) => return refusal,
+    };
+
+    let data = bag.join("data");
+    let mut records = Vec::new();
+    let mut mismatched = Vec::new();
+    for (line_number, line) in &lines {
+        let (value, drifted) = import_record(line, &data);
+        if drifted {
+            mismatched.push(line.path.clone());
+        }
+        records.push(InputRecord {
+            line_number: *line_number,
+            value,
+        });
+    }
+    if let Some(declared) = info.get("payload-oxum")
+        && let Err(refusal) = check_oxum(bag, declared, &records)
+    {
+        return lockfile_refusal_output(refusal, LOCK_VERSION);
+    }
+    if !mismatched.is_empty() {
+        return lockfile_refusal_output(mismatch_refusal(&data, &mismatched), LOCK_VERSION);
+    }
+
+    let field = |flag: &Option<String>, label: &str| {
+        flag.clone()
+            .or_else(|| info.get(&label.to_ascii_lowercase()).cloned())
+    };
+    seal_records(
+        &records,
+        field(&args.dataset_id, "External-Identifier").as_deref(),
+        field(&args.as_of, AS_OF_LABEL).as_deref(),
+        field(&args.note, "External-Description").as_deref(),
+    )
+}
+
+// ---------------------------------------------------------------------------
+// Tests
+// ---------------------------------------------------------------------------
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::lockfile::Member;
+
+    fn member(path: &str, content: &[u8]) -> Member {
+        Member {
+            path: path.to_string(),
+            bytes_hash: digest_of("sha256", content),
+            size: content.len() as u64,
+            fingerprint: None,
+            mtime: None,
+            extra: None,
+            hashes: None,
+        }
+    }
+
+    fn base(members: Vec<Member>) -> BaseLockfile {
+        BaseLockfile {
+            version: LOCK_VERSION.to_string(),
+            lock_hash: "sha256:base".to_string(),
+            dataset_id: Some("raw-dec".to_string()),
+            as_of: Some("2025-12-31T00:00:00Z".to_string()),
+            note: Some("first line\nsecond line".to_string()),
+            tool_versions: BTreeMap::new(),
+            profiles: Vec::new(),
+            skipped: Vec::new(),
+            members,
+        }
+    }
+
+    #[test]
+    fn bag_info_round_trips_metadata() {
+        let info = parse_bag_info(&bag_info(&base(vec![member("a.csv", b"alpha")])));
+        assert_eq!(info["external-identifier"], "raw-dec");
+        assert_eq!(info["external-description"], "first line\nsecond line");
+        assert_eq!(info["lock-as-of"], "2025-12-31T00:00:00Z");
+        assert_eq!(info["payload-oxum"], "5.1");
+    }
+
+    #[test]
+    fn tag_manifest_covers_every_tag_file() {
+        let dir = tempfile::tempdir().unwrap();
+        let tag_manifest = write_bag(&base(vec![member("a%b.csv", b"alpha")]), dir.path()).unwrap();
+
+        let listed: Vec<&str> = tag_manifest
+            .lines()
+            .map(|line| line.split_once("  ").unwrap().1)
+            .collect();
+        assert_eq!(listed, ["bag-info.txt", "bagit.txt", "manifest-sha256.txt"]);
+        for line in tag_manifest.lines() {
+            let (hex, name) = line.split_once("  ").unwrap();
+            let contents = std::fs::read(dir.path().join(name)).unwrap();
+            assert_eq!(digest_of("sha256", &contents), format!("sha256:{hex}"));
+        }
+        assert!(!dir.path().join("manifest-sha512.txt").exists());
+
+        let manifest = std::fs::read_to_string(dir.path().join("manifest-sha256.txt")).unwrap();
+        assert!(manifest.ends_with("  data/a%25b.csv\n"), "{manifest}");
+        let parsed = parse_manifest_line(manifest.trim_end(), "sha256").unwrap();
+        assert_eq!(parsed.path, "a%b.csv");
+    }
+
+    #[test]
+    fn manifest_lines_must_be_under_data() {
+        let hex = hex(&digest_of("sha256", b"alpha")).to_owned();
+        assert!(parse_manifest_line(&format!("{hex} data/a.csv"), "sha256").is_some());
+        assert!(parse_manifest_line(&format!("{hex}\tdata/a.csv"), "sha256").is_some());
+        assert!(parse_manifest_line(&format!("{hex} a.csv"), "sha256").is_none());
+        assert!(parse_manifest_line(&format!("{hex} data/a.csv"), "sha512").is_none());
+    }
+
+    #[test]
+    fn manifest_paths_may_not_leave_data() {
+        let hex = hex(&digest_of("sha256", b"alpha")).to_owned();
+        assert!(parse_manifest_line(&format!("{hex} data/sub/a.csv"), "sha256").is_some());
+        assert!(parse_manifest_line(&format!("{hex} data/../../root/a.csv"), "sha256").is_none());
+        assert!(parse_manifest_line(&format!("{hex} data//etc/hostname"), "sha256").is_none());
+    }
+
+    #[test]
+    fn secondary_manifests_must_list_the_primary_paths_once_each() {
+        let dir = tempfile::tempdir().unwrap();
+        let sha256 = "a".repeat(64);
+        let sha512 = "b".repeat(128);
+        std::fs::write(
+            dir.path().join("manifest-sha256.txt"),
+            format!("{sha256}  data/a.csv\n{sha256}  data/b.csv\n"),
+        )
+        .unwrap();
+        let write_sha512 = |paths: &[&str]| {
+            let listing: String = paths
+                .iter()
+                .map(|path| format!("{sha512}  data/{path}\n"))
+                .collect();
+            std::fs::write(dir.path().join("manifest-sha512.txt"), listing).unwrap();
+        };
+        let refused = |paths: &[&str]| {
+            write_sha512(paths);
+            read_manifests(dir.path()).is_err()
+        };
+
+        assert!(!refused(&["b.csv", "a.csv"]));
+        assert!(refused(&["a.csv", "a.csv"]));
+        assert!(refused(&["a.csv"]));
+        assert!(refused(&["a.csv", "b.csv", "c.csv"]));
+
+        write_sha512(&["a.csv", "b.csv"]);
+        let lines = read_manifests(dir.path()).unwrap();
+        assert_eq!(lines[1].1.hashes["sha512"], format!("sha512:{sha512}"));
+    }
+
+    #[test]
+    fn oxum_compares_bytes_and_count() {
+        let records = [
+            InputRecord {
+                line_number: 1,
+                value: json!({ "size": 5 }),
+            },
+            InputRecord {
+                line_number: 2,
+                value: json!({ "_skipped": true }),
+            },
+        ];
+        assert!(check_oxum(Path::new("bag"), "5.1", &records).is_ok());
+        let refusal = check_oxum(Path::new("bag"), "5.2", &records).unwrap_err();
+        assert_eq!(refusal.code, VerifyRefusalCode::OxumMismatch);
+        assert_eq!(refusal.detail["actual"], "5.1");
+    }
+}
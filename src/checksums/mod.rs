@@ -0,0 +1,629 @@
+//! Standard checksum files: `lock export` and `lock import`.
+//!
+//! `export` renders the members of a self-hash-verified lockfile as a
+//! `sha256sum`/`b3sum` file, BSD-style tagged lines, CSV, or JSONL, for
+//! consumers that do not have `lock`. `import` goes the other way: it turns a
+//! `sha256sum`/`b3sum` file into `hash.v0`-shaped records and runs them
+//! through the same validator, classifier, and sealing steps as `lock scan`.
+//! Every listed file is hashed on import, so a sealed member never pairs a
+//! manifest digest with bytes it does not describe.
+//! BagIt bags are handled in [`bagit`].
+
+mod bagit;
+
+use std::collections::{BTreeMap, BTreeSet};
+use std::path::Path;
+
+use serde_json::{Value, json};
+
+use crate::cli::{ExportArgs, ImportArgs};
+use crate::input::{InputRecord, RecordValidator};
+use crate::lockfile::{ClassifyOptions, Member, RecordClassifier};
+use crate::refusal::LOCK_VERSION;
+use crate::update::load_base;
+use crate::verify::members::stream_hashes;
+use crate::verify::{LockfileRefusal, VerifyRefusalCode};
+use crate::{OrchestrationOutput, lockfile_refusal_output, refusal_output};
+
+/// Record version import synthesizes, matching `hashbytes` output.
+const IMPORT_RECORD_VERSION: &str = "hash.v0";
+
+/// Hex length of a `sha256sum` or `b3sum` digest; both are 256-bit.
+const DIGEST_HEX_LEN: usize = 64;
+
+/// Export formats, as accepted by `--format`.
+pub const EXPORT_FORMATS: &[&str] = &["sha256sum", "b3sum", "bsd", "csv", "jsonl", "bagit"];
+
+/// Import formats, as accepted by `--format`.
+pub const IMPORT_FORMATS: &[&str] = &["sha256sum", "b3sum", "bagit"];
+
+/// The algorithm a coreutils-style format lists, or `None` for formats that
+/// carry each member's own `bytes_hash`.
+fn format_algorithm(format: &str) -> Option<&'static str> {
+    match format {
+        "sha256sum" => Some("sha256"),
+        "b3sum" => Some("blake3"),
+        _ => None,
+    }
+}
+
+// ---------------------------------------------------------------------------
+// Export
+// ---------------------------------------------------------------------------
+
+/// A member's digest under `algorithm`, from `bytes_hash` or `hashes`.
+fn member_digest<'m>(member: &'m Member, algorithm: &str) -> Option<&'m str> {
+    if crate::digest::prefix(&member.bytes_hash) == algorithm {
+        return Some(&member.bytes_hash);
+    }
+    member
+        .hashes
+        .as_ref()
+        .and_then(|hashes| hashes.get(algorithm))
+        .map(String::as_str)
+}
+
+fn hex(digest: &str) -> &str {
+    digest.split_once(':').map_or(digest, |(_, hex)| hex)
+}
+
+/// Escape a path the way GNU coreutils does: with a backslash or newline in
+/// the name, the line starts with `\` and those characters are escaped.
+fn escape_path(path: &str) -> (&'static str, String) {
+    if path.contains(['\\', '\n', '\r']) {
+        let escaped = path
+            .replace('\\', "\\\\")
+            .replace('\n', "\\n")
+            .replace('\r', "\\r");
+        ("\\", escaped)
+    } else {
+        ("", path.to_owned())
+    }
+}
+
+fn csv_field(field: &str) -> String {
+    if field.contains([',', '"', '\n', '\r']) {
+        format!("\"{}\"", field.replace('"', "\"\""))
+    } else {
+        field.to_owned()
+    }
+}
+
+/// Render `members` in `format`. Coreutils formats need every member to
+/// carry a digest under their algorithm; the paths of those that do not are
+/// returned as the error.
+fn render(members: &[Member], format: &str) -> Result<String, Vec<String>> {
+    let mut out = String::new();
+    match format {
+        "sha256sum" | "b3sum" => {
+            let algorithm = format_algorithm(format).expect("coreutils format");
+            let mut lacking = Vec::new();
+            for member in members {
+                match member_digest(member, algorithm) {
+                    Some(digest) => {
+                        let (marker, path) = escape_path(&member.path);
+                        out.push_str(&format!("{marker}{}  {path}\n", hex(digest)));
+                    }
+                    None => lacking.push(member.path.clone()),
+                }
+            }
+            if !lacking.is_empty() {
+                return Err(lacking);
+            }
+        }
+        "bsd" => {
+            for member in members {
+                let tag = crate::digest::prefix(&member.bytes_hash).to_ascii_uppercase();
+                let (marker, path) = escape_path(&member.path);
+                out.push_str(&format!(
+                    "{marker}{tag} ({path}) = {}\n",
+                    hex(&member.bytes_hash)
+                ));
+            }
+        }
+        "csv" => {
+            out.push_str("path,size,bytes_hash\n");
+            for member in members {
+                out.push_str(&format!(
+                    "{},{},{}\n",
+                    csv_field(&member.path),
+                    member.size,
+                    member.bytes_hash
+                ));
+            }
+        }
+        "jsonl" => {
+            for member in members {
+                let mut line = json!({
+                    "path": member.path,
+                    "size": member.size,
+                    "bytes_hash": member.bytes_hash,
+                });
+                if let Some(hashes) = &member.hashes {
+                    line["hashes"] = json!(hashes);
+                }
+                out.push_str(&crate::refusal::sort_value(line).to_string());
+                out.push('\n');
+            }
+        }
+        other => unreachable!("clap restricts --format, got {other}"),
+    }
+    Ok(out)
+}
+
+fn no_digest_refusal(format: &str, algorithm: &str, lacking: Vec<String>) -> LockfileRefusal {
+    LockfileRefusal::new(
+        VerifyRefusalCode::NoDigest,
+        format!(
+            "{} member(s) have no {algorithm} digest for {format}; add one with `lock rehash --add {algorithm}`",
+            lacking.len()
+        ),
+        json!({
+            "format": format,
+            "algorithm": algorithm,
+            "paths": lacking,
+        }),
+    )
+}
+
+/// Run the export subcommand. Returns the exit code.
+///
+/// Exit codes: `0` manifest (or bag tag files) written, `2` refusal.
+pub fn run_export(args: &ExportArgs) -> u8 {
+    let bag_root = args.root.as_deref().filter(|_| args.format == "bagit");
+    let rendered = load_base(&args.lockfile).and_then(|base| match bag_root {
+        Some(root) => bagit::write_bag(&base, root),
+        None => render(&base.members, &args.format).map_err(|lacking| {
+            let algorithm = format_algorithm(&args.format).unwrap_or_default();
+            no_digest_refusal(&args.format, algorithm, lacking)
+        }),
+    });
+
+    match rendered {
+        Ok(payload) => {
+            if bag_root.is_none() && !crate::write_payload(args.output.as_deref(), &payload) {
+                return 2;
+            }
+            emit_export_witness(args, 0, "EXPORTED", payload.as_bytes());
+            0
+        }
+        Err(refusal) => {
+            let payload = refusal.to_json(LOCK_VERSION);
+            print!("{payload}");
+            emit_export_witness(args, 2, "REFUSAL", payload.as_bytes());
+            2
+        }
+    }
+}
+
+fn emit_export_witness(args: &ExportArgs, exit_code: u8, outcome: &str, payload: &[u8]) {
+    if args.no_witness {
+        return;
+    }
+
+    let params = json!({
+        "subcommand": "export",
+        "format": args.format,
+        "root": args.root.as_ref().map(|root| root.display().to_string()),
+    });
+    let inputs = json!([
+        { "path": args.lockfile.display().to_string(), "hash": null, "bytes": null }
+    ]);
+
+    crate::witness::append_witness_record(outcome, exit_code, payload, params, inputs);
+}
+
+// ---------------------------------------------------------------------------
+// Import
+// ---------------------------------------------------------------------------
+
+/// One parsed checksum line. `hashes` holds digests for the same path from
+/// other manifests, when the source has more than one.
+#[derive(Debug, Clone, PartialEq, Eq)]
+struct ChecksumLine {
+    digest: String,
+    path: String,
+    hashes: BTreeMap<String, String>,
+}
+
+fn unescape_path(path: &str) -> String {
+    let mut out = String::with_capacity(path.len());
+    let mut chars = path.chars();
+    while let Some(c) = chars.next() {
+        match (c, chars.clone().next()) {
+            ('\\', Some('\\')) => {
+                out.push('\\');
+                chars.next();
+            }
+            ('\\', Some('n')) => {
+                out.push('\n');
+                chars.next();
+            }
+            ('\\', Some('r')) => {
+                out.push('\r');
+                chars.next();
+            }
+            _ => out.push(c),
+        }
+    }
+    out
+}
+
+/// Parse one `<hex>  <path>` line (text or `*` binary mode), with the
+/// coreutils escape marker and a leading `./` on the path allowed.
+fn parse_line(line: &str, algorithm: &str, hex_len: usize) -> Option<ChecksumLine> {
+    let (escaped, line) = match line.strip_prefix('\\') {
+        Some(rest) => (true, rest),
+        None => (false, line),
+    };
+    let (hex, rest) = line.split_at_checked(hex_len)?;
+    if !hex.chars().all(|c| c.is_ascii_hexdigit()) {
+        return None;
+    }
+    let path = rest
+        .strip_prefix("  ")
+        .or_else(|| rest.strip_prefix(" *"))?;
+    let path = if escaped {
+        unescape_path(path)
+    } else {
+        path.to_owned()
+    };
+    let path = path.strip_prefix("./").unwrap_or(&path).to_owned();
+    if path.is_empty() {
+        return None;
+    }
+    Some(ChecksumLine {
+        digest: format!("{algorithm}:{}", hex.to_ascii_lowercase()),
+        path,
+        hashes: BTreeMap::new(),
+    })
+}
+
+/// Whether `path` can be a member path: relative and free of `..`, as
+/// `lock verify` requires. Anything else would be read from outside the root.
+fn is_member_path(path: &str) -> bool {
+    !crate::verify::is_absolute_member_path(path) && !crate::verify::has_traversal(path)
+}
+
+/// Build a pipeline-shaped record for one checksum line from the file under
+/// `root`, which is read once and hashed with every algorithm the line
+/// lists. The second value is whether any listed digest disagrees with the
+/// file; callers refuse the import with `E_DRIFT` rather than seal it. A
+/// file that cannot be read becomes a `_skipped` record with an `E_IO`
+/// warning, as in `lock scan`.
+fn import_record(line: &ChecksumLine, root: &Path) -> (Value, bool) {
+    let tool_versions = json!({ "lock": env!("CARGO_PKG_VERSION") });
+    let path = root.join(&line.path);
+    let mut listed = line.hashes.clone();
+    listed
+        .entry(crate::digest::prefix(&line.digest).to_owned())
+        .or_insert_with(|| line.digest.clone());
+    let algorithms: Vec<&str> = listed.keys().map(String::as_str).collect();
+    let read = std::fs::metadata(&path).and_then(|metadata| {
+        if !metadata.is_file() {
+            return Err(std::io::Error::other("not a regular file"));
+        }
+        let digests = stream_hashes(&path, &algorithms).map_err(std::io::Error::other)?;
+        Ok((metadata.len(), digests))
+    });
+
+    match read {
+        Ok((size, digests)) => {
+            let mismatched = listed
+                .values()
+                .zip(&digests)
+                .any(|(listed, actual)| listed != actual);
+            let mut record = json!({
+                "version": IMPORT_RECORD_VERSION,
+                "relative_path": line.path,
+                "bytes_hash": line.digest,
+                "size": size,
+                "tool_versions": tool_versions,
+            });
+            if !line.hashes.is_empty() {
+                record["hashes"] = json!(line.hashes);
+            }
+            (record, mismatched)
+        }
+        Err(error) => (
+            json!({
+            "version": IMPORT_RECORD_VERSION,
+            "relative_path": line.path,
+            "_skipped": true,
+            "_warnings": [{
+                "tool": "lock",
+                "code": "E_IO",
+                "message": format!("Cannot read file: {error}"),
+                "detail": { "path": path.display().to_string() },
+            }],
+            "tool_versions": tool_versions,
+            }),
+            false,
+        ),
+    }
+}
+
+/// Refusal for listed files whose bytes do not hash to the manifest's
+/// digests; same code and detail shape as `lock rehash`.
+fn mismatch_refusal(root: &Path, mismatched: &[String]) -> LockfileRefusal {
+    let drifted: Vec<Value> = mismatched
+        .iter()
+        .map(|path| json!({ "path": path, "reason": "HASH_MISMATCH" }))
+        .collect();
+    LockfileRefusal::new(
+        VerifyRefusalCode::Drift,
+        format!(
+            "{} file(s) under {} do not match the manifest's digests",
+            drifted.len(),
+            root.display()
+        ),
+        json!({
+            "root": root.display().to_string(),
+            "drifted": drifted,
+        }),
+    )
+}
+
+/// Read `args.manifest` and build the lockfile output (or refusal).
+fn import(args: &ImportArgs) -> OrchestrationOutput {
+    if args.format == "bagit" {
+        return bagit::import_bag(args);
+    }
+    let root = args
+        .root
+        .as_deref()
+        .expect("clap requires --root for coreutils formats");
+
+    let text = match std::fs::read_to_string(&args.manifest) {
+        Ok(text) => text,
+        Err(e) => {
+            return lockfile_refusal_output(manifest_unreadable(&args.manifest, &e), LOCK_VERSION);
+        }
+    };
+    if !root.is_dir() {
+        return lockfile_refusal_output(LockfileRefusal::root_not_found(root), LOCK_VERSION);
+    }
+
+    let algorithm = format_algorithm(&args.format).expect("clap restricts --format");
+    let mut records = Vec::new();
+    let mut mismatched = Vec::new();
+    let mut seen = BTreeSet::new();
+    for (index, line) in text.lines().enumerate() {
+        let line = line.strip_suffix('\r').unwrap_or(line);
+        if line.trim().is_empty() {
+            continue;
+        }
+        let bad_line = |expected: &str| {
+            refusal_output(crate::refusal::bad_checksum_line(
+                &args.format,
+                index + 1,
+                expected,
+            ))
+        };
+        let Some(parsed) = parse_line(line, algorithm, DIGEST_HEX_LEN) else {
+            return bad_line(&format!("`<{algorithm} hex>  <path>`"));
+        };
+        if !is_member_path(&parsed.path) {
+            return bad_line("a relative path without `..` segments");
+        }
+        if !seen.insert(parsed.path.clone()) {
+            return bad_line("a path not listed on an earlier line");
+        }
+        let (value, drifted) = import_record(&parsed, root);
+        if drifted {
+            mismatched.push(parsed.path);
+        }
+        records.push(InputRecord {
+            line_number: index + 1,
+            value,
+        });
+    }
+    if !mismatched.is_empty() {
+        return lockfile_refusal_output(mismatch_refusal(root, &mismatched), LOCK_VERSION);
+    }
+
+    seal_records(
+        &records,
+        args.dataset_id.as_deref(),
+        args.as_of.as_deref(),
+        args.note.as_deref(),
+    )
+}
+
+fn manifest_unreadable(path: &Path, error: &std::io::Error) -> LockfileRefusal {
+    LockfileRefusal::new(
+        VerifyRefusalCode::Io,
+        format!("cannot read manifest — {error}"),
+        json!({
+            "path": path.display().to_string(),
+            "error": error.to_string(),
+        }),
+    )
+}
+
+/// Run synthesized records through the validator and classifier and seal
+/// the resulting `lock.v0` lockfile.
+fn seal_records(
+    records: &[InputRecord],
+    dataset_id: Option<&str>,
+    as_of: Option<&str>,
+    note: Option<&str>,
+) -> OrchestrationOutput {
+    let mut validator = RecordValidator::default();
+    let mut classifier = RecordClassifier::new(ClassifyOptions::default());
+    for record in records {
+        validator.observe(record);
+        classifier.push(record);
+    }
+
+    match crate::finish_ingest(
+        records.len(),
+        validator,
+        classifier,
+        dataset_id,
+        as_of,
+        note,
+    ) {
+        Ok(ingested) => crate::seal_lockfile(crate::new_lockfile(ingested, LOCK_VERSION, None)),
+        Err(refusal) => refusal,
+    }
+}
+
+/// Run the import subcommand. Returns the exit code.
+///
+/// Exit codes match lock creation: `0` LOCK_CREATED, `1` LOCK_PARTIAL,
+/// `2` refusal.
+pub fn run_import(args: &ImportArgs) -> u8 {
+    let orchestrated = import(args);
+    if !crate::write_payload(args.output.as_deref(), &orchestrated.payload_json) {
+        return 2;
+    }
+    emit_import_witness(args, &orchestrated);
+    orchestrated.outcome.exit_code()
+}
+
+fn emit_import_witness(args: &ImportArgs, orchestrated: &OrchestrationOutput) {
+    if args.no_witness {
+        return;
+    }
+
+    let params = json!({
+        "subcommand": "import",
+        "format": args.format,
+        "root": args.root.as_ref().map(|root| root.display().to_string()),
+        "dataset_id": args.dataset_id,
+        "as_of": args.as_of,
+        "note": args.note,
+    });
+    let inputs = json!([
+        { "path": args.manifest.display().to_string(), "hash": null, "bytes": null }
+    ]);
+
+    crate::witness::append_witness_record(
+        orchestrated.outcome.as_str(),
+        orchestrated.outcome.exit_code(),
+        orchestrated.payload_json.as_bytes(),
+        params,
+        inputs,
+    );
+}
+
+// ---------------------------------------------------------------------------
+// Tests
+// ---------------------------------------------------------------------------
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const A_HEX: &str = "8ed3f6ad685b959ead7022518e1af76cd816f8e8ec7ccdda1ed4018e8f2223f8";
+
+    fn member(path: &str, bytes_hash: &str) -> Member {
+        Member {
+            path: path.to_string(),
+            bytes_hash: bytes_hash.to_string(),
+            size: 5,
+            fingerprint: None,
+            mtime: None,
+            extra: None,
+            hashes: None,
+        }
+    }
+
+    #[test]
+    fn coreutils_formats_use_bytes_hash_or_hashes() {
+        let mut dual = member("b.csv", "blake3:bb");
+        dual.hashes = Some(BTreeMap::from([
+            ("blake3".to_string(), "blake3:bb".to_string()),
+            ("sha256".to_string(), "sha256:aa".to_string()),
+        ]));
+        let members = [member("a.csv", "sha256:11"), dual];
+
+        assert_eq!(
+            render(&members, "sha256sum").unwrap(),
+            "11  a.csv\naa  b.csv\n"
+        );
+        assert_eq!(render(&members, "b3sum").unwrap_err(), ["a.csv"]);
+        assert_eq!(
+            render(&members, "bsd").unwrap(),
+            "SHA256 (a.csv) = 11\nBLAKE3 (b.csv) = bb\n"
+        );
+    }
+
+    #[test]
+    fn csv_and_jsonl_quote_and_sort() {
+        let members = [member("a,\"b\".csv", "sha256:11")];
+        assert_eq!(
+            render(&members, "csv").unwrap(),
+            "path,size,bytes_hash\n\"a,\"\"b\"\".csv\",5,sha256:11\n"
+        );
+        assert_eq!(
+            render(&members, "jsonl").unwrap(),
+            "{\"bytes_hash\":\"sha256:11\",\"path\":\"a,\\\"b\\\".csv\",\"size\":5}\n"
+        );
+    }
+
+    #[test]
+    fn escaped_paths_round_trip() {
+        let members = [member("odd\\name\n.csv", "sha256:11")];
+        let rendered = render(&members, "sha256sum").unwrap();
+        assert_eq!(rendered, "\\11  odd\\\\name\\n.csv\n");
+
+        let parsed = parse_line(rendered.trim_end_matches('\n'), "sha256", 2).unwrap();
+        assert_eq!(parsed.path, "odd\\name\n.csv");
+    }
+
+    #[test]
+    fn parse_line_accepts_text_and_binary_mode() {
+        let text = parse_line(&format!("{}  ./a.csv", A_HEX.to_uppercase()), "sha256", 64);
+        assert_eq!(
+            text,
+            Some(ChecksumLine {
+                digest: format!("sha256:{A_HEX}"),
+                path: "a.csv".to_string(),
+                hashes: BTreeMap::new(),
+            })
+        );
+        let binary = parse_line(&format!("{A_HEX} *a.csv"), "sha256", 64).unwrap();
+        assert_eq!(binary.path, "a.csv");
+
+        assert!(parse_line(&format!("{A_HEX} a.csv"), "sha256", 64).is_none());
+        assert!(parse_line("abc  a.csv", "sha256", 64).is_none());
+        assert!(parse_line(&format!("{A_HEX}  "), "sha256", 64).is_none());
+    }
+
+    #[test]
+    fn member_paths_stay_under_the_root() {
+        assert!(is_member_path("a.csv"));
+        assert!(is_member_path("sub/..data/a.csv"));
+        assert!(!is_member_path("/etc/hostname"));
+        assert!(!is_member_path("C:\\data\\a.csv"));
+        assert!(!is_member_path("../a.csv"));
+        assert!(!is_member_path("sub/../../a.csv"));
+        assert!(!is_member_path("sub\\..\\a.csv"));
+    }
+
+    #[test]
+    fn missing_files_become_skipped_records() {
+        let dir = tempfile::tempdir().unwrap();
+        std::fs::write(dir.path().join("a.csv"), b"alpha").unwrap();
+        let line = |path: &str| ChecksumLine {
+            digest: format!("sha256:{A_HEX}"),
+            path: path.to_string(),
+            hashes: BTreeMap::new(),
+        };
+
+        let (present, mismatched) = import_record(&line("a.csv"), dir.path());
+        assert!(!mismatched);
+        assert_eq!(present["size"], 5);
+        assert_eq!(present["bytes_hash"], format!("sha256:{A_HEX}"));
+
+        std::fs::write(dir.path().join("b.csv"), b"bravo").unwrap();
+        assert!(import_record(&line("b.csv"), dir.path()).1);
+
+        let (absent, mismatched) = import_record(&line("gone.csv"), dir.path());
+        assert!(!mismatched);
+        assert_eq!(absent["_skipped"], true);
+        assert_eq!(absent["_warnings"][0]["code"], "E_IO");
+    }
+}
@@ -1,5 +1,7 @@
+use std::num::NonZeroUsize;
 use std::path::PathBuf;
 
+use clap::builder::PossibleValuesParser;
 use clap::{Parser, Subcommand};
 
 /// Dataset lockfile tool: pins artifacts, fingerprints, and tool versions
@@ -9,7 +11,7 @@ use clap::{Parser, Subcommand};
     name = "lock",
     version,
     about,
-    override_usage = "lock [<INPUT>] [OPTIONS]\n       lock --robot-triage\n       lock capabilities --json\n       lock robot-docs guide\n       lock verify <LOCKFILE> [--root <DIR>] [--json] [--no-witness] [--strict]\n       lock witness <query|last|count> [OPTIONS]\n       lock doctor <health|capabilities|robot-docs> [OPTIONS]"
+    override_usage = "lock [<INPUT>] [OPTIONS]\n       lock --robot-triage\n       lock capabilities --json\n       lock robot-docs guide\n       lock scan <DIR> [--dataset-id <ID>] [--algorithm <sha256|blake3|sha512|sha3-256>] [--exclude <GLOB>]... [--keep-mtime] [--output <PATH>] [OPTIONS]\n       lock verify <LOCKFILE> [--root <DIR> | --archive <FILE>] [--json] [--no-witness] [--strict] [--jobs <N>] [--fast] [--cache <FILE>] [--detect-extra [--exclude <GLOB>]...] [--signature <SIG> --trusted-keys <DIR>]\n       lock diff <OLD> <NEW> [--json] [--no-witness]\n       lock update <BASE> [<INPUT>] [--prune] [--as-of <TS>] [--note <TEXT>] [--keep-mtime] [--keep-fields <F,...> | --keep-all-fields] [--output <PATH>] [--no-witness]\n       lock rehash <LOCKFILE> --root <DIR> --add <ALGORITHM>... [--output <PATH>] [--no-witness]\n       lock export <LOCKFILE> --format <sha256sum|b3sum|bsd|csv|jsonl> [--output <PATH>] [--no-witness]\n       lock export <LOCKFILE> --format bagit --root <BAG_DIR> [--no-witness]\n       lock import <MANIFEST> --format <sha256sum|b3sum> --root <DIR> [--dataset-id <ID>] [--as-of <TS>] [--note <TEXT>] [--output <PATH>] [--no-witness]\n       lock import <BAG_DIR> --format bagit [--dataset-id <ID>] [--as-of <TS>] [--note <TEXT>] [--output <PATH>] [--no-witness]\n       lock stats <LOCKFILE> [--json] [--no-witness]\n       lock log <LOCKFILE> --search-dir <DIR> [--json] [--no-witness]\n       lock prove <LOCKFILE> --path <PATH> [--output <PATH>] [--no-witness]\n       lock verify-proof <PROOF> [--file <PATH>] [--members-root <DIGEST> | --lockfile <PATH>] [--json] [--no-witness]\n       lock sign <LOCKFILE> --key <KEYFILE> [--output <PATH>] [--no-witness]\n       lock keygen --output <PATH> [--signer <NAME>]\n       lock cache <stats|prune|clear> <FILE> [--json]\n       lock witness <query|last|count|find-output|verify|rotate|reindex> [OPTIONS]\n       lock doctor <health|capabilities|robot-docs> [OPTIONS]"
 )]
 pub struct Cli {
     #[command(subcommand)]
@@ -34,6 +36,26 @@ pub struct Cli {
     #[arg(long, value_name = "PATH")]
     pub output: Option<PathBuf>,
 
+    /// Previous lockfile for this dataset; its lock_hash is recorded as parent
+    #[arg(long, value_name = "LOCKFILE")]
+    pub parent: Option<PathBuf>,
+
+    /// Lockfile format to write; lock.v1 adds a members Merkle root
+    #[arg(long, value_name = "VERSION", value_parser = ["lock.v0", "lock.v1"], default_value = "lock.v0")]
+    pub lock_version: String,
+
+    /// Keep each record's mtime on its member so `verify --fast` can skip unchanged files
+    #[arg(long)]
+    pub keep_mtime: bool,
+
+    /// Upstream fields to keep in each member's `extra` object (comma-separated)
+    #[arg(long, value_name = "FIELD", value_delimiter = ',', value_parser = parse_keep_field)]
+    pub keep_fields: Vec<String>,
+
+    /// Keep every upstream field lock does not already consume in `extra`
+    #[arg(long, conflicts_with = "keep_fields")]
+    pub keep_all_fields: bool,
+
     /// Suppress witness ledger recording for this run
     #[arg(long)]
     pub no_witness: bool,
@@ -53,8 +75,32 @@ pub struct Cli {
 
 #[derive(Debug, Subcommand)]
 pub enum Command {
+    /// Walk a directory, hash its files, and write a lockfile without the pipeline
+    Scan(ScanArgs),
     /// Verify lockfile integrity and optionally member content
     Verify(VerifyArgs),
+    /// Compare two lockfiles member by member
+    Diff(DiffArgs),
+    /// Apply new pipeline records to an existing lockfile
+    Update(UpdateArgs),
+    /// Add digests under more algorithms to every member of a lockfile
+    Rehash(RehashArgs),
+    /// Render a lockfile's members as a sha256sum, b3sum, BSD, CSV, or JSONL manifest
+    Export(ExportArgs),
+    /// Build a lockfile from a sha256sum or b3sum manifest
+    Import(ImportArgs),
+    /// Summarize a lockfile's members: sizes, extensions, directories, algorithms, fingerprints, skips
+    Stats(StatsArgs),
+    /// Walk a lockfile's parent chain through lockfiles on disk
+    Log(LogArgs),
+    /// Emit an inclusion proof for one lockfile member
+    Prove(ProveArgs),
+    /// Check an inclusion proof without the full lockfile
+    VerifyProof(VerifyProofArgs),
+    /// Write a detached Ed25519 signature over a lockfile's lock_hash
+    Sign(SignArgs),
+    /// Generate a local Ed25519 signing key pair
+    Keygen(KeygenArgs),
     /// Print the machine-readable lock capability contract
     Capabilities(TopLevelCapabilitiesArgs),
     /// Print paste-ready operating notes for agents
@@ -62,6 +108,11 @@ pub enum Command {
         #[command(subcommand)]
         action: Option<RobotDocsAction>,
     },
+    /// Inspect or clean up a `verify --cache` hash cache
+    Cache {
+        #[command(subcommand)]
+        action: CacheAction,
+    },
     /// Query the witness ledger
     Witness {
         #[command(subcommand)]
@@ -114,6 +165,53 @@ pub enum DoctorAction {
     RobotDocs,
 }
 
+/// Arguments for the `lock scan` subcommand.
+#[derive(Debug, clap::Args)]
+pub struct ScanArgs {
+    /// Directory to lock; member paths are relative to it
+    pub dir: PathBuf,
+
+    /// Logical dataset identifier
+    #[arg(long)]
+    pub dataset_id: Option<String>,
+
+    /// Point-in-time for this lock (ISO 8601)
+    #[arg(long)]
+    pub as_of: Option<String>,
+
+    /// Free-text annotation
+    #[arg(long)]
+    pub note: Option<String>,
+
+    /// Hash algorithm for bytes_hash (cryptographic algorithms only)
+    #[arg(long, value_parser = PossibleValuesParser::new(crate::digest::cryptographic_names()), default_value = "sha256")]
+    pub algorithm: String,
+
+    /// Glob for files to leave out of the lock (repeatable)
+    #[arg(long, value_name = "GLOB")]
+    pub exclude: Vec<String>,
+
+    /// Write lockfile to this path instead of stdout; it is never locked itself
+    #[arg(long, value_name = "PATH")]
+    pub output: Option<PathBuf>,
+
+    /// Previous lockfile for this dataset; its lock_hash is recorded as parent
+    #[arg(long, value_name = "LOCKFILE")]
+    pub parent: Option<PathBuf>,
+
+    /// Lockfile format to write; lock.v1 adds a members Merkle root
+    #[arg(long, value_name = "VERSION", value_parser = ["lock.v0", "lock.v1"], default_value = "lock.v0")]
+    pub lock_version: String,
+
+    /// Record each file's mtime on its member so `verify --fast` can skip unchanged files
+    #[arg(long)]
+    pub keep_mtime: bool,
+
+    /// Suppress witness ledger recording for this run
+    #[arg(long)]
+    pub no_witness: bool,
+}
+
 /// Arguments for the `lock verify` subcommand.
 #[derive(Debug, clap::Args)]
 pub struct VerifyArgs {
@@ -121,9 +219,13 @@ pub struct VerifyArgs {
     pub lockfile: std::path::PathBuf,
 
     /// Root directory for resolving member paths (enables content verification; without this, only self-hash is checked)
-    #[arg(long)]
+    #[arg(long, group = "content")]
     pub root: Option<std::path::PathBuf>,
 
+    /// Verify members against a .tar, .tar.gz/.tgz, or .zip archive instead of a root directory
+    #[arg(long, value_name = "FILE", group = "content", value_parser = parse_archive_path)]
+    pub archive: Option<PathBuf>,
+
     /// Emit structured JSON output instead of human-readable
     #[arg(long)]
     pub json: bool,
@@ -135,6 +237,291 @@ pub struct VerifyArgs {
     /// Promote VERIFY_PARTIAL to VERIFY_FAILED
     #[arg(long)]
     pub strict: bool,
+
+    /// Number of members to hash concurrently with --root
+    #[arg(long, value_name = "N", default_value = "1")]
+    pub jobs: NonZeroUsize,
+
+    /// Also fail on files under --root (or entries in --archive) that the lockfile does not list
+    #[arg(long, requires = "content")]
+    pub detect_extra: bool,
+
+    /// Glob for files --detect-extra should ignore (repeatable)
+    #[arg(long, value_name = "GLOB", requires = "detect_extra")]
+    pub exclude: Vec<String>,
+
+    /// Skip rehashing members whose size and recorded mtime still match the file
+    #[arg(long, requires = "root", conflicts_with = "archive")]
+    pub fast: bool,
+
+    /// Reuse digests from this local hash cache for files whose identity is unchanged, and record new ones
+    #[arg(
+        long,
+        value_name = "FILE",
+        requires = "root",
+        conflicts_with = "archive"
+    )]
+    pub cache: Option<PathBuf>,
+
+    /// Detached signature file (from `lock sign`) to check against the lockfile's lock_hash
+    #[arg(long, value_name = "SIG", requires = "trusted_keys")]
+    pub signature: Option<PathBuf>,
+
+    /// Directory of trusted public key files used to check --signature
+    #[arg(long, value_name = "DIR", requires = "signature")]
+    pub trusted_keys: Option<PathBuf>,
+}
+
+/// Arguments for the `lock diff` subcommand.
+#[derive(Debug, clap::Args)]
+pub struct DiffArgs {
+    /// Baseline lockfile
+    pub old: PathBuf,
+
+    /// Lockfile to compare against the baseline
+    pub new: PathBuf,
+
+    /// Emit structured JSON output instead of human-readable
+    #[arg(long)]
+    pub json: bool,
+
+    /// Suppress witness ledger recording for this run
+    #[arg(long)]
+    pub no_witness: bool,
+}
+
+/// Arguments for the `lock prove` subcommand.
+#[derive(Debug, clap::Args)]
+pub struct ProveArgs {
+    /// Lockfile containing the member (its self-hash must verify)
+    pub lockfile: PathBuf,
+
+    /// Member path to prove, as recorded in the lockfile
+    #[arg(long, value_name = "PATH")]
+    pub path: String,
+
+    /// Write the proof to this path instead of stdout
+    #[arg(long, value_name = "PATH")]
+    pub output: Option<PathBuf>,
+
+    /// Suppress witness ledger recording for this run
+    #[arg(long)]
+    pub no_witness: bool,
+}
+
+/// Arguments for the `lock verify-proof` subcommand.
+#[derive(Debug, clap::Args)]
+pub struct VerifyProofArgs {
+    /// Proof written by `lock prove`
+    pub proof: PathBuf,
+
+    /// Also check this file's size and bytes_hash against the proven member
+    #[arg(long, value_name = "PATH")]
+    pub file: Option<PathBuf>,
+
+    /// Require the proof's members_root to equal this trusted root
+    #[arg(long, value_name = "DIGEST", value_parser = parse_members_root)]
+    pub members_root: Option<String>,
+
+    /// Require the proof to match this lockfile's lock_hash and members_root
+    #[arg(long, value_name = "PATH", conflicts_with = "members_root")]
+    pub lockfile: Option<PathBuf>,
+
+    /// Emit structured JSON output instead of human-readable
+    #[arg(long)]
+    pub json: bool,
+
+    /// Suppress witness ledger recording for this run
+    #[arg(long)]
+    pub no_witness: bool,
+}
+
+/// Arguments for the `lock sign` subcommand.
+#[derive(Debug, clap::Args)]
+pub struct SignArgs {
+    /// Lockfile to sign
+    pub lockfile: PathBuf,
+
+    /// Secret key file written by `lock keygen`
+    #[arg(long, value_name = "KEYFILE")]
+    pub key: PathBuf,
+
+    /// Write the signature envelope to this path instead of stdout
+    #[arg(long, value_name = "PATH")]
+    pub output: Option<PathBuf>,
+
+    /// Suppress witness ledger recording for this run
+    #[arg(long)]
+    pub no_witness: bool,
+}
+
+/// Arguments for the `lock update` subcommand.
+#[derive(Debug, clap::Args)]
+pub struct UpdateArgs {
+    /// Lockfile to update (its self-hash must verify)
+    pub base: PathBuf,
+
+    /// JSONL manifest with the new records (default: stdin)
+    pub input: Option<PathBuf>,
+
+    /// Remove members and skipped entries whose paths are absent from the input
+    #[arg(long)]
+    pub prune: bool,
+
+    /// Replace the base lockfile's as_of (ISO 8601)
+    #[arg(long)]
+    pub as_of: Option<String>,
+
+    /// Replace the base lockfile's note
+    #[arg(long)]
+    pub note: Option<String>,
+
+    /// Write the updated lockfile to this path instead of stdout
+    #[arg(long, value_name = "PATH")]
+    pub output: Option<PathBuf>,
+
+    /// Keep each new record's mtime on its member so `verify --fast` can skip unchanged files
+    #[arg(long)]
+    pub keep_mtime: bool,
+
+    /// Upstream fields to keep in each member's `extra` object (comma-separated)
+    #[arg(long, value_name = "FIELD", value_delimiter = ',', value_parser = parse_keep_field)]
+    pub keep_fields: Vec<String>,
+
+    /// Keep every upstream field lock does not already consume in `extra`
+    #[arg(long, conflicts_with = "keep_fields")]
+    pub keep_all_fields: bool,
+
+    /// Suppress witness ledger recording for this run
+    #[arg(long)]
+    pub no_witness: bool,
+}
+
+/// Arguments for the `lock rehash` subcommand.
+#[derive(Debug, clap::Args)]
+pub struct RehashArgs {
+    /// Lockfile to rehash (its self-hash must verify)
+    pub lockfile: PathBuf,
+
+    /// Directory the member paths are relative to
+    #[arg(long, value_name = "DIR")]
+    pub root: PathBuf,
+
+    /// Algorithm to record in each member's `hashes` (repeatable or comma-separated)
+    #[arg(long, required = true, value_name = "ALGORITHM", value_delimiter = ',', value_parser = PossibleValuesParser::new(crate::digest::names()))]
+    pub add: Vec<String>,
+
+    /// Write the rehashed lockfile to this path instead of stdout
+    #[arg(long, value_name = "PATH")]
+    pub output: Option<PathBuf>,
+
+    /// Suppress witness ledger recording for this run
+    #[arg(long)]
+    pub no_witness: bool,
+}
+
+/// Arguments for the `lock export` subcommand.
+#[derive(Debug, clap::Args)]
+pub struct ExportArgs {
+    /// Lockfile to export (its self-hash must verify)
+    pub lockfile: PathBuf,
+
+    /// Manifest format to write
+    #[arg(long, value_parser = PossibleValuesParser::new(crate::checksums::EXPORT_FORMATS))]
+    pub format: String,
+
+    /// Bag directory to write BagIt tag files into (bagit only); the payload belongs under its data/
+    #[arg(long, value_name = "DIR", required_if_eq("format", "bagit"))]
+    pub root: Option<PathBuf>,
+
+    /// Write the manifest to this path instead of stdout
+    #[arg(long, value_name = "PATH", conflicts_with = "root")]
+    pub output: Option<PathBuf>,
+
+    /// Suppress witness ledger recording for this run
+    #[arg(long)]
+    pub no_witness: bool,
+}
+
+/// Arguments for the `lock import` subcommand.
+#[derive(Debug, clap::Args)]
+pub struct ImportArgs {
+    /// Checksum manifest to import, or the bag directory for bagit
+    pub manifest: PathBuf,
+
+    /// Manifest format to read
+    #[arg(long, value_parser = PossibleValuesParser::new(crate::checksums::IMPORT_FORMATS))]
+    pub format: String,
+
+    /// Directory the manifest paths are relative to; listed files are hashed there (refused with bagit)
+    #[arg(long, value_name = "DIR", required_if_eq_any([("format", "sha256sum"), ("format", "b3sum")]))]
+    pub root: Option<PathBuf>,
+
+    /// Logical dataset identifier
+    #[arg(long)]
+    pub dataset_id: Option<String>,
+
+    /// Point-in-time for this lock (ISO 8601)
+    #[arg(long)]
+    pub as_of: Option<String>,
+
+    /// Free-text annotation
+    #[arg(long)]
+    pub note: Option<String>,
+
+    /// Write lockfile to this path instead of stdout
+    #[arg(long, value_name = "PATH")]
+    pub output: Option<PathBuf>,
+
+    /// Suppress witness ledger recording for this run
+    #[arg(long)]
+    pub no_witness: bool,
+}
+
+/// Arguments for the `lock stats` subcommand.
+#[derive(Debug, clap::Args)]
+pub struct StatsArgs {
+    /// Lockfile to summarize (its self-hash must verify)
+    pub lockfile: PathBuf,
+
+    /// Emit structured JSON output instead of human-readable
+    #[arg(long)]
+    pub json: bool,
+
+    /// Suppress witness ledger recording for this run
+    #[arg(long)]
+    pub no_witness: bool,
+}
+
+/// Arguments for the `lock log` subcommand.
+#[derive(Debug, clap::Args)]
+pub struct LogArgs {
+    /// Lockfile to start the history from
+    pub lockfile: PathBuf,
+
+    /// Directory searched recursively for ancestor lockfiles
+    #[arg(long, value_name = "DIR")]
+    pub search_dir: PathBuf,
+
+    /// Emit structured JSON output instead of human-readable
+    #[arg(long)]
+    pub json: bool,
+
+    /// Suppress witness ledger recording for this run
+    #[arg(long)]
+    pub no_witness: bool,
+}
+
+/// Arguments for the `lock keygen` subcommand.
+#[derive(Debug, clap::Args)]
+pub struct KeygenArgs {
+    /// Secret key path; the public key is written to `<PATH>.pub`
+    #[arg(long, value_name = "PATH")]
+    pub output: PathBuf,
+
+    /// Human-readable signer name recorded in the key and in signatures
+    #[arg(long, value_name = "NAME")]
+    pub signer: Option<String>,
 }
 
 /// Witness query filter flags shared by `query` and `count` subcommands.
@@ -159,6 +546,30 @@ pub struct WitnessFilters {
     /// Filter by input hash substring
     #[arg(long)]
     pub input_hash: Option<String>,
+
+    /// Filter by params.dataset_id
+    #[arg(long, value_name = "ID")]
+    pub dataset_id: Option<String>,
+
+    /// Filter by lock subcommand (lock for the default run, verify, scan, ...)
+    #[arg(long, value_name = "NAME")]
+    pub subcommand: Option<String>,
+
+    /// Filter by recorded exit code
+    #[arg(long, value_name = "CODE")]
+    pub exit_code: Option<i32>,
+
+    /// Filter by exact output hash (e.g. blake3:...)
+    #[arg(long, value_name = "HASH")]
+    pub output_hash: Option<String>,
+
+    /// Require params.KEY to equal VALUE (JSON or plain string); repeatable
+    #[arg(long = "param", value_name = "KEY=VALUE", value_parser = parse_param_filter)]
+    pub params: Vec<(String, String)>,
+
+    /// Filter by a lockfile among the run's inputs, matched on trailing path components
+    #[arg(long, value_name = "PATH")]
+    pub lockfile: Option<PathBuf>,
 }
 
 #[derive(Debug, Subcommand)]
@@ -172,6 +583,14 @@ pub enum WitnessAction {
         #[arg(long, default_value_t = 20)]
         limit: usize,
 
+        /// Number of matching records to skip before returning any
+        #[arg(long, default_value_t = 0)]
+        offset: usize,
+
+        /// Newest first (desc) or oldest first (asc)
+        #[arg(long, value_parser = ["asc", "desc"], default_value = "desc")]
+        order: String,
+
         /// Output as JSON instead of human-readable
         #[arg(long)]
         json: bool,
@@ -193,6 +612,114 @@ pub enum WitnessAction {
         #[arg(long)]
         json: bool,
     },
+
+    /// Find the runs whose stdout was this artifact (by BLAKE3 output_hash)
+    #[command(alias = "verify-output")]
+    FindOutput {
+        /// Lockfile or other artifact a lock run emitted
+        artifact: PathBuf,
+
+        /// Output as JSON
+        #[arg(long)]
+        json: bool,
+    },
+
+    /// Check the ledger's prev_id hash chain and recompute record ids
+    Verify {
+        /// Output as JSON
+        #[arg(long)]
+        json: bool,
+    },
+
+    /// Seal the live ledger into a compressed, hash-linked segment
+    Rotate {
+        /// Only rotate once the live ledger is at least this many bytes
+        #[arg(long, value_name = "BYTES")]
+        max_bytes: Option<u64>,
+
+        /// Only rotate once the oldest live record is at least this many days old
+        #[arg(long, value_name = "DAYS")]
+        max_age_days: Option<u64>,
+
+        /// Output as JSON
+        #[arg(long)]
+        json: bool,
+    },
+
+    /// Build or rebuild the sidecar index that speeds up query and count
+    Reindex {
+        /// Output as JSON
+        #[arg(long)]
+        json: bool,
+    },
+}
+
+#[derive(Debug, Subcommand)]
+pub enum CacheAction {
+    /// Show how many digests the cache holds
+    Stats {
+        /// Cache file written by `lock verify --cache`
+        cache: PathBuf,
+
+        /// Output as JSON
+        #[arg(long)]
+        json: bool,
+    },
+
+    /// Drop entries for files that are gone or have changed
+    Prune {
+        /// Cache file written by `lock verify --cache`
+        cache: PathBuf,
+
+        /// Output as JSON
+        #[arg(long)]
+        json: bool,
+    },
+
+    /// Delete the cache file
+    Clear {
+        /// Cache file written by `lock verify --cache`
+        cache: PathBuf,
+
+        /// Output as JSON
+        #[arg(long)]
+        json: bool,
+    },
+}
+
+/// Reject `--keep-fields` names that are already first-class member data.
+fn parse_keep_field(field: &str) -> Result<String, String> {
+    if field.is_empty() {
+        Err("field name must not be empty".to_owned())
+    } else if field.starts_with('_') || crate::lockfile::CONSUMED_FIELDS.contains(&field) {
+        Err(format!("`{field}` is not a passthrough field"))
+    } else {
+        Ok(field.to_owned())
+    }
+}
+
+/// Accept only `sha256:<64 hex>` roots, the form `members_root` takes.
+fn parse_members_root(root: &str) -> Result<String, String> {
+    crate::lockfile::merkle::parse_digest(root)
+        .map(|digest| crate::lockfile::merkle::format_digest(&digest))
+        .ok_or_else(|| "expected sha256:<64 hex>".to_owned())
+}
+
+/// Accept only archive names whose format `verify --archive` can read.
+fn parse_archive_path(path: &str) -> Result<PathBuf, String> {
+    let path = PathBuf::from(path);
+    match crate::verify::archive::ArchiveFormat::from_path(&path) {
+        Some(_) => Ok(path),
+        None => Err("unsupported archive format; expected .tar, .tar.gz, .tgz, or .zip".to_owned()),
+    }
+}
+
+/// Split a `--param KEY=VALUE` filter at the first `=`.
+fn parse_param_filter(param: &str) -> Result<(String, String), String> {
+    match param.split_once('=') {
+        Some((key, value)) if !key.is_empty() => Ok((key.to_owned(), value.to_owned())),
+        _ => Err("expected KEY=VALUE".to_owned()),
+    }
 }
 
 /// Parse CLI arguments and dispatch to the appropriate handler.
@@ -209,7 +736,7 @@ pub fn run() -> u8 {
         return dispatch_describe();
     }
     if cli.schema {
-        return dispatch_schema();
+        return dispatch_schema(&cli.lock_version);
     }
     if cli.robot_triage {
         return crate::doctor::dispatch_robot_triage();
@@ -217,13 +744,26 @@ pub fn run() -> u8 {
 
     // Subcommands dispatch after display-mode short-circuits.
     match &cli.command {
+        Some(Command::Scan(args)) => return crate::scan::run_scan(args),
         Some(Command::Verify(args)) => return dispatch_verify(args),
+        Some(Command::Diff(args)) => return crate::diff::run_diff(args),
+        Some(Command::Update(args)) => return crate::update::run_update(args),
+        Some(Command::Rehash(args)) => return crate::rehash::run_rehash(args),
+        Some(Command::Export(args)) => return crate::checksums::run_export(args),
+        Some(Command::Import(args)) => return crate::checksums::run_import(args),
+        Some(Command::Stats(args)) => return crate::stats::run_stats(args),
+        Some(Command::Log(args)) => return crate::log::run_log(args),
+        Some(Command::Prove(args)) => return crate::proof::run_prove(args),
+        Some(Command::VerifyProof(args)) => return crate::proof::run_verify_proof(args),
+        Some(Command::Sign(args)) => return crate::signing::run_sign(args),
+        Some(Command::Keygen(args)) => return crate::signing::run_keygen(args),
         Some(Command::Capabilities(args)) => {
             return crate::doctor::dispatch_capabilities(args.json);
         }
         Some(Command::RobotDocs { action }) => {
             return crate::doctor::dispatch_robot_docs(action.as_ref());
         }
+        Some(Command::Cache { action }) => return crate::cache::dispatch(action),
         Some(Command::Witness { action }) => return dispatch_witness(action),
         Some(Command::Doctor {
             robot_triage,
@@ -251,10 +791,22 @@ fn dispatch_witness(action: &WitnessAction) -> u8 {
         WitnessAction::Query {
             filters,
             limit,
+            offset,
+            order,
             json,
-        } => witness::dispatch_query(filters, *limit, *json),
+        } => witness::dispatch_query(filters, *limit, *offset, order == "asc", *json),
         WitnessAction::Last { json } => witness::dispatch_last(*json),
         WitnessAction::Count { filters, json } => witness::dispatch_count(filters, *json),
+        WitnessAction::FindOutput { artifact, json } => {
+            witness::dispatch_find_output(artifact, *json)
+        }
+        WitnessAction::Verify { json } => witness::dispatch_verify(*json),
+        WitnessAction::Rotate {
+            max_bytes,
+            max_age_days,
+            json,
+        } => witness::dispatch_rotate(*max_bytes, *max_age_days, *json),
+        WitnessAction::Reindex { json } => witness::dispatch_reindex(*json),
     }
 }
 
@@ -264,15 +816,22 @@ const OPERATOR_JSON: &str = include_str!("../../operator.json");
 /// Compiled-in lock.v0 JSON Schema, embedded at build time.
 const LOCK_SCHEMA: &str = include_str!("../../schemas/lock-v0.schema.json");
 
+/// Compiled-in lock.v1 JSON Schema, embedded at build time.
+const LOCK_V1_SCHEMA: &str = include_str!("../../schemas/lock-v1.schema.json");
+
 /// Emit the compiled-in operator.json to stdout and exit 0.
 fn dispatch_describe() -> u8 {
     print!("{OPERATOR_JSON}");
     0
 }
 
-/// Emit the lock.v0 JSON Schema to stdout and exit 0.
-fn dispatch_schema() -> u8 {
-    print!("{LOCK_SCHEMA}");
+/// Emit the JSON Schema for `--lock-version` (lock.v0 by default) and exit 0.
+fn dispatch_schema(lock_version: &str) -> u8 {
+    if lock_version == crate::refusal::LOCK_V1_VERSION {
+        print!("{LOCK_V1_SCHEMA}");
+    } else {
+        print!("{LOCK_SCHEMA}");
+    }
     0
 }
 
@@ -409,10 +968,14 @@ mod tests {
                 WitnessAction::Query {
                     filters,
                     limit,
+                    offset,
+                    order,
                     json,
                 },
         }) = &cli.command
         {
+            assert_eq!(*offset, 0);
+            assert_eq!(order, "desc");
             assert_eq!(filters.tool.as_deref(), Some("lock"));
             assert_eq!(filters.since.as_deref(), Some("2026-01-01T00:00:00Z"));
             assert_eq!(filters.outcome.as_deref(), Some("LOCK_CREATED"));
@@ -442,6 +1005,71 @@ mod tests {
         }
     }
 
+    #[test]
+    fn parse_witness_find_output() {
+        for name in ["find-output", "verify-output"] {
+            let cli = Cli::try_parse_from(["lock", "witness", name, "dec.lock.json"]).unwrap();
+            match &cli.command {
+                Some(Command::Witness {
+                    action: WitnessAction::FindOutput { artifact, json },
+                }) => {
+                    assert_eq!(artifact, &PathBuf::from("dec.lock.json"));
+                    assert!(!json);
+                }
+                other => panic!("expected Witness/FindOutput, got {other:?}"),
+            }
+        }
+        assert!(Cli::try_parse_from(["lock", "witness", "find-output"]).is_err());
+    }
+
+    #[test]
+    fn parse_witness_rotate() {
+        let cli =
+            Cli::try_parse_from(["lock", "witness", "rotate", "--max-age-days", "30"]).unwrap();
+        match &cli.command {
+            Some(Command::Witness {
+                action:
+                    WitnessAction::Rotate {
+                        max_bytes,
+                        max_age_days,
+                        json,
+                    },
+            }) => {
+                assert_eq!(*max_bytes, None);
+                assert_eq!(*max_age_days, Some(30));
+                assert!(!json);
+            }
+            other => panic!("expected Witness/Rotate, got {other:?}"),
+        }
+        assert!(Cli::try_parse_from(["lock", "witness", "rotate", "--max-bytes", "lots"]).is_err());
+    }
+
+    #[test]
+    fn parse_witness_reindex() {
+        let cli = Cli::try_parse_from(["lock", "witness", "reindex", "--json"]).unwrap();
+        assert!(matches!(
+            &cli.command,
+            Some(Command::Witness {
+                action: WitnessAction::Reindex { json: true },
+            })
+        ));
+    }
+
+    #[test]
+    fn parse_witness_verify() {
+        let cli = Cli::try_parse_from(["lock", "witness", "verify", "--json"]).unwrap();
+        assert!(
+            matches!(
+                &cli.command,
+                Some(Command::Witness {
+                    action: WitnessAction::Verify { json: true },
+                })
+            ),
+            "expected Witness/Verify, got {:?}",
+            cli.command
+        );
+    }
+
     #[test]
     fn parse_witness_count() {
         let cli = Cli::try_parse_from([
@@ -475,6 +1103,60 @@ mod tests {
         }
     }
 
+    #[test]
+    fn parse_witness_query_rich_filters_and_paging() {
+        let cli = Cli::try_parse_from([
+            "lock",
+            "witness",
+            "query",
+            "--dataset-id",
+            "raw-dec",
+            "--subcommand",
+            "verify",
+            "--exit-code",
+            "1",
+            "--param",
+            "strict=true",
+            "--param",
+            "note=a=b",
+            "--lockfile",
+            "dec.lock.json",
+            "--order",
+            "asc",
+            "--offset",
+            "5",
+        ])
+        .unwrap();
+        match &cli.command {
+            Some(Command::Witness {
+                action:
+                    WitnessAction::Query {
+                        filters,
+                        offset,
+                        order,
+                        ..
+                    },
+            }) => {
+                assert_eq!(filters.dataset_id.as_deref(), Some("raw-dec"));
+                assert_eq!(filters.subcommand.as_deref(), Some("verify"));
+                assert_eq!(filters.exit_code, Some(1));
+                assert_eq!(
+                    filters.params,
+                    [
+                        ("strict".to_string(), "true".to_string()),
+                        ("note".to_string(), "a=b".to_string()),
+                    ]
+                );
+                assert_eq!(filters.lockfile, Some(PathBuf::from("dec.lock.json")));
+                assert_eq!(*offset, 5);
+                assert_eq!(order, "asc");
+            }
+            other => panic!("expected Witness/Query, got {other:?}"),
+        }
+        assert!(Cli::try_parse_from(["lock", "witness", "query", "--param", "strict"]).is_err());
+        assert!(Cli::try_parse_from(["lock", "witness", "query", "--order", "up"]).is_err());
+    }
+
     #[test]
     fn parse_witness_query_with_until() {
         let cli = Cli::try_parse_from([
@@ -503,6 +1185,54 @@ mod tests {
         }
     }
 
+    #[test]
+    fn parse_scan_defaults_and_flags() {
+        let cli = Cli::try_parse_from(["lock", "scan", "/data/dec"]).unwrap();
+        match &cli.command {
+            Some(Command::Scan(args)) => {
+                assert_eq!(args.dir, PathBuf::from("/data/dec"));
+                assert_eq!(args.algorithm, "sha256");
+                assert_eq!(args.lock_version, "lock.v0");
+                assert!(args.exclude.is_empty());
+                assert!(args.output.is_none());
+                assert!(!args.keep_mtime);
+            }
+            other => panic!("expected Scan, got {other:?}"),
+        }
+
+        let cli = Cli::try_parse_from([
+            "lock",
+            "scan",
+            "/data/dec",
+            "--dataset-id",
+            "raw-dec",
+            "--algorithm",
+            "blake3",
+            "--exclude",
+            "*.tmp",
+            "--keep-mtime",
+            "--output",
+            "dec.lock.json",
+            "--no-witness",
+        ])
+        .unwrap();
+        match &cli.command {
+            Some(Command::Scan(args)) => {
+                assert_eq!(args.dataset_id.as_deref(), Some("raw-dec"));
+                assert_eq!(args.algorithm, "blake3");
+                assert_eq!(args.exclude, ["*.tmp"]);
+                assert!(args.keep_mtime);
+                assert_eq!(args.output, Some(PathBuf::from("dec.lock.json")));
+                assert!(args.no_witness);
+            }
+            other => panic!("expected Scan, got {other:?}"),
+        }
+
+        assert!(Cli::try_parse_from(["lock", "scan", "/data", "--algorithm", "md5"]).is_err());
+        assert!(Cli::try_parse_from(["lock", "scan", "/data", "--algorithm", "xxh3"]).is_err());
+        assert!(Cli::try_parse_from(["lock", "scan", "/data", "--algorithm", "sha3-256"]).is_ok());
+    }
+
     #[test]
     fn parse_verify_lockfile_only() {
         let cli = Cli::try_parse_from(["lock", "verify", "dec.lock.json"]).unwrap();
@@ -544,6 +1274,558 @@ mod tests {
             assert!(args.json);
             assert!(args.no_witness);
             assert!(args.strict);
+            assert_eq!(args.jobs.get(), 1);
+        }
+    }
+
+    #[test]
+    fn parse_verify_jobs() {
+        let cli = Cli::try_parse_from(["lock", "verify", "dec.lock.json", "--jobs", "8"]).unwrap();
+        match &cli.command {
+            Some(Command::Verify(args)) => assert_eq!(args.jobs.get(), 8),
+            other => panic!("expected Verify, got {other:?}"),
+        }
+        assert!(Cli::try_parse_from(["lock", "verify", "dec.lock.json", "--jobs", "0"]).is_err());
+    }
+
+    #[test]
+    fn parse_verify_detect_extra() {
+        assert!(
+            Cli::try_parse_from(["lock", "verify", "dec.lock.json", "--detect-extra"]).is_err()
+        );
+        assert!(
+            Cli::try_parse_from(["lock", "verify", "dec.lock.json", "--exclude", "*.tmp"]).is_err()
+        );
+
+        let cli = Cli::try_parse_from([
+            "lock",
+            "verify",
+            "dec.lock.json",
+            "--root",
+            "/data/dec",
+            "--detect-extra",
+            "--exclude",
+            "*.tmp",
+            "--exclude",
+            "logs/**",
+        ])
+        .unwrap();
+        match &cli.command {
+            Some(Command::Verify(args)) => {
+                assert!(args.detect_extra);
+                assert_eq!(args.exclude, ["*.tmp", "logs/**"]);
+            }
+            other => panic!("expected Verify, got {other:?}"),
+        }
+    }
+
+    #[test]
+    fn parse_verify_archive() {
+        let parse = |args: &[&str]| {
+            Cli::try_parse_from(["lock", "verify", "dec.lock.json"].iter().chain(args))
+        };
+        assert!(parse(&["--archive", "delivery.rar"]).is_err());
+        assert!(parse(&["--archive", "delivery.zip", "--root", "/data/dec"]).is_err());
+        assert!(parse(&["--archive", "delivery.zip", "--fast"]).is_err());
+        assert!(parse(&["--archive", "delivery.zip", "--cache", "h.cache"]).is_err());
+
+        let cli = parse(&["--archive", "delivery.tar.gz", "--detect-extra"]).unwrap();
+        match &cli.command {
+            Some(Command::Verify(args)) => {
+                assert_eq!(args.archive, Some(PathBuf::from("delivery.tar.gz")));
+                assert_eq!(args.root, None);
+                assert!(args.detect_extra);
+            }
+            other => panic!("expected Verify, got {other:?}"),
+        }
+    }
+
+    #[test]
+    fn parse_verify_fast_requires_root() {
+        assert!(Cli::try_parse_from(["lock", "verify", "dec.lock.json", "--fast"]).is_err());
+
+        let cli = Cli::try_parse_from([
+            "lock",
+            "verify",
+            "dec.lock.json",
+            "--root",
+            "/data/dec",
+            "--fast",
+        ])
+        .unwrap();
+        match &cli.command {
+            Some(Command::Verify(args)) => assert!(args.fast),
+            other => panic!("expected Verify, got {other:?}"),
+        }
+    }
+
+    #[test]
+    fn parse_verify_cache_requires_root() {
+        assert!(
+            Cli::try_parse_from(["lock", "verify", "dec.lock.json", "--cache", "h.cache"]).is_err()
+        );
+
+        let cli = Cli::try_parse_from([
+            "lock",
+            "verify",
+            "dec.lock.json",
+            "--root",
+            "/data/dec",
+            "--cache",
+            "h.cache",
+        ])
+        .unwrap();
+        match &cli.command {
+            Some(Command::Verify(args)) => {
+                assert_eq!(args.cache, Some(PathBuf::from("h.cache")));
+            }
+            other => panic!("expected Verify, got {other:?}"),
+        }
+    }
+
+    #[test]
+    fn parse_cache_actions() {
+        let cli = Cli::try_parse_from(["lock", "cache", "stats", "h.cache", "--json"]).unwrap();
+        match &cli.command {
+            Some(Command::Cache {
+                action: CacheAction::Stats { cache, json },
+            }) => {
+                assert_eq!(cache, &PathBuf::from("h.cache"));
+                assert!(json);
+            }
+            other => panic!("expected Cache/Stats, got {other:?}"),
+        }
+
+        let cli = Cli::try_parse_from(["lock", "cache", "prune", "h.cache"]).unwrap();
+        assert!(matches!(
+            &cli.command,
+            Some(Command::Cache {
+                action: CacheAction::Prune { json: false, .. },
+            })
+        ));
+
+        let cli = Cli::try_parse_from(["lock", "cache", "clear", "h.cache", "--json"]).unwrap();
+        assert!(matches!(
+            &cli.command,
+            Some(Command::Cache {
+                action: CacheAction::Clear { json: true, .. },
+            })
+        ));
+
+        assert!(Cli::try_parse_from(["lock", "cache", "stats"]).is_err());
+        assert!(Cli::try_parse_from(["lock", "cache", "clear"]).is_err());
+    }
+
+    #[test]
+    fn parse_verify_signature_requires_trusted_keys() {
+        assert!(
+            Cli::try_parse_from(["lock", "verify", "dec.lock.json", "--signature", "dec.sig"])
+                .is_err()
+        );
+        assert!(
+            Cli::try_parse_from(["lock", "verify", "dec.lock.json", "--trusted-keys", "keys"])
+                .is_err()
+        );
+
+        let cli = Cli::try_parse_from([
+            "lock",
+            "verify",
+            "dec.lock.json",
+            "--signature",
+            "dec.sig",
+            "--trusted-keys",
+            "keys",
+        ])
+        .unwrap();
+        match &cli.command {
+            Some(Command::Verify(args)) => {
+                assert_eq!(args.signature, Some(PathBuf::from("dec.sig")));
+                assert_eq!(args.trusted_keys, Some(PathBuf::from("keys")));
+            }
+            other => panic!("expected Verify, got {other:?}"),
+        }
+    }
+
+    #[test]
+    fn parse_diff_positional_and_flags() {
+        let cli = Cli::try_parse_from([
+            "lock",
+            "diff",
+            "nov.lock.json",
+            "dec.lock.json",
+            "--json",
+            "--no-witness",
+        ])
+        .unwrap();
+        match &cli.command {
+            Some(Command::Diff(args)) => {
+                assert_eq!(args.old, PathBuf::from("nov.lock.json"));
+                assert_eq!(args.new, PathBuf::from("dec.lock.json"));
+                assert!(args.json);
+                assert!(args.no_witness);
+            }
+            other => panic!("expected Diff, got {other:?}"),
+        }
+    }
+
+    #[test]
+    fn parse_diff_requires_two_lockfiles() {
+        assert!(Cli::try_parse_from(["lock", "diff", "nov.lock.json"]).is_err());
+    }
+
+    #[test]
+    fn parse_update_base_input_and_flags() {
+        let cli = Cli::try_parse_from([
+            "lock",
+            "update",
+            "base.lock.json",
+            "new.jsonl",
+            "--prune",
+            "--as-of",
+            "2026-01-02",
+            "--keep-mtime",
+            "--keep-fields",
+            "mime_guess,owner",
+            "--output",
+            "next.lock.json",
+        ])
+        .unwrap();
+        match &cli.command {
+            Some(Command::Update(args)) => {
+                assert_eq!(args.base, PathBuf::from("base.lock.json"));
+                assert_eq!(args.input, Some(PathBuf::from("new.jsonl")));
+                assert!(args.prune);
+                assert_eq!(args.as_of.as_deref(), Some("2026-01-02"));
+                assert_eq!(args.note, None);
+                assert!(args.keep_mtime);
+                assert_eq!(args.keep_fields, ["mime_guess", "owner"]);
+                assert!(!args.keep_all_fields);
+                assert_eq!(args.output, Some(PathBuf::from("next.lock.json")));
+            }
+            other => panic!("expected Update, got {other:?}"),
+        }
+    }
+
+    #[test]
+    fn parse_rehash_requires_root_and_algorithm() {
+        assert!(
+            Cli::try_parse_from(["lock", "rehash", "dec.lock.json", "--root", "data"]).is_err()
+        );
+        assert!(
+            Cli::try_parse_from(["lock", "rehash", "dec.lock.json", "--add", "blake3"]).is_err()
+        );
+        assert!(
+            Cli::try_parse_from([
+                "lock",
+                "rehash",
+                "dec.lock.json",
+                "--root",
+                "data",
+                "--add",
+                "md5"
+            ])
+            .is_err()
+        );
+
+        let cli = Cli::try_parse_from([
+            "lock",
+            "rehash",
+            "dec.lock.json",
+            "--root",
+            "data",
+            "--add",
+            "blake3,sha256",
+            "--output",
+            "next.lock.json",
+        ])
+        .unwrap();
+        match &cli.command {
+            Some(Command::Rehash(args)) => {
+                assert_eq!(args.lockfile, PathBuf::from("dec.lock.json"));
+                assert_eq!(args.root, PathBuf::from("data"));
+                assert_eq!(args.add, ["blake3", "sha256"]);
+                assert_eq!(args.output, Some(PathBuf::from("next.lock.json")));
+                assert!(!args.no_witness);
+            }
+            other => panic!("expected Rehash, got {other:?}"),
+        }
+    }
+
+    #[test]
+    fn parse_export_and_import_formats() {
+        assert!(Cli::try_parse_from(["lock", "export", "dec.lock.json"]).is_err());
+        assert!(
+            Cli::try_parse_from(["lock", "export", "dec.lock.json", "--format", "md5sum"]).is_err()
+        );
+        let cli =
+            Cli::try_parse_from(["lock", "export", "dec.lock.json", "--format", "bsd"]).unwrap();
+        match &cli.command {
+            Some(Command::Export(args)) => {
+                assert_eq!(args.lockfile, PathBuf::from("dec.lock.json"));
+                assert_eq!(args.format, "bsd");
+                assert_eq!(args.output, None);
+            }
+            other => panic!("expected Export, got {other:?}"),
+        }
+
+        assert!(
+            Cli::try_parse_from(["lock", "import", "SHA256SUMS", "--format", "sha256sum"]).is_err()
+        );
+        assert!(
+            Cli::try_parse_from([
+                "lock",
+                "import",
+                "SHA256SUMS",
+                "--format",
+                "csv",
+                "--root",
+                "data"
+            ])
+            .is_err()
+        );
+        let cli = Cli::try_parse_from([
+            "lock",
+            "import",
+            "SHA256SUMS",
+            "--format",
+            "sha256sum",
+            "--root",
+            "data",
+            "--dataset-id",
+            "legacy",
+        ])
+        .unwrap();
+        match &cli.command {
+            Some(Command::Import(args)) => {
+                assert_eq!(args.manifest, PathBuf::from("SHA256SUMS"));
+                assert_eq!(args.format, "sha256sum");
+                assert_eq!(args.root, Some(PathBuf::from("data")));
+                assert_eq!(args.dataset_id.as_deref(), Some("legacy"));
+            }
+            other => panic!("expected Import, got {other:?}"),
+        }
+    }
+
+    #[test]
+    fn parse_bagit_root_rules() {
+        let export = |args: &[&str]| {
+            Cli::try_parse_from(
+                ["lock", "export", "dec.lock.json", "--format"]
+                    .iter()
+                    .chain(args),
+            )
+        };
+        assert!(export(&["bagit"]).is_err());
+        assert!(export(&["bagit", "--root", "bag", "--output", "x"]).is_err());
+        match &export(&["bagit", "--root", "bag"]).unwrap().command {
+            Some(Command::Export(args)) => assert_eq!(args.root, Some(PathBuf::from("bag"))),
+            other => panic!("expected Export, got {other:?}"),
+        }
+
+        let cli = Cli::try_parse_from(["lock", "import", "bag", "--format", "bagit"]).unwrap();
+        match &cli.command {
+            Some(Command::Import(args)) => {
+                assert_eq!(args.manifest, PathBuf::from("bag"));
+                assert_eq!(args.root, None);
+            }
+            other => panic!("expected Import, got {other:?}"),
+        }
+    }
+
+    #[test]
+    fn parse_stats() {
+        assert!(Cli::try_parse_from(["lock", "stats"]).is_err());
+        let cli = Cli::try_parse_from(["lock", "stats", "dec.lock.json", "--json"]).unwrap();
+        match &cli.command {
+            Some(Command::Stats(args)) => {
+                assert_eq!(args.lockfile, PathBuf::from("dec.lock.json"));
+                assert!(args.json);
+                assert!(!args.no_witness);
+            }
+            other => panic!("expected Stats, got {other:?}"),
+        }
+    }
+
+    #[test]
+    fn parse_log_requires_search_dir() {
+        assert!(Cli::try_parse_from(["lock", "log", "dec.lock.json"]).is_err());
+
+        let cli = Cli::try_parse_from([
+            "lock",
+            "log",
+            "dec.lock.json",
+            "--search-dir",
+            "locks",
+            "--json",
+        ])
+        .unwrap();
+        match &cli.command {
+            Some(Command::Log(args)) => {
+                assert_eq!(args.lockfile, PathBuf::from("dec.lock.json"));
+                assert_eq!(args.search_dir, PathBuf::from("locks"));
+                assert!(args.json);
+                assert!(!args.no_witness);
+            }
+            other => panic!("expected Log, got {other:?}"),
+        }
+    }
+
+    #[test]
+    fn parse_main_flow_parent() {
+        let cli = Cli::try_parse_from(["lock", "dec.jsonl", "--parent", "nov.lock.json"]).unwrap();
+        assert!(cli.command.is_none());
+        assert_eq!(cli.parent, Some(PathBuf::from("nov.lock.json")));
+    }
+
+    #[test]
+    fn parse_main_flow_lock_version() {
+        let cli = Cli::try_parse_from(["lock", "dec.jsonl"]).unwrap();
+        assert_eq!(cli.lock_version, "lock.v0");
+
+        let cli = Cli::try_parse_from(["lock", "dec.jsonl", "--lock-version", "lock.v1"]).unwrap();
+        assert_eq!(cli.lock_version, "lock.v1");
+
+        assert!(Cli::try_parse_from(["lock", "--lock-version", "lock.v2"]).is_err());
+    }
+
+    #[test]
+    fn parse_main_flow_keep_fields() {
+        let cli = Cli::try_parse_from([
+            "lock",
+            "dec.jsonl",
+            "--keep-fields",
+            "mime_guess,mtime",
+            "--keep-fields",
+            "owner",
+        ])
+        .unwrap();
+        assert_eq!(cli.keep_fields, ["mime_guess", "mtime", "owner"]);
+
+        let cli = Cli::try_parse_from(["lock", "dec.jsonl", "--keep-all-fields"]).unwrap();
+        assert!(cli.keep_all_fields);
+
+        assert!(Cli::try_parse_from(["lock", "--keep-fields", "bytes_hash"]).is_err());
+        assert!(Cli::try_parse_from(["lock", "--keep-fields", "_skipped"]).is_err());
+        assert!(
+            Cli::try_parse_from(["lock", "--keep-fields", "owner", "--keep-all-fields"]).is_err()
+        );
+    }
+
+    #[test]
+    fn parse_prove_requires_path() {
+        assert!(Cli::try_parse_from(["lock", "prove", "dec.lock.json"]).is_err());
+
+        let cli = Cli::try_parse_from([
+            "lock",
+            "prove",
+            "dec.lock.json",
+            "--path",
+            "tape.csv",
+            "--output",
+            "tape.proof.json",
+        ])
+        .unwrap();
+        match &cli.command {
+            Some(Command::Prove(args)) => {
+                assert_eq!(args.lockfile, PathBuf::from("dec.lock.json"));
+                assert_eq!(args.path, "tape.csv");
+                assert_eq!(args.output, Some(PathBuf::from("tape.proof.json")));
+                assert!(!args.no_witness);
+            }
+            other => panic!("expected Prove, got {other:?}"),
+        }
+    }
+
+    #[test]
+    fn parse_verify_proof_flags() {
+        let cli = Cli::try_parse_from([
+            "lock",
+            "verify-proof",
+            "tape.proof.json",
+            "--file",
+            "tape.csv",
+            "--json",
+        ])
+        .unwrap();
+        match &cli.command {
+            Some(Command::VerifyProof(args)) => {
+                assert_eq!(args.proof, PathBuf::from("tape.proof.json"));
+                assert_eq!(args.file, Some(PathBuf::from("tape.csv")));
+                assert!(args.json);
+                assert!(!args.no_witness);
+            }
+            other => panic!("expected VerifyProof, got {other:?}"),
+        }
+    }
+
+    #[test]
+    fn parse_verify_proof_pins() {
+        let root = format!("sha256:{}", "AB".repeat(32));
+        let cli = Cli::try_parse_from(["lock", "verify-proof", "p.json", "--members-root", &root])
+            .unwrap();
+        match &cli.command {
+            Some(Command::VerifyProof(args)) => {
+                assert_eq!(
+                    args.members_root,
+                    Some(format!("sha256:{}", "ab".repeat(32)))
+                );
+                assert_eq!(args.lockfile, None);
+            }
+            other => panic!("expected VerifyProof, got {other:?}"),
+        }
+
+        for bad in [
+            &["--members-root", "sha256:abc"][..],
+            &["--members-root", &root, "--lockfile", "dec.lock.json"],
+        ] {
+            let mut argv = vec!["lock", "verify-proof", "p.json"];
+            argv.extend_from_slice(bad);
+            assert!(Cli::try_parse_from(argv).is_err(), "{bad:?}");
+        }
+    }
+
+    #[test]
+    fn parse_sign_requires_key() {
+        assert!(Cli::try_parse_from(["lock", "sign", "dec.lock.json"]).is_err());
+
+        let cli = Cli::try_parse_from([
+            "lock",
+            "sign",
+            "dec.lock.json",
+            "--key",
+            "auditor.key",
+            "--output",
+            "dec.lock.sig",
+        ])
+        .unwrap();
+        match &cli.command {
+            Some(Command::Sign(args)) => {
+                assert_eq!(args.lockfile, PathBuf::from("dec.lock.json"));
+                assert_eq!(args.key, PathBuf::from("auditor.key"));
+                assert_eq!(args.output, Some(PathBuf::from("dec.lock.sig")));
+                assert!(!args.no_witness);
+            }
+            other => panic!("expected Sign, got {other:?}"),
+        }
+    }
+
+    #[test]
+    fn parse_keygen_flags() {
+        let cli = Cli::try_parse_from([
+            "lock",
+            "keygen",
+            "--output",
+            "auditor.key",
+            "--signer",
+            "audit-team",
+        ])
+        .unwrap();
+        match &cli.command {
+            Some(Command::Keygen(args)) => {
+                assert_eq!(args.output, PathBuf::from("auditor.key"));
+                assert_eq!(args.signer.as_deref(), Some("audit-team"));
+            }
+            other => panic!("expected Keygen, got {other:?}"),
         }
     }
 
@@ -655,6 +1937,20 @@ mod tests {
         assert!(parsed["properties"]["skipped"].is_object());
     }
 
+    #[test]
+    fn lock_v1_schema_requires_members_root() {
+        let parsed: serde_json::Value =
+            serde_json::from_str(LOCK_V1_SCHEMA).expect("lock.v1 schema must be valid JSON");
+        assert_eq!(parsed["title"], "lock.v1");
+        assert_eq!(parsed["properties"]["version"]["const"], "lock.v1");
+        assert!(
+            parsed["required"]
+                .as_array()
+                .unwrap()
+                .contains(&serde_json::json!("members_root"))
+        );
+    }
+
     #[test]
     fn dispatch_describe_returns_zero() {
         assert_eq!(dispatch_describe(), 0);
@@ -662,7 +1958,7 @@ mod tests {
 
     #[test]
     fn dispatch_schema_returns_zero() {
-        assert_eq!(dispatch_schema(), 0);
+        assert_eq!(dispatch_schema("lock.v0"), 0);
     }
 
     #[test]
@@ -0,0 +1,593 @@
+pub mod output;
+
+use std::collections::{BTreeMap, BTreeSet};
+use std::path::Path;
+
+use serde::Serialize;
+use serde_json::Value;
+
+use crate::cli::DiffArgs;
+use crate::refusal::sort_value;
+use crate::verify::load_sealed_lockfile;
+
+/// Diff output schema version.
+pub const DIFF_VERSION: &str = "lock-diff.v0";
+
+// ---------------------------------------------------------------------------
+// Diff result types
+// ---------------------------------------------------------------------------
+
+/// The top-level result emitted by `lock diff`.
+#[derive(Debug, Clone, Serialize)]
+struct DiffResult {
+    version: String,
+    outcome: String,
+    old: DiffSide,
+    new: DiffSide,
+    members: MemberDiff,
+    fingerprints: Vec<FingerprintChange>,
+    skipped: SkippedDiff,
+    metadata: Vec<MetadataChange>,
+    tool_versions_drift: ToolVersionsDrift,
+    summary: DiffSummary,
+    tool_versions: BTreeMap<String, String>,
+}
+
+/// Identity of one side of the comparison.
+#[derive(Debug, Clone, Serialize)]
+struct DiffSide {
+    lockfile: String,
+    lock_hash: String,
+    member_count: usize,
+}
+
+/// Member-level changes keyed by path.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize)]
+pub struct MemberDiff {
+    pub added: Vec<MemberEntry>,
+    pub removed: Vec<MemberEntry>,
+    pub changed: Vec<MemberChange>,
+}
+
+/// A member present on only one side.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct MemberEntry {
+    pub path: String,
+    pub bytes_hash: String,
+    pub size: u64,
+}
+
+/// A member present on both sides whose content identity changed.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct MemberChange {
+    pub path: String,
+    pub old_bytes_hash: String,
+    pub new_bytes_hash: String,
+    pub old_size: u64,
+    pub new_size: u64,
+    pub size_delta: i64,
+}
+
+/// A fingerprint result that differs for a member present on both sides.
+#[derive(Debug, Clone, PartialEq, Serialize)]
+struct FingerprintChange {
+    path: String,
+    old: Value,
+    new: Value,
+}
+
+/// Paths that entered or left the skipped set.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize)]
+struct SkippedDiff {
+    added: Vec<String>,
+    removed: Vec<String>,
+}
+
+/// A top-level metadata field (`dataset_id`, `as_of`, `note`, ...) that changed.
+#[derive(Debug, Clone, PartialEq, Serialize)]
+struct MetadataChange {
+    field: String,
+    old: Value,
+    new: Value,
+}
+
+/// Upstream tool version drift between the two lockfiles.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize)]
+struct ToolVersionsDrift {
+    added: BTreeMap<String, String>,
+    removed: BTreeMap<String, String>,
+    changed: BTreeMap<String, VersionChange>,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+struct VersionChange {
+    old: String,
+    new: String,
+}
+
+#[derive(Debug, Clone, Serialize)]
+struct DiffSummary {
+    added: usize,
+    removed: usize,
+    changed: usize,
+    fingerprints_changed: usize,
+    skipped_added: usize,
+    skipped_removed: usize,
+    metadata_changed: usize,
+    tool_versions_changed: usize,
+}
+
+/// Metadata fields compared between lockfiles. `created` and `lock_hash` are
+/// excluded: they differ on every run and carry no content change.
+const METADATA_FIELDS: &[&str] = &["version", "dataset_id", "as_of", "note", "profiles"];
+
+// ---------------------------------------------------------------------------
+// Comparison
+// ---------------------------------------------------------------------------
+
+fn members_by_path(lockfile: &Value) -> BTreeMap<&str, &Value> {
+    lockfile
+        .get("members")
+        .and_then(Value::as_array)
+        .map(|members| {
+            members
+                .iter()
+                .filter_map(|m| m.get("path").and_then(Value::as_str).map(|p| (p, m)))
+                .collect()
+        })
+        .unwrap_or_default()
+}
+
+fn member_entry(member: &Value) -> MemberEntry {
+    MemberEntry {
+        path: member["path"].as_str().unwrap_or_default().to_string(),
+        bytes_hash: member["bytes_hash"]
+            .as_str()
+            .unwrap_or_default()
+            .to_string(),
+        size: member["size"].as_u64().unwrap_or(0),
+    }
+}
+
+fn size_delta(old: u64, new: u64) -> i64 {
+    let delta = i128::from(new) - i128::from(old);
+    i64::try_from(delta).unwrap_or(if delta < 0 { i64::MIN } else { i64::MAX })
+}
+
+/// Compare the members of two validated lockfiles by path.
+///
+/// A member counts as changed when its `bytes_hash` or `size` differs.
+/// Output vectors are ordered by path.
+pub fn diff_members(old: &Value, new: &Value) -> MemberDiff {
+    let old_members = members_by_path(old);
+    let new_members = members_by_path(new);
+    let mut diff = MemberDiff::default();
+
+    for (path, old_member) in &old_members {
+        match new_members.get(path) {
+            None => diff.removed.push(member_entry(old_member)),
+            Some(new_member) => {
+                let before = member_entry(old_member);
+                let after = member_entry(new_member);
+                if before.bytes_hash != after.bytes_hash || before.size != after.size {
+                    diff.changed.push(MemberChange {
+                        path: before.path,
+                        size_delta: size_delta(before.size, after.size),
+                        old_bytes_hash: before.bytes_hash,
+                        new_bytes_hash: after.bytes_hash,
+                        old_size: before.size,
+                        new_size: after.size,
+                    });
+                }
+            }
+        }
+    }
+
+    for (path, new_member) in &new_members {
+        if !old_members.contains_key(path) {
+            diff.added.push(member_entry(new_member));
+        }
+    }
+
+    diff
+}
+
+fn diff_fingerprints(old: &Value, new: &Value) -> Vec<FingerprintChange> {
+    let old_members = members_by_path(old);
+    let new_members = members_by_path(new);
+
+    old_members
+        .iter()
+        .filter_map(|(path, old_member)| {
+            let new_member = new_members.get(path)?;
+            let before = old_member
+                .get("fingerprint")
+                .cloned()
+                .unwrap_or(Value::Null);
+            let after = new_member
+                .get("fingerprint")
+                .cloned()
+                .unwrap_or(Value::Null);
+            (before != after).then(|| FingerprintChange {
+                path: (*path).to_string(),
+                old: before,
+                new: after,
+            })
+        })
+        .collect()
+}
+
+fn skipped_paths(lockfile: &Value) -> BTreeSet<&str> {
+    lockfile
+        .get("skipped")
+        .and_then(Value::as_array)
+        .map(|skipped| {
+            skipped
+                .iter()
+                .filter_map(|entry| entry.get("path").and_then(Value::as_str))
+                .collect()
+        })
+        .unwrap_or_default()
+}
+
+fn diff_skipped(old: &Value, new: &Value) -> SkippedDiff {
+    let old_paths = skipped_paths(old);
+    let new_paths = skipped_paths(new);
+    SkippedDiff {
+        added: new_paths
+            .difference(&old_paths)
+            .map(|p| (*p).to_string())
+            .collect(),
+        removed: old_paths
+            .difference(&new_paths)
+            .map(|p| (*p).to_string())
+            .collect(),
+    }
+}
+
+fn diff_metadata(old: &Value, new: &Value) -> Vec<MetadataChange> {
+    METADATA_FIELDS
+        .iter()
+        .filter_map(|field| {
+            let before = old.get(*field).cloned().unwrap_or(Value::Null);
+            let after = new.get(*field).cloned().unwrap_or(Value::Null);
+            (before != after).then(|| MetadataChange {
+                field: (*field).to_string(),
+                old: before,
+                new: after,
+            })
+        })
+        .collect()
+}
+
+fn tool_versions(lockfile: &Value) -> BTreeMap<String, String> {
+    lockfile
+        .get("tool_versions")
+        .and_then(Value::as_object)
+        .map(|map| {
+            map.iter()
+                .filter_map(|(tool, version)| {
+                    version.as_str().map(|v| (tool.clone(), v.to_string()))
+                })
+                .collect()
+        })
+        .unwrap_or_default()
+}
+
+fn diff_tool_versions(old: &Value, new: &Value) -> ToolVersionsDrift {
+    let old_versions = tool_versions(old);
+    let new_versions = tool_versions(new);
+    let mut drift = ToolVersionsDrift::default();
+
+    for (tool, old_version) in &old_versions {
+        match new_versions.get(tool) {
+            None => {
+                drift.removed.insert(tool.clone(), old_version.clone());
+            }
+            Some(new_version) if new_version != old_version => {
+                drift.changed.insert(
+                    tool.clone(),
+                    VersionChange {
+                        old: old_version.clone(),
+                        new: new_version.clone(),
+                    },
+                );
+            }
+            Some(_) => {}
+        }
+    }
+    for (tool, new_version) in &new_versions {
+        if !old_versions.contains_key(tool) {
+            drift.added.insert(tool.clone(), new_version.clone());
+        }
+    }
+
+    drift
+}
+
+fn diff_side(path: &Path, lockfile: &Value) -> DiffSide {
+    DiffSide {
+        lockfile: path.display().to_string(),
+        lock_hash: lockfile["lock_hash"]
+            .as_str()
+            .unwrap_or_default()
+            .to_string(),
+        member_count: members_by_path(lockfile).len(),
+    }
+}
+
+fn build_diff(old_path: &Path, old: &Value, new_path: &Path, new: &Value) -> DiffResult {
+    let members = diff_members(old, new);
+    let fingerprints = diff_fingerprints(old, new);
+    let skipped = diff_skipped(old, new);
+    let metadata = diff_metadata(old, new);
+    let tool_versions_drift = diff_tool_versions(old, new);
+
+    let summary = DiffSummary {
+        added: members.added.len(),
+        removed: members.removed.len(),
+        changed: members.changed.len(),
+        fingerprints_changed: fingerprints.len(),
+        skipped_added: skipped.added.len(),
+        skipped_removed: skipped.removed.len(),
+        metadata_changed: metadata.len(),
+        tool_versions_changed: tool_versions_drift.added.len()
+            + tool_versions_drift.removed.len()
+            + tool_versions_drift.changed.len(),
+    };
+    let identical = summary.added
+        + summary.removed
+        + summary.changed
+        + summary.fingerprints_changed
+        + summary.skipped_added
+        + summary.skipped_removed
+        + summary.metadata_changed
+        + summary.tool_versions_changed
+        == 0;
+
+    let mut lock_versions = BTreeMap::new();
+    lock_versions.insert("lock".to_string(), env!("CARGO_PKG_VERSION").to_string());
+
+    DiffResult {
+        version: DIFF_VERSION.to_string(),
+        outcome: if identical {
+            "DIFF_IDENTICAL"
+        } else {
+            "DIFF_CHANGED"
+        }
+        .to_string(),
+        old: diff_side(old_path, old),
+        new: diff_side(new_path, new),
+        members,
+        fingerprints,
+        skipped,
+        metadata,
+        tool_versions_drift,
+        summary,
+        tool_versions: lock_versions,
+    }
+}
+
+// ---------------------------------------------------------------------------
+// Orchestration
+// ---------------------------------------------------------------------------
+
+/// Run the diff subcommand. Returns the exit code.
+///
+/// Exit codes: `0` identical, `1` differences found, `2` refusal.
+pub fn run_diff(args: &DiffArgs) -> u8 {
+    // Step 1: Load both lockfiles; either side failing validation or its
+    // self-hash check refuses the whole comparison.
+    let loaded = load_sealed_lockfile(&args.old)
+        .and_then(|old| load_sealed_lockfile(&args.new).map(|new| (old, new)));
+    let (old, new) = match loaded {
+        Ok(pair) => pair,
+        Err(refusal) => {
+            let payload = refusal.to_json(DIFF_VERSION);
+            print!("{payload}");
+            emit_witness(args, 2, "REFUSAL", payload.as_bytes());
+            return 2;
+        }
+    };
+
+    // Step 2: Compare.
+    let result = build_diff(&args.old, &old, &args.new, &new);
+    let exit_code = if result.outcome == "DIFF_IDENTICAL" {
+        0
+    } else {
+        1
+    };
+
+    // Step 3: Emit output.
+    let value = sort_value(serde_json::to_value(&result).expect("DiffResult is serializable"));
+    let payload = if args.json {
+        serde_json::to_string(&value).expect("sorted Value is always serializable")
+    } else {
+        output::render_human(&value)
+    };
+    print!("{payload}");
+    emit_witness(args, exit_code, &result.outcome, payload.as_bytes());
+    exit_code
+}
+
+fn emit_witness(args: &DiffArgs, exit_code: u8, outcome: &str, output_bytes: &[u8]) {
+    if args.no_witness {
+        return;
+    }
+
+    let params = serde_json::json!({
+        "subcommand": "diff",
+    });
+    let inputs = serde_json::json!([
+        { "path": args.old.display().to_string(), "hash": null, "bytes": null },
+        { "path": args.new.display().to_string(), "hash": null, "bytes": null },
+    ]);
+
+    crate::witness::append_witness_record(outcome, exit_code, output_bytes, params, inputs);
+}
+
+// ---------------------------------------------------------------------------
+// Tests
+// ---------------------------------------------------------------------------
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    fn lockfile(members: Value, skipped: Value, tool_versions: Value) -> Value {
+        json!({
+            "version": "lock.v0",
+            "lock_hash": "sha256:00",
+            "dataset_id": "raw-dec",
+            "as_of": null,
+            "note": null,
+            "profiles": [],
+            "tool_versions": tool_versions,
+            "members": members,
+            "skipped": skipped,
+        })
+    }
+
+    fn member(path: &str, hash: &str, size: u64) -> Value {
+        json!({ "path": path, "bytes_hash": hash, "size": size, "fingerprint": null })
+    }
+
+    #[test]
+    fn identical_lockfiles_produce_empty_diff() {
+        let a = lockfile(
+            json!([member("a.csv", "sha256:aa", 10)]),
+            json!([]),
+            json!({"lock": "0.5.0"}),
+        );
+        let result = build_diff(Path::new("a"), &a, Path::new("b"), &a);
+        assert_eq!(result.outcome, "DIFF_IDENTICAL");
+        assert_eq!(result.members, MemberDiff::default());
+        assert!(result.fingerprints.is_empty());
+        assert!(result.metadata.is_empty());
+    }
+
+    #[test]
+    fn members_added_removed_and_changed_by_path() {
+        let old = lockfile(
+            json!([
+                member("a.csv", "sha256:aa", 10),
+                member("b.csv", "sha256:bb", 20),
+            ]),
+            json!([]),
+            json!({}),
+        );
+        let new = lockfile(
+            json!([
+                member("b.csv", "sha256:b2", 25),
+                member("c.csv", "sha256:cc", 30),
+            ]),
+            json!([]),
+            json!({}),
+        );
+
+        let diff = diff_members(&old, &new);
+        assert_eq!(diff.added.len(), 1);
+        assert_eq!(diff.added[0].path, "c.csv");
+        assert_eq!(diff.removed.len(), 1);
+        assert_eq!(diff.removed[0].path, "a.csv");
+        assert_eq!(diff.changed.len(), 1);
+        assert_eq!(diff.changed[0].path, "b.csv");
+        assert_eq!(diff.changed[0].old_bytes_hash, "sha256:bb");
+        assert_eq!(diff.changed[0].new_bytes_hash, "sha256:b2");
+        assert_eq!(diff.changed[0].size_delta, 5);
+    }
+
+    #[test]
+    fn shrinking_member_has_negative_size_delta() {
+        let old = lockfile(
+            json!([member("a.csv", "sha256:aa", 100)]),
+            json!([]),
+            json!({}),
+        );
+        let new = lockfile(
+            json!([member("a.csv", "sha256:ab", 40)]),
+            json!([]),
+            json!({}),
+        );
+        let diff = diff_members(&old, &new);
+        assert_eq!(diff.changed[0].size_delta, -60);
+    }
+
+    #[test]
+    fn fingerprint_changes_are_reported_for_shared_members() {
+        let mut old = lockfile(
+            json!([member("a.csv", "sha256:aa", 10)]),
+            json!([]),
+            json!({}),
+        );
+        let new = old.clone();
+        old["members"][0]["fingerprint"] = json!({
+            "fingerprint_id": "csv.v0",
+            "fingerprint_version": "0.1.0",
+            "matched": true,
+            "content_hash": null
+        });
+
+        let result = build_diff(Path::new("a"), &old, Path::new("b"), &new);
+        assert_eq!(result.outcome, "DIFF_CHANGED");
+        assert!(result.members.changed.is_empty());
+        assert_eq!(result.fingerprints.len(), 1);
+        assert_eq!(result.fingerprints[0].path, "a.csv");
+        assert!(result.fingerprints[0].new.is_null());
+    }
+
+    #[test]
+    fn skipped_set_changes_are_reported() {
+        let old = lockfile(
+            json!([]),
+            json!([{"path": "x.bin", "warnings": []}]),
+            json!({}),
+        );
+        let new = lockfile(
+            json!([]),
+            json!([{"path": "y.bin", "warnings": []}]),
+            json!({}),
+        );
+        let skipped = diff_skipped(&old, &new);
+        assert_eq!(skipped.added, vec!["y.bin"]);
+        assert_eq!(skipped.removed, vec!["x.bin"]);
+    }
+
+    #[test]
+    fn tool_versions_drift_is_classified() {
+        let old = lockfile(
+            json!([]),
+            json!([]),
+            json!({"lock": "0.5.0", "vacuum": "0.1.0", "fingerprint": "0.2.0"}),
+        );
+        let new = lockfile(
+            json!([]),
+            json!([]),
+            json!({"lock": "0.5.0", "vacuum": "0.2.0", "hash": "0.1.0"}),
+        );
+        let drift = diff_tool_versions(&old, &new);
+        assert_eq!(drift.added.get("hash").map(String::as_str), Some("0.1.0"));
+        assert_eq!(
+            drift.removed.get("fingerprint").map(String::as_str),
+            Some("0.2.0")
+        );
+        assert_eq!(drift.changed["vacuum"].old, "0.1.0");
+        assert_eq!(drift.changed["vacuum"].new, "0.2.0");
+        assert!(!drift.changed.contains_key("lock"));
+    }
+
+    #[test]
+    fn metadata_changes_ignore_created_and_lock_hash() {
+        let old = lockfile(json!([]), json!([]), json!({}));
+        let mut new = old.clone();
+        new["created"] = json!("2026-02-01T00:00:00Z");
+        new["lock_hash"] = json!("sha256:ff");
+        new["note"] = json!("redelivery");
+
+        let changes = diff_metadata(&old, &new);
+        assert_eq!(changes.len(), 1);
+        assert_eq!(changes[0].field, "note");
+        assert_eq!(changes[0].new, "redelivery");
+    }
+}
@@ -0,0 +1,162 @@
+use serde_json::Value;
+
+/// Render a diff result as human-readable text.
+///
+/// Returns a string suitable for printing to stdout (no trailing newline).
+pub fn render_human(result: &Value) -> String {
+    let outcome = result["outcome"].as_str().unwrap_or("?");
+    let old = result["old"]["lockfile"].as_str().unwrap_or("?");
+    let new = result["new"]["lockfile"].as_str().unwrap_or("?");
+
+    match outcome {
+        "DIFF_IDENTICAL" => format!("\u{2713} {old} and {new}: no differences"),
+        "DIFF_CHANGED" => render_changed(result, old, new),
+        "REFUSAL" => render_refusal(result),
+        _ => format!("? {old} -> {new}: unknown outcome {outcome}"),
+    }
+}
+
+fn render_changed(result: &Value, old: &str, new: &str) -> String {
+    let summary = &result["summary"];
+    let count = |key: &str| summary[key].as_u64().unwrap_or(0);
+
+    let mut lines = vec![format!(
+        "\u{26A0} {old} -> {new}: +{} -{} ~{} members",
+        count("added"),
+        count("removed"),
+        count("changed")
+    )];
+
+    let members = &result["members"];
+    for entry in members["added"].as_array().into_iter().flatten() {
+        let path = entry["path"].as_str().unwrap_or("?");
+        let size = entry["size"].as_u64().unwrap_or(0);
+        lines.push(format!("  + {path} ({size} bytes)"));
+    }
+    for entry in members["removed"].as_array().into_iter().flatten() {
+        let path = entry["path"].as_str().unwrap_or("?");
+        lines.push(format!("  - {path}"));
+    }
+    for change in members["changed"].as_array().into_iter().flatten() {
+        let path = change["path"].as_str().unwrap_or("?");
+        let old_hash = change["old_bytes_hash"].as_str().unwrap_or("?");
+        let new_hash = change["new_bytes_hash"].as_str().unwrap_or("?");
+        let delta = change["size_delta"].as_i64().unwrap_or(0);
+        lines.push(format!(
+            "  ~ {path}: {old_hash} -> {new_hash} ({delta:+} bytes)"
+        ));
+    }
+
+    for change in result["fingerprints"].as_array().into_iter().flatten() {
+        let path = change["path"].as_str().unwrap_or("?");
+        lines.push(format!("  fingerprint changed: {path}"));
+    }
+
+    for path in result["skipped"]["added"].as_array().into_iter().flatten() {
+        lines.push(format!("  skipped +: {}", path.as_str().unwrap_or("?")));
+    }
+    for path in result["skipped"]["removed"]
+        .as_array()
+        .into_iter()
+        .flatten()
+    {
+        lines.push(format!("  skipped -: {}", path.as_str().unwrap_or("?")));
+    }
+
+    for change in result["metadata"].as_array().into_iter().flatten() {
+        let field = change["field"].as_str().unwrap_or("?");
+        lines.push(format!("  {field}: {} -> {}", change["old"], change["new"]));
+    }
+
+    let drift = &result["tool_versions_drift"];
+    for (tool, version) in drift["added"].as_object().into_iter().flatten() {
+        lines.push(format!(
+            "  tool {tool}: added {}",
+            version.as_str().unwrap_or("?")
+        ));
+    }
+    for (tool, version) in drift["removed"].as_object().into_iter().flatten() {
+        lines.push(format!(
+            "  tool {tool}: removed {}",
+            version.as_str().unwrap_or("?")
+        ));
+    }
+    for (tool, change) in drift["changed"].as_object().into_iter().flatten() {
+        lines.push(format!(
+            "  tool {tool}: {} -> {}",
+            change["old"].as_str().unwrap_or("?"),
+            change["new"].as_str().unwrap_or("?")
+        ));
+    }
+
+    lines.join("\n")
+}
+
+fn render_refusal(result: &Value) -> String {
+    let code = result["refusal"]["code"].as_str().unwrap_or("?");
+    let message = result["refusal"]["message"].as_str().unwrap_or("?");
+    format!("\u{2717} {code}: {message}")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn render_identical() {
+        let result = serde_json::json!({
+            "outcome": "DIFF_IDENTICAL",
+            "old": { "lockfile": "nov.lock.json" },
+            "new": { "lockfile": "dec.lock.json" }
+        });
+        let output = render_human(&result);
+        assert!(output.starts_with('\u{2713}'));
+        assert!(output.contains("no differences"));
+    }
+
+    #[test]
+    fn render_changed_lists_members_and_drift() {
+        let result = serde_json::json!({
+            "outcome": "DIFF_CHANGED",
+            "old": { "lockfile": "nov.lock.json" },
+            "new": { "lockfile": "dec.lock.json" },
+            "summary": { "added": 1, "removed": 1, "changed": 1 },
+            "members": {
+                "added": [{ "path": "new.csv", "bytes_hash": "sha256:aa", "size": 7 }],
+                "removed": [{ "path": "old.csv", "bytes_hash": "sha256:bb", "size": 9 }],
+                "changed": [{
+                    "path": "tape.csv",
+                    "old_bytes_hash": "sha256:cc",
+                    "new_bytes_hash": "sha256:dd",
+                    "old_size": 10,
+                    "new_size": 4,
+                    "size_delta": -6
+                }]
+            },
+            "fingerprints": [],
+            "skipped": { "added": ["locked.bin"], "removed": [] },
+            "metadata": [],
+            "tool_versions_drift": {
+                "added": {},
+                "removed": {},
+                "changed": { "vacuum": { "old": "0.1.0", "new": "0.2.0" } }
+            }
+        });
+        let output = render_human(&result);
+        assert!(output.contains("+1 -1 ~1 members"));
+        assert!(output.contains("+ new.csv (7 bytes)"));
+        assert!(output.contains("- old.csv"));
+        assert!(output.contains("~ tape.csv: sha256:cc -> sha256:dd (-6 bytes)"));
+        assert!(output.contains("skipped +: locked.bin"));
+        assert!(output.contains("tool vacuum: 0.1.0 -> 0.2.0"));
+    }
+
+    #[test]
+    fn render_refusal_shows_code() {
+        let result = serde_json::json!({
+            "outcome": "REFUSAL",
+            "refusal": { "code": "E_TAMPERED", "message": "lockfile self-hash does not match" }
+        });
+        assert!(render_human(&result).contains("E_TAMPERED"));
+    }
+}
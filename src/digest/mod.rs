@@ -0,0 +1,171 @@
+//! Registry of the digest algorithms `lock` can compute and check.
+//!
+//! Every digest `lock` writes or reads is `<algorithm>:<lowercase hex>`,
+//! where `<algorithm>` is the `name` of an entry in [`ALGORITHMS`]. Adding an
+//! algorithm means adding an entry here and an arm to [`Hasher`]; verify,
+//! scan, rehash, the hash cache, and `lock capabilities` all read from this
+//! table.
+
+use serde::Serialize;
+use sha2::{Digest, Sha256, Sha512};
+use sha3::Sha3_256;
+use xxhash_rust::xxh3::Xxh3;
+
+/// One supported digest algorithm.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+pub struct Algorithm {
+    /// Prefix used in digests, e.g. `sha256` in `sha256:<hex>`.
+    pub name: &'static str,
+    /// Whether the digest resists deliberate collisions. Only cryptographic
+    /// algorithms may stand alone as a member's `bytes_hash`; the others are
+    /// quick pre-checks recorded next to one in `hashes`.
+    pub cryptographic: bool,
+    pub description: &'static str,
+}
+
+/// Every algorithm `lock` supports, in the order it reports them.
+pub const ALGORITHMS: &[Algorithm] = &[
+    Algorithm {
+        name: "sha256",
+        cryptographic: true,
+        description: "SHA-256 (FIPS 180-4); the default",
+    },
+    Algorithm {
+        name: "blake3",
+        cryptographic: true,
+        description: "BLAKE3, 256-bit output",
+    },
+    Algorithm {
+        name: "sha512",
+        cryptographic: true,
+        description: "SHA-512 (FIPS 180-4)",
+    },
+    Algorithm {
+        name: "sha3-256",
+        cryptographic: true,
+        description: "SHA3-256 (FIPS 202)",
+    },
+    Algorithm {
+        name: "xxh3",
+        cryptographic: false,
+        description: "XXH3 64-bit; non-cryptographic pre-check, only alongside a cryptographic digest",
+    },
+];
+
+/// Names of the cryptographic algorithms, for CLI flags that set `bytes_hash`.
+pub fn cryptographic_names() -> impl Iterator<Item = &'static str> {
+    ALGORITHMS
+        .iter()
+        .filter(|algorithm| algorithm.cryptographic)
+        .map(|algorithm| algorithm.name)
+}
+
+/// Names of every algorithm, for CLI flags that add to `hashes`.
+pub fn names() -> impl Iterator<Item = &'static str> {
+    ALGORITHMS.iter().map(|algorithm| algorithm.name)
+}
+
+/// Look up an algorithm by its digest prefix.
+pub fn lookup(name: &str) -> Option<&'static Algorithm> {
+    ALGORITHMS.iter().find(|algorithm| algorithm.name == name)
+}
+
+/// The algorithm prefix of a `<algorithm>:<hex>` digest.
+pub fn prefix(digest: &str) -> &str {
+    digest.split(':').next().unwrap_or_default()
+}
+
+/// A running digest for one registered algorithm.
+pub enum Hasher {
+    Sha256(Sha256),
+    Blake3(Box<blake3::Hasher>),
+    Sha512(Sha512),
+    Sha3_256(Sha3_256),
+    Xxh3(Box<Xxh3>),
+}
+
+impl Hasher {
+    /// Start a digest, or `None` for an unregistered algorithm.
+    pub fn new(algorithm: &str) -> Option<Self> {
+        match algorithm {
+            "sha256" => Some(Self::Sha256(Sha256::new())),
+            "blake3" => Some(Self::Blake3(Box::default())),
+            "sha512" => Some(Self::Sha512(Sha512::new())),
+            "sha3-256" => Some(Self::Sha3_256(Sha3_256::new())),
+            "xxh3" => Some(Self::Xxh3(Box::default())),
+            _ => None,
+        }
+    }
+
+    pub fn update(&mut self, bytes: &[u8]) {
+        match self {
+            Self::Sha256(hasher) => hasher.update(bytes),
+            Self::Blake3(hasher) => {
+                hasher.update(bytes);
+            }
+            Self::Sha512(hasher) => hasher.update(bytes),
+            Self::Sha3_256(hasher) => hasher.update(bytes),
+            Self::Xxh3(hasher) => hasher.update(bytes),
+        }
+    }
+
+    /// Finish and return the prefixed digest.
+    pub fn finish(self) -> String {
+        match self {
+            Self::Sha256(hasher) => format!("sha256:{:x}", hasher.finalize()),
+            Self::Blake3(hasher) => format!("blake3:{}", hasher.finalize().to_hex()),
+            Self::Sha512(hasher) => format!("sha512:{:x}", hasher.finalize()),
+            Self::Sha3_256(hasher) => format!("sha3-256:{:x}", hasher.finalize()),
+            Self::Xxh3(hasher) => format!("xxh3:{:016x}", hasher.digest()),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn digest(algorithm: &str, bytes: &[u8]) -> String {
+        let mut hasher = Hasher::new(algorithm).unwrap();
+        hasher.update(bytes);
+        hasher.finish()
+    }
+
+    #[test]
+    fn every_registered_algorithm_has_a_hasher() {
+        for algorithm in ALGORITHMS {
+            let value = digest(algorithm.name, b"abc");
+            assert_eq!(prefix(&value), algorithm.name);
+        }
+        assert_eq!(
+            cryptographic_names().collect::<Vec<_>>(),
+            ["sha256", "blake3", "sha512", "sha3-256"]
+        );
+        assert_eq!(names().last(), Some("xxh3"));
+        assert!(Hasher::new("md5").is_none());
+        assert!(lookup("md5").is_none());
+    }
+
+    #[test]
+    fn known_answer_digests() {
+        assert_eq!(
+            digest("sha512", b"abc"),
+            "sha512:ddaf35a193617abacc417349ae20413112e6fa4e89a97ea20a9eeee64b55d39a2192992a274fc1a836ba3c23a3feebbd454d4423643ce80e2a9ac94fa54ca49f"
+        );
+        assert_eq!(
+            digest("sha3-256", b"abc"),
+            "sha3-256:3a985da74fe225b2045c172d6bd390bd855f086e3e9d525b46bfe24511431532"
+        );
+        assert_eq!(digest("xxh3", b""), "xxh3:2d06800538d394c2");
+    }
+
+    #[test]
+    fn split_updates_match_one_shot() {
+        for algorithm in names() {
+            let mut hasher = Hasher::new(algorithm).unwrap();
+            hasher.update(b"hello ");
+            hasher.update(b"world");
+            assert_eq!(hasher.finish(), digest(algorithm, b"hello world"));
+        }
+    }
+}
@@ -12,7 +12,12 @@ const READ_ONLY_DOCTOR_CONTRACT: &str = "cmdrvl.read_only_doctor.v1";
 
 const OPERATOR_JSON: &str = include_str!("../operator.json");
 const LOCK_SCHEMA: &str = include_str!("../schemas/lock-v0.schema.json");
+const LOCK_V1_SCHEMA: &str = include_str!("../schemas/lock-v1.schema.json");
 const VERIFY_SCHEMA: &str = include_str!("../schemas/lock-verify-v0.schema.json");
+const DIFF_SCHEMA: &str = include_str!("../schemas/lock-diff-v0.schema.json");
+const LOG_SCHEMA: &str = include_str!("../schemas/lock-log-v0.schema.json");
+const STATS_SCHEMA: &str = include_str!("../schemas/lock-stats-v0.schema.json");
+const PROOF_VERIFY_SCHEMA: &str = include_str!("../schemas/lock-proof-verify-v0.schema.json");
 
 pub fn dispatch(
     robot_triage: bool,
@@ -78,7 +83,12 @@ fn health_report() -> Value {
     let checks = vec![
         operator_manifest_check(),
         lock_schema_check(),
+        lock_v1_schema_check(),
         verify_schema_check(),
+        diff_schema_check(),
+        log_schema_check(),
+        stats_schema_check(),
+        proof_verify_schema_check(),
         witness_path_check(),
         artifact_stdout_contract_check(),
     ];
@@ -114,15 +124,93 @@ fn capabilities_report() -> Value {
         "agent_surfaces": {
             "lock_creation": {
                 "command": "lock [<INPUT>] [OPTIONS]",
-                "output": "lock.v0 artifact JSON or REFUSAL envelope",
+                "output": "lock.v0 (or lock.v1 with --lock-version lock.v1) artifact JSON or REFUSAL envelope",
                 "mutates": true,
-                "notes": "May append a witness record by default and may write the lockfile when --output is provided."
+                "notes": "May append a witness record by default and may write the lockfile when --output is provided. lock.v1 adds members_root, a Merkle root over members."
+            },
+            "scan": {
+                "command": "lock scan <DIR> [--dataset-id <ID>] [--algorithm <sha256|blake3|sha512|sha3-256>] [--exclude <GLOB>]... [--keep-mtime] [--output <PATH>] [OPTIONS]",
+                "output": "lock.v0 (or lock.v1 with --lock-version lock.v1) artifact JSON or REFUSAL envelope",
+                "mutates": true,
+                "notes": "Reads and hashes every file under <DIR>; unreadable files become skipped entries (LOCK_PARTIAL). May write the lockfile when --output is provided and append a witness record unless --no-witness is provided."
             },
             "verify": {
-                "command": "lock verify <LOCKFILE> [--root <DIR>] [--json] [--no-witness] [--strict]",
+                "command": "lock verify <LOCKFILE> [--root <DIR> | --archive <FILE>] [--json] [--no-witness] [--strict] [--jobs <N>] [--fast] [--cache <FILE>] [--detect-extra [--exclude <GLOB>]...] [--signature <SIG> --trusted-keys <DIR>]",
                 "output": "human text or lock-verify.v0 JSON depending on --json",
                 "mutates": true,
-                "notes": "May append a witness record unless --no-witness is provided."
+                "notes": "May append a witness record unless --no-witness is provided. --signature with --trusted-keys adds a signature block; an invalid or untrusted signature is VERIFY_FAILED. --detect-extra walks --root (or --archive) and fails on files the lockfile does not list. --archive checks members against tar, tar.gz, or zip entries without extracting. --fast trusts matching size and recorded mtime instead of rehashing (VERIFIED_BY_METADATA). --cache reads and rewrites a local hash cache and lists reused digests in members.cache_hits."
+            },
+            "diff": {
+                "command": "lock diff <OLD> <NEW> [--json] [--no-witness]",
+                "output": "human text or lock-diff.v0 JSON depending on --json",
+                "mutates": true,
+                "notes": "Refuses unless both lockfiles pass self-hash verification. May append a witness record unless --no-witness is provided."
+            },
+            "update": {
+                "command": "lock update <BASE> [<INPUT>] [--prune] [--as-of <TS>] [--note <TEXT>] [--keep-mtime] [--keep-fields <F,...> | --keep-all-fields] [--output <PATH>] [--no-witness]",
+                "output": "lockfile JSON in the base's version (lock.v0 or lock.v1) or REFUSAL envelope",
+                "mutates": true,
+                "notes": "Refuses unless the base lockfile passes self-hash verification. Records the base lock_hash as parent. May write the lockfile when --output is provided and may append a witness record unless --no-witness is provided."
+            },
+            "rehash": {
+                "command": "lock rehash <LOCKFILE> --root <DIR> --add <ALGORITHM>... [--output <PATH>] [--no-witness]",
+                "output": "lockfile JSON in the input's version (lock.v0 or lock.v1) or REFUSAL envelope",
+                "mutates": true,
+                "notes": "Reads every member under --root once, checks its recorded digests, and records the --add digests in member hashes. Refuses with E_DRIFT if any member changed. Records the input lock_hash as parent. May write the lockfile when --output is provided and may append a witness record unless --no-witness is provided."
+            },
+            "export": {
+                "command": "lock export <LOCKFILE> --format <sha256sum|b3sum|bsd|csv|jsonl> [--output <PATH>] [--no-witness] | lock export <LOCKFILE> --format bagit --root <BAG_DIR> [--no-witness]",
+                "output": "checksum manifest text, BagIt tag files under --root, or REFUSAL envelope",
+                "mutates": true,
+                "notes": "Refuses unless the lockfile passes self-hash verification. sha256sum and b3sum refuse with E_NO_DIGEST when a member has no digest under their algorithm. Skipped entries are not exported. bagit writes tag files into --root (never the payload). May write the manifest when --output is provided and may append a witness record unless --no-witness is provided."
+            },
+            "import": {
+                "command": "lock import <MANIFEST> --format <sha256sum|b3sum> --root <DIR> [--dataset-id <ID>] [--as-of <TS>] [--note <TEXT>] [--output <PATH>] [--no-witness] | lock import <BAG_DIR> --format bagit [--dataset-id <ID>] [--as-of <TS>] [--note <TEXT>] [--output <PATH>] [--no-witness]",
+                "output": "lock.v0 JSON or REFUSAL envelope",
+                "mutates": true,
+                "notes": "Files under --root (or a bag's data/) are hashed to check the manifest's digests and give member sizes; a mismatch refuses with E_DRIFT, and unreadable files become skipped entries (LOCK_PARTIAL). A bag whose Payload-Oxum disagrees with its payload is refused with E_OXUM_MISMATCH. May write the lockfile when --output is provided and may append a witness record unless --no-witness is provided."
+            },
+            "log": {
+                "command": "lock log <LOCKFILE> --search-dir <DIR> [--json] [--no-witness]",
+                "output": "human text or lock-log.v0 JSON depending on --json",
+                "mutates": true,
+                "notes": "Reads .json files under --search-dir to follow parent links; files that are not self-hash-valid lockfiles are ignored. LOG_BROKEN (exit 1) when a parent is not found, LOG_CYCLE (exit 1) when a parent is already in the chain. May append a witness record unless --no-witness is provided."
+            },
+            "stats": {
+                "command": "lock stats <LOCKFILE> [--json] [--no-witness]",
+                "output": "human text or lock-stats.v0 JSON depending on --json",
+                "mutates": true,
+                "notes": "Refuses unless the lockfile passes self-hash verification. Reads only the lockfile, never the files it describes. May append a witness record unless --no-witness is provided."
+            },
+            "prove": {
+                "command": "lock prove <LOCKFILE> --path <PATH> [--output <PATH>] [--no-witness]",
+                "output": "lock-proof.v0 JSON inclusion proof or REFUSAL envelope",
+                "mutates": true,
+                "notes": "Refuses tampered lockfiles and paths that are not members. May write the proof when --output is provided and append a witness record unless --no-witness is provided."
+            },
+            "verify_proof": {
+                "command": "lock verify-proof <PROOF> [--file <PATH>] [--members-root <DIGEST> | --lockfile <PATH>] [--json] [--no-witness]",
+                "output": "human text or lock-proof-verify.v0 JSON depending on --json",
+                "mutates": true,
+                "notes": "Needs only the proof; --members-root or --lockfile pins the root it must reach. PROOF_INVALID (exit 1) when the audit path does not recompute members_root, the pin does not match, or --file does not match the member. PROOF_UNCOMMITTED (exit 1) whenever no pin is given; only a pinned proof is PROOF_VALID. May append a witness record unless --no-witness is provided."
+            },
+            "sign": {
+                "command": "lock sign <LOCKFILE> --key <KEYFILE> [--output <PATH>] [--no-witness]",
+                "output": "lock-signature.v0 JSON envelope or REFUSAL envelope",
+                "mutates": true,
+                "notes": "Refuses tampered lockfiles. May write the envelope when --output is provided and append a witness record unless --no-witness is provided."
+            },
+            "keygen": {
+                "command": "lock keygen --output <PATH> [--signer <NAME>]",
+                "output": "public lock-key.v0 JSON",
+                "mutates": true,
+                "notes": "Writes <PATH> (secret, mode 0600 on Unix) and <PATH>.pub; never overwrites existing files."
+            },
+            "cache": {
+                "command": "lock cache <stats|prune|clear> <FILE> [--json]",
+                "output": "human text or JSON summary of a verify --cache hash cache",
+                "mutates": true,
+                "notes": "stats is read-only; prune rewrites the cache without stale entries; clear deletes the cache file. Never appends witness records."
             },
             "robot_triage": {
                 "command": "lock --robot-triage",
@@ -160,7 +248,8 @@ fn capabilities_report() -> Value {
             "operator_describe": true,
             "schema_describe": true,
             "witness_query": true,
-            "streaming": false
+            "streaming": false,
+            "digest_algorithms": crate::digest::ALGORITHMS
         },
         "composition": {
             "family": {
@@ -269,10 +358,62 @@ fn capabilities_report() -> Value {
                 "command": "lock [<INPUT>] [OPTIONS]",
                 "description": "Create a self-hashed lock.v0 artifact from upstream JSONL."
             },
+            {
+                "command": "lock scan <DIR> --dataset-id <ID>",
+                "description": "Hash a directory directly and create the same lock.v0 artifact without the pipeline."
+            },
             {
                 "command": "lock verify <LOCKFILE> --json",
                 "description": "Verify lockfile integrity and optionally member content."
             },
+            {
+                "command": "lock diff <OLD> <NEW> --json",
+                "description": "Compare two lockfiles: members, fingerprints, skipped set, and tool versions."
+            },
+            {
+                "command": "lock update <BASE> [<INPUT>]",
+                "description": "Apply new pipeline records to an existing lockfile and link it to the base via parent."
+            },
+            {
+                "command": "lock rehash <LOCKFILE> --root <DIR> --add blake3",
+                "description": "Record digests under additional algorithms for every member, re-sealed with the input as parent."
+            },
+            {
+                "command": "lock export <LOCKFILE> --format sha256sum",
+                "description": "Render verified members as a sha256sum, b3sum, BSD, CSV, JSONL, or BagIt manifest."
+            },
+            {
+                "command": "lock import <MANIFEST> --format sha256sum --root <DIR>",
+                "description": "Bring a legacy sha256sum or b3sum manifest, or a BagIt bag, under a self-hashed lock.v0 lockfile."
+            },
+            {
+                "command": "lock log <LOCKFILE> --search-dir <DIR> --json",
+                "description": "Walk a lockfile's parent chain through lockfiles on disk with per-step member counts."
+            },
+            {
+                "command": "lock stats <LOCKFILE> --json",
+                "description": "Summarize members by size, extension, top-level directory, hash algorithm, and fingerprint, with skip warnings by tool and code."
+            },
+            {
+                "command": "lock prove <LOCKFILE> --path <PATH>",
+                "description": "Emit a self-contained inclusion proof for one member against the members Merkle root."
+            },
+            {
+                "command": "lock verify-proof <PROOF> --lockfile <LOCKFILE> [--file <PATH>] --json",
+                "description": "Check an inclusion proof against a trusted lockfile, and optionally a local file against the proven member."
+            },
+            {
+                "command": "lock sign <LOCKFILE> --key <KEYFILE>",
+                "description": "Write a detached Ed25519 signature envelope over lock_hash."
+            },
+            {
+                "command": "lock keygen --output <PATH>",
+                "description": "Generate a local Ed25519 key pair for signing."
+            },
+            {
+                "command": "lock cache stats <FILE> --json",
+                "description": "Summarize, prune, or clear a verify --cache hash cache."
+            },
             {
                 "command": "lock doctor health",
                 "json": "lock doctor health --json",
@@ -306,11 +447,36 @@ fn capabilities_report() -> Value {
                 "mode": "compiled_static_json",
                 "mutates": false
             },
+            {
+                "name": "lock_v1_schema",
+                "mode": "compiled_static_json",
+                "mutates": false
+            },
             {
                 "name": "verify_schema",
                 "mode": "compiled_static_json",
                 "mutates": false
             },
+            {
+                "name": "diff_schema",
+                "mode": "compiled_static_json",
+                "mutates": false
+            },
+            {
+                "name": "log_schema",
+                "mode": "compiled_static_json",
+                "mutates": false
+            },
+            {
+                "name": "stats_schema",
+                "mode": "compiled_static_json",
+                "mutates": false
+            },
+            {
+                "name": "proof_verify_schema",
+                "mode": "compiled_static_json",
+                "mutates": false
+            },
             {
                 "name": "witness_path_resolution",
                 "mode": "environment_resolution_only",
@@ -323,8 +489,12 @@ fn capabilities_report() -> Value {
             }
         ],
         "output_contract": {
-            "lock_stdout": "lock.v0 artifact JSON or REFUSAL envelope",
+            "lock_stdout": "lock.v0 or lock.v1 artifact JSON or REFUSAL envelope",
             "verify_stdout": "human text or lock-verify.v0 JSON depending on --json",
+            "diff_stdout": "human text or lock-diff.v0 JSON depending on --json",
+            "log_stdout": "human text or lock-log.v0 JSON depending on --json",
+            "stats_stdout": "human text or lock-stats.v0 JSON depending on --json",
+            "proof_verify_stdout": "human text or lock-proof-verify.v0 JSON depending on --json",
             "doctor_stdout": "human text or JSON doctor reports",
             "doctor_stderr": "unused on successful doctor commands"
         },
@@ -414,6 +584,15 @@ fn lock_schema_check() -> Value {
     )
 }
 
+fn lock_v1_schema_check() -> Value {
+    schema_title_check(
+        "lock_v1_schema",
+        LOCK_V1_SCHEMA,
+        "lock.v1",
+        "schemas/lock-v1.schema.json",
+    )
+}
+
 fn verify_schema_check() -> Value {
     schema_title_check(
         "verify_schema",
@@ -423,6 +602,42 @@ fn verify_schema_check() -> Value {
     )
 }
 
+fn diff_schema_check() -> Value {
+    schema_title_check(
+        "diff_schema",
+        DIFF_SCHEMA,
+        "lock-diff.v0",
+        "schemas/lock-diff-v0.schema.json",
+    )
+}
+
+fn log_schema_check() -> Value {
+    schema_title_check(
+        "log_schema",
+        LOG_SCHEMA,
+        "lock-log.v0",
+        "schemas/lock-log-v0.schema.json",
+    )
+}
+
+fn stats_schema_check() -> Value {
+    schema_title_check(
+        "stats_schema",
+        STATS_SCHEMA,
+        "lock-stats.v0",
+        "schemas/lock-stats-v0.schema.json",
+    )
+}
+
+fn proof_verify_schema_check() -> Value {
+    schema_title_check(
+        "proof_verify_schema",
+        PROOF_VERIFY_SCHEMA,
+        "lock-proof-verify.v0",
+        "schemas/lock-proof-verify-v0.schema.json",
+    )
+}
+
 fn schema_title_check(name: &str, source: &str, expected_title: &str, source_path: &str) -> Value {
     let parsed = match serde_json::from_str::<Value>(source) {
         Ok(value) => value,
@@ -603,7 +818,25 @@ fn print_robot_docs(action: Option<&RobotDocsAction>) {
     println!("- `lock capabilities --json` for command and side-effect policy.");
     println!("- `lock robot-docs guide` for this agent-oriented guide.");
     println!("- `lock [<INPUT>] [OPTIONS]` to create a lock.v0 artifact.");
+    println!("- `lock scan <DIR> --dataset-id <ID>` to lock a directory without the pipeline.");
     println!("- `lock verify <LOCKFILE> --json` to verify lockfile integrity.");
+    println!("- `lock diff <OLD> <NEW> --json` to compare two lockfiles.");
+    println!("- `lock update <BASE> [<INPUT>]` to apply new records to an existing lockfile.");
+    println!(
+        "- `lock rehash <LOCKFILE> --root <DIR> --add blake3` to add digests under another algorithm."
+    );
+    println!("- `lock export <LOCKFILE> --format sha256sum` to hand members to coreutils tools.");
+    println!(
+        "- `lock import <MANIFEST> --format sha256sum --root <DIR>` to lock a legacy checksum file."
+    );
+    println!("- `lock log <LOCKFILE> --search-dir <DIR> --json` to walk a lockfile's lineage.");
+    println!("- `lock stats <LOCKFILE> --json` to summarize a lockfile's contents.");
+    println!("- `lock prove <LOCKFILE> --path <PATH>` to emit a single-member inclusion proof.");
+    println!(
+        "- `lock verify-proof <PROOF> --members-root <DIGEST> --json` to check an inclusion proof against a trusted root."
+    );
+    println!("- `lock sign <LOCKFILE> --key <KEYFILE>` to sign a lockfile's lock_hash.");
+    println!("- `lock cache stats <FILE> --json` to inspect a verify hash cache.");
     println!("- `lock doctor health` for human health output.");
     println!("- `lock doctor health --json` for machine-readable health.");
     println!("- `lock doctor capabilities --json` for command and side-effect policy.");
@@ -0,0 +1,78 @@
+//! Minimal glob matching for `/`-separated relative paths.
+//!
+//! Supported syntax: `*` matches any run of characters within one path
+//! segment, `?` matches one character other than `/`, and `**` as a whole
+//! segment matches zero or more segments. A pattern without `/` is matched
+//! against the file name alone, so `*.tmp` excludes temp files at any depth.
+
+/// Whether `path` matches `pattern`.
+pub fn glob_match(pattern: &str, path: &str) -> bool {
+    if !pattern.contains('/') {
+        let name = path.rsplit('/').next().unwrap_or(path);
+        return segment_match(pattern.as_bytes(), name.as_bytes());
+    }
+    let pattern: Vec<&str> = pattern.split('/').collect();
+    let path: Vec<&str> = path.split('/').collect();
+    segments_match(&pattern, &path)
+}
+
+/// Whether `path` matches any of `patterns`.
+pub fn any_match(patterns: &[String], path: &str) -> bool {
+    patterns.iter().any(|pattern| glob_match(pattern, path))
+}
+
+fn segments_match(pattern: &[&str], path: &[&str]) -> bool {
+    match pattern.split_first() {
+        None => path.is_empty(),
+        Some((&"**", rest)) => (0..=path.len()).any(|skip| segments_match(rest, &path[skip..])),
+        Some((first, rest)) => path.split_first().is_some_and(|(segment, path_rest)| {
+            segment_match(first.as_bytes(), segment.as_bytes()) && segments_match(rest, path_rest)
+        }),
+    }
+}
+
+fn segment_match(pattern: &[u8], text: &[u8]) -> bool {
+    match pattern.split_first() {
+        None => text.is_empty(),
+        Some((b'*', rest)) => (0..=text.len()).any(|skip| segment_match(rest, &text[skip..])),
+        Some((b'?', rest)) => !text.is_empty() && segment_match(rest, &text[1..]),
+        Some((c, rest)) => text.first() == Some(c) && segment_match(rest, &text[1..]),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn bare_pattern_matches_file_name_at_any_depth() {
+        assert!(glob_match("*.tmp", "a.tmp"));
+        assert!(glob_match("*.tmp", "deep/dir/a.tmp"));
+        assert!(glob_match(".DS_Store", "x/.DS_Store"));
+        assert!(!glob_match("*.tmp", "a.tmp.csv"));
+    }
+
+    #[test]
+    fn slash_pattern_matches_whole_path() {
+        assert!(glob_match("staging/*.csv", "staging/a.csv"));
+        assert!(!glob_match("staging/*.csv", "staging/nested/a.csv"));
+        assert!(!glob_match("staging/*.csv", "other/staging/a.csv"));
+        assert!(glob_match("data/??.csv", "data/ab.csv"));
+        assert!(!glob_match("data/??.csv", "data/abc.csv"));
+    }
+
+    #[test]
+    fn double_star_spans_segments() {
+        assert!(glob_match("logs/**", "logs/a.txt"));
+        assert!(glob_match("logs/**", "logs/2026/01/a.txt"));
+        assert!(glob_match("**/cache/*", "cache/x"));
+        assert!(glob_match("**/cache/*", "a/b/cache/x"));
+        assert!(!glob_match("logs/**/*.txt", "other/a.txt"));
+    }
+
+    #[test]
+    fn star_does_not_cross_slash() {
+        assert!(!glob_match("a/*", "a/b/c"));
+        assert!(glob_match("a/*/c", "a/b/c"));
+    }
+}
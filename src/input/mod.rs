@@ -1,5 +1,5 @@
 use std::fs::File;
-use std::io::{self, BufRead, BufReader, Cursor, Read};
+use std::io::{self, BufRead, BufReader, Read};
 use std::path::Path;
 
 use serde_json::Value;
@@ -32,6 +32,14 @@ pub struct ReadWithSource {
     pub source: SourceMetadata,
 }
 
+/// Result of a streaming read: records were handed to the caller one at a
+/// time, so only their count is kept.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct StreamWithSource {
+    pub record_count: usize,
+    pub source: SourceMetadata,
+}
+
 #[derive(Debug, Clone, PartialEq, Eq)]
 pub struct ParseErrorDetail {
     pub line: usize,
@@ -101,46 +109,168 @@ pub fn read_jsonl(input: Option<&Path>) -> Result<ReadResult, InputError> {
 }
 
 pub fn read_jsonl_with_source(input: Option<&Path>) -> Result<ReadWithSource, ReadWithSourceError> {
+    let mut records = Vec::new();
+    let stream = stream_jsonl_with_source(input, |record| records.push(record))?;
+
+    let result = if records.is_empty() {
+        ReadResult::Empty
+    } else {
+        ReadResult::Records(records)
+    };
+
+    Ok(ReadWithSource {
+        result,
+        source: stream.source,
+    })
+}
+
+/// Parse JSONL from a file (or stdin) one line at a time, handing each record
+/// to `on_record` as soon as it is parsed.
+///
+/// The source is hashed with blake3 as it is read, so memory use is bounded
+/// by the longest line rather than the whole input. On a parse error the rest
+/// of the input is still drained into the hash, so the reported source hash
+/// always covers the full input, matching a buffered read.
+pub fn stream_jsonl_with_source<F>(
+    input: Option<&Path>,
+    on_record: F,
+) -> Result<StreamWithSource, ReadWithSourceError>
+where
+    F: FnMut(InputRecord),
+{
     match input {
         Some(path) => {
             let file = File::open(path).map_err(|error| ReadWithSourceError {
                 error: InputError::Io(error),
                 source: None,
             })?;
-            read_jsonl_source_reader(BufReader::new(file))
+            stream_jsonl_source_reader(file, on_record)
         }
         None => {
             let stdin = io::stdin();
-            read_jsonl_source_reader(stdin.lock())
+            stream_jsonl_source_reader(stdin.lock(), on_record)
         }
     }
 }
 
-fn read_jsonl_source_reader<R>(mut reader: R) -> Result<ReadWithSource, ReadWithSourceError>
+fn stream_jsonl_source_reader<R, F>(
+    reader: R,
+    mut on_record: F,
+) -> Result<StreamWithSource, ReadWithSourceError>
 where
     R: Read,
+    F: FnMut(InputRecord),
 {
-    let mut bytes = Vec::new();
-    reader
-        .read_to_end(&mut bytes)
-        .map_err(|error| ReadWithSourceError {
-            error: InputError::Io(error),
-            source: None,
-        })?;
-
-    let source = SourceMetadata {
-        source_hash: format!("blake3:{}", blake3::hash(&bytes).to_hex()),
-        source_bytes: bytes.len() as u64,
+    let read_failed = |error| ReadWithSourceError {
+        error: InputError::Io(error),
+        source: None,
     };
 
-    let result = read_jsonl_reader(BufReader::new(Cursor::new(bytes))).map_err(|error| {
-        ReadWithSourceError {
+    let mut reader = BufReader::new(HashingReader::new(reader));
+    let mut line = Vec::new();
+    let mut line_number = 0;
+    let mut record_count = 0;
+    let mut failure = None;
+
+    loop {
+        line.clear();
+        if reader.read_until(b'\n', &mut line).map_err(read_failed)? == 0 {
+            break;
+        }
+        line_number += 1;
+
+        match parse_line_bytes(&line, line_number) {
+            Ok(value) => {
+                record_count += 1;
+                on_record(InputRecord { line_number, value });
+            }
+            Err(error) => {
+                failure = Some(error);
+                break;
+            }
+        }
+    }
+
+    if failure.is_some() {
+        io::copy(&mut reader, &mut io::sink()).map_err(read_failed)?;
+    }
+    let source = reader.into_inner().finish();
+
+    match failure {
+        Some(error) => Err(ReadWithSourceError {
             error,
-            source: Some(source.clone()),
+            source: Some(source),
+        }),
+        None => Ok(StreamWithSource {
+            record_count,
+            source,
+        }),
+    }
+}
+
+/// Reader adapter that blake3-hashes and counts every byte passing through.
+struct HashingReader<R> {
+    inner: R,
+    hasher: blake3::Hasher,
+    bytes: u64,
+}
+
+impl<R> HashingReader<R> {
+    fn new(inner: R) -> Self {
+        Self {
+            inner,
+            hasher: blake3::Hasher::new(),
+            bytes: 0,
+        }
+    }
+
+    fn finish(self) -> SourceMetadata {
+        SourceMetadata {
+            source_hash: format!("blake3:{}", self.hasher.finalize().to_hex()),
+            source_bytes: self.bytes,
         }
+    }
+}
+
+impl<R: Read> Read for HashingReader<R> {
+    fn read(&mut self, buf: &mut [u8]) -> io::Result<usize> {
+        let n = self.inner.read(buf)?;
+        self.hasher.update(&buf[..n]);
+        self.bytes += n as u64;
+        Ok(n)
+    }
+}
+
+/// Decode one raw line (as returned by `read_until`) with the same newline
+/// and UTF-8 handling as `BufRead::lines`, then parse it.
+fn parse_line_bytes(raw: &[u8], line_number: usize) -> Result<Value, InputError> {
+    let mut raw = raw;
+    if let Some(stripped) = raw.strip_suffix(b"\n") {
+        raw = stripped.strip_suffix(b"\r").unwrap_or(stripped);
+    }
+    let line = std::str::from_utf8(raw).map_err(|_| {
+        InputError::Io(io::Error::new(
+            io::ErrorKind::InvalidData,
+            "stream did not contain valid UTF-8",
+        ))
     })?;
+    parse_line(line, line_number)
+}
+
+fn parse_line(line: &str, line_number: usize) -> Result<Value, InputError> {
+    if line.trim().is_empty() {
+        return Err(InputError::Parse(ParseErrorDetail {
+            line: line_number,
+            error: "line is empty; expected one JSON value per line".to_owned(),
+        }));
+    }
 
-    Ok(ReadWithSource { result, source })
+    serde_json::from_str::<Value>(line).map_err(|error| {
+        InputError::Parse(ParseErrorDetail {
+            line: line_number,
+            error: error.to_string(),
+        })
+    })
 }
 
 pub fn read_jsonl_reader<R>(reader: R) -> Result<ReadResult, InputError>
@@ -152,21 +282,7 @@ where
     for (index, line_result) in reader.lines().enumerate() {
         let line_number = index + 1;
         let line = line_result.map_err(InputError::Io)?;
-
-        if line.trim().is_empty() {
-            return Err(InputError::Parse(ParseErrorDetail {
-                line: line_number,
-                error: "line is empty; expected one JSON value per line".to_owned(),
-            }));
-        }
-
-        let value = serde_json::from_str::<Value>(&line).map_err(|error| {
-            InputError::Parse(ParseErrorDetail {
-                line: line_number,
-                error: error.to_string(),
-            })
-        })?;
-
+        let value = parse_line(&line, line_number)?;
         records.push(InputRecord { line_number, value });
     }
 
@@ -178,32 +294,58 @@ where
 }
 
 pub fn validate_records(records: &[InputRecord]) -> Result<(), ValidationError> {
-    let mut missing_hash_paths = Vec::new();
-
+    let mut validator = RecordValidator::default();
     for record in records {
-        validate_version(record)?;
+        validator.observe(record);
+    }
+    validator.finish()
+}
+
+/// Incremental form of [`validate_records`] for streamed input.
+///
+/// Reports the first bad version, otherwise the missing-hash count and
+/// sample, exactly as a single pass over the full record list would.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct RecordValidator {
+    bad_version: Option<VersionErrorDetail>,
+    missing_hash_count: usize,
+    missing_hash_sample: Vec<String>,
+}
 
-        if is_skipped(record) {
-            continue;
+impl RecordValidator {
+    pub fn observe(&mut self, record: &InputRecord) {
+        if self.bad_version.is_some() {
+            return;
         }
 
-        if !has_non_empty_string_field(&record.value, "bytes_hash") {
-            missing_hash_paths.push(path_for_missing_hash(record));
+        if let Err(ValidationError::BadVersion(detail)) = validate_version(record) {
+            self.bad_version = Some(detail);
+            return;
+        }
+
+        if is_skipped(record) || has_non_empty_string_field(&record.value, "bytes_hash") {
+            return;
+        }
+
+        self.missing_hash_count += 1;
+        if self.missing_hash_sample.len() < MISSING_HASH_SAMPLE_LIMIT {
+            self.missing_hash_sample.push(path_for_missing_hash(record));
         }
     }
 
-    if missing_hash_paths.is_empty() {
-        Ok(())
-    } else {
-        let sample_paths = missing_hash_paths
-            .iter()
-            .take(MISSING_HASH_SAMPLE_LIMIT)
-            .cloned()
-            .collect();
-        Err(ValidationError::MissingHash(MissingHashDetail {
-            count: missing_hash_paths.len(),
-            sample_paths,
-        }))
+    pub fn finish(self) -> Result<(), ValidationError> {
+        if let Some(detail) = self.bad_version {
+            return Err(ValidationError::BadVersion(detail));
+        }
+
+        if self.missing_hash_count == 0 {
+            Ok(())
+        } else {
+            Err(ValidationError::MissingHash(MissingHashDetail {
+                count: self.missing_hash_count,
+                sample_paths: self.missing_hash_sample,
+            }))
+        }
     }
 }
 
@@ -257,7 +399,7 @@ mod tests {
 
     use super::{
         InputError, InputRecord, ReadResult, ValidationError, read_jsonl, read_jsonl_reader,
-        read_jsonl_with_source, validate_records,
+        read_jsonl_with_source, stream_jsonl_source_reader, validate_records,
     };
 
     #[test]
@@ -352,6 +494,56 @@ mod tests {
         assert!(matches!(read.result, ReadResult::Records(_)));
     }
 
+    #[test]
+    fn stream_delivers_records_in_order_with_source_hash() {
+        let input = b"{\"path\":\"a\"}\r\n{\"path\":\"b\"}";
+        let mut seen = Vec::new();
+
+        let stream = stream_jsonl_source_reader(Cursor::new(input), |record| seen.push(record))
+            .expect("valid JSONL should stream");
+
+        assert_eq!(stream.record_count, 2);
+        assert_eq!(seen[0].line_number, 1);
+        assert_eq!(seen[1].value["path"], "b");
+        assert_eq!(
+            stream.source.source_hash,
+            format!("blake3:{}", blake3::hash(input).to_hex())
+        );
+        assert_eq!(stream.source.source_bytes, input.len() as u64);
+    }
+
+    #[test]
+    fn stream_parse_error_still_hashes_full_input() {
+        let mut input = b"{\"path\":\"ok\"}\nnot-json\n".to_vec();
+        input.extend(std::iter::repeat_n(b'x', 64 * 1024));
+
+        let error = stream_jsonl_source_reader(Cursor::new(&input), |_| {})
+            .expect_err("invalid JSON must error");
+
+        assert_eq!(error.error.parse_detail().map(|d| d.line), Some(2));
+        let source = error.source.expect("source is known after a parse error");
+        assert_eq!(
+            source.source_hash,
+            format!("blake3:{}", blake3::hash(&input).to_hex())
+        );
+        assert_eq!(source.source_bytes, input.len() as u64);
+    }
+
+    #[test]
+    fn stream_invalid_utf8_is_io_error_with_source() {
+        let input = b"{\"path\":\"ok\"}\n\xff\xfe\n";
+
+        let error = stream_jsonl_source_reader(Cursor::new(input), |_| {})
+            .expect_err("invalid UTF-8 must error");
+
+        assert!(matches!(error.error, InputError::Io(_)));
+        assert_eq!(
+            error.error.to_string(),
+            "I/O error while reading input: stream did not contain valid UTF-8"
+        );
+        assert!(error.source.is_some());
+    }
+
     #[test]
     fn validate_records_rejects_missing_version() {
         let records = vec![InputRecord {
@@ -2,13 +2,26 @@
 
 use chrono::{SecondsFormat, Utc};
 
+pub mod cache;
+pub mod checksums;
 pub mod cli;
+pub mod diff;
+pub mod digest;
 pub mod doctor;
+pub mod glob;
 pub mod input;
 pub mod lockfile;
+pub mod log;
 pub mod output;
+pub mod proof;
 pub mod refusal;
+pub mod rehash;
+pub mod scan;
+pub mod signing;
+pub mod stats;
+pub mod update;
 pub mod verify;
+pub mod walk;
 pub mod witness;
 
 pub fn run() -> u8 {
@@ -16,138 +29,252 @@ pub fn run() -> u8 {
 }
 
 #[derive(Debug, Clone, PartialEq, Eq)]
-struct OrchestrationOutput {
-    outcome: output::DomainOutcome,
-    payload_json: String,
+pub(crate) struct OrchestrationOutput {
+    pub(crate) outcome: output::DomainOutcome,
+    pub(crate) payload_json: String,
+}
+
+/// JSONL input after streaming it through validation and classification.
+pub(crate) struct IngestedInput {
+    pub(crate) result: Result<Ingested, OrchestrationOutput>,
+    /// Hash and size of the input bytes, when the input could be read.
+    pub(crate) source: Option<input::SourceMetadata>,
+}
+
+pub(crate) struct Ingested {
+    pub(crate) classification: lockfile::Classification,
+    pub(crate) metadata: lockfile::MetadataHydration,
 }
 
 pub fn run_lock(cli: &cli::Cli) -> u8 {
-    let (orchestrated, input_hash, input_bytes) =
-        match input::read_jsonl_with_source(cli.input.as_deref()) {
-            Ok(read) => (
-                orchestrate_from_read_result(cli, read.result),
-                Some(read.source.source_hash),
-                Some(read.source.source_bytes),
-            ),
-            Err(error) => {
-                let input_hash = error
-                    .source
-                    .as_ref()
-                    .map(|source| source.source_hash.clone());
-                let input_bytes = error.source.as_ref().map(|source| source.source_bytes);
-
-                let orchestrated = match error.error {
-                    input::InputError::Parse(detail) => {
-                        refusal_output(refusal::bad_input_parse(detail.line, &detail.error))
-                    }
-                    input::InputError::Io(io_error) => {
-                        refusal_output(refusal::bad_input_parse(0, &io_error.to_string()))
-                    }
-                };
-
-                (orchestrated, input_hash, input_bytes)
+    // Resolve --parent before consuming input, so a bad parent refuses fast.
+    let parent = match cli.parent.as_deref().map(resolve_parent).transpose() {
+        Ok(parent) => parent,
+        Err(refusal) => {
+            let orchestrated = lockfile_refusal_output(refusal, &cli.lock_version);
+            if !write_payload(cli.output.as_deref(), &orchestrated.payload_json) {
+                return 2;
             }
-        };
-
-    if let Some(ref output_path) = cli.output {
-        if let Err(e) = std::fs::write(output_path, &orchestrated.payload_json) {
-            eprintln!(
-                "lock: error: failed to write output file '{}': {e}",
-                output_path.display()
-            );
-            return 2;
+            if !cli.no_witness {
+                emit_lock_witness(cli, &orchestrated, None, None);
+            }
+            return orchestrated.outcome.exit_code();
         }
-    } else {
-        print!("{}", orchestrated.payload_json);
+    };
+
+    let ingested = ingest_input(
+        cli.input.as_deref(),
+        cli.dataset_id.as_deref(),
+        cli.as_of.as_deref(),
+        cli.note.as_deref(),
+        lockfile::ClassifyOptions {
+            keep_mtime: cli.keep_mtime,
+            keep_fields: lockfile::KeepFields::from_flags(&cli.keep_fields, cli.keep_all_fields),
+        },
+    );
+    let orchestrated = match ingested.result {
+        Ok(ingested) => seal_lockfile(new_lockfile(ingested, &cli.lock_version, parent.clone())),
+        Err(refusal) => refusal,
+    };
+
+    if !write_payload(cli.output.as_deref(), &orchestrated.payload_json) {
+        return 2;
     }
 
     // Append witness record unless --no-witness.
     if !cli.no_witness {
-        let input_path = cli
-            .input
-            .as_ref()
-            .map(|p| p.display().to_string())
-            .unwrap_or_else(|| "stdin".to_string());
-
-        let outcome_str = match orchestrated.outcome {
-            output::DomainOutcome::LockCreated => "LOCK_CREATED",
-            output::DomainOutcome::LockPartial => "LOCK_PARTIAL",
-            output::DomainOutcome::Refusal => "REFUSAL",
-        };
-
-        let params = serde_json::json!({
-            "dataset_id": cli.dataset_id,
-            "as_of": cli.as_of,
-            "note": cli.note,
-        });
-
-        let inputs =
-            serde_json::json!([{ "path": input_path, "hash": input_hash, "bytes": input_bytes }]);
-
-        witness::append_witness_record(
-            outcome_str,
-            orchestrated.outcome.exit_code(),
-            orchestrated.payload_json.as_bytes(),
-            params,
-            inputs,
+        emit_lock_witness(
+            cli,
+            &orchestrated,
+            ingested.source.as_ref(),
+            parent.as_deref(),
         );
     }
 
     orchestrated.outcome.exit_code()
 }
 
-fn orchestrate_from_read_result(
+/// Load the `--parent` lockfile, requiring a valid self-hash, and return
+/// its `lock_hash`: the value the new lockfile records as `parent`.
+pub(crate) fn resolve_parent(path: &std::path::Path) -> Result<String, verify::LockfileRefusal> {
+    let value = verify::load_sealed_lockfile(path)?;
+    Ok(value
+        .get("lock_hash")
+        .and_then(|v| v.as_str())
+        .unwrap_or_default()
+        .to_owned())
+}
+
+fn emit_lock_witness(
     cli: &cli::Cli,
-    read_result: input::ReadResult,
-) -> OrchestrationOutput {
-    let input::ReadResult::Records(records) = read_result else {
-        return refusal_output(refusal::empty());
-    };
+    orchestrated: &OrchestrationOutput,
+    source: Option<&input::SourceMetadata>,
+    parent: Option<&str>,
+) {
+    let input_path = cli
+        .input
+        .as_ref()
+        .map(|p| p.display().to_string())
+        .unwrap_or_else(|| "stdin".to_string());
+
+    let params = serde_json::json!({
+        "dataset_id": cli.dataset_id,
+        "as_of": cli.as_of,
+        "note": cli.note,
+    });
+
+    let mut inputs = vec![serde_json::json!({
+        "path": input_path,
+        "hash": source.map(|s| s.source_hash.clone()),
+        "bytes": source.map(|s| s.source_bytes),
+    })];
+    if let Some(path) = &cli.parent {
+        inputs.push(serde_json::json!({
+            "path": path.display().to_string(),
+            "hash": parent,
+            "bytes": null,
+        }));
+    }
+
+    witness::append_witness_record(
+        orchestrated.outcome.as_str(),
+        orchestrated.outcome.exit_code(),
+        orchestrated.payload_json.as_bytes(),
+        params,
+        serde_json::Value::Array(inputs),
+    );
+}
 
-    if let Err(error) = input::validate_records(&records) {
-        return match error {
+/// Stream JSONL from `input` (or stdin) through the validator and classifier.
+pub(crate) fn ingest_input(
+    input: Option<&std::path::Path>,
+    dataset_id: Option<&str>,
+    as_of: Option<&str>,
+    note: Option<&str>,
+    options: lockfile::ClassifyOptions,
+) -> IngestedInput {
+    let mut validator = input::RecordValidator::default();
+    let mut classifier = lockfile::RecordClassifier::new(options);
+    let streamed = input::stream_jsonl_with_source(input, |record| {
+        validator.observe(&record);
+        classifier.push(&record);
+    });
+
+    match streamed {
+        Ok(read) => IngestedInput {
+            result: finish_ingest(
+                read.record_count,
+                validator,
+                classifier,
+                dataset_id,
+                as_of,
+                note,
+            ),
+            source: Some(read.source),
+        },
+        Err(error) => {
+            let envelope = match error.error {
+                input::InputError::Parse(detail) => {
+                    refusal::bad_input_parse(detail.line, &detail.error)
+                }
+                input::InputError::Io(io_error) => {
+                    refusal::bad_input_parse(0, &io_error.to_string())
+                }
+            };
+            IngestedInput {
+                result: Err(refusal_output(envelope)),
+                source: error.source,
+            }
+        }
+    }
+}
+
+/// Resolve records already fed through the validator and classifier.
+/// Refusal precedence matches a buffered pass: empty input, then
+/// version/missing-hash validation, then classification.
+pub(crate) fn finish_ingest(
+    record_count: usize,
+    validator: input::RecordValidator,
+    classifier: lockfile::RecordClassifier,
+    dataset_id: Option<&str>,
+    as_of: Option<&str>,
+    note: Option<&str>,
+) -> Result<Ingested, OrchestrationOutput> {
+    if record_count == 0 {
+        return Err(refusal_output(refusal::empty()));
+    }
+
+    if let Err(error) = validator.finish() {
+        return Err(refusal_output(match error {
             input::ValidationError::BadVersion(detail) => {
                 let version = detail.version.as_deref().unwrap_or("<missing>");
-                refusal_output(refusal::bad_input_version(detail.line, version))
+                refusal::bad_input_version(detail.line, version)
             }
             input::ValidationError::MissingHash(detail) => {
-                refusal_output(refusal::missing_hash(detail.count, detail.sample_paths))
+                refusal::missing_hash(detail.count, detail.sample_paths)
             }
-        };
+        }));
     }
 
-    let classification = match lockfile::classify_records(&records) {
-        Ok(classification) => classification,
-        Err(error) => {
-            let (line, message) = match error {
-                lockfile::ClassificationError::MissingPath { line_number } => {
-                    (line_number, "missing path/relative_path")
-                }
-                lockfile::ClassificationError::MissingBytesHash { line_number } => {
-                    (line_number, "missing bytes_hash")
-                }
-                lockfile::ClassificationError::MissingSize { line_number } => {
-                    (line_number, "missing size")
-                }
-            };
-            return refusal_output(refusal::bad_input_parse(line, message));
-        }
-    };
+    let metadata = classifier.hydrate_metadata(env!("CARGO_PKG_VERSION"), dataset_id, as_of, note);
 
-    let metadata = lockfile::hydrate_metadata(
-        &records,
-        env!("CARGO_PKG_VERSION"),
-        cli.dataset_id.as_deref(),
-        cli.as_of.as_deref(),
-        cli.note.as_deref(),
-    );
+    let classification = classifier.finish().map_err(|error| {
+        let (line, message) = match error {
+            lockfile::ClassificationError::MissingPath { line_number } => {
+                (line_number, "missing path/relative_path".to_owned())
+            }
+            lockfile::ClassificationError::MissingBytesHash { line_number } => {
+                (line_number, "missing bytes_hash".to_owned())
+            }
+            lockfile::ClassificationError::MissingSize { line_number } => {
+                (line_number, "missing size".to_owned())
+            }
+            lockfile::ClassificationError::InvalidHashes { line_number } => (
+                line_number,
+                "hashes must map algorithms to digests agreeing with bytes_hash".to_owned(),
+            ),
+            lockfile::ClassificationError::UnknownAlgorithm {
+                line_number,
+                algorithm,
+            } => (
+                line_number,
+                format!("unknown digest algorithm `{algorithm}`; see `lock capabilities --json`"),
+            ),
+            lockfile::ClassificationError::NonCryptographicBytesHash {
+                line_number,
+                algorithm,
+            } => (
+                line_number,
+                format!("{algorithm} is not cryptographic and cannot be a member's bytes_hash"),
+            ),
+        };
+        refusal_output(refusal::bad_input_parse(line, &message))
+    })?;
+
+    Ok(Ingested {
+        classification,
+        metadata,
+    })
+}
 
-    let mut lockfile = lockfile::Lockfile {
-        version: refusal::LOCK_VERSION.to_owned(),
+pub(crate) fn new_lockfile(
+    ingested: Ingested,
+    version: &str,
+    parent: Option<String>,
+) -> lockfile::Lockfile {
+    let Ingested {
+        classification,
+        metadata,
+    } = ingested;
+
+    lockfile::Lockfile {
+        version: version.to_owned(),
         lock_hash: String::new(),
         dataset_id: metadata.dataset_id,
         as_of: metadata.as_of,
         note: metadata.note,
+        parent,
+        members_root: None,
         created: current_created_timestamp(),
         tool_versions: metadata.tool_versions,
         profiles: metadata.profiles,
@@ -155,8 +282,14 @@ fn orchestrate_from_read_result(
         members: classification.members,
         skipped_count: classification.skipped_count,
         member_count: classification.member_count,
-    };
+    }
+}
 
+/// Compute `members_root` (`lock.v1`) and `lock_hash`, and render the
+/// lockfile as the output payload.
+pub(crate) fn seal_lockfile(mut lockfile: lockfile::Lockfile) -> OrchestrationOutput {
+    lockfile.members_root = (lockfile.version == refusal::LOCK_V1_VERSION)
+        .then(|| lockfile::merkle::members_root(&lockfile.members));
     lockfile.lock_hash = lockfile::self_hash::compute_lock_hash(&lockfile);
 
     match output::render_lockfile(&lockfile) {
@@ -168,14 +301,47 @@ fn orchestrate_from_read_result(
     }
 }
 
-fn refusal_output(envelope: refusal::RefusalEnvelope) -> OrchestrationOutput {
+/// Write the payload to `--output` or stdout. Returns `false` (after
+/// reporting on stderr) when the output file cannot be written.
+pub(crate) fn write_payload(output_path: Option<&std::path::Path>, payload: &str) -> bool {
+    match output_path {
+        Some(output_path) => match std::fs::write(output_path, payload) {
+            Ok(()) => true,
+            Err(e) => {
+                eprintln!(
+                    "lock: error: failed to write output file '{}': {e}",
+                    output_path.display()
+                );
+                false
+            }
+        },
+        None => {
+            print!("{payload}");
+            true
+        }
+    }
+}
+
+pub(crate) fn refusal_output(envelope: refusal::RefusalEnvelope) -> OrchestrationOutput {
     OrchestrationOutput {
         outcome: output::DomainOutcome::Refusal,
         payload_json: envelope.to_json(),
     }
 }
 
-fn current_created_timestamp() -> String {
+/// Render a lockfile refusal under `version`, the lockfile version the run
+/// would have written.
+pub(crate) fn lockfile_refusal_output(
+    refusal: verify::LockfileRefusal,
+    version: &str,
+) -> OrchestrationOutput {
+    OrchestrationOutput {
+        outcome: output::DomainOutcome::Refusal,
+        payload_json: refusal.to_json(version),
+    }
+}
+
+pub(crate) fn current_created_timestamp() -> String {
     Utc::now().to_rfc3339_opts(SecondsFormat::Secs, true)
 }
 
@@ -186,8 +352,41 @@ mod tests {
     use serde_json::json;
     use tempfile::TempDir;
 
-    use super::{orchestrate_from_read_result, output, run_lock};
-    use crate::{cli, input, witness::TestWitnessEnvGuard};
+    use super::{
+        OrchestrationOutput, finish_ingest, new_lockfile, output, refusal_output, run_lock,
+        seal_lockfile,
+    };
+    use crate::{cli, input, lockfile, refusal, witness::TestWitnessEnvGuard};
+
+    /// Buffered entry point: feed an already-read record list through the
+    /// same validator/classifier path that `run_lock` streams into.
+    fn orchestrate_from_read_result(
+        cli: &cli::Cli,
+        read_result: input::ReadResult,
+    ) -> OrchestrationOutput {
+        let input::ReadResult::Records(records) = read_result else {
+            return refusal_output(refusal::empty());
+        };
+
+        let mut validator = input::RecordValidator::default();
+        let mut classifier = lockfile::RecordClassifier::default();
+        for record in &records {
+            validator.observe(record);
+            classifier.push(record);
+        }
+
+        match finish_ingest(
+            records.len(),
+            validator,
+            classifier,
+            cli.dataset_id.as_deref(),
+            cli.as_of.as_deref(),
+            cli.note.as_deref(),
+        ) {
+            Ok(ingested) => seal_lockfile(new_lockfile(ingested, refusal::LOCK_VERSION, None)),
+            Err(refusal) => refusal,
+        }
+    }
 
     fn make_cli() -> cli::Cli {
         cli::Cli {
@@ -197,6 +396,11 @@ mod tests {
             as_of: Some("2026-02-24T00:00:00Z".to_owned()),
             note: Some("note".to_owned()),
             output: None,
+            parent: None,
+            lock_version: "lock.v0".to_string(),
+            keep_mtime: false,
+            keep_fields: vec![],
+            keep_all_fields: false,
             no_witness: false,
             describe: false,
             schema: false,
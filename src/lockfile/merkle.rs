@@ -0,0 +1,231 @@
+use serde::Serialize;
+use sha2::{Digest, Sha256};
+
+use super::self_hash::to_canonical_json;
+
+/// Domain-separation prefix for leaf hashes (RFC 6962 §2.1).
+const LEAF_PREFIX: u8 = 0x00;
+/// Domain-separation prefix for interior node hashes (RFC 6962 §2.1).
+const NODE_PREFIX: u8 = 0x01;
+
+pub type Digest32 = [u8; 32];
+
+/// Hash one member: `SHA256(0x00 || canonical_json(member))`.
+pub fn leaf_hash<T: Serialize>(member: &T) -> Digest32 {
+    let canonical = to_canonical_json(member).expect("members always serialize to JSON");
+    let mut hasher = Sha256::new();
+    hasher.update([LEAF_PREFIX]);
+    hasher.update(canonical.as_bytes());
+    hasher.finalize().into()
+}
+
+/// Hash two children: `SHA256(0x01 || left || right)`.
+pub fn node_hash(left: &Digest32, right: &Digest32) -> Digest32 {
+    let mut hasher = Sha256::new();
+    hasher.update([NODE_PREFIX]);
+    hasher.update(left);
+    hasher.update(right);
+    hasher.finalize().into()
+}
+
+/// Merkle tree hash over `leaves` as defined by RFC 6962 §2.1.
+///
+/// The tree splits at the largest power of two smaller than the leaf count,
+/// so it is defined for any number of leaves. An empty tree hashes to
+/// `SHA256("")`.
+pub fn root(leaves: &[Digest32]) -> Digest32 {
+    match leaves {
+        [] => Sha256::digest([]).into(),
+        [leaf] => *leaf,
+        _ => {
+            let split = split_point(leaves.len());
+            node_hash(&root(&leaves[..split]), &root(&leaves[split..]))
+        }
+    }
+}
+
+/// Largest power of two strictly smaller than `n` (`n >= 2`).
+pub fn split_point(n: usize) -> usize {
+    debug_assert!(n >= 2);
+    1 << (usize::BITS - 1 - (n - 1).leading_zeros())
+}
+
+/// Audit path for the leaf at `index`: sibling hashes from the leaf up to
+/// the root (RFC 6962 §2.1.1). Empty for a single-leaf tree.
+pub fn audit_path(leaves: &[Digest32], index: usize) -> Vec<Digest32> {
+    debug_assert!(index < leaves.len());
+    if leaves.len() <= 1 {
+        return Vec::new();
+    }
+    let split = split_point(leaves.len());
+    if index < split {
+        let mut path = audit_path(&leaves[..split], index);
+        path.push(root(&leaves[split..]));
+        path
+    } else {
+        let mut path = audit_path(&leaves[split..], index - split);
+        path.push(root(&leaves[..split]));
+        path
+    }
+}
+
+/// Recompute the root from a leaf and its audit path (RFC 9162 §2.1.3.2).
+///
+/// Returns `None` when the path length is inconsistent with `index` and
+/// `size`, which a forged or truncated proof produces.
+pub fn root_from_audit_path(
+    leaf: &Digest32,
+    index: u64,
+    size: u64,
+    path: &[Digest32],
+) -> Option<Digest32> {
+    if index >= size {
+        return None;
+    }
+    let mut fn_ = index;
+    let mut sn = size - 1;
+    let mut r = *leaf;
+    for sibling in path {
+        if sn == 0 {
+            return None;
+        }
+        if fn_ & 1 == 1 || fn_ == sn {
+            r = node_hash(sibling, &r);
+            while fn_ & 1 == 0 && fn_ != 0 {
+                fn_ >>= 1;
+                sn >>= 1;
+            }
+        } else {
+            r = node_hash(&r, sibling);
+        }
+        fn_ >>= 1;
+        sn >>= 1;
+    }
+    (sn == 0).then_some(r)
+}
+
+/// `members_root` for a lockfile's (already sorted) members array.
+pub fn members_root<T: Serialize>(members: &[T]) -> String {
+    let leaves: Vec<Digest32> = members.iter().map(leaf_hash).collect();
+    format_digest(&root(&leaves))
+}
+
+/// Render a digest as `sha256:<hex>`, the form used throughout lockfiles.
+pub fn format_digest(digest: &Digest32) -> String {
+    let hex: String = digest.iter().map(|byte| format!("{byte:02x}")).collect();
+    format!("sha256:{hex}")
+}
+
+/// Parse a `sha256:<hex>` digest produced by [`format_digest`].
+pub fn parse_digest(text: &str) -> Option<Digest32> {
+    let hex = text.strip_prefix("sha256:")?;
+    if hex.len() != 64 || !hex.is_ascii() {
+        return None;
+    }
+    let mut digest = [0u8; 32];
+    for (byte, pair) in digest.iter_mut().zip(hex.as_bytes().chunks(2)) {
+        let pair = std::str::from_utf8(pair).ok()?;
+        *byte = u8::from_str_radix(pair, 16).ok()?;
+    }
+    Some(digest)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::{Value, json};
+
+    fn member(path: &str) -> Value {
+        json!({ "path": path, "bytes_hash": "sha256:00", "size": 1, "fingerprint": null })
+    }
+
+    #[test]
+    fn split_point_is_largest_smaller_power_of_two() {
+        assert_eq!(split_point(2), 1);
+        assert_eq!(split_point(3), 2);
+        assert_eq!(split_point(4), 2);
+        assert_eq!(split_point(5), 4);
+        assert_eq!(split_point(8), 4);
+        assert_eq!(split_point(9), 8);
+    }
+
+    #[test]
+    fn empty_tree_is_sha256_of_empty_string() {
+        assert_eq!(
+            format_digest(&root(&[])),
+            "sha256:e3b0c44298fc1c149afbf4c8996fb92427ae41e4649b934ca495991b7852b855"
+        );
+    }
+
+    #[test]
+    fn single_member_root_is_its_leaf_hash() {
+        let a = member("a.csv");
+        assert_eq!(
+            members_root(std::slice::from_ref(&a)),
+            format_digest(&leaf_hash(&a))
+        );
+    }
+
+    #[test]
+    fn three_leaves_split_two_and_one() {
+        let members = [member("a.csv"), member("b.csv"), member("c.csv")];
+        let leaves: Vec<Digest32> = members.iter().map(leaf_hash).collect();
+        let expected = node_hash(&node_hash(&leaves[0], &leaves[1]), &leaves[2]);
+        assert_eq!(members_root(&members), format_digest(&expected));
+    }
+
+    #[test]
+    fn leaf_hash_ignores_key_order() {
+        let a =
+            json!({ "path": "a.csv", "size": 1, "bytes_hash": "sha256:00", "fingerprint": null });
+        assert_eq!(leaf_hash(&a), leaf_hash(&member("a.csv")));
+    }
+
+    #[test]
+    fn audit_paths_recompute_root_for_every_leaf() {
+        for size in 1..=9 {
+            let members: Vec<Value> = (0..size).map(|i| member(&format!("{i}.csv"))).collect();
+            let leaves: Vec<Digest32> = members.iter().map(leaf_hash).collect();
+            let expected = root(&leaves);
+            for index in 0..size {
+                let path = audit_path(&leaves, index);
+                assert_eq!(
+                    root_from_audit_path(&leaves[index], index as u64, size as u64, &path),
+                    Some(expected),
+                    "size {size}, index {index}"
+                );
+            }
+        }
+    }
+
+    #[test]
+    fn audit_path_rejects_wrong_index_or_size() {
+        let members: Vec<Value> = (0..5).map(|i| member(&format!("{i}.csv"))).collect();
+        let leaves: Vec<Digest32> = members.iter().map(leaf_hash).collect();
+        let expected = root(&leaves);
+        let path = audit_path(&leaves, 2);
+
+        assert_ne!(
+            root_from_audit_path(&leaves[2], 3, 5, &path),
+            Some(expected)
+        );
+        assert_eq!(root_from_audit_path(&leaves[2], 2, 2, &path), None);
+        assert_eq!(root_from_audit_path(&leaves[2], 5, 5, &path), None);
+        assert_eq!(root_from_audit_path(&leaves[2], 2, 5, &path[..1]), None);
+    }
+
+    #[test]
+    fn digest_round_trips_through_text() {
+        let digest = leaf_hash(&member("a.csv"));
+        assert_eq!(parse_digest(&format_digest(&digest)), Some(digest));
+        assert_eq!(parse_digest("sha256:zz"), None);
+        assert_eq!(parse_digest("blake3:00"), None);
+    }
+
+    #[test]
+    fn leaf_and_node_hashes_are_domain_separated() {
+        let leaf = leaf_hash(&member("a.csv"));
+        assert_ne!(node_hash(&leaf, &leaf), leaf);
+        assert_ne!(root(&[leaf, leaf]), leaf);
+    }
+}
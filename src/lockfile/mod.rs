@@ -1,11 +1,12 @@
 use std::collections::BTreeMap;
 
-use serde::Serialize;
+use serde::{Deserialize, Serialize};
 use serde_json::Value;
 
 use crate::input::InputRecord;
 use crate::output::DomainOutcome;
 
+pub mod merkle;
 pub mod self_hash;
 
 #[derive(Debug, Clone, PartialEq, Eq, Serialize)]
@@ -15,6 +16,14 @@ pub struct Lockfile {
     pub dataset_id: Option<String>,
     pub as_of: Option<String>,
     pub note: Option<String>,
+    /// `lock_hash` of the lockfile this one was derived from. Omitted (not
+    /// null) for lockfiles built from scratch, so their output is unchanged.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub parent: Option<String>,
+    /// RFC 6962 Merkle root over `members` (`lock.v1` only; omitted in
+    /// `lock.v0`). Covered by `lock_hash` like every other field.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub members_root: Option<String>,
     pub created: String,
     pub tool_versions: BTreeMap<String, String>,
     pub profiles: Vec<String>,
@@ -24,15 +33,27 @@ pub struct Lockfile {
     pub member_count: u64,
 }
 
-#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
 pub struct Member {
     pub path: String,
     pub bytes_hash: String,
     pub size: u64,
     pub fingerprint: Option<FingerprintResult>,
+    /// Upstream `mtime`, kept only with `--keep-mtime` so that
+    /// `verify --fast` can skip rehashing unchanged files.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub mtime: Option<String>,
+    /// Upstream passthrough fields kept with `--keep-fields` or
+    /// `--keep-all-fields`; absent when none were kept.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub extra: Option<BTreeMap<String, Value>>,
+    /// Digests keyed by algorithm, `bytes_hash` included, when a member
+    /// records more than one. `verify` checks every entry.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub hashes: Option<BTreeMap<String, String>>,
 }
 
-#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
 pub struct FingerprintResult {
     pub fingerprint_id: String,
     pub fingerprint_version: String,
@@ -40,13 +61,13 @@ pub struct FingerprintResult {
     pub content_hash: Option<String>,
 }
 
-#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
 pub struct SkippedEntry {
     pub path: String,
     pub warnings: Vec<Warning>,
 }
 
-#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
 pub struct Warning {
     pub tool: String,
     pub code: String,
@@ -65,9 +86,26 @@ pub struct Classification {
 
 #[derive(Debug, Clone, PartialEq, Eq)]
 pub enum ClassificationError {
-    MissingPath { line_number: usize },
-    MissingBytesHash { line_number: usize },
-    MissingSize { line_number: usize },
+    MissingPath {
+        line_number: usize,
+    },
+    MissingBytesHash {
+        line_number: usize,
+    },
+    MissingSize {
+        line_number: usize,
+    },
+    InvalidHashes {
+        line_number: usize,
+    },
+    UnknownAlgorithm {
+        line_number: usize,
+        algorithm: String,
+    },
+    NonCryptographicBytesHash {
+        line_number: usize,
+        algorithm: String,
+    },
 }
 
 #[derive(Debug, Clone, PartialEq, Eq)]
@@ -80,10 +118,159 @@ pub struct MetadataHydration {
 }
 
 pub fn classify_records(records: &[InputRecord]) -> Result<Classification, ClassificationError> {
-    let mut members = Vec::new();
-    let mut skipped = Vec::new();
+    let mut classifier = RecordClassifier::default();
+    for record in records {
+        classifier.push(record);
+    }
+    classifier.finish()
+}
+
+pub fn hydrate_metadata(
+    records: &[InputRecord],
+    lock_version: &str,
+    dataset_id: Option<&str>,
+    as_of: Option<&str>,
+    note: Option<&str>,
+) -> MetadataHydration {
+    metadata_with(
+        merge_tool_versions(records, lock_version),
+        dataset_id,
+        as_of,
+        note,
+    )
+}
+
+fn metadata_with(
+    tool_versions: BTreeMap<String, String>,
+    dataset_id: Option<&str>,
+    as_of: Option<&str>,
+    note: Option<&str>,
+) -> MetadataHydration {
+    MetadataHydration {
+        dataset_id: dataset_id.map(str::to_owned),
+        as_of: as_of.map(str::to_owned),
+        note: note.map(str::to_owned),
+        profiles: Vec::new(),
+        tool_versions,
+    }
+}
+
+pub fn merge_tool_versions(
+    records: &[InputRecord],
+    lock_version: &str,
+) -> BTreeMap<String, String> {
+    let mut merged = BTreeMap::new();
 
     for record in records {
+        merge_record_tool_versions(&mut merged, &record.value);
+    }
+
+    with_lock_version(merged, lock_version)
+}
+
+/// Record fields `lock` consumes itself; they never land in `Member::extra`.
+pub const CONSUMED_FIELDS: &[&str] = &[
+    "version",
+    "path",
+    "relative_path",
+    "bytes_hash",
+    "size",
+    "fingerprint",
+    "tool_versions",
+    "hashes",
+];
+
+/// Which upstream passthrough fields go into `Member::extra`.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub enum KeepFields {
+    #[default]
+    None,
+    /// Only these fields, when a record has them.
+    Named(Vec<String>),
+    /// Every field except [`CONSUMED_FIELDS`] and `_`-prefixed markers.
+    All,
+}
+
+impl KeepFields {
+    /// From `--keep-fields <F,...>` and `--keep-all-fields`.
+    pub fn from_flags(named: &[String], all: bool) -> Self {
+        if all {
+            Self::All
+        } else if named.is_empty() {
+            Self::None
+        } else {
+            Self::Named(named.to_vec())
+        }
+    }
+
+    fn keeps(&self, field: &str) -> bool {
+        match self {
+            Self::None => false,
+            Self::Named(fields) => fields.iter().any(|kept| kept == field),
+            Self::All => !field.starts_with('_') && !CONSUMED_FIELDS.contains(&field),
+        }
+    }
+
+    /// The kept fields of `record`, or `None` if there are none.
+    fn extract(&self, record: &Value) -> Option<BTreeMap<String, Value>> {
+        if *self == Self::None {
+            return None;
+        }
+        let extra: BTreeMap<String, Value> = record
+            .as_object()?
+            .iter()
+            .filter(|(field, _)| self.keeps(field))
+            .map(|(field, value)| (field.clone(), value.clone()))
+            .collect();
+        (!extra.is_empty()).then_some(extra)
+    }
+}
+
+/// Which optional upstream fields the classifier carries into members.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct ClassifyOptions {
+    /// Keep each record's `mtime` string as `Member::mtime`.
+    pub keep_mtime: bool,
+    /// Passthrough fields to keep in `Member::extra`.
+    pub keep_fields: KeepFields,
+}
+
+/// Incremental form of [`classify_records`] and [`hydrate_metadata`] for
+/// streamed input.
+///
+/// Keeps only the compact member and skipped entries (plus merged
+/// `tool_versions`), so the source records can be dropped as soon as they
+/// are pushed. The first classification error is remembered and reported
+/// by [`RecordClassifier::finish`].
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct RecordClassifier {
+    members: Vec<Member>,
+    skipped: Vec<SkippedEntry>,
+    tool_versions: BTreeMap<String, String>,
+    error: Option<ClassificationError>,
+    options: ClassifyOptions,
+}
+
+impl RecordClassifier {
+    pub fn new(options: ClassifyOptions) -> Self {
+        Self {
+            options,
+            ..Self::default()
+        }
+    }
+
+    pub fn push(&mut self, record: &InputRecord) {
+        merge_record_tool_versions(&mut self.tool_versions, &record.value);
+
+        if self.error.is_some() {
+            return;
+        }
+        if let Err(error) = self.classify(record) {
+            self.error = Some(error);
+        }
+    }
+
+    fn classify(&mut self, record: &InputRecord) -> Result<(), ClassificationError> {
         let path = extract_record_path(&record.value, record.line_number)?;
         let is_skipped = record
             .value
@@ -92,11 +279,11 @@ pub fn classify_records(records: &[InputRecord]) -> Result<Classification, Class
             .unwrap_or(false);
 
         if is_skipped {
-            skipped.push(SkippedEntry {
+            self.skipped.push(SkippedEntry {
                 path,
                 warnings: extract_warnings(&record.value),
             });
-            continue;
+            return Ok(());
         }
 
         let bytes_hash = record
@@ -107,6 +294,22 @@ pub fn classify_records(records: &[InputRecord]) -> Result<Classification, Class
                 line_number: record.line_number,
             })?
             .to_owned();
+        let algorithm = crate::digest::prefix(&bytes_hash);
+        match crate::digest::lookup(algorithm) {
+            Some(known) if known.cryptographic => {}
+            Some(_) => {
+                return Err(ClassificationError::NonCryptographicBytesHash {
+                    line_number: record.line_number,
+                    algorithm: algorithm.to_owned(),
+                });
+            }
+            None => {
+                return Err(ClassificationError::UnknownAlgorithm {
+                    line_number: record.line_number,
+                    algorithm: algorithm.to_owned(),
+                });
+            }
+        }
 
         let size = record.value.get("size").and_then(Value::as_u64).ok_or(
             ClassificationError::MissingSize {
@@ -114,73 +317,104 @@ pub fn classify_records(records: &[InputRecord]) -> Result<Classification, Class
             },
         )?;
 
-        members.push(Member {
+        let hashes = extract_hashes(&record.value, &bytes_hash).ok_or(
+            ClassificationError::InvalidHashes {
+                line_number: record.line_number,
+            },
+        )?;
+        if let Some(algorithm) = hashes
+            .iter()
+            .flat_map(BTreeMap::keys)
+            .find(|algorithm| crate::digest::lookup(algorithm).is_none())
+        {
+            return Err(ClassificationError::UnknownAlgorithm {
+                line_number: record.line_number,
+                algorithm: algorithm.clone(),
+            });
+        }
+
+        self.members.push(Member {
             path,
-            bytes_hash,
             size,
             fingerprint: extract_fingerprint(&record.value),
+            mtime: self
+                .options
+                .keep_mtime
+                .then(|| record.value.get("mtime").and_then(Value::as_str))
+                .flatten()
+                .map(str::to_owned),
+            extra: self.options.keep_fields.extract(&record.value),
+            hashes,
+            bytes_hash,
         });
+        Ok(())
     }
 
-    members.sort_unstable_by(|left, right| left.path.cmp(&right.path));
-    skipped.sort_unstable_by(|left, right| left.path.cmp(&right.path));
+    pub fn hydrate_metadata(
+        &self,
+        lock_version: &str,
+        dataset_id: Option<&str>,
+        as_of: Option<&str>,
+        note: Option<&str>,
+    ) -> MetadataHydration {
+        metadata_with(
+            with_lock_version(self.tool_versions.clone(), lock_version),
+            dataset_id,
+            as_of,
+            note,
+        )
+    }
 
-    let skipped_count = skipped.len() as u64;
-    let member_count = members.len() as u64;
-    let outcome = if skipped.is_empty() {
-        DomainOutcome::LockCreated
-    } else {
-        DomainOutcome::LockPartial
-    };
+    pub fn finish(self) -> Result<Classification, ClassificationError> {
+        if let Some(error) = self.error {
+            return Err(error);
+        }
 
-    Ok(Classification {
-        members,
-        skipped,
-        skipped_count,
-        member_count,
-        outcome,
-    })
-}
+        let mut members = self.members;
+        let mut skipped = self.skipped;
+        members.sort_unstable_by(|left, right| left.path.cmp(&right.path));
+        skipped.sort_unstable_by(|left, right| left.path.cmp(&right.path));
+
+        let skipped_count = skipped.len() as u64;
+        let member_count = members.len() as u64;
+        let outcome = if skipped.is_empty() {
+            DomainOutcome::LockCreated
+        } else {
+            DomainOutcome::LockPartial
+        };
 
-pub fn hydrate_metadata(
-    records: &[InputRecord],
-    lock_version: &str,
-    dataset_id: Option<&str>,
-    as_of: Option<&str>,
-    note: Option<&str>,
-) -> MetadataHydration {
-    MetadataHydration {
-        dataset_id: dataset_id.map(str::to_owned),
-        as_of: as_of.map(str::to_owned),
-        note: note.map(str::to_owned),
-        profiles: Vec::new(),
-        tool_versions: merge_tool_versions(records, lock_version),
+        Ok(Classification {
+            members,
+            skipped,
+            skipped_count,
+            member_count,
+            outcome,
+        })
     }
 }
 
-pub fn merge_tool_versions(
-    records: &[InputRecord],
-    lock_version: &str,
-) -> BTreeMap<String, String> {
-    let mut merged = BTreeMap::new();
+/// Merge one record's `tool_versions` into `merged`; the first version seen
+/// for a tool wins.
+fn merge_record_tool_versions(merged: &mut BTreeMap<String, String>, value: &Value) {
+    let Some(tool_versions) = value.get("tool_versions").and_then(Value::as_object) else {
+        return;
+    };
 
-    for record in records {
-        let Some(tool_versions) = record.value.get("tool_versions").and_then(Value::as_object)
-        else {
+    for (tool, version_value) in tool_versions {
+        let Some(version) = version_value.as_str() else {
             continue;
         };
 
-        for (tool, version_value) in tool_versions {
-            let Some(version) = version_value.as_str() else {
-                continue;
-            };
-
-            merged
-                .entry(tool.clone())
-                .or_insert_with(|| version.to_owned());
-        }
+        merged
+            .entry(tool.clone())
+            .or_insert_with(|| version.to_owned());
     }
+}
 
+fn with_lock_version(
+    mut merged: BTreeMap<String, String>,
+    lock_version: &str,
+) -> BTreeMap<String, String> {
     merged
         .entry("lock".to_owned())
         .or_insert_with(|| lock_version.to_owned());
@@ -196,6 +430,32 @@ fn extract_record_path(value: &Value, line_number: usize) -> Result<String, Clas
         .ok_or(ClassificationError::MissingPath { line_number })
 }
 
+/// Read an upstream `hashes` object into algorithm-keyed, prefixed digests.
+///
+/// Bare hex values get their key as prefix. Returns `None` when the object is
+/// malformed or disagrees with `bytes_hash`, and `Some(None)` when it records
+/// nothing beyond `bytes_hash`.
+fn extract_hashes(value: &Value, bytes_hash: &str) -> Option<Option<BTreeMap<String, String>>> {
+    let Some(hashes) = value.get("hashes") else {
+        return Some(None);
+    };
+    let (own_algorithm, _) = bytes_hash.split_once(':')?;
+    let mut digests = BTreeMap::from([(own_algorithm.to_owned(), bytes_hash.to_owned())]);
+    for (algorithm, digest) in hashes.as_object()? {
+        let digest = digest.as_str()?;
+        let digest = match digest.split_once(':') {
+            Some((prefix, _)) if prefix == algorithm => digest.to_owned(),
+            Some(_) => return None,
+            None => format!("{algorithm}:{digest}"),
+        };
+        if digests.get(algorithm).is_some_and(|known| *known != digest) {
+            return None;
+        }
+        digests.insert(algorithm.clone(), digest);
+    }
+    Some((digests.len() > 1).then_some(digests))
+}
+
 fn extract_fingerprint(value: &Value) -> Option<FingerprintResult> {
     let object = value.get("fingerprint")?.as_object()?;
 
@@ -263,7 +523,10 @@ fn extract_warning_detail(value: Option<&Value>) -> BTreeMap<String, String> {
 mod tests {
     use serde_json::json;
 
-    use super::{ClassificationError, classify_records, hydrate_metadata, merge_tool_versions};
+    use super::{
+        ClassificationError, ClassifyOptions, KeepFields, RecordClassifier, classify_records,
+        hydrate_metadata, merge_tool_versions,
+    };
     use crate::input::InputRecord;
     use crate::output::DomainOutcome;
 
@@ -537,4 +800,165 @@ mod tests {
             Some(&"0.1.0".to_owned())
         );
     }
+
+    #[test]
+    fn record_classifier_keeps_first_error_and_merges_all_tool_versions() {
+        let mut classifier = RecordClassifier::default();
+        classifier.push(&InputRecord {
+            line_number: 1,
+            value: json!({ "relative_path": "a.csv", "tool_versions": { "hash": "0.1.0" } }),
+        });
+        classifier.push(&InputRecord {
+            line_number: 2,
+            value: json!({ "bytes_hash": "sha256:b", "tool_versions": { "vacuum": "0.2.0" } }),
+        });
+
+        let metadata = classifier.hydrate_metadata("0.5.0", None, None, None);
+        assert_eq!(metadata.tool_versions.len(), 3);
+        assert_eq!(
+            classifier.finish(),
+            Err(ClassificationError::MissingBytesHash { line_number: 1 })
+        );
+    }
+
+    #[test]
+    fn record_classifier_keeps_mtime_only_when_asked() {
+        let record = InputRecord {
+            line_number: 1,
+            value: json!({
+                "relative_path": "a.csv",
+                "bytes_hash": "sha256:a",
+                "size": 1,
+                "mtime": "2026-01-15T10:00:00Z"
+            }),
+        };
+
+        let classification = classify_records(std::slice::from_ref(&record)).unwrap();
+        assert_eq!(classification.members[0].mtime, None);
+
+        let mut classifier = RecordClassifier::new(ClassifyOptions {
+            keep_mtime: true,
+            ..ClassifyOptions::default()
+        });
+        classifier.push(&record);
+        let classification = classifier.finish().unwrap();
+        assert_eq!(
+            classification.members[0].mtime.as_deref(),
+            Some("2026-01-15T10:00:00Z")
+        );
+    }
+
+    #[test]
+    fn record_classifier_keeps_named_or_all_passthrough_fields() {
+        let record = InputRecord {
+            line_number: 1,
+            value: json!({
+                "version": "hash.v0",
+                "relative_path": "a.csv",
+                "bytes_hash": "sha256:a",
+                "size": 1,
+                "mime_guess": "text/csv",
+                "mtime": "2026-01-15T10:00:00Z",
+                "owner": { "uid": 1000 },
+                "tool_versions": { "hash": "0.1.0" },
+                "_warnings": []
+            }),
+        };
+        let classify = |keep_fields| {
+            let mut classifier = RecordClassifier::new(ClassifyOptions {
+                keep_fields,
+                ..ClassifyOptions::default()
+            });
+            classifier.push(&record);
+            classifier.finish().unwrap().members.remove(0).extra
+        };
+
+        assert_eq!(classify(KeepFields::None), None);
+        assert_eq!(
+            classify(KeepFields::Named(vec![
+                "mime_guess".into(),
+                "absent".into()
+            ])),
+            Some([("mime_guess".to_owned(), json!("text/csv"))].into())
+        );
+        assert_eq!(classify(KeepFields::Named(vec!["absent".into()])), None);
+
+        let all = classify(KeepFields::All).unwrap();
+        let fields: Vec<&str> = all.keys().map(String::as_str).collect();
+        assert_eq!(fields, ["mime_guess", "mtime", "owner"]);
+        assert_eq!(all["owner"], json!({ "uid": 1000 }));
+    }
+
+    #[test]
+    fn record_classifier_reads_upstream_hashes() {
+        let classify = |hashes: serde_json::Value| {
+            let record = InputRecord {
+                line_number: 4,
+                value: json!({
+                    "relative_path": "a.csv",
+                    "bytes_hash": "sha256:aa",
+                    "size": 1,
+                    "hashes": hashes
+                }),
+            };
+            classify_records(std::slice::from_ref(&record)).map(|c| c.members[0].hashes.clone())
+        };
+
+        assert_eq!(
+            classify(json!({ "sha256": "aa", "blake3": "blake3:bb" })),
+            Ok(Some(
+                [
+                    ("blake3".to_owned(), "blake3:bb".to_owned()),
+                    ("sha256".to_owned(), "sha256:aa".to_owned()),
+                ]
+                .into()
+            ))
+        );
+        assert_eq!(classify(json!({ "sha256": "sha256:aa" })), Ok(None));
+        for bad in [
+            json!({ "sha256": "sha256:cc" }),
+            json!({ "blake3": "sha256:bb" }),
+            json!({ "blake3": 7 }),
+            json!(["blake3:bb"]),
+        ] {
+            assert_eq!(
+                classify(bad),
+                Err(ClassificationError::InvalidHashes { line_number: 4 })
+            );
+        }
+        assert_eq!(
+            classify(json!({ "md5": "md5:00" })),
+            Err(ClassificationError::UnknownAlgorithm {
+                line_number: 4,
+                algorithm: "md5".to_owned(),
+            })
+        );
+    }
+
+    #[test]
+    fn record_classifier_requires_a_registered_cryptographic_bytes_hash() {
+        let classify = |bytes_hash: &str| {
+            let record = InputRecord {
+                line_number: 2,
+                value: json!({ "relative_path": "a.csv", "bytes_hash": bytes_hash, "size": 1 }),
+            };
+            classify_records(std::slice::from_ref(&record)).map(|c| c.member_count)
+        };
+
+        assert_eq!(classify("blake3:aa"), Ok(1));
+        assert_eq!(
+            classify("xxh3:0123456789abcdef"),
+            Err(ClassificationError::NonCryptographicBytesHash {
+                line_number: 2,
+                algorithm: "xxh3".to_owned(),
+            })
+        );
+        assert_eq!(
+            classify("md5:aa"),
+            Err(ClassificationError::UnknownAlgorithm {
+                line_number: 2,
+                algorithm: "md5".to_owned(),
+            })
+        );
+    }
 }
@@ -167,6 +167,8 @@ mod tests {
             dataset_id: Some("test-dataset".to_string()),
             as_of: None,
             note: None,
+            parent: None,
+            members_root: None,
             created: "2026-01-15T10:30:00Z".to_string(),
             tool_versions,
             profiles: vec![],
@@ -177,12 +179,18 @@ mod tests {
                     bytes_hash: "sha256:aaaa".to_string(),
                     size: 100,
                     fingerprint: None,
+                    mtime: None,
+                    extra: None,
+                    hashes: None,
                 },
                 Member {
                     path: "beta.csv".to_string(),
                     bytes_hash: "sha256:bbbb".to_string(),
                     size: 200,
                     fingerprint: None,
+                    mtime: None,
+                    extra: None,
+                    hashes: None,
                 },
             ],
             skipped_count: 0,
@@ -0,0 +1,366 @@
+pub mod output;
+
+use std::collections::{BTreeMap, BTreeSet};
+use std::path::{Path, PathBuf};
+
+use serde::Serialize;
+use serde_json::Value;
+
+use crate::cli::LogArgs;
+use crate::refusal::sort_value;
+use crate::verify::{LockfileRefusal, VerifyRefusalCode, load_sealed_lockfile};
+
+/// Log output schema version.
+pub const LOG_VERSION: &str = "lock-log.v0";
+
+// ---------------------------------------------------------------------------
+// Log result types
+// ---------------------------------------------------------------------------
+
+/// The top-level result emitted by `lock log`.
+#[derive(Debug, Clone, Serialize)]
+struct LogResult {
+    version: String,
+    outcome: String,
+    lockfile: String,
+    search_dir: String,
+    entries: Vec<LogEntry>,
+    missing_parent: Option<String>,
+    cycle_at: Option<String>,
+    tool_versions: BTreeMap<String, String>,
+}
+
+/// One lockfile in the lineage, newest first.
+#[derive(Debug, Clone, Serialize)]
+struct LogEntry {
+    lock_hash: String,
+    path: String,
+    created: Option<String>,
+    dataset_id: Option<String>,
+    as_of: Option<String>,
+    member_count: usize,
+    parent: Option<String>,
+    changes: Option<LogChanges>,
+}
+
+/// Member counts relative to the entry's parent.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+struct LogChanges {
+    added: usize,
+    removed: usize,
+    changed: usize,
+}
+
+// ---------------------------------------------------------------------------
+// Lineage
+// ---------------------------------------------------------------------------
+
+/// Where a walk up the parent links stopped.
+#[derive(Debug, Clone, PartialEq, Eq)]
+enum LineageEnd {
+    /// At a lockfile without a parent.
+    Root,
+    /// At a parent not found under the search directory.
+    MissingParent(String),
+    /// At a parent already in the chain.
+    Cycle(String),
+}
+
+/// A self-hash-valid lockfile found on disk.
+struct Located {
+    path: PathBuf,
+    value: Value,
+}
+
+/// Index every self-hash-valid `.json` lockfile under `dir` by `lock_hash`.
+///
+/// Files that are not lockfiles, or fail their self-hash, are ignored, and
+/// subdirectories that cannot be read are not searched. When two files carry
+/// the same `lock_hash`, the first by relative path wins.
+fn index_lockfiles(dir: &Path) -> Result<BTreeMap<String, Located>, LockfileRefusal> {
+    let walk = crate::walk::walk_files(dir).map_err(|e| {
+        LockfileRefusal::new(
+            VerifyRefusalCode::Io,
+            format!("cannot read search directory — {e}"),
+            serde_json::json!({
+                "path": dir.display().to_string(),
+                "error": e.to_string(),
+            }),
+        )
+    })?;
+
+    let mut index = BTreeMap::new();
+    for file in walk.files {
+        if !file.relative.ends_with(".json") {
+            continue;
+        }
+        let Ok(value) = load_sealed_lockfile(&file.path) else {
+            continue;
+        };
+        let lock_hash = str_field(&value, "lock_hash").unwrap_or_default();
+        index.entry(lock_hash).or_insert(Located {
+            path: file.path,
+            value,
+        });
+    }
+    Ok(index)
+}
+
+/// Walk parent links from `start` through `index`.
+///
+/// Returns the entries newest first and where the chain ended.
+fn walk_lineage(start: Located, index: &BTreeMap<String, Located>) -> (Vec<LogEntry>, LineageEnd) {
+    let mut entries = Vec::new();
+    let mut seen = BTreeSet::new();
+    let mut current = &start;
+
+    loop {
+        let lock_hash = str_field(&current.value, "lock_hash").unwrap_or_default();
+        let parent = str_field(&current.value, "parent");
+        seen.insert(lock_hash.clone());
+
+        let found = parent
+            .as_ref()
+            .filter(|hash| !seen.contains(*hash))
+            .and_then(|hash| index.get(hash));
+        let changes = found.map(|ancestor| {
+            let diff = crate::diff::diff_members(&ancestor.value, &current.value);
+            LogChanges {
+                added: diff.added.len(),
+                removed: diff.removed.len(),
+                changed: diff.changed.len(),
+            }
+        });
+
+        entries.push(LogEntry {
+            lock_hash,
+            path: current.path.display().to_string(),
+            created: str_field(&current.value, "created"),
+            dataset_id: str_field(&current.value, "dataset_id"),
+            as_of: str_field(&current.value, "as_of"),
+            member_count: current.value["members"].as_array().map_or(0, Vec::len),
+            parent: parent.clone(),
+            changes,
+        });
+
+        match (parent, found) {
+            (None, _) => return (entries, LineageEnd::Root),
+            (Some(hash), None) if seen.contains(&hash) => {
+                return (entries, LineageEnd::Cycle(hash));
+            }
+            (Some(hash), None) => return (entries, LineageEnd::MissingParent(hash)),
+            (Some(_), Some(ancestor)) => current = ancestor,
+        }
+    }
+}
+
+fn str_field(value: &Value, key: &str) -> Option<String> {
+    value[key].as_str().map(str::to_owned)
+}
+
+fn build_log(args: &LogArgs, start: Value, index: &BTreeMap<String, Located>) -> LogResult {
+    let start = Located {
+        path: args.lockfile.clone(),
+        value: start,
+    };
+    let (entries, end) = walk_lineage(start, index);
+    let (outcome, missing_parent, cycle_at) = match end {
+        LineageEnd::Root => ("LOG_COMPLETE", None, None),
+        LineageEnd::MissingParent(hash) => ("LOG_BROKEN", Some(hash), None),
+        LineageEnd::Cycle(hash) => ("LOG_CYCLE", None, Some(hash)),
+    };
+
+    let mut lock_versions = BTreeMap::new();
+    lock_versions.insert("lock".to_string(), env!("CARGO_PKG_VERSION").to_string());
+
+    LogResult {
+        version: LOG_VERSION.to_string(),
+        outcome: outcome.to_string(),
+        lockfile: args.lockfile.display().to_string(),
+        search_dir: args.search_dir.display().to_string(),
+        entries,
+        missing_parent,
+        cycle_at,
+        tool_versions: lock_versions,
+    }
+}
+
+// ---------------------------------------------------------------------------
+// Orchestration
+// ---------------------------------------------------------------------------
+
+/// Run the log subcommand. Returns the exit code.
+///
+/// Exit codes: `0` chain reaches a root lockfile, `1` a parent is missing
+/// from the search directory or the chain loops back on itself, `2` refusal.
+pub fn run_log(args: &LogArgs) -> u8 {
+    // Step 1: Load the starting lockfile and index the search directory.
+    let loaded = load_sealed_lockfile(&args.lockfile)
+        .and_then(|start| index_lockfiles(&args.search_dir).map(|index| (start, index)));
+    let (start, index) = match loaded {
+        Ok(pair) => pair,
+        Err(refusal) => {
+            let payload = refusal.to_json(LOG_VERSION);
+            print!("{payload}");
+            emit_witness(args, 2, "REFUSAL", payload.as_bytes());
+            return 2;
+        }
+    };
+
+    // Step 2: Follow parent links.
+    let result = build_log(args, start, &index);
+    let exit_code = if result.outcome == "LOG_COMPLETE" {
+        0
+    } else {
+        1
+    };
+
+    // Step 3: Emit output.
+    let value = sort_value(serde_json::to_value(&result).expect("LogResult is serializable"));
+    let payload = if args.json {
+        serde_json::to_string(&value).expect("sorted Value is always serializable")
+    } else {
+        output::render_human(&value)
+    };
+    print!("{payload}");
+    emit_witness(args, exit_code, &result.outcome, payload.as_bytes());
+    exit_code
+}
+
+fn emit_witness(args: &LogArgs, exit_code: u8, outcome: &str, output_bytes: &[u8]) {
+    if args.no_witness {
+        return;
+    }
+
+    let params = serde_json::json!({
+        "subcommand": "log",
+        "search_dir": args.search_dir.display().to_string(),
+    });
+    let inputs = serde_json::json!([
+        { "path": args.lockfile.display().to_string(), "hash": null, "bytes": null },
+    ]);
+
+    crate::witness::append_witness_record(outcome, exit_code, output_bytes, params, inputs);
+}
+
+// ---------------------------------------------------------------------------
+// Tests
+// ---------------------------------------------------------------------------
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    fn lockfile(hash: &str, parent: Option<&str>, paths: &[&str]) -> Value {
+        let members: Vec<Value> = paths
+            .iter()
+            .map(|path| json!({ "path": path, "bytes_hash": format!("sha256:{path}"), "size": 1 }))
+            .collect();
+        let mut value = json!({
+            "lock_hash": hash,
+            "created": "2026-01-01T00:00:00Z",
+            "dataset_id": "raw",
+            "as_of": null,
+            "members": members,
+        });
+        if let Some(parent) = parent {
+            value["parent"] = json!(parent);
+        }
+        value
+    }
+
+    fn located(name: &str, value: Value) -> Located {
+        Located {
+            path: PathBuf::from(name),
+            value,
+        }
+    }
+
+    #[test]
+    fn walks_to_root_with_member_changes() {
+        let mut index = BTreeMap::new();
+        index.insert(
+            "sha256:a".to_string(),
+            located("a.lock.json", lockfile("sha256:a", None, &["x.csv"])),
+        );
+        index.insert(
+            "sha256:b".to_string(),
+            located(
+                "b.lock.json",
+                lockfile("sha256:b", Some("sha256:a"), &["x.csv", "y.csv"]),
+            ),
+        );
+        let start = located(
+            "c.lock.json",
+            lockfile("sha256:c", Some("sha256:b"), &["y.csv"]),
+        );
+
+        let (entries, end) = walk_lineage(start, &index);
+        assert_eq!(end, LineageEnd::Root);
+        let hashes: Vec<&str> = entries.iter().map(|e| e.lock_hash.as_str()).collect();
+        assert_eq!(hashes, ["sha256:c", "sha256:b", "sha256:a"]);
+        assert_eq!(
+            entries[0].changes,
+            Some(LogChanges {
+                added: 0,
+                removed: 1,
+                changed: 0
+            })
+        );
+        assert_eq!(
+            entries[1].changes,
+            Some(LogChanges {
+                added: 1,
+                removed: 0,
+                changed: 0
+            })
+        );
+        assert_eq!(entries[2].changes, None);
+        assert_eq!(entries[2].path, "a.lock.json");
+    }
+
+    #[test]
+    fn missing_parent_breaks_the_chain() {
+        let start = located(
+            "c.lock.json",
+            lockfile("sha256:c", Some("sha256:gone"), &["y.csv"]),
+        );
+
+        let (entries, end) = walk_lineage(start, &BTreeMap::new());
+        assert_eq!(entries.len(), 1);
+        assert_eq!(entries[0].changes, None);
+        assert_eq!(end, LineageEnd::MissingParent("sha256:gone".to_string()));
+    }
+
+    #[test]
+    fn repeated_hash_is_reported_as_a_cycle() {
+        let mut index = BTreeMap::new();
+        index.insert(
+            "sha256:a".to_string(),
+            located("a.lock.json", lockfile("sha256:a", Some("sha256:a"), &[])),
+        );
+        let start = located("a.lock.json", lockfile("sha256:a", Some("sha256:a"), &[]));
+
+        let (entries, end) = walk_lineage(start, &index);
+        assert_eq!(entries.len(), 1);
+        assert_eq!(end, LineageEnd::Cycle("sha256:a".to_string()));
+
+        // A longer loop stops where it first comes back round.
+        index.insert(
+            "sha256:b".to_string(),
+            located("b.lock.json", lockfile("sha256:b", Some("sha256:c"), &[])),
+        );
+        index.insert(
+            "sha256:c".to_string(),
+            located("c.lock.json", lockfile("sha256:c", Some("sha256:b"), &[])),
+        );
+        let start = located("d.lock.json", lockfile("sha256:d", Some("sha256:b"), &[]));
+
+        let (entries, end) = walk_lineage(start, &index);
+        let hashes: Vec<&str> = entries.iter().map(|e| e.lock_hash.as_str()).collect();
+        assert_eq!(hashes, ["sha256:d", "sha256:b", "sha256:c"]);
+        assert_eq!(end, LineageEnd::Cycle("sha256:b".to_string()));
+        assert_eq!(entries[2].changes, None);
+    }
+}
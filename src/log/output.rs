@@ -0,0 +1,148 @@
+use serde_json::Value;
+
+/// Render a log result as human-readable text.
+///
+/// Returns a string suitable for printing to stdout (no trailing newline).
+pub fn render_human(result: &Value) -> String {
+    let outcome = result["outcome"].as_str().unwrap_or("?");
+    let lockfile = result["lockfile"].as_str().unwrap_or("?");
+    let entries = result["entries"].as_array().map_or(&[][..], Vec::as_slice);
+
+    let mut lines = vec![match outcome {
+        "LOG_COMPLETE" => format!(
+            "\u{2713} {lockfile}: {} lockfile(s) back to root",
+            entries.len()
+        ),
+        "LOG_BROKEN" => format!(
+            "\u{26A0} {lockfile}: lineage broken after {} lockfile(s) \u{2014} parent {} not found under {}",
+            entries.len(),
+            result["missing_parent"].as_str().unwrap_or("?"),
+            result["search_dir"].as_str().unwrap_or("?")
+        ),
+        "LOG_CYCLE" => format!(
+            "\u{26A0} {lockfile}: lineage loops after {} lockfile(s) \u{2014} parent {} is already in the chain",
+            entries.len(),
+            result["cycle_at"].as_str().unwrap_or("?")
+        ),
+        "REFUSAL" => return render_refusal(result),
+        _ => return format!("? {lockfile}: unknown outcome {outcome}"),
+    }];
+
+    for entry in entries {
+        lines.push(render_entry(entry));
+    }
+
+    lines.join("\n")
+}
+
+fn render_entry(entry: &Value) -> String {
+    let lock_hash = entry["lock_hash"].as_str().unwrap_or("?");
+    let created = entry["created"].as_str().unwrap_or("-");
+    let path = entry["path"].as_str().unwrap_or("?");
+    let member_count = entry["member_count"].as_u64().unwrap_or(0);
+
+    let changes = &entry["changes"];
+    let delta = if changes.is_object() {
+        let count = |key: &str| changes[key].as_u64().unwrap_or(0);
+        format!(
+            ", +{} -{} ~{}",
+            count("added"),
+            count("removed"),
+            count("changed")
+        )
+    } else if entry["parent"].is_null() {
+        ", root".to_string()
+    } else {
+        String::new()
+    };
+
+    format!("  {lock_hash} {created} {path} ({member_count} members{delta})")
+}
+
+fn render_refusal(result: &Value) -> String {
+    let code = result["refusal"]["code"].as_str().unwrap_or("?");
+    let message = result["refusal"]["message"].as_str().unwrap_or("?");
+    format!("\u{2717} {code}: {message}")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn render_complete_lists_entries_with_changes() {
+        let result = serde_json::json!({
+            "outcome": "LOG_COMPLETE",
+            "lockfile": "dec.lock.json",
+            "entries": [
+                {
+                    "lock_hash": "sha256:bb",
+                    "created": "2026-01-02T00:00:00Z",
+                    "path": "dec.lock.json",
+                    "member_count": 3,
+                    "parent": "sha256:aa",
+                    "changes": { "added": 1, "removed": 0, "changed": 2 }
+                },
+                {
+                    "lock_hash": "sha256:aa",
+                    "created": "2026-01-01T00:00:00Z",
+                    "path": "locks/nov.lock.json",
+                    "member_count": 2,
+                    "parent": null,
+                    "changes": null
+                }
+            ]
+        });
+        let output = render_human(&result);
+        assert!(output.starts_with('\u{2713}'));
+        assert!(output.contains("2 lockfile(s) back to root"));
+        assert!(
+            output.contains("sha256:bb 2026-01-02T00:00:00Z dec.lock.json (3 members, +1 -0 ~2)")
+        );
+        assert!(output.contains("locks/nov.lock.json (2 members, root)"));
+    }
+
+    #[test]
+    fn render_broken_names_missing_parent() {
+        let result = serde_json::json!({
+            "outcome": "LOG_BROKEN",
+            "lockfile": "dec.lock.json",
+            "search_dir": "locks",
+            "missing_parent": "sha256:gone",
+            "entries": [{
+                "lock_hash": "sha256:bb",
+                "created": "2026-01-02T00:00:00Z",
+                "path": "dec.lock.json",
+                "member_count": 3,
+                "parent": "sha256:gone",
+                "changes": null
+            }]
+        });
+        let output = render_human(&result);
+        assert!(output.starts_with('\u{26A0}'));
+        assert!(output.contains("parent sha256:gone not found under locks"));
+        assert!(output.contains("dec.lock.json (3 members)"));
+    }
+
+    #[test]
+    fn render_cycle_names_repeated_parent() {
+        let result = serde_json::json!({
+            "outcome": "LOG_CYCLE",
+            "lockfile": "dec.lock.json",
+            "search_dir": "locks",
+            "missing_parent": null,
+            "cycle_at": "sha256:bb",
+            "entries": [{
+                "lock_hash": "sha256:bb",
+                "created": "2026-01-02T00:00:00Z",
+                "path": "dec.lock.json",
+                "member_count": 3,
+                "parent": "sha256:bb",
+                "changes": null
+            }]
+        });
+        let output = render_human(&result);
+        assert!(output.starts_with('\u{26A0}'));
+        assert!(output.contains("parent sha256:bb is already in the chain"));
+    }
+}
@@ -16,6 +16,14 @@ impl DomainOutcome {
             Self::Refusal => 2,
         }
     }
+    /// Wire-format outcome string (e.g. `"LOCK_CREATED"`).
+    pub fn as_str(self) -> &'static str {
+        match self {
+            Self::LockCreated => "LOCK_CREATED",
+            Self::LockPartial => "LOCK_PARTIAL",
+            Self::Refusal => "REFUSAL",
+        }
+    }
 }
 
 #[derive(Debug, Clone, PartialEq, Eq)]
@@ -53,6 +61,8 @@ mod tests {
             dataset_id: Some("dataset-a".to_owned()),
             as_of: None,
             note: None,
+            parent: None,
+            members_root: None,
             created: "2026-02-24T00:00:00Z".to_owned(),
             tool_versions: BTreeMap::from([("lock".to_owned(), "0.1.0".to_owned())]),
             profiles: vec![],
@@ -62,6 +72,9 @@ mod tests {
                 bytes_hash: "sha256:aaaa".to_owned(),
                 size: 10,
                 fingerprint: None,
+                mtime: None,
+                extra: None,
+                hashes: None,
             }],
             skipped_count: 0,
             member_count: 1,
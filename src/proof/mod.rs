@@ -0,0 +1,585 @@
+//! Single-member inclusion proofs.
+//!
+//! `lock prove` extracts one member from a sealed lockfile together with the
+//! RFC 6962 audit path that links it to the Merkle root over all members
+//! (the `members_root` of a `lock.v1` lockfile). `lock verify-proof` checks
+//! such a proof — and optionally the file it describes — without the
+//! lockfile itself.
+
+pub mod output;
+
+use std::collections::BTreeMap;
+use std::fs;
+use std::path::Path;
+
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+
+use crate::cli::{ProveArgs, VerifyProofArgs};
+use crate::lockfile::merkle::{self, Digest32};
+use crate::lockfile::self_hash::to_canonical_json;
+use crate::refusal::sort_value;
+use crate::verify::{LockfileRefusal, VerifyRefusalCode, load_sealed_lockfile, members};
+
+/// Inclusion proof schema version.
+pub const PROOF_VERSION: &str = "lock-proof.v0";
+
+/// `lock verify-proof` output schema version.
+pub const PROOF_VERIFY_VERSION: &str = "lock-proof-verify.v0";
+
+// ---------------------------------------------------------------------------
+// Proof document
+// ---------------------------------------------------------------------------
+
+/// A self-contained proof that one member belongs to a sealed lockfile.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct InclusionProof {
+    pub version: String,
+    /// `lock_hash` of the lockfile the proof was taken from.
+    pub lock_hash: String,
+    /// Version of that lockfile (`lock.v0` or `lock.v1`).
+    pub lock_version: String,
+    pub dataset_id: Option<String>,
+    /// The member record exactly as it appears in the lockfile.
+    pub member: Value,
+    pub leaf_index: u64,
+    pub tree_size: u64,
+    /// Sibling hashes from the member's leaf up to the root.
+    pub audit_path: Vec<String>,
+    /// Merkle root over all members.
+    pub members_root: String,
+    /// Whether the lockfile itself records `members_root` (and so `lock_hash`
+    /// covers it). False for `lock.v0` lockfiles, where the root was computed
+    /// only for this proof.
+    pub root_committed: bool,
+}
+
+/// Build a proof for the member at `path` in an already sealed lockfile.
+fn build_proof(lockfile: &Value, path: &str) -> Result<InclusionProof, LockfileRefusal> {
+    let members = lockfile["members"]
+        .as_array()
+        .map_or(&[][..], Vec::as_slice);
+    let Some(index) = members
+        .iter()
+        .position(|m| m["path"].as_str() == Some(path))
+    else {
+        return Err(not_member_refusal(lockfile, path));
+    };
+
+    let leaves: Vec<Digest32> = members.iter().map(merkle::leaf_hash).collect();
+    let audit_path = merkle::audit_path(&leaves, index)
+        .iter()
+        .map(merkle::format_digest)
+        .collect();
+
+    Ok(InclusionProof {
+        version: PROOF_VERSION.to_string(),
+        lock_hash: str_field(lockfile, "lock_hash").unwrap_or_default(),
+        lock_version: str_field(lockfile, "version").unwrap_or_default(),
+        dataset_id: str_field(lockfile, "dataset_id"),
+        member: members[index].clone(),
+        leaf_index: index as u64,
+        tree_size: members.len() as u64,
+        audit_path,
+        members_root: merkle::format_digest(&merkle::root(&leaves)),
+        root_committed: lockfile.get("members_root").is_some_and(Value::is_string),
+    })
+}
+
+fn str_field(value: &Value, key: &str) -> Option<String> {
+    value[key].as_str().map(str::to_owned)
+}
+
+fn not_member_refusal(lockfile: &Value, path: &str) -> LockfileRefusal {
+    let skipped = lockfile["skipped"]
+        .as_array()
+        .is_some_and(|entries| entries.iter().any(|e| e["path"].as_str() == Some(path)));
+    LockfileRefusal::new(
+        VerifyRefusalCode::NotMember,
+        format!("path is not a member of the lockfile: {path}"),
+        serde_json::json!({
+            "path": path,
+            "skipped": skipped,
+        }),
+    )
+}
+
+// ---------------------------------------------------------------------------
+// Proof verification
+// ---------------------------------------------------------------------------
+
+/// The top-level result emitted by `lock verify-proof`.
+#[derive(Debug, Clone, Serialize)]
+struct ProofVerifyResult {
+    version: String,
+    outcome: String,
+    proof: String,
+    lock_hash: String,
+    path: String,
+    root: RootCheck,
+    root_committed: bool,
+    pinned: Option<PinCheck>,
+    file: Option<FileCheck>,
+    tool_versions: BTreeMap<String, String>,
+}
+
+/// Recomputed Merkle root versus the one the proof claims.
+#[derive(Debug, Clone, Serialize)]
+struct RootCheck {
+    stored: String,
+    computed: Option<String>,
+    valid: bool,
+}
+
+/// The proof's claims compared with a root the caller trusts, from
+/// `--members-root` or `--lockfile`.
+#[derive(Debug, Clone, Serialize)]
+struct PinCheck {
+    members_root: String,
+    /// `lock_hash` of `--lockfile`; null for `--members-root`.
+    lock_hash: Option<String>,
+    lockfile: Option<String>,
+    valid: bool,
+}
+
+/// `--file` content compared with the proven member record.
+#[derive(Debug, Clone, Serialize)]
+struct FileCheck {
+    path: String,
+    valid: bool,
+    reason: Option<String>,
+    expected: String,
+    actual: Option<String>,
+    expected_size: u64,
+    actual_size: u64,
+}
+
+/// Parse a proof document, checking its version and member shape.
+fn parse_proof(path: &Path) -> Result<InclusionProof, LockfileRefusal> {
+    let json = fs::read_to_string(path).map_err(|e| {
+        LockfileRefusal::new(
+            VerifyRefusalCode::Io,
+            format!("cannot read proof — {e}"),
+            serde_json::json!({
+                "path": path.display().to_string(),
+                "error": e.to_string(),
+            }),
+        )
+    })?;
+    let bad_proof = |error: String| {
+        LockfileRefusal::new(
+            VerifyRefusalCode::BadProof,
+            format!("invalid inclusion proof — {error}"),
+            serde_json::json!({
+                "path": path.display().to_string(),
+                "error": error,
+            }),
+        )
+    };
+
+    let proof: InclusionProof =
+        serde_json::from_str(&json).map_err(|e| bad_proof(e.to_string()))?;
+    if proof.version != PROOF_VERSION {
+        return Err(bad_proof(format!(
+            "unsupported proof version: {}",
+            proof.version
+        )));
+    }
+    if member_identity(&proof.member).is_none() {
+        return Err(bad_proof(
+            "member must have string path and bytes_hash and integer size".to_string(),
+        ));
+    }
+    Ok(proof)
+}
+
+/// `(path, bytes_hash, size)` of a member record.
+fn member_identity(member: &Value) -> Option<(&str, &str, u64)> {
+    Some((
+        member["path"].as_str()?,
+        member["bytes_hash"].as_str()?,
+        member["size"].as_u64()?,
+    ))
+}
+
+/// Recompute the root from the proof's member and audit path.
+fn check_root(proof: &InclusionProof) -> RootCheck {
+    let path: Option<Vec<Digest32>> = proof
+        .audit_path
+        .iter()
+        .map(|hash| merkle::parse_digest(hash))
+        .collect();
+    let computed = path.and_then(|path| {
+        merkle::root_from_audit_path(
+            &merkle::leaf_hash(&proof.member),
+            proof.leaf_index,
+            proof.tree_size,
+            &path,
+        )
+    });
+    let computed = computed.map(|root| merkle::format_digest(&root));
+
+    RootCheck {
+        valid: computed.as_deref() == Some(proof.members_root.as_str()),
+        stored: proof.members_root.clone(),
+        computed,
+    }
+}
+
+/// Compare `file` against the member's size and `bytes_hash`.
+fn check_file(file: &Path, member: &Value) -> Result<FileCheck, LockfileRefusal> {
+    let (_, expected, expected_size) = member_identity(member).expect("validated in parse_proof");
+    let io_refusal = |error: String| {
+        LockfileRefusal::new(
+            VerifyRefusalCode::Io,
+            format!("cannot read file — {error}"),
+            serde_json::json!({
+                "path": file.display().to_string(),
+                "error": error,
+            }),
+        )
+    };
+
+    let actual_size = fs::metadata(file)
+        .map_err(|e| io_refusal(e.to_string()))?
+        .len();
+    let (reason, actual) = if actual_size != expected_size {
+        (Some("SIZE_MISMATCH"), None)
+    } else {
+        let actual = members::stream_hash(file, expected).map_err(io_refusal)?;
+        let reason = (actual != expected).then_some("HASH_MISMATCH");
+        (reason, Some(actual))
+    };
+
+    Ok(FileCheck {
+        path: file.display().to_string(),
+        valid: reason.is_none(),
+        reason: reason.map(str::to_owned),
+        expected: expected.to_string(),
+        actual,
+        expected_size,
+        actual_size,
+    })
+}
+
+/// Check the proof against `--members-root` or `--lockfile`, if given.
+///
+/// A `lock.v0` lockfile records no root, so its root is recomputed from its
+/// members; `load_sealed_lockfile` has already checked `lock_hash` over them.
+fn check_pin(
+    args: &VerifyProofArgs,
+    proof: &InclusionProof,
+) -> Result<Option<PinCheck>, LockfileRefusal> {
+    if let Some(members_root) = &args.members_root {
+        return Ok(Some(PinCheck {
+            valid: proof.members_root == *members_root,
+            members_root: members_root.clone(),
+            lock_hash: None,
+            lockfile: None,
+        }));
+    }
+    let Some(path) = &args.lockfile else {
+        return Ok(None);
+    };
+
+    let lockfile = load_sealed_lockfile(path)?;
+    let members_root = str_field(&lockfile, "members_root").unwrap_or_else(|| {
+        merkle::members_root(
+            lockfile["members"]
+                .as_array()
+                .map_or(&[][..], Vec::as_slice),
+        )
+    });
+    let lock_hash = str_field(&lockfile, "lock_hash").unwrap_or_default();
+    Ok(Some(PinCheck {
+        valid: proof.members_root == members_root && proof.lock_hash == lock_hash,
+        members_root,
+        lock_hash: Some(lock_hash),
+        lockfile: Some(path.display().to_string()),
+    }))
+}
+
+fn build_verify_result(
+    args: &VerifyProofArgs,
+    proof: &InclusionProof,
+) -> Result<ProofVerifyResult, LockfileRefusal> {
+    let root = check_root(proof);
+    let pinned = check_pin(args, proof)?;
+    let file = args
+        .file
+        .as_deref()
+        .map(|file| check_file(file, &proof.member))
+        .transpose()?;
+    let valid = root.valid
+        && pinned.as_ref().is_none_or(|p| p.valid)
+        && file.as_ref().is_none_or(|f| f.valid);
+    // `root_committed` comes from the proof file like everything else in
+    // it, so only a root the caller supplied makes the proof valid.
+    let outcome = if !valid {
+        "PROOF_INVALID"
+    } else if pinned.is_none() {
+        "PROOF_UNCOMMITTED"
+    } else {
+        "PROOF_VALID"
+    };
+
+    let mut tool_versions = BTreeMap::new();
+    tool_versions.insert("lock".to_string(), env!("CARGO_PKG_VERSION").to_string());
+
+    Ok(ProofVerifyResult {
+        version: PROOF_VERIFY_VERSION.to_string(),
+        outcome: outcome.to_string(),
+        proof: args.proof.display().to_string(),
+        lock_hash: proof.lock_hash.clone(),
+        path: member_identity(&proof.member)
+            .map(|(path, _, _)| path.to_string())
+            .unwrap_or_default(),
+        root,
+        root_committed: proof.root_committed,
+        pinned,
+        file,
+        tool_versions,
+    })
+}
+
+// ---------------------------------------------------------------------------
+// Orchestration
+// ---------------------------------------------------------------------------
+
+/// Run the prove subcommand. Returns the exit code.
+///
+/// Exit codes: `0` proof written, `2` refusal.
+pub fn run_prove(args: &ProveArgs) -> u8 {
+    let proof = load_sealed_lockfile(&args.lockfile)
+        .and_then(|lockfile| build_proof(&lockfile, &args.path));
+
+    match proof {
+        Ok(proof) => {
+            let payload = to_canonical_json(&proof).expect("InclusionProof is serializable");
+            if !crate::write_payload(args.output.as_deref(), &payload) {
+                return 2;
+            }
+            emit_prove_witness(args, 0, "PROVED", payload.as_bytes());
+            0
+        }
+        Err(refusal) => {
+            let payload = refusal.to_json(PROOF_VERSION);
+            print!("{payload}");
+            emit_prove_witness(args, 2, "REFUSAL", payload.as_bytes());
+            2
+        }
+    }
+}
+
+/// Run the verify-proof subcommand. Returns the exit code.
+///
+/// Exit codes: `0` proof (and `--file`) valid, `1` invalid or uncommitted,
+/// `2` refusal.
+pub fn run_verify_proof(args: &VerifyProofArgs) -> u8 {
+    let result = parse_proof(&args.proof).and_then(|proof| build_verify_result(args, &proof));
+    let result = match result {
+        Ok(result) => result,
+        Err(refusal) => {
+            let payload = refusal.to_json(PROOF_VERIFY_VERSION);
+            print!("{payload}");
+            emit_verify_witness(args, 2, "REFUSAL", payload.as_bytes());
+            return 2;
+        }
+    };
+    let exit_code = if result.outcome == "PROOF_VALID" {
+        0
+    } else {
+        1
+    };
+
+    let value =
+        sort_value(serde_json::to_value(&result).expect("ProofVerifyResult is serializable"));
+    let payload = if args.json {
+        serde_json::to_string(&value).expect("sorted Value is always serializable")
+    } else {
+        output::render_human(&value)
+    };
+    print!("{payload}");
+    emit_verify_witness(args, exit_code, &result.outcome, payload.as_bytes());
+    exit_code
+}
+
+fn emit_prove_witness(args: &ProveArgs, exit_code: u8, outcome: &str, output_bytes: &[u8]) {
+    if args.no_witness {
+        return;
+    }
+
+    let params = serde_json::json!({
+        "subcommand": "prove",
+        "path": args.path,
+    });
+    let inputs = serde_json::json!([
+        { "path": args.lockfile.display().to_string(), "hash": null, "bytes": null },
+    ]);
+
+    crate::witness::append_witness_record(outcome, exit_code, output_bytes, params, inputs);
+}
+
+fn emit_verify_witness(args: &VerifyProofArgs, exit_code: u8, outcome: &str, output_bytes: &[u8]) {
+    if args.no_witness {
+        return;
+    }
+
+    let params = serde_json::json!({
+        "subcommand": "verify-proof",
+        "file": args.file.as_ref().map(|p| p.display().to_string()),
+        "members_root": args.members_root,
+        "lockfile": args.lockfile.as_ref().map(|p| p.display().to_string()),
+    });
+    let mut inputs = vec![serde_json::json!({
+        "path": args.proof.display().to_string(),
+        "hash": null,
+        "bytes": null,
+    })];
+    if let Some(lockfile) = &args.lockfile {
+        inputs.push(serde_json::json!({
+            "path": lockfile.display().to_string(),
+            "hash": null,
+            "bytes": null,
+        }));
+    }
+    if let Some(file) = &args.file {
+        inputs.push(serde_json::json!({
+            "path": file.display().to_string(),
+            "hash": null,
+            "bytes": null,
+        }));
+    }
+
+    crate::witness::append_witness_record(
+        outcome,
+        exit_code,
+        output_bytes,
+        params,
+        Value::Array(inputs),
+    );
+}
+
+// ---------------------------------------------------------------------------
+// Tests
+// ---------------------------------------------------------------------------
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    fn lockfile(paths: &[&str], members_root: bool) -> Value {
+        let members: Vec<Value> = paths
+            .iter()
+            .map(|path| json!({ "path": path, "bytes_hash": "sha256:00", "size": 1, "fingerprint": null }))
+            .collect();
+        let mut value = json!({
+            "version": if members_root { "lock.v1" } else { "lock.v0" },
+            "lock_hash": "sha256:lock",
+            "dataset_id": "raw",
+            "members": members,
+            "skipped": [{ "path": "locked.bin", "warnings": [] }],
+        });
+        if members_root {
+            value["members_root"] =
+                json!(merkle::members_root(value["members"].as_array().unwrap()));
+        }
+        value
+    }
+
+    #[test]
+    fn proof_for_each_member_checks_against_members_root() {
+        let lockfile = lockfile(&["a.csv", "b.csv", "c.csv", "d.csv", "e.csv"], true);
+        for path in ["a.csv", "c.csv", "e.csv"] {
+            let proof = build_proof(&lockfile, path).unwrap();
+            assert_eq!(proof.member["path"], path);
+            assert_eq!(proof.tree_size, 5);
+            assert_eq!(
+                proof.members_root,
+                lockfile["members_root"].as_str().unwrap()
+            );
+            assert!(proof.root_committed);
+            assert!(check_root(&proof).valid);
+        }
+    }
+
+    #[test]
+    fn v0_proof_is_marked_uncommitted() {
+        let proof = build_proof(&lockfile(&["a.csv", "b.csv"], false), "b.csv").unwrap();
+        assert_eq!(proof.lock_version, "lock.v0");
+        assert!(!proof.root_committed);
+        assert!(check_root(&proof).valid);
+    }
+
+    fn verify_args(members_root: Option<&str>) -> VerifyProofArgs {
+        VerifyProofArgs {
+            proof: "p.json".into(),
+            file: None,
+            members_root: members_root.map(str::to_owned),
+            lockfile: None,
+            json: true,
+            no_witness: true,
+        }
+    }
+
+    #[test]
+    fn forged_proof_is_never_valid_without_a_trusted_pin() {
+        let real = lockfile(&["a.csv", "b.csv"], true);
+        let forged = build_proof(&lockfile(&["a.csv", "evil.csv"], true), "evil.csv").unwrap();
+        assert!(forged.root_committed);
+        let outcome = |args: &VerifyProofArgs| build_verify_result(args, &forged).unwrap().outcome;
+
+        assert_eq!(outcome(&verify_args(None)), "PROOF_UNCOMMITTED");
+        assert_eq!(
+            outcome(&verify_args(real["members_root"].as_str())),
+            "PROOF_INVALID"
+        );
+        assert_eq!(
+            outcome(&verify_args(Some(&forged.members_root))),
+            "PROOF_VALID"
+        );
+    }
+
+    #[test]
+    fn uncommitted_root_needs_a_pin() {
+        let proof = build_proof(&lockfile(&["a.csv", "b.csv"], false), "b.csv").unwrap();
+        let result = build_verify_result(&verify_args(None), &proof).unwrap();
+        assert_eq!(result.outcome, "PROOF_UNCOMMITTED");
+        assert!(result.pinned.is_none());
+
+        let result = build_verify_result(&verify_args(Some(&proof.members_root)), &proof).unwrap();
+        assert_eq!(result.outcome, "PROOF_VALID");
+        assert!(result.pinned.unwrap().valid);
+    }
+
+    #[test]
+    fn altered_member_fails_root_check() {
+        let mut proof =
+            build_proof(&lockfile(&["a.csv", "b.csv", "c.csv"], true), "b.csv").unwrap();
+        proof.member["size"] = json!(2);
+        let root = check_root(&proof);
+        assert!(!root.valid);
+        assert!(root.computed.is_some());
+    }
+
+    #[test]
+    fn malformed_audit_path_fails_root_check() {
+        let mut proof =
+            build_proof(&lockfile(&["a.csv", "b.csv", "c.csv"], true), "b.csv").unwrap();
+        proof.audit_path[0] = "sha256:nothex".to_string();
+        let root = check_root(&proof);
+        assert!(!root.valid);
+        assert_eq!(root.computed, None);
+    }
+
+    #[test]
+    fn skipped_path_is_not_a_member() {
+        let refusal = build_proof(&lockfile(&["a.csv"], true), "locked.bin").unwrap_err();
+        assert_eq!(refusal.code, VerifyRefusalCode::NotMember);
+        assert_eq!(refusal.detail["skipped"], true);
+
+        let refusal = build_proof(&lockfile(&["a.csv"], true), "absent.csv").unwrap_err();
+        assert_eq!(refusal.detail["skipped"], false);
+    }
+}
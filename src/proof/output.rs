@@ -0,0 +1,160 @@
+use serde_json::Value;
+
+/// Render a verify-proof result as human-readable text.
+///
+/// Returns a string suitable for printing to stdout (no trailing newline).
+pub fn render_human(result: &Value) -> String {
+    let outcome = result["outcome"].as_str().unwrap_or("?");
+    let path = result["path"].as_str().unwrap_or("?");
+    let lock_hash = result["lock_hash"].as_str().unwrap_or("?");
+
+    let mut lines = match outcome {
+        "PROOF_VALID" => vec![format!("\u{2713} {path}: included in {lock_hash}")],
+        "PROOF_INVALID" => vec![format!(
+            "\u{2717} {path}: proof does not establish inclusion in {lock_hash}"
+        )],
+        "PROOF_UNCOMMITTED" => vec![format!(
+            "\u{26A0} {path}: audit path is consistent, but no trusted root ties it to {lock_hash}"
+        )],
+        "REFUSAL" => return render_refusal(result),
+        _ => return format!("? {path}: unknown outcome {outcome}"),
+    };
+
+    let root = &result["root"];
+    let stored = root["stored"].as_str().unwrap_or("?");
+    if root["valid"].as_bool() == Some(true) {
+        lines.push(format!("  members_root: {stored}"));
+    } else {
+        let computed = root["computed"].as_str().unwrap_or("unrecomputable");
+        lines.push(format!("  members_root: {stored} (recomputed {computed})"));
+    }
+    let pinned = &result["pinned"];
+    if pinned.is_object() {
+        let source = pinned["lockfile"].as_str().unwrap_or("--members-root");
+        if pinned["valid"].as_bool() == Some(true) {
+            lines.push(format!("  pinned by {source}: matches"));
+        } else {
+            let expected = pinned["members_root"].as_str().unwrap_or("?");
+            let mut line = format!("  pinned by {source}: expected members_root {expected}");
+            if let Some(lock_hash) = pinned["lock_hash"].as_str() {
+                line.push_str(&format!(" in {lock_hash}"));
+            }
+            lines.push(line);
+        }
+    } else if result["root_committed"].as_bool() == Some(true) {
+        lines.push(
+            "  note: members_root comes from the proof; pin it with --lockfile or --members-root"
+                .to_string(),
+        );
+    } else {
+        lines.push("  note: lock.v0 lockfile does not record members_root; pin it with --lockfile or --members-root".to_string());
+    }
+
+    let file = &result["file"];
+    if file.is_object() {
+        let file_path = file["path"].as_str().unwrap_or("?");
+        match file["reason"].as_str() {
+            None => lines.push(format!("  file {file_path}: matches member")),
+            Some("SIZE_MISMATCH") => lines.push(format!(
+                "  file {file_path}: SIZE_MISMATCH (expected {} bytes, got {})",
+                file["expected_size"].as_u64().unwrap_or(0),
+                file["actual_size"].as_u64().unwrap_or(0)
+            )),
+            Some(reason) => lines.push(format!(
+                "  file {file_path}: {reason} (expected {}, got {})",
+                file["expected"].as_str().unwrap_or("?"),
+                file["actual"].as_str().unwrap_or("?")
+            )),
+        }
+    }
+
+    lines.join("\n")
+}
+
+fn render_refusal(result: &Value) -> String {
+    let code = result["refusal"]["code"].as_str().unwrap_or("?");
+    let message = result["refusal"]["message"].as_str().unwrap_or("?");
+    format!("\u{2717} {code}: {message}")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn render_valid_with_file() {
+        let result = serde_json::json!({
+            "outcome": "PROOF_VALID",
+            "path": "tape.csv",
+            "lock_hash": "sha256:aa",
+            "root": { "stored": "sha256:rr", "computed": "sha256:rr", "valid": true },
+            "root_committed": true,
+            "pinned": {
+                "members_root": "sha256:rr",
+                "lock_hash": "sha256:aa",
+                "lockfile": "dec.lock.json",
+                "valid": true
+            },
+            "file": { "path": "tape.csv", "valid": true, "reason": null }
+        });
+        let output = render_human(&result);
+        assert!(output.starts_with("\u{2713} tape.csv: included in sha256:aa"));
+        assert!(output.contains("members_root: sha256:rr"));
+        assert!(output.contains("pinned by dec.lock.json: matches"));
+        assert!(output.contains("file tape.csv: matches member"));
+        assert!(!output.contains("note:"));
+    }
+
+    #[test]
+    fn render_invalid_shows_mismatches() {
+        let result = serde_json::json!({
+            "outcome": "PROOF_INVALID",
+            "path": "tape.csv",
+            "lock_hash": "sha256:aa",
+            "root": { "stored": "sha256:rr", "computed": "sha256:xx", "valid": false },
+            "root_committed": false,
+            "file": {
+                "path": "local/tape.csv",
+                "valid": false,
+                "reason": "HASH_MISMATCH",
+                "expected": "sha256:11",
+                "actual": "sha256:22"
+            }
+        });
+        let output = render_human(&result);
+        assert!(output.starts_with('\u{2717}'));
+        assert!(output.contains("(recomputed sha256:xx)"));
+        assert!(output.contains("note: lock.v0"));
+        assert!(output.contains("HASH_MISMATCH (expected sha256:11, got sha256:22)"));
+    }
+
+    #[test]
+    fn render_uncommitted_and_pin_mismatch() {
+        let mut result = serde_json::json!({
+            "outcome": "PROOF_UNCOMMITTED",
+            "path": "tape.csv",
+            "lock_hash": "sha256:aa",
+            "root": { "stored": "sha256:rr", "computed": "sha256:rr", "valid": true },
+            "root_committed": false,
+            "pinned": null,
+            "file": null
+        });
+        let output = render_human(&result);
+        assert!(output.starts_with("\u{26A0} tape.csv:"), "{output}");
+        assert!(output.contains("pin it with --lockfile"));
+
+        result["outcome"] = serde_json::json!("PROOF_INVALID");
+        result["pinned"] = serde_json::json!({
+            "members_root": "sha256:pp",
+            "lock_hash": "sha256:bb",
+            "lockfile": "dec.lock.json",
+            "valid": false
+        });
+        let output = render_human(&result);
+        assert!(
+            output
+                .contains("pinned by dec.lock.json: expected members_root sha256:pp in sha256:bb")
+        );
+        assert!(!output.contains("note:"));
+    }
+}
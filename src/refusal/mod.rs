@@ -4,6 +4,9 @@ use serde_json::Value;
 /// Lock schema version, shared across lockfile and refusal envelopes.
 pub const LOCK_VERSION: &str = "lock.v0";
 
+/// Lockfile version that adds a `members_root` Merkle root over `members`.
+pub const LOCK_V1_VERSION: &str = "lock.v1";
+
 /// Maximum sample paths included in `E_MISSING_HASH` detail.
 const MAX_SAMPLE_PATHS: usize = 5;
 const PIPELINE_NEXT_COMMAND: &str =
@@ -135,6 +138,42 @@ pub fn bad_input_parse(line: usize, error: &str) -> RefusalEnvelope {
     }
 }
 
+/// Build an `E_BAD_INPUT` refusal for a line `lock import` cannot parse.
+pub fn bad_checksum_line(format: &str, line: usize, expected: &str) -> RefusalEnvelope {
+    RefusalEnvelope {
+        version: LOCK_VERSION.to_string(),
+        outcome: "REFUSAL".to_string(),
+        refusal: Refusal {
+            code: RefusalCode::BadInput,
+            message: format!("invalid {format} line {line} — expected {expected}"),
+            detail: serde_json::json!({
+                "line": line,
+                "format": format,
+                "expected": expected,
+            }),
+            next_command: None,
+        },
+    }
+}
+
+/// Build an `E_BAD_INPUT` refusal for a directory `lock import` cannot read
+/// as a BagIt bag.
+pub fn bad_bag(bag: &str, error: &str) -> RefusalEnvelope {
+    RefusalEnvelope {
+        version: LOCK_VERSION.to_string(),
+        outcome: "REFUSAL".to_string(),
+        refusal: Refusal {
+            code: RefusalCode::BadInput,
+            message: format!("{bag} is not a usable BagIt bag — {error}"),
+            detail: serde_json::json!({
+                "bag": bag,
+                "error": error,
+            }),
+            next_command: None,
+        },
+    }
+}
+
 /// Build an `E_BAD_INPUT` refusal for an unknown record version.
 pub fn bad_input_version(line: usize, version: &str) -> RefusalEnvelope {
     RefusalEnvelope {
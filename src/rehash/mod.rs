@@ -0,0 +1,287 @@
+use std::collections::{BTreeMap, BTreeSet};
+use std::path::Path;
+
+use serde_json::Value;
+
+use crate::OrchestrationOutput;
+use crate::cli::RehashArgs;
+use crate::lockfile::{Lockfile, Member};
+use crate::update::{BaseLockfile, load_base};
+use crate::verify::members::stream_hashes;
+use crate::verify::{LockfileRefusal, VerifyRefusalCode};
+
+// ---------------------------------------------------------------------------
+// Rehash
+// ---------------------------------------------------------------------------
+
+/// Why a member could not be rehashed; same vocabulary as verify failures.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Drift {
+    Missing,
+    IoError,
+    SizeMismatch,
+    HashMismatch,
+}
+
+impl Drift {
+    fn as_str(self) -> &'static str {
+        match self {
+            Self::Missing => "MISSING",
+            Self::IoError => "IO_ERROR",
+            Self::SizeMismatch => "SIZE_MISMATCH",
+            Self::HashMismatch => "HASH_MISMATCH",
+        }
+    }
+}
+
+/// Hash one member's file under `root` with every algorithm it already
+/// records plus `add`, in a single read.
+///
+/// The recorded digests must still match: a new digest is only worth
+/// sealing if it describes the bytes the lockfile already pinned.
+fn rehash_member(
+    member: &Member,
+    root: &Path,
+    add: &[String],
+) -> Result<BTreeMap<String, String>, Drift> {
+    let mut recorded = member.hashes.clone().unwrap_or_default();
+    let own = member.bytes_hash.split(':').next().unwrap_or("sha256");
+    recorded.insert(own.to_owned(), member.bytes_hash.clone());
+
+    let path = root.join(&member.path);
+    let metadata = std::fs::metadata(&path).map_err(|e| match e.kind() {
+        std::io::ErrorKind::NotFound => Drift::Missing,
+        _ => Drift::IoError,
+    })?;
+    if metadata.len() != member.size {
+        return Err(Drift::SizeMismatch);
+    }
+
+    let algorithms: Vec<&str> = recorded
+        .keys()
+        .map(String::as_str)
+        .chain(add.iter().map(String::as_str))
+        .collect::<BTreeSet<_>>()
+        .into_iter()
+        .collect();
+    let digests = stream_hashes(&path, &algorithms).map_err(|_| Drift::IoError)?;
+
+    let hashes: BTreeMap<String, String> = algorithms
+        .iter()
+        .map(|algorithm| algorithm.to_string())
+        .zip(digests)
+        .collect();
+    if recorded
+        .iter()
+        .any(|(algorithm, digest)| hashes.get(algorithm) != Some(digest))
+    {
+        return Err(Drift::HashMismatch);
+    }
+    Ok(hashes)
+}
+
+/// Record `add` digests on every member of `base`, re-sealed with the base
+/// as parent. Members keep a `hashes` map only when it names more than one
+/// algorithm, matching how upstream `hashes` are classified.
+fn rehash(base: BaseLockfile, root: &Path, add: &[String]) -> Result<Lockfile, LockfileRefusal> {
+    let mut members = base.members;
+    let mut drifted = Vec::new();
+    for member in &mut members {
+        match rehash_member(member, root, add) {
+            Ok(hashes) => member.hashes = (hashes.len() > 1).then_some(hashes),
+            Err(drift) => drifted.push(serde_json::json!({
+                "path": member.path,
+                "reason": drift.as_str(),
+            })),
+        }
+    }
+    if !drifted.is_empty() {
+        return Err(drift_refusal(root, drifted));
+    }
+
+    let mut tool_versions = base.tool_versions;
+    tool_versions.insert("lock".to_string(), env!("CARGO_PKG_VERSION").to_string());
+
+    Ok(Lockfile {
+        version: base.version,
+        lock_hash: String::new(),
+        dataset_id: base.dataset_id,
+        as_of: base.as_of,
+        note: base.note,
+        parent: Some(base.lock_hash),
+        members_root: None,
+        created: crate::current_created_timestamp(),
+        tool_versions,
+        profiles: base.profiles,
+        skipped_count: base.skipped.len() as u64,
+        member_count: members.len() as u64,
+        skipped: base.skipped,
+        members,
+    })
+}
+
+fn drift_refusal(root: &Path, drifted: Vec<Value>) -> LockfileRefusal {
+    LockfileRefusal::new(
+        VerifyRefusalCode::Drift,
+        format!(
+            "{} member(s) no longer match their files under {}",
+            drifted.len(),
+            root.display()
+        ),
+        serde_json::json!({
+            "root": root.display().to_string(),
+            "drifted": drifted,
+        }),
+    )
+}
+
+// ---------------------------------------------------------------------------
+// Orchestration
+// ---------------------------------------------------------------------------
+
+/// Run the rehash subcommand. Returns the exit code.
+pub fn run_rehash(args: &RehashArgs) -> u8 {
+    let base = load_base(&args.lockfile);
+    let base_hash = base.as_ref().ok().map(|base| base.lock_hash.clone());
+    let version = base
+        .as_ref()
+        .map_or(crate::refusal::LOCK_VERSION.to_string(), |base| {
+            base.version.clone()
+        });
+    let rehashed = base.and_then(|base| {
+        if !args.root.is_dir() {
+            return Err(LockfileRefusal::root_not_found(&args.root));
+        }
+        rehash(base, &args.root, &args.add)
+    });
+    let orchestrated = match rehashed {
+        Ok(lockfile) => crate::seal_lockfile(lockfile),
+        Err(refusal) => crate::lockfile_refusal_output(refusal, &version),
+    };
+
+    if !crate::write_payload(args.output.as_deref(), &orchestrated.payload_json) {
+        return 2;
+    }
+    emit_witness(args, &orchestrated, base_hash.as_deref());
+    orchestrated.outcome.exit_code()
+}
+
+/// `base_hash` is the input's verified `lock_hash`, once it has loaded.
+fn emit_witness(args: &RehashArgs, orchestrated: &OrchestrationOutput, base_hash: Option<&str>) {
+    if args.no_witness {
+        return;
+    }
+
+    let params = serde_json::json!({
+        "subcommand": "rehash",
+        "root": args.root.display().to_string(),
+        "add": args.add,
+    });
+    let inputs = serde_json::json!([
+        { "path": args.lockfile.display().to_string(), "hash": base_hash, "bytes": null }
+    ]);
+
+    crate::witness::append_witness_record(
+        orchestrated.outcome.as_str(),
+        orchestrated.outcome.exit_code(),
+        orchestrated.payload_json.as_bytes(),
+        params,
+        inputs,
+    );
+}
+
+// ---------------------------------------------------------------------------
+// Tests
+// ---------------------------------------------------------------------------
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::refusal::LOCK_VERSION;
+
+    fn sha256(data: &[u8]) -> String {
+        use sha2::{Digest, Sha256};
+        format!("sha256:{:x}", Sha256::digest(data))
+    }
+
+    fn blake3(data: &[u8]) -> String {
+        format!("blake3:{}", ::blake3::hash(data).to_hex())
+    }
+
+    fn member(path: &str, content: &[u8]) -> Member {
+        Member {
+            path: path.to_string(),
+            bytes_hash: sha256(content),
+            size: content.len() as u64,
+            fingerprint: None,
+            mtime: None,
+            extra: None,
+            hashes: None,
+        }
+    }
+
+    fn base(members: Vec<Member>) -> BaseLockfile {
+        BaseLockfile {
+            version: LOCK_VERSION.to_string(),
+            lock_hash: "sha256:base".to_string(),
+            dataset_id: Some("raw-dec".to_string()),
+            as_of: None,
+            note: None,
+            tool_versions: BTreeMap::from([("lock".to_string(), "0.4.0".to_string())]),
+            profiles: Vec::new(),
+            skipped: Vec::new(),
+            members,
+        }
+    }
+
+    #[test]
+    fn adds_digests_and_links_parent() {
+        let dir = tempfile::tempdir().unwrap();
+        std::fs::write(dir.path().join("a.csv"), b"alpha").unwrap();
+
+        let rehashed = rehash(
+            base(vec![member("a.csv", b"alpha")]),
+            dir.path(),
+            &["blake3".to_string()],
+        )
+        .unwrap();
+
+        assert_eq!(rehashed.parent.as_deref(), Some("sha256:base"));
+        assert_eq!(rehashed.members[0].bytes_hash, sha256(b"alpha"));
+        assert_eq!(
+            rehashed.members[0].hashes,
+            Some(BTreeMap::from([
+                ("blake3".to_string(), blake3(b"alpha")),
+                ("sha256".to_string(), sha256(b"alpha")),
+            ]))
+        );
+        assert_eq!(rehashed.tool_versions["lock"], env!("CARGO_PKG_VERSION"));
+    }
+
+    #[test]
+    fn refuses_when_files_drifted() {
+        let dir = tempfile::tempdir().unwrap();
+        std::fs::write(dir.path().join("a.csv"), b"ALPHA").unwrap();
+        std::fs::write(dir.path().join("b.csv"), b"longer").unwrap();
+
+        let refusal = rehash(
+            base(vec![
+                member("a.csv", b"alpha"),
+                member("b.csv", b"bravo"),
+                member("c.csv", b"charlie"),
+            ]),
+            dir.path(),
+            &["blake3".to_string()],
+        )
+        .unwrap_err();
+
+        assert_eq!(refusal.code, VerifyRefusalCode::Drift);
+        let reasons: Vec<&str> = refusal.detail["drifted"]
+            .as_array()
+            .unwrap()
+            .iter()
+            .map(|entry| entry["reason"].as_str().unwrap())
+            .collect();
+        assert_eq!(reasons, ["HASH_MISMATCH", "SIZE_MISMATCH", "MISSING"]);
+    }
+}
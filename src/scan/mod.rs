@@ -0,0 +1,294 @@
+//! Built-in directory scanning: `lock scan <DIR>`.
+//!
+//! Walks the directory and hashes each file itself, then feeds the results
+//! through the same validator, classifier, and sealing steps as JSONL from
+//! the `vacuum | hashbytes` pipeline. The records it synthesizes are
+//! `hash.v0`-shaped, so the lockfile is indistinguishable from a pipeline
+//! lock of the same files apart from `tool_versions`.
+
+use std::path::{Path, PathBuf};
+use std::time::SystemTime;
+
+use chrono::{DateTime, SecondsFormat, Utc};
+use serde_json::{Value, json};
+
+use crate::cli::ScanArgs;
+use crate::input::{InputRecord, RecordValidator};
+use crate::lockfile::{ClassifyOptions, RecordClassifier};
+use crate::verify::members::stream_hash_with;
+use crate::verify::{LockfileRefusal, VerifyRefusalCode};
+use crate::walk::{UnreadableDir, walk_files};
+use crate::{OrchestrationOutput, lockfile_refusal_output};
+
+/// Record version the scanner emits, matching `hashbytes` output.
+const SCAN_RECORD_VERSION: &str = "hash.v0";
+
+/// Hash one walked file into a pipeline-shaped record.
+///
+/// An unreadable file becomes a `_skipped` record with an `E_IO` warning,
+/// the same way `hashbytes` reports it, so the lock is `LOCK_PARTIAL`.
+fn scan_record(path: &Path, relative: &str, algorithm: &str) -> Value {
+    let tool_versions = json!({ "lock": env!("CARGO_PKG_VERSION") });
+    let hashed = std::fs::metadata(path)
+        .map_err(|e| e.to_string())
+        .and_then(|metadata| Ok((metadata, stream_hash_with(path, algorithm)?)));
+
+    match hashed {
+        Ok((metadata, bytes_hash)) => json!({
+            "version": SCAN_RECORD_VERSION,
+            "relative_path": relative,
+            "bytes_hash": bytes_hash,
+            "size": metadata.len(),
+            "mtime": metadata.modified().ok().map(format_mtime),
+            "tool_versions": tool_versions,
+        }),
+        Err(error) => json!({
+            "version": SCAN_RECORD_VERSION,
+            "relative_path": relative,
+            "_skipped": true,
+            "_warnings": [{
+                "tool": "lock",
+                "code": "E_IO",
+                "message": format!("Cannot read file: {error}"),
+                "detail": { "path": path.display().to_string() },
+            }],
+            "tool_versions": tool_versions,
+        }),
+    }
+}
+
+/// A `_skipped` record standing in for a subdirectory the walk could not
+/// list, so its unknown contents make the lock `LOCK_PARTIAL` rather than
+/// silently missing.
+fn unreadable_dir_record(dir: &UnreadableDir) -> Value {
+    json!({
+        "version": SCAN_RECORD_VERSION,
+        "relative_path": dir.relative,
+        "_skipped": true,
+        "_warnings": [{
+            "tool": "lock",
+            "code": "E_IO",
+            "message": format!("Cannot read directory: {}", dir.error),
+            "detail": { "path": dir.path.display().to_string() },
+        }],
+        "tool_versions": { "lock": env!("CARGO_PKG_VERSION") },
+    })
+}
+
+/// RFC 3339 UTC with the filesystem's full sub-second precision, so
+/// `verify --fast` can compare it exactly.
+fn format_mtime(modified: SystemTime) -> String {
+    DateTime::<Utc>::from(modified).to_rfc3339_opts(SecondsFormat::AutoSi, true)
+}
+
+/// Walk `args.dir` and build the lockfile output (or refusal).
+fn scan(args: &ScanArgs, parent: Option<String>) -> OrchestrationOutput {
+    let walk = match walk_files(&args.dir) {
+        Ok(walk) => walk,
+        Err(e) => {
+            return lockfile_refusal_output(
+                LockfileRefusal::new(
+                    VerifyRefusalCode::Io,
+                    format!("cannot read directory: {}", args.dir.display()),
+                    json!({
+                        "path": args.dir.display().to_string(),
+                        "error": e.to_string(),
+                    }),
+                ),
+                &args.lock_version,
+            );
+        }
+    };
+
+    // Never lock the lockfile being written, when --output is under the root.
+    let output = args.output.as_deref().and_then(canonical);
+
+    let mut validator = RecordValidator::default();
+    let mut classifier = RecordClassifier::new(ClassifyOptions {
+        keep_mtime: args.keep_mtime,
+        ..ClassifyOptions::default()
+    });
+    let mut record_count = 0;
+    let mut push = |value: Value| {
+        record_count += 1;
+        let record = InputRecord {
+            line_number: record_count,
+            value,
+        };
+        validator.observe(&record);
+        classifier.push(&record);
+    };
+    for file in walk.files {
+        if crate::glob::any_match(&args.exclude, &file.relative)
+            || (output.is_some() && canonical(&file.path) == output)
+        {
+            continue;
+        }
+        push(scan_record(&file.path, &file.relative, &args.algorithm));
+    }
+    for dir in &walk.unreadable {
+        if !crate::glob::any_match(&args.exclude, &dir.relative) {
+            push(unreadable_dir_record(dir));
+        }
+    }
+
+    match crate::finish_ingest(
+        record_count,
+        validator,
+        classifier,
+        args.dataset_id.as_deref(),
+        args.as_of.as_deref(),
+        args.note.as_deref(),
+    ) {
+        Ok(ingested) => {
+            crate::seal_lockfile(crate::new_lockfile(ingested, &args.lock_version, parent))
+        }
+        Err(refusal) => refusal,
+    }
+}
+
+fn canonical(path: &Path) -> Option<PathBuf> {
+    path.canonicalize().ok()
+}
+
+/// Run the scan subcommand. Returns the exit code.
+///
+/// Exit codes match lock creation: `0` LOCK_CREATED, `1` LOCK_PARTIAL,
+/// `2` refusal.
+pub fn run_scan(args: &ScanArgs) -> u8 {
+    // Resolve --parent before hashing anything, so a bad parent refuses fast.
+    let parent = match args
+        .parent
+        .as_deref()
+        .map(crate::resolve_parent)
+        .transpose()
+    {
+        Ok(parent) => parent,
+        Err(refusal) => {
+            return finish(
+                args,
+                &lockfile_refusal_output(refusal, &args.lock_version),
+                None,
+            );
+        }
+    };
+
+    let orchestrated = scan(args, parent.clone());
+    finish(args, &orchestrated, parent.as_deref())
+}
+
+fn finish(args: &ScanArgs, orchestrated: &OrchestrationOutput, parent: Option<&str>) -> u8 {
+    if !crate::write_payload(args.output.as_deref(), &orchestrated.payload_json) {
+        return 2;
+    }
+    emit_witness(args, orchestrated, parent);
+    orchestrated.outcome.exit_code()
+}
+
+/// `parent` is the `--parent` lockfile's `lock_hash`, once it has loaded.
+fn emit_witness(args: &ScanArgs, orchestrated: &OrchestrationOutput, parent: Option<&str>) {
+    if args.no_witness {
+        return;
+    }
+
+    let params = json!({
+        "subcommand": "scan",
+        "dataset_id": args.dataset_id,
+        "as_of": args.as_of,
+        "note": args.note,
+        "algorithm": args.algorithm,
+        "exclude": args.exclude,
+    });
+    let mut inputs = vec![json!({
+        "path": args.dir.display().to_string(),
+        "hash": null,
+        "bytes": null,
+    })];
+    if let Some(path) = &args.parent {
+        inputs.push(json!({
+            "path": path.display().to_string(),
+            "hash": parent,
+            "bytes": null,
+        }));
+    }
+
+    crate::witness::append_witness_record(
+        orchestrated.outcome.as_str(),
+        orchestrated.outcome.exit_code(),
+        orchestrated.payload_json.as_bytes(),
+        params,
+        Value::Array(inputs),
+    );
+}
+
+// ---------------------------------------------------------------------------
+// Tests
+// ---------------------------------------------------------------------------
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn record_matches_hashbytes_shape() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("a.csv");
+        std::fs::write(&path, b"hello").unwrap();
+
+        let record = scan_record(&path, "a.csv", "sha256");
+        assert_eq!(record["version"], "hash.v0");
+        assert_eq!(record["relative_path"], "a.csv");
+        assert_eq!(record["size"], 5);
+        assert_eq!(
+            record["bytes_hash"],
+            "sha256:2cf24dba5fb0a30e26e83b2ac5b9e29e1b161e5c1fa7425e73043362938b9824"
+        );
+        assert_eq!(record["tool_versions"]["lock"], env!("CARGO_PKG_VERSION"));
+        assert!(
+            DateTime::parse_from_rfc3339(record["mtime"].as_str().unwrap()).is_ok(),
+            "mtime must be RFC 3339"
+        );
+
+        let record = scan_record(&path, "a.csv", "blake3");
+        assert!(
+            record["bytes_hash"]
+                .as_str()
+                .unwrap()
+                .starts_with("blake3:")
+        );
+    }
+
+    #[test]
+    fn unreadable_file_becomes_skipped_record() {
+        let dir = tempfile::tempdir().unwrap();
+        let record = scan_record(&dir.path().join("gone.csv"), "gone.csv", "sha256");
+        assert_eq!(record["_skipped"], true);
+        assert_eq!(record["_warnings"][0]["tool"], "lock");
+        assert_eq!(record["_warnings"][0]["code"], "E_IO");
+        assert!(record.get("bytes_hash").is_none());
+    }
+
+    #[test]
+    fn unreadable_directory_becomes_skipped_record() {
+        let dir = UnreadableDir {
+            path: PathBuf::from("/data/dec/locked"),
+            relative: "locked".to_string(),
+            error: "Permission denied (os error 13)".to_string(),
+        };
+        let record = unreadable_dir_record(&dir);
+        assert_eq!(record["relative_path"], "locked");
+        assert_eq!(record["_skipped"], true);
+        assert_eq!(record["_warnings"][0]["code"], "E_IO");
+        assert_eq!(
+            record["_warnings"][0]["message"],
+            "Cannot read directory: Permission denied (os error 13)"
+        );
+
+        let mut classifier = RecordClassifier::new(ClassifyOptions::default());
+        classifier.push(&InputRecord {
+            line_number: 1,
+            value: record,
+        });
+        assert_eq!(classifier.finish().unwrap().skipped[0].path, "locked");
+    }
+}
@@ -0,0 +1,689 @@
+//! Detached Ed25519 signatures over a lockfile's `lock_hash`.
+//!
+//! The self-hash proves a lockfile is internally consistent; it does not say
+//! who produced it. `lock sign` binds a sealed lockfile's `lock_hash` to a
+//! local Ed25519 key in a separate `lock-signature.v0` envelope, leaving the
+//! lockfile itself untouched.
+
+use std::fs::{self, OpenOptions};
+use std::io::Write;
+use std::path::{Path, PathBuf};
+
+use chrono::{SecondsFormat, Utc};
+use ed25519_dalek::{Signature, Signer, SigningKey, VerifyingKey};
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use sha2::{Digest, Sha256};
+
+use crate::cli::{KeygenArgs, SignArgs};
+use crate::lockfile::self_hash::to_canonical_json;
+use crate::verify::{LockfileRefusal, VerifyRefusalCode, load_sealed_lockfile};
+
+/// Signature envelope schema version.
+pub const SIGNATURE_VERSION: &str = "lock-signature.v0";
+
+/// Key file schema version (both secret and public key files).
+pub const KEY_VERSION: &str = "lock-key.v0";
+
+/// The only signature algorithm supported in v0.
+pub const ED25519: &str = "ed25519";
+
+// ---------------------------------------------------------------------------
+// Key and envelope types
+// ---------------------------------------------------------------------------
+
+/// On-disk key file. Public key files omit `secret_key`.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct KeyFile {
+    pub version: String,
+    pub algorithm: String,
+    pub key_id: String,
+    pub signer: Option<String>,
+    pub public_key: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub secret_key: Option<String>,
+}
+
+/// Detached signature over a lockfile's `lock_hash`.
+///
+/// `signature` covers the canonical JSON of the envelope with `signature`
+/// set to `""`, so the signer, key id, and timestamp are bound along with
+/// the `lock_hash` — the same blanking convention `lock_hash` itself uses.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct SignatureEnvelope {
+    pub version: String,
+    pub algorithm: String,
+    pub key_id: String,
+    pub signer: Option<String>,
+    pub public_key: String,
+    pub lock_hash: String,
+    pub created: String,
+    pub signature: String,
+}
+
+impl SignatureEnvelope {
+    /// Bytes the signature is computed over.
+    pub fn signing_message(&self) -> Vec<u8> {
+        let mut unsigned = self.clone();
+        unsigned.signature = String::new();
+        to_canonical_json(&unsigned)
+            .expect("SignatureEnvelope is always serializable")
+            .into_bytes()
+    }
+}
+
+// ---------------------------------------------------------------------------
+// Key handling
+// ---------------------------------------------------------------------------
+
+fn encode_hex(bytes: &[u8]) -> String {
+    bytes.iter().map(|byte| format!("{byte:02x}")).collect()
+}
+
+fn decode_hex(text: &str) -> Option<Vec<u8>> {
+    if !text.len().is_multiple_of(2) {
+        return None;
+    }
+    (0..text.len())
+        .step_by(2)
+        .map(|i| u8::from_str_radix(text.get(i..i + 2)?, 16).ok())
+        .collect()
+}
+
+fn decode_fixed<const N: usize>(text: &str, field: &str) -> Result<[u8; N], String> {
+    decode_hex(text)
+        .and_then(|bytes| <[u8; N]>::try_from(bytes).ok())
+        .ok_or_else(|| format!("{field} must be {N} hex-encoded bytes"))
+}
+
+/// Stable identifier for a public key: `ed25519:` plus the first 16 hex
+/// characters of the SHA-256 of the raw key bytes.
+pub fn key_id_for(verifying_key: &VerifyingKey) -> String {
+    let digest = format!("{:x}", Sha256::digest(verifying_key.as_bytes()));
+    format!("{ED25519}:{}", &digest[..16])
+}
+
+fn public_key_file(verifying_key: &VerifyingKey, signer: Option<&str>) -> KeyFile {
+    KeyFile {
+        version: KEY_VERSION.to_string(),
+        algorithm: ED25519.to_string(),
+        key_id: key_id_for(verifying_key),
+        signer: signer.map(str::to_string),
+        public_key: encode_hex(verifying_key.as_bytes()),
+        secret_key: None,
+    }
+}
+
+fn parse_key_file(json: &str) -> Result<KeyFile, String> {
+    let key: KeyFile = serde_json::from_str(json).map_err(|e| e.to_string())?;
+    if key.version != KEY_VERSION {
+        return Err(format!("unsupported key file version: {}", key.version));
+    }
+    if key.algorithm != ED25519 {
+        return Err(format!("unsupported key algorithm: {}", key.algorithm));
+    }
+    Ok(key)
+}
+
+/// Parse a secret key file and check that its public half and key id agree.
+pub fn parse_secret_key(json: &str) -> Result<(SigningKey, KeyFile), String> {
+    let key = parse_key_file(json)?;
+    let secret = key
+        .secret_key
+        .as_deref()
+        .ok_or_else(|| "key file has no secret_key (is this a public key?)".to_string())?;
+    let signing_key = SigningKey::from_bytes(&decode_fixed::<32>(secret, "secret_key")?);
+    let verifying_key = signing_key.verifying_key();
+
+    if encode_hex(verifying_key.as_bytes()) != key.public_key {
+        return Err("public_key does not match secret_key".to_string());
+    }
+    if key_id_for(&verifying_key) != key.key_id {
+        return Err("key_id does not match public_key".to_string());
+    }
+    Ok((signing_key, key))
+}
+
+/// Sign a lockfile's `lock_hash`.
+pub fn sign_lock_hash(
+    lock_hash: &str,
+    signing_key: &SigningKey,
+    signer: Option<&str>,
+    created: &str,
+) -> SignatureEnvelope {
+    let public = public_key_file(&signing_key.verifying_key(), signer);
+    let mut envelope = SignatureEnvelope {
+        version: SIGNATURE_VERSION.to_string(),
+        algorithm: ED25519.to_string(),
+        key_id: public.key_id,
+        signer: public.signer,
+        public_key: public.public_key,
+        lock_hash: lock_hash.to_string(),
+        created: created.to_string(),
+        signature: String::new(),
+    };
+    let signature = signing_key.sign(&envelope.signing_message());
+    envelope.signature = encode_hex(&signature.to_bytes());
+    envelope
+}
+
+// ---------------------------------------------------------------------------
+// Verification
+// ---------------------------------------------------------------------------
+
+/// Outcome of checking a detached signature against a lockfile and a set of
+/// trusted keys. Reported as the `signature` block of `lock-verify.v0`.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct SignatureCheck {
+    pub signer: Option<String>,
+    pub key_id: String,
+    pub algorithm: String,
+    /// The signature verifies under its public key and covers this lockfile's `lock_hash`.
+    pub valid: bool,
+    /// The signing public key is present in the trusted key directory.
+    pub trusted: bool,
+    /// Why the signature is not acceptable: `LOCK_HASH_MISMATCH`,
+    /// `SIGNATURE_INVALID`, or `KEY_UNTRUSTED`. Null when valid and trusted.
+    pub reason: Option<String>,
+}
+
+impl SignatureCheck {
+    pub fn accepted(&self) -> bool {
+        self.valid && self.trusted
+    }
+}
+
+/// Parse a `lock-signature.v0` envelope.
+pub fn parse_signature_envelope(json: &str) -> Result<SignatureEnvelope, String> {
+    let envelope: SignatureEnvelope = serde_json::from_str(json).map_err(|e| e.to_string())?;
+    if envelope.version != SIGNATURE_VERSION {
+        return Err(format!(
+            "unsupported signature version: {}",
+            envelope.version
+        ));
+    }
+    if envelope.algorithm != ED25519 {
+        return Err(format!(
+            "unsupported signature algorithm: {}",
+            envelope.algorithm
+        ));
+    }
+    Ok(envelope)
+}
+
+fn signature_verifies(envelope: &SignatureEnvelope) -> bool {
+    let Ok(public_key) = decode_fixed::<32>(&envelope.public_key, "public_key") else {
+        return false;
+    };
+    let Ok(verifying_key) = VerifyingKey::from_bytes(&public_key) else {
+        return false;
+    };
+    let Ok(signature) = decode_fixed::<64>(&envelope.signature, "signature") else {
+        return false;
+    };
+    key_id_for(&verifying_key) == envelope.key_id
+        && verifying_key
+            .verify_strict(
+                &envelope.signing_message(),
+                &Signature::from_bytes(&signature),
+            )
+            .is_ok()
+}
+
+/// Check a signature envelope against the verified `lock_hash` of the
+/// lockfile it claims to cover and the locally trusted public keys.
+///
+/// Trust is decided by the full public key, never by `key_id` alone.
+pub fn check_signature(
+    envelope: &SignatureEnvelope,
+    lock_hash: &str,
+    trusted_keys: &[KeyFile],
+) -> SignatureCheck {
+    let covers_lockfile = envelope.lock_hash == lock_hash;
+    let verifies = signature_verifies(envelope);
+    let trusted = trusted_keys
+        .iter()
+        .any(|key| key.public_key == envelope.public_key);
+
+    let reason = if !covers_lockfile {
+        Some("LOCK_HASH_MISMATCH")
+    } else if !verifies {
+        Some("SIGNATURE_INVALID")
+    } else if !trusted {
+        Some("KEY_UNTRUSTED")
+    } else {
+        None
+    };
+
+    SignatureCheck {
+        signer: envelope.signer.clone(),
+        key_id: envelope.key_id.clone(),
+        algorithm: envelope.algorithm.clone(),
+        valid: covers_lockfile && verifies,
+        trusted,
+        reason: reason.map(str::to_string),
+    }
+}
+
+/// Read and parse a signature envelope from disk.
+pub fn load_signature(path: &Path) -> Result<SignatureEnvelope, LockfileRefusal> {
+    let json = fs::read_to_string(path).map_err(|e| {
+        LockfileRefusal::new(
+            VerifyRefusalCode::Io,
+            format!("cannot read signature file — {e}"),
+            serde_json::json!({
+                "path": path.display().to_string(),
+                "error": e.to_string(),
+            }),
+        )
+    })?;
+    parse_signature_envelope(&json).map_err(|e| {
+        LockfileRefusal::new(
+            VerifyRefusalCode::BadSignature,
+            format!("invalid signature file — {e}"),
+            serde_json::json!({
+                "path": path.display().to_string(),
+                "error": e,
+            }),
+        )
+    })
+}
+
+/// Load every usable public key from a trusted key directory.
+///
+/// Each regular file is read as a `lock-key.v0` key file (public or secret;
+/// only the public half is used). Files that are not key files, or whose
+/// `key_id` does not match their `public_key`, are ignored rather than
+/// trusted.
+pub fn load_trusted_keys(dir: &Path) -> Result<Vec<KeyFile>, LockfileRefusal> {
+    let entries = fs::read_dir(dir).map_err(|e| key_io_refusal(dir, &e.to_string()))?;
+    let mut paths: Vec<PathBuf> = entries
+        .filter_map(|entry| entry.ok().map(|entry| entry.path()))
+        .filter(|path| path.is_file())
+        .collect();
+    paths.sort();
+
+    Ok(paths
+        .iter()
+        .filter_map(|path| fs::read_to_string(path).ok())
+        .filter_map(|json| parse_key_file(&json).ok())
+        .filter(|key| {
+            decode_fixed::<32>(&key.public_key, "public_key")
+                .ok()
+                .and_then(|bytes| VerifyingKey::from_bytes(&bytes).ok())
+                .is_some_and(|verifying_key| key_id_for(&verifying_key) == key.key_id)
+        })
+        .map(|key| KeyFile {
+            secret_key: None,
+            ..key
+        })
+        .collect())
+}
+
+// ---------------------------------------------------------------------------
+// Refusals
+// ---------------------------------------------------------------------------
+
+fn key_io_refusal(path: &Path, error: &str) -> LockfileRefusal {
+    LockfileRefusal::new(
+        VerifyRefusalCode::Io,
+        format!("cannot access key file — {error}"),
+        serde_json::json!({
+            "path": path.display().to_string(),
+            "error": error,
+        }),
+    )
+}
+
+fn bad_key_refusal(path: &Path, error: &str) -> LockfileRefusal {
+    LockfileRefusal::new(
+        VerifyRefusalCode::BadKey,
+        format!("invalid key file — {error}"),
+        serde_json::json!({
+            "path": path.display().to_string(),
+            "error": error,
+        }),
+    )
+}
+
+// ---------------------------------------------------------------------------
+// lock keygen
+// ---------------------------------------------------------------------------
+
+/// Public key path written next to a secret key: `<secret>.pub`.
+pub fn public_key_path(secret_path: &Path) -> PathBuf {
+    let mut name = secret_path.as_os_str().to_os_string();
+    name.push(".pub");
+    PathBuf::from(name)
+}
+
+/// Create `path` and write `contents`, removing the file again if the write
+/// fails part way.
+fn write_new_file(path: &Path, contents: &str, secret: bool) -> std::io::Result<()> {
+    let mut options = OpenOptions::new();
+    options.write(true).create_new(true);
+    #[cfg(unix)]
+    if secret {
+        use std::os::unix::fs::OpenOptionsExt;
+        options.mode(0o600);
+    }
+    #[cfg(not(unix))]
+    let _ = secret;
+    let mut file = options.open(path)?;
+    file.write_all(contents.as_bytes())
+        .and_then(|()| file.sync_all())
+        .inspect_err(|_| {
+            let _ = fs::remove_file(path);
+        })
+}
+
+/// Run the keygen subcommand. Returns the exit code.
+///
+/// Writes the secret key to `--output` (mode 0600 on Unix) and the public key
+/// to `<output>.pub`, then prints the public key file. Neither file is ever
+/// overwritten, and a refusal leaves neither behind.
+pub fn run_keygen(args: &KeygenArgs) -> u8 {
+    let secret_path = &args.output;
+    let public_path = public_key_path(secret_path);
+
+    let refuse = |refusal: LockfileRefusal| {
+        print!("{}", refusal.to_json(KEY_VERSION));
+        2
+    };
+
+    for path in [secret_path, &public_path] {
+        if path.exists() {
+            return refuse(key_io_refusal(path, "file already exists"));
+        }
+    }
+
+    let mut seed = [0u8; 32];
+    if let Err(e) = getrandom::getrandom(&mut seed) {
+        return refuse(key_io_refusal(secret_path, &e.to_string()));
+    }
+    let signing_key = SigningKey::from_bytes(&seed);
+
+    let public = public_key_file(&signing_key.verifying_key(), args.signer.as_deref());
+    let secret = KeyFile {
+        secret_key: Some(encode_hex(&seed)),
+        ..public.clone()
+    };
+
+    let public_json = to_canonical_json(&public).expect("KeyFile is always serializable");
+    let secret_json = to_canonical_json(&secret).expect("KeyFile is always serializable");
+
+    if let Err(e) = write_new_file(secret_path, &secret_json, true) {
+        return refuse(key_io_refusal(secret_path, &e.to_string()));
+    }
+    if let Err(e) = write_new_file(&public_path, &public_json, false) {
+        // A secret key without its public half would block a retry.
+        let _ = fs::remove_file(secret_path);
+        return refuse(key_io_refusal(&public_path, &e.to_string()));
+    }
+
+    print!("{public_json}");
+    0
+}
+
+// ---------------------------------------------------------------------------
+// lock sign
+// ---------------------------------------------------------------------------
+
+/// Run the sign subcommand. Returns the exit code.
+///
+/// The lockfile must pass validation and its self-hash check; a tampered
+/// lockfile is never signed.
+pub fn run_sign(args: &SignArgs) -> u8 {
+    match build_signature(args) {
+        Ok(envelope) => {
+            let payload = to_canonical_json(&envelope).expect("envelope is always serializable");
+            if let Some(output_path) = &args.output {
+                if let Err(e) = fs::write(output_path, &payload) {
+                    eprintln!(
+                        "lock: error: failed to write output file '{}': {e}",
+                        output_path.display()
+                    );
+                    return 2;
+                }
+            } else {
+                print!("{payload}");
+            }
+            emit_witness(
+                args,
+                0,
+                "SIGNED",
+                Some(&envelope.key_id),
+                payload.as_bytes(),
+            );
+            0
+        }
+        Err(refusal) => {
+            let payload = refusal.to_json(SIGNATURE_VERSION);
+            print!("{payload}");
+            emit_witness(args, 2, "REFUSAL", None, payload.as_bytes());
+            2
+        }
+    }
+}
+
+fn build_signature(args: &SignArgs) -> Result<SignatureEnvelope, LockfileRefusal> {
+    let lockfile = load_sealed_lockfile(&args.lockfile)?;
+    let lock_hash = lockfile
+        .get("lock_hash")
+        .and_then(Value::as_str)
+        .expect("validated lockfile has a string lock_hash");
+
+    let key_json =
+        fs::read_to_string(&args.key).map_err(|e| key_io_refusal(&args.key, &e.to_string()))?;
+    let (signing_key, key) =
+        parse_secret_key(&key_json).map_err(|e| bad_key_refusal(&args.key, &e))?;
+
+    let created = Utc::now().to_rfc3339_opts(SecondsFormat::Secs, true);
+    Ok(sign_lock_hash(
+        lock_hash,
+        &signing_key,
+        key.signer.as_deref(),
+        &created,
+    ))
+}
+
+fn emit_witness(
+    args: &SignArgs,
+    exit_code: u8,
+    outcome: &str,
+    key_id: Option<&str>,
+    output_bytes: &[u8],
+) {
+    if args.no_witness {
+        return;
+    }
+
+    let params = serde_json::json!({
+        "subcommand": "sign",
+        "key_id": key_id,
+    });
+    let inputs = serde_json::json!([
+        { "path": args.lockfile.display().to_string(), "hash": null, "bytes": null }
+    ]);
+
+    crate::witness::append_witness_record(outcome, exit_code, output_bytes, params, inputs);
+}
+
+// ---------------------------------------------------------------------------
+// Tests
+// ---------------------------------------------------------------------------
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn fixed_key() -> SigningKey {
+        SigningKey::from_bytes(&[7u8; 32])
+    }
+
+    #[test]
+    fn hex_round_trip() {
+        let bytes = [0u8, 1, 127, 255];
+        assert_eq!(encode_hex(&bytes), "00017fff");
+        assert_eq!(decode_hex("00017fff").unwrap(), bytes);
+        assert!(decode_hex("abc").is_none());
+        assert!(decode_hex("zz").is_none());
+    }
+
+    #[test]
+    fn key_id_is_prefixed_and_short() {
+        let key_id = key_id_for(&fixed_key().verifying_key());
+        assert!(key_id.starts_with("ed25519:"));
+        assert_eq!(key_id.len(), "ed25519:".len() + 16);
+    }
+
+    #[test]
+    fn signature_verifies_over_signing_message() {
+        let key = fixed_key();
+        let envelope = sign_lock_hash("sha256:abc", &key, Some("auditor"), "2026-01-01T00:00:00Z");
+        assert_eq!(envelope.version, SIGNATURE_VERSION);
+        assert_eq!(envelope.signer.as_deref(), Some("auditor"));
+
+        let bytes = decode_fixed::<64>(&envelope.signature, "signature").unwrap();
+        let signature = Signature::from_bytes(&bytes);
+        key.verifying_key()
+            .verify_strict(&envelope.signing_message(), &signature)
+            .expect("signature should verify");
+
+        let mut forged = envelope.clone();
+        forged.lock_hash = "sha256:def".to_string();
+        assert!(
+            key.verifying_key()
+                .verify_strict(&forged.signing_message(), &signature)
+                .is_err()
+        );
+    }
+
+    #[test]
+    fn parse_secret_key_round_trip() {
+        let key = fixed_key();
+        let mut file = public_key_file(&key.verifying_key(), None);
+        file.secret_key = Some(encode_hex(&key.to_bytes()));
+        let json = to_canonical_json(&file).unwrap();
+
+        let (parsed, parsed_file) = parse_secret_key(&json).unwrap();
+        assert_eq!(parsed.to_bytes(), key.to_bytes());
+        assert_eq!(parsed_file.key_id, file.key_id);
+    }
+
+    #[test]
+    fn parse_secret_key_rejects_public_key_file() {
+        let file = public_key_file(&fixed_key().verifying_key(), None);
+        let json = to_canonical_json(&file).unwrap();
+        let error = parse_secret_key(&json).unwrap_err();
+        assert!(error.contains("no secret_key"));
+    }
+
+    #[test]
+    fn parse_secret_key_rejects_mismatched_public_key() {
+        let key = fixed_key();
+        let mut file = public_key_file(&SigningKey::from_bytes(&[9u8; 32]).verifying_key(), None);
+        file.secret_key = Some(encode_hex(&key.to_bytes()));
+        let json = to_canonical_json(&file).unwrap();
+        assert!(parse_secret_key(&json).is_err());
+    }
+
+    #[test]
+    fn check_signature_accepts_trusted_valid_signature() {
+        let key = fixed_key();
+        let envelope = sign_lock_hash("sha256:abc", &key, None, "2026-01-01T00:00:00Z");
+        let trusted = vec![public_key_file(&key.verifying_key(), None)];
+
+        let check = check_signature(&envelope, "sha256:abc", &trusted);
+        assert!(check.valid);
+        assert!(check.trusted);
+        assert!(check.accepted());
+        assert_eq!(check.reason, None);
+    }
+
+    #[test]
+    fn check_signature_reports_untrusted_key() {
+        let envelope = sign_lock_hash("sha256:abc", &fixed_key(), None, "2026-01-01T00:00:00Z");
+        let other = public_key_file(&SigningKey::from_bytes(&[9u8; 32]).verifying_key(), None);
+
+        let check = check_signature(&envelope, "sha256:abc", &[other]);
+        assert!(check.valid);
+        assert!(!check.trusted);
+        assert_eq!(check.reason.as_deref(), Some("KEY_UNTRUSTED"));
+    }
+
+    #[test]
+    fn check_signature_reports_lock_hash_mismatch() {
+        let key = fixed_key();
+        let envelope = sign_lock_hash("sha256:abc", &key, None, "2026-01-01T00:00:00Z");
+        let trusted = vec![public_key_file(&key.verifying_key(), None)];
+
+        let check = check_signature(&envelope, "sha256:other", &trusted);
+        assert!(!check.valid);
+        assert_eq!(check.reason.as_deref(), Some("LOCK_HASH_MISMATCH"));
+    }
+
+    #[test]
+    fn check_signature_reports_edited_envelope_as_invalid() {
+        let key = fixed_key();
+        let mut envelope =
+            sign_lock_hash("sha256:abc", &key, Some("alice"), "2026-01-01T00:00:00Z");
+        envelope.signer = Some("mallory".to_string());
+        let trusted = vec![public_key_file(&key.verifying_key(), None)];
+
+        let check = check_signature(&envelope, "sha256:abc", &trusted);
+        assert!(!check.valid);
+        assert!(check.trusted);
+        assert_eq!(check.reason.as_deref(), Some("SIGNATURE_INVALID"));
+    }
+
+    #[test]
+    fn load_trusted_keys_ignores_non_key_files() {
+        let dir = tempfile::tempdir().unwrap();
+        let public = public_key_file(&fixed_key().verifying_key(), Some("alice"));
+        fs::write(
+            dir.path().join("alice.key.pub"),
+            to_canonical_json(&public).unwrap(),
+        )
+        .unwrap();
+        fs::write(dir.path().join("README"), "not a key").unwrap();
+
+        let mut forged = public.clone();
+        forged.key_id = "ed25519:0000000000000000".to_string();
+        fs::write(
+            dir.path().join("forged.pub"),
+            to_canonical_json(&forged).unwrap(),
+        )
+        .unwrap();
+
+        let keys = load_trusted_keys(dir.path()).unwrap();
+        assert_eq!(keys, vec![public]);
+    }
+
+    #[test]
+    fn load_trusted_keys_missing_dir_is_io_refusal() {
+        let dir = tempfile::tempdir().unwrap();
+        let refusal = load_trusted_keys(&dir.path().join("missing")).unwrap_err();
+        assert_eq!(refusal.code, VerifyRefusalCode::Io);
+    }
+
+    #[test]
+    fn load_signature_rejects_wrong_version() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("sig.json");
+        let mut envelope = sign_lock_hash("sha256:abc", &fixed_key(), None, "2026-01-01T00:00:00Z");
+        envelope.version = "lock-signature.v9".to_string();
+        fs::write(&path, to_canonical_json(&envelope).unwrap()).unwrap();
+
+        let refusal = load_signature(&path).unwrap_err();
+        assert_eq!(refusal.code, VerifyRefusalCode::BadSignature);
+    }
+
+    #[test]
+    fn public_key_path_appends_pub() {
+        assert_eq!(
+            public_key_path(Path::new("keys/auditor.key")),
+            PathBuf::from("keys/auditor.key.pub")
+        );
+    }
+}
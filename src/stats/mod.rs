@@ -0,0 +1,404 @@
+pub mod output;
+
+use std::collections::BTreeMap;
+
+use serde::Serialize;
+
+use crate::cli::StatsArgs;
+use crate::lockfile::{Member, SkippedEntry};
+use crate::refusal::sort_value;
+use crate::update::{BaseLockfile, load_base};
+
+/// Stats output schema version.
+pub const STATS_VERSION: &str = "lock-stats.v0";
+
+/// Size histogram buckets: an upper bound (exclusive) and its label. Empty
+/// files get their own bucket ahead of these.
+const SIZE_BUCKETS: &[(u64, &str)] = &[
+    (1 << 10, "< 1 KiB"),
+    (1 << 20, "< 1 MiB"),
+    (1 << 30, "< 1 GiB"),
+    (1 << 40, "< 1 TiB"),
+    (u64::MAX, ">= 1 TiB"),
+];
+
+/// Key for files with no extension, and for members at the top level.
+const NO_EXTENSION: &str = "(none)";
+const TOP_LEVEL: &str = ".";
+
+// ---------------------------------------------------------------------------
+// Stats result types
+// ---------------------------------------------------------------------------
+
+/// The top-level result emitted by `lock stats`.
+#[derive(Debug, Clone, Serialize)]
+struct StatsResult {
+    version: String,
+    outcome: String,
+    lockfile: String,
+    lock_hash: String,
+    lock_version: String,
+    dataset_id: Option<String>,
+    member_count: usize,
+    skipped_count: usize,
+    total_bytes: u64,
+    size_histogram: Vec<SizeBucket>,
+    by_extension: BTreeMap<String, Tally>,
+    by_top_level: BTreeMap<String, Tally>,
+    algorithms: BTreeMap<String, AlgorithmTally>,
+    fingerprints: BTreeMap<String, FingerprintTally>,
+    unfingerprinted: usize,
+    skip_warnings: BTreeMap<String, BTreeMap<String, usize>>,
+    tool_versions: BTreeMap<String, String>,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+struct SizeBucket {
+    label: String,
+    count: usize,
+    bytes: u64,
+}
+
+/// Member count and summed size for one group.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize)]
+struct Tally {
+    count: usize,
+    bytes: u64,
+}
+
+/// How many members use an algorithm for `bytes_hash`, and how many record
+/// it in `hashes`.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize)]
+struct AlgorithmTally {
+    bytes_hash: usize,
+    hashes: usize,
+}
+
+#[derive(Debug, Clone, Default, PartialEq, Serialize)]
+struct FingerprintTally {
+    members: usize,
+    matched: usize,
+    match_rate: f64,
+}
+
+// ---------------------------------------------------------------------------
+// Aggregation
+// ---------------------------------------------------------------------------
+
+/// Lowercased extension of the member's file name; dotfiles have none.
+fn extension(path: &str) -> String {
+    let name = path.rsplit('/').next().unwrap_or(path);
+    match name.rsplit_once('.') {
+        Some((stem, ext)) if !stem.is_empty() && !ext.is_empty() => ext.to_ascii_lowercase(),
+        _ => NO_EXTENSION.to_string(),
+    }
+}
+
+fn top_level(path: &str) -> String {
+    match path.split_once('/') {
+        Some((dir, _)) => dir.to_string(),
+        None => TOP_LEVEL.to_string(),
+    }
+}
+
+fn size_histogram(members: &[Member]) -> Vec<SizeBucket> {
+    let mut buckets: Vec<SizeBucket> = std::iter::once("empty")
+        .chain(SIZE_BUCKETS.iter().map(|(_, label)| *label))
+        .map(|label| SizeBucket {
+            label: label.to_string(),
+            count: 0,
+            bytes: 0,
+        })
+        .collect();
+    for member in members {
+        let index = if member.size == 0 {
+            0
+        } else {
+            1 + SIZE_BUCKETS
+                .iter()
+                .position(|(bound, _)| member.size < *bound)
+                .unwrap_or(SIZE_BUCKETS.len() - 1)
+        };
+        buckets[index].count += 1;
+        buckets[index].bytes += member.size;
+    }
+    buckets
+}
+
+fn tally_by(members: &[Member], key: impl Fn(&str) -> String) -> BTreeMap<String, Tally> {
+    let mut tallies: BTreeMap<String, Tally> = BTreeMap::new();
+    for member in members {
+        let tally = tallies.entry(key(&member.path)).or_default();
+        tally.count += 1;
+        tally.bytes += member.size;
+    }
+    tallies
+}
+
+fn algorithms(members: &[Member]) -> BTreeMap<String, AlgorithmTally> {
+    let mut tallies: BTreeMap<String, AlgorithmTally> = BTreeMap::new();
+    for member in members {
+        let own = crate::digest::prefix(&member.bytes_hash).to_string();
+        tallies.entry(own).or_default().bytes_hash += 1;
+        for algorithm in member.hashes.iter().flat_map(BTreeMap::keys) {
+            tallies.entry(algorithm.clone()).or_default().hashes += 1;
+        }
+    }
+    tallies
+}
+
+fn fingerprints(members: &[Member]) -> (BTreeMap<String, FingerprintTally>, usize) {
+    let mut tallies: BTreeMap<String, FingerprintTally> = BTreeMap::new();
+    let mut unfingerprinted = 0;
+    for member in members {
+        match &member.fingerprint {
+            Some(fingerprint) => {
+                let tally = tallies
+                    .entry(fingerprint.fingerprint_id.clone())
+                    .or_default();
+                tally.members += 1;
+                tally.matched += usize::from(fingerprint.matched);
+            }
+            None => unfingerprinted += 1,
+        }
+    }
+    for tally in tallies.values_mut() {
+        tally.match_rate = tally.matched as f64 / tally.members as f64;
+    }
+    (tallies, unfingerprinted)
+}
+
+/// Count skip warnings by `tool`, then `code`.
+fn skip_warnings(skipped: &[SkippedEntry]) -> BTreeMap<String, BTreeMap<String, usize>> {
+    let mut counts: BTreeMap<String, BTreeMap<String, usize>> = BTreeMap::new();
+    for warning in skipped.iter().flat_map(|entry| &entry.warnings) {
+        *counts
+            .entry(warning.tool.clone())
+            .or_default()
+            .entry(warning.code.clone())
+            .or_default() += 1;
+    }
+    counts
+}
+
+fn build_stats(args: &StatsArgs, base: BaseLockfile) -> StatsResult {
+    let (fingerprints, unfingerprinted) = fingerprints(&base.members);
+
+    let mut tool_versions = BTreeMap::new();
+    tool_versions.insert("lock".to_string(), env!("CARGO_PKG_VERSION").to_string());
+
+    StatsResult {
+        version: STATS_VERSION.to_string(),
+        outcome: "STATS".to_string(),
+        lockfile: args.lockfile.display().to_string(),
+        lock_hash: base.lock_hash,
+        lock_version: base.version,
+        dataset_id: base.dataset_id,
+        member_count: base.members.len(),
+        skipped_count: base.skipped.len(),
+        total_bytes: base.members.iter().map(|member| member.size).sum(),
+        size_histogram: size_histogram(&base.members),
+        by_extension: tally_by(&base.members, extension),
+        by_top_level: tally_by(&base.members, top_level),
+        algorithms: algorithms(&base.members),
+        fingerprints,
+        unfingerprinted,
+        skip_warnings: skip_warnings(&base.skipped),
+        tool_versions,
+    }
+}
+
+// ---------------------------------------------------------------------------
+// Orchestration
+// ---------------------------------------------------------------------------
+
+/// Run the stats subcommand. Returns the exit code.
+///
+/// Exit codes: `0` statistics reported, `2` refusal.
+pub fn run_stats(args: &StatsArgs) -> u8 {
+    // Step 1: Load the lockfile; a tampered one is refused.
+    let base = match load_base(&args.lockfile) {
+        Ok(base) => base,
+        Err(refusal) => {
+            let payload = refusal.to_json(STATS_VERSION);
+            print!("{payload}");
+            emit_witness(args, 2, "REFUSAL", payload.as_bytes());
+            return 2;
+        }
+    };
+
+    // Step 2: Aggregate and emit.
+    let result = build_stats(args, base);
+    let value = sort_value(serde_json::to_value(&result).expect("StatsResult is serializable"));
+    let payload = if args.json {
+        serde_json::to_string(&value).expect("sorted Value is always serializable")
+    } else {
+        output::render_human(&value)
+    };
+    print!("{payload}");
+    emit_witness(args, 0, &result.outcome, payload.as_bytes());
+    0
+}
+
+fn emit_witness(args: &StatsArgs, exit_code: u8, outcome: &str, output_bytes: &[u8]) {
+    if args.no_witness {
+        return;
+    }
+
+    let params = serde_json::json!({ "subcommand": "stats" });
+    let inputs = serde_json::json!([
+        { "path": args.lockfile.display().to_string(), "hash": null, "bytes": null },
+    ]);
+
+    crate::witness::append_witness_record(outcome, exit_code, output_bytes, params, inputs);
+}
+
+// ---------------------------------------------------------------------------
+// Tests
+// ---------------------------------------------------------------------------
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::lockfile::{FingerprintResult, Warning};
+
+    fn member(path: &str, size: u64) -> Member {
+        Member {
+            path: path.to_string(),
+            bytes_hash: format!("sha256:{path}"),
+            size,
+            fingerprint: None,
+            mtime: None,
+            extra: None,
+            hashes: None,
+        }
+    }
+
+    fn fingerprinted(path: &str, id: &str, matched: bool) -> Member {
+        Member {
+            fingerprint: Some(FingerprintResult {
+                fingerprint_id: id.to_string(),
+                fingerprint_version: "fingerprint.v0".to_string(),
+                matched,
+                content_hash: None,
+            }),
+            ..member(path, 1)
+        }
+    }
+
+    #[test]
+    fn groups_by_extension_and_top_level_directory() {
+        let members = [
+            member("a.csv", 10),
+            member("sub/b.CSV", 5),
+            member("sub/deep/.env", 1),
+            member("README", 2),
+        ];
+        let by_extension = tally_by(&members, extension);
+        assert_eq!(
+            by_extension["csv"],
+            Tally {
+                count: 2,
+                bytes: 15
+            }
+        );
+        assert_eq!(by_extension[NO_EXTENSION], Tally { count: 2, bytes: 3 });
+
+        let by_top_level = tally_by(&members, top_level);
+        assert_eq!(
+            by_top_level[TOP_LEVEL],
+            Tally {
+                count: 2,
+                bytes: 12
+            }
+        );
+        assert_eq!(by_top_level["sub"], Tally { count: 2, bytes: 6 });
+    }
+
+    #[test]
+    fn histogram_buckets_by_power_of_1024() {
+        let members = [
+            member("empty", 0),
+            member("small", 1023),
+            member("kib", 1024),
+            member("mib", 5 << 20),
+        ];
+        let counts: Vec<(String, usize)> = size_histogram(&members)
+            .into_iter()
+            .map(|bucket| (bucket.label, bucket.count))
+            .collect();
+        assert_eq!(
+            counts,
+            [
+                ("empty".to_string(), 1),
+                ("< 1 KiB".to_string(), 1),
+                ("< 1 MiB".to_string(), 1),
+                ("< 1 GiB".to_string(), 1),
+                ("< 1 TiB".to_string(), 0),
+                (">= 1 TiB".to_string(), 0),
+            ]
+        );
+    }
+
+    #[test]
+    fn algorithm_mix_counts_bytes_hash_and_hashes() {
+        let mut dual = member("b.csv", 1);
+        dual.hashes = Some(BTreeMap::from([
+            ("blake3".to_string(), "blake3:bb".to_string()),
+            ("sha256".to_string(), "sha256:b.csv".to_string()),
+        ]));
+        let tallies = algorithms(&[member("a.csv", 1), dual]);
+        assert_eq!(
+            tallies["sha256"],
+            AlgorithmTally {
+                bytes_hash: 2,
+                hashes: 1
+            }
+        );
+        assert_eq!(
+            tallies["blake3"],
+            AlgorithmTally {
+                bytes_hash: 0,
+                hashes: 1
+            }
+        );
+    }
+
+    #[test]
+    fn fingerprint_match_rates_by_id() {
+        let members = [
+            fingerprinted("a.csv", "csv.v0", true),
+            fingerprinted("b.csv", "csv.v0", false),
+            fingerprinted("c.pdf", "pdf.v0", true),
+            member("d.bin", 1),
+        ];
+        let (tallies, unfingerprinted) = fingerprints(&members);
+        assert_eq!(tallies["csv.v0"].members, 2);
+        assert_eq!(tallies["csv.v0"].match_rate, 0.5);
+        assert_eq!(tallies["pdf.v0"].match_rate, 1.0);
+        assert_eq!(unfingerprinted, 1);
+    }
+
+    #[test]
+    fn skip_warnings_group_by_tool_and_code() {
+        let warning = |tool: &str, code: &str| Warning {
+            tool: tool.to_string(),
+            code: code.to_string(),
+            message: String::new(),
+            detail: BTreeMap::new(),
+        };
+        let skipped = [
+            SkippedEntry {
+                path: "a".to_string(),
+                warnings: vec![warning("hashbytes", "E_IO"), warning("vacuum", "E_PERM")],
+            },
+            SkippedEntry {
+                path: "b".to_string(),
+                warnings: vec![warning("hashbytes", "E_IO")],
+            },
+        ];
+        let counts = skip_warnings(&skipped);
+        assert_eq!(counts["hashbytes"]["E_IO"], 2);
+        assert_eq!(counts["vacuum"]["E_PERM"], 1);
+    }
+}
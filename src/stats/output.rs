@@ -0,0 +1,153 @@
+use serde_json::{Map, Value};
+
+/// Render a stats result as human-readable text.
+///
+/// Returns a string suitable for printing to stdout (no trailing newline).
+pub fn render_human(result: &Value) -> String {
+    let lockfile = result["lockfile"].as_str().unwrap_or("?");
+    let count = |key: &str| result[key].as_u64().unwrap_or(0);
+
+    let mut lines = vec![format!(
+        "\u{2713} {lockfile}: {} members ({}), {} skipped",
+        count("member_count"),
+        human_bytes(count("total_bytes")),
+        count("skipped_count")
+    )];
+
+    let histogram: Vec<String> = result["size_histogram"]
+        .as_array()
+        .into_iter()
+        .flatten()
+        .filter(|bucket| bucket["count"].as_u64().unwrap_or(0) > 0)
+        .map(|bucket| {
+            format!(
+                "{} {}",
+                bucket["label"].as_str().unwrap_or("?"),
+                bucket["count"].as_u64().unwrap_or(0)
+            )
+        })
+        .collect();
+    push_section(&mut lines, "sizes", histogram);
+    push_section(&mut lines, "extensions", tallies(&result["by_extension"]));
+    push_section(&mut lines, "top-level", tallies(&result["by_top_level"]));
+
+    let algorithms = entries(&result["algorithms"])
+        .map(|(name, tally)| {
+            let recorded = tally["hashes"].as_u64().unwrap_or(0);
+            let extra = if recorded > 0 {
+                format!(" (+{recorded} in hashes)")
+            } else {
+                String::new()
+            };
+            format!(
+                "{name} {}{extra}",
+                tally["bytes_hash"].as_u64().unwrap_or(0)
+            )
+        })
+        .collect();
+    push_section(&mut lines, "algorithms", algorithms);
+
+    let mut fingerprints: Vec<String> = entries(&result["fingerprints"])
+        .map(|(id, tally)| {
+            format!(
+                "{id} {}/{} matched ({:.1}%)",
+                tally["matched"].as_u64().unwrap_or(0),
+                tally["members"].as_u64().unwrap_or(0),
+                tally["match_rate"].as_f64().unwrap_or(0.0) * 100.0
+            )
+        })
+        .collect();
+    if !fingerprints.is_empty() && count("unfingerprinted") > 0 {
+        fingerprints.push(format!("none {}", count("unfingerprinted")));
+    }
+    push_section(&mut lines, "fingerprints", fingerprints);
+
+    let warnings = entries(&result["skip_warnings"])
+        .flat_map(|(tool, codes)| {
+            entries(codes)
+                .map(move |(code, n)| format!("{tool} {code} {}", n.as_u64().unwrap_or(0)))
+        })
+        .collect();
+    push_section(&mut lines, "skip warnings", warnings);
+
+    lines.join("\n")
+}
+
+fn entries(value: &Value) -> impl Iterator<Item = (&String, &Value)> {
+    value.as_object().into_iter().flat_map(Map::iter)
+}
+
+fn tallies(value: &Value) -> Vec<String> {
+    entries(value)
+        .map(|(key, tally)| {
+            format!(
+                "{key} {} ({})",
+                tally["count"].as_u64().unwrap_or(0),
+                human_bytes(tally["bytes"].as_u64().unwrap_or(0))
+            )
+        })
+        .collect()
+}
+
+fn push_section(lines: &mut Vec<String>, name: &str, items: Vec<String>) {
+    if !items.is_empty() {
+        lines.push(format!("  {name}: {}", items.join(", ")));
+    }
+}
+
+/// Format a byte count with binary units, e.g. `1.5 MiB`.
+fn human_bytes(bytes: u64) -> String {
+    const UNITS: &[&str] = &["KiB", "MiB", "GiB", "TiB", "PiB"];
+    if bytes < 1024 {
+        return format!("{bytes} B");
+    }
+    let mut value = bytes as f64 / 1024.0;
+    let mut unit = 0;
+    while value >= 1024.0 && unit < UNITS.len() - 1 {
+        value /= 1024.0;
+        unit += 1;
+    }
+    format!("{value:.1} {}", UNITS[unit])
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn render_summarizes_each_breakdown() {
+        let result = serde_json::json!({
+            "outcome": "STATS",
+            "lockfile": "dec.lock.json",
+            "member_count": 3,
+            "skipped_count": 1,
+            "total_bytes": 1536,
+            "size_histogram": [
+                { "label": "empty", "count": 0, "bytes": 0 },
+                { "label": "< 1 KiB", "count": 2, "bytes": 512 },
+                { "label": "< 1 MiB", "count": 1, "bytes": 1024 }
+            ],
+            "by_extension": { "csv": { "count": 3, "bytes": 1536 } },
+            "by_top_level": { ".": { "count": 1, "bytes": 1024 }, "sub": { "count": 2, "bytes": 512 } },
+            "algorithms": { "sha256": { "bytes_hash": 3, "hashes": 0 } },
+            "fingerprints": { "csv.v0": { "members": 2, "matched": 1, "match_rate": 0.5 } },
+            "unfingerprinted": 1,
+            "skip_warnings": { "hashbytes": { "E_IO": 1 } }
+        });
+        let output = render_human(&result);
+        assert!(output.starts_with("\u{2713} dec.lock.json: 3 members (1.5 KiB), 1 skipped"));
+        assert!(output.contains("  sizes: < 1 KiB 2, < 1 MiB 1\n"));
+        assert!(output.contains("  top-level: . 1 (1.0 KiB), sub 2 (512 B)\n"));
+        assert!(output.contains("  algorithms: sha256 3\n"));
+        assert!(output.contains("  fingerprints: csv.v0 1/2 matched (50.0%), none 1\n"));
+        assert!(output.ends_with("  skip warnings: hashbytes E_IO 1"));
+    }
+
+    #[test]
+    fn human_bytes_uses_binary_units() {
+        assert_eq!(human_bytes(0), "0 B");
+        assert_eq!(human_bytes(1023), "1023 B");
+        assert_eq!(human_bytes(1 << 20), "1.0 MiB");
+        assert_eq!(human_bytes(3 << 29), "1.5 GiB");
+    }
+}
@@ -0,0 +1,441 @@
+use std::collections::{BTreeMap, BTreeSet};
+use std::path::Path;
+
+use serde::Deserialize;
+
+use crate::cli::UpdateArgs;
+use crate::lockfile::{Lockfile, Member, SkippedEntry};
+use crate::verify::{LockfileRefusal, VerifyRefusalCode, load_sealed_lockfile};
+use crate::{Ingested, OrchestrationOutput};
+
+// ---------------------------------------------------------------------------
+// Base lockfile
+// ---------------------------------------------------------------------------
+
+/// The parts of the base lockfile carried into the updated one (or the
+/// rehashed one, for `lock rehash`).
+#[derive(Debug, Deserialize)]
+pub(crate) struct BaseLockfile {
+    pub(crate) version: String,
+    pub(crate) lock_hash: String,
+    pub(crate) dataset_id: Option<String>,
+    pub(crate) as_of: Option<String>,
+    pub(crate) note: Option<String>,
+    pub(crate) tool_versions: BTreeMap<String, String>,
+    pub(crate) profiles: Vec<String>,
+    pub(crate) skipped: Vec<SkippedEntry>,
+    pub(crate) members: Vec<Member>,
+}
+
+/// Load the base lockfile, requiring a valid self-hash.
+pub(crate) fn load_base(path: &Path) -> Result<BaseLockfile, LockfileRefusal> {
+    let value = load_sealed_lockfile(path)?;
+    serde_json::from_value(value).map_err(|e| {
+        LockfileRefusal::new(
+            VerifyRefusalCode::BadLockfile,
+            format!("invalid lockfile — {e}"),
+            serde_json::json!({
+                "lockfile": path.display().to_string(),
+                "error": e.to_string(),
+            }),
+        )
+    })
+}
+
+// ---------------------------------------------------------------------------
+// Merge
+// ---------------------------------------------------------------------------
+
+/// Apply classified input records to the base lockfile.
+///
+/// Each incoming path replaces whatever the base held for it: a member
+/// record upserts the member (and clears a stale skipped entry), a skipped
+/// record replaces the member with the skipped entry. A member whose
+/// `bytes_hash` is unchanged keeps the base's `hashes` (from `lock rehash`),
+/// with any the input records added on top. With `prune`, base
+/// paths that do not appear in the input are dropped. `tool_versions` from
+/// the input override the base's.
+fn apply_update(base: BaseLockfile, ingested: Ingested, args: &UpdateArgs) -> Lockfile {
+    let Ingested {
+        classification,
+        metadata,
+    } = ingested;
+
+    let mut members: BTreeMap<String, Member> = base
+        .members
+        .into_iter()
+        .map(|member| (member.path.clone(), member))
+        .collect();
+    let mut skipped: BTreeMap<String, SkippedEntry> = base
+        .skipped
+        .into_iter()
+        .map(|entry| (entry.path.clone(), entry))
+        .collect();
+
+    if args.prune {
+        let incoming: BTreeSet<&str> = classification
+            .members
+            .iter()
+            .map(|member| member.path.as_str())
+            .chain(
+                classification
+                    .skipped
+                    .iter()
+                    .map(|entry| entry.path.as_str()),
+            )
+            .collect();
+        members.retain(|path, _| incoming.contains(path.as_str()));
+        skipped.retain(|path, _| incoming.contains(path.as_str()));
+    }
+
+    for mut member in classification.members {
+        skipped.remove(&member.path);
+        if let Some(previous) = members.get(&member.path)
+            && previous.bytes_hash == member.bytes_hash
+            && let Some(recorded) = &previous.hashes
+        {
+            let mut hashes = recorded.clone();
+            hashes.extend(member.hashes.take().unwrap_or_default());
+            member.hashes = Some(hashes);
+        }
+        members.insert(member.path.clone(), member);
+    }
+    for entry in classification.skipped {
+        members.remove(&entry.path);
+        skipped.insert(entry.path.clone(), entry);
+    }
+
+    let mut tool_versions = base.tool_versions;
+    tool_versions.extend(metadata.tool_versions);
+
+    let members: Vec<Member> = members.into_values().collect();
+    let skipped: Vec<SkippedEntry> = skipped.into_values().collect();
+
+    Lockfile {
+        version: base.version,
+        lock_hash: String::new(),
+        dataset_id: base.dataset_id,
+        as_of: args.as_of.clone().or(base.as_of),
+        note: args.note.clone().or(base.note),
+        parent: Some(base.lock_hash),
+        members_root: None,
+        created: crate::current_created_timestamp(),
+        tool_versions,
+        profiles: base.profiles,
+        skipped_count: skipped.len() as u64,
+        member_count: members.len() as u64,
+        skipped,
+        members,
+    }
+}
+
+// ---------------------------------------------------------------------------
+// Orchestration
+// ---------------------------------------------------------------------------
+
+/// Run the update subcommand. Returns the exit code.
+pub fn run_update(args: &UpdateArgs) -> u8 {
+    // Step 1: Load the base before consuming input, so a bad base refuses fast.
+    let base = match load_base(&args.base) {
+        Ok(base) => base,
+        Err(refusal) => {
+            // The base's version is unknown until it loads.
+            let orchestrated =
+                crate::lockfile_refusal_output(refusal, crate::refusal::LOCK_VERSION);
+            return finish(args, &orchestrated, None, None);
+        }
+    };
+
+    // Step 2: Stream and classify the incoming records.
+    let options = crate::lockfile::ClassifyOptions {
+        keep_mtime: args.keep_mtime,
+        keep_fields: crate::lockfile::KeepFields::from_flags(
+            &args.keep_fields,
+            args.keep_all_fields,
+        ),
+    };
+    let ingested = crate::ingest_input(args.input.as_deref(), None, None, None, options);
+
+    // Step 3: Merge, re-seal, and render.
+    let base_hash = base.lock_hash.clone();
+    let orchestrated = match ingested.result {
+        Ok(records) => crate::seal_lockfile(apply_update(base, records, args)),
+        Err(refusal) => refusal,
+    };
+
+    finish(
+        args,
+        &orchestrated,
+        Some(&base_hash),
+        ingested.source.as_ref(),
+    )
+}
+
+fn finish(
+    args: &UpdateArgs,
+    orchestrated: &OrchestrationOutput,
+    base_hash: Option<&str>,
+    source: Option<&crate::input::SourceMetadata>,
+) -> u8 {
+    if !crate::write_payload(args.output.as_deref(), &orchestrated.payload_json) {
+        return 2;
+    }
+    emit_witness(args, orchestrated, base_hash, source);
+    orchestrated.outcome.exit_code()
+}
+
+/// `base_hash` is the base's verified `lock_hash`, once it has loaded.
+fn emit_witness(
+    args: &UpdateArgs,
+    orchestrated: &OrchestrationOutput,
+    base_hash: Option<&str>,
+    source: Option<&crate::input::SourceMetadata>,
+) {
+    if args.no_witness {
+        return;
+    }
+
+    let input_path = args
+        .input
+        .as_ref()
+        .map(|p| p.display().to_string())
+        .unwrap_or_else(|| "stdin".to_string());
+
+    let params = serde_json::json!({
+        "subcommand": "update",
+        "prune": args.prune,
+        "as_of": args.as_of,
+        "note": args.note,
+    });
+    let inputs = serde_json::json!([
+        { "path": args.base.display().to_string(), "hash": base_hash, "bytes": null },
+        {
+            "path": input_path,
+            "hash": source.map(|s| s.source_hash.clone()),
+            "bytes": source.map(|s| s.source_bytes),
+        }
+    ]);
+
+    crate::witness::append_witness_record(
+        orchestrated.outcome.as_str(),
+        orchestrated.outcome.exit_code(),
+        orchestrated.payload_json.as_bytes(),
+        params,
+        inputs,
+    );
+}
+
+// ---------------------------------------------------------------------------
+// Tests
+// ---------------------------------------------------------------------------
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::lockfile::{Classification, MetadataHydration};
+    use crate::output;
+    use crate::refusal::{LOCK_V1_VERSION, LOCK_VERSION};
+
+    fn member(path: &str, hash: &str) -> Member {
+        Member {
+            path: path.to_string(),
+            bytes_hash: hash.to_string(),
+            size: 1,
+            fingerprint: None,
+            mtime: None,
+            extra: None,
+            hashes: None,
+        }
+    }
+
+    fn skipped(path: &str) -> SkippedEntry {
+        SkippedEntry {
+            path: path.to_string(),
+            warnings: Vec::new(),
+        }
+    }
+
+    fn base(members: Vec<Member>, skipped: Vec<SkippedEntry>) -> BaseLockfile {
+        BaseLockfile {
+            version: LOCK_VERSION.to_string(),
+            lock_hash: "sha256:base".to_string(),
+            dataset_id: Some("raw-dec".to_string()),
+            as_of: Some("2026-01-01".to_string()),
+            note: None,
+            tool_versions: BTreeMap::from([
+                ("hash".to_string(), "0.1.0".to_string()),
+                ("lock".to_string(), "0.4.0".to_string()),
+            ]),
+            profiles: Vec::new(),
+            skipped,
+            members,
+        }
+    }
+
+    fn incoming(members: Vec<Member>, skipped: Vec<SkippedEntry>) -> Ingested {
+        Ingested {
+            classification: Classification {
+                member_count: members.len() as u64,
+                skipped_count: skipped.len() as u64,
+                outcome: output::DomainOutcome::LockCreated,
+                members,
+                skipped,
+            },
+            metadata: MetadataHydration {
+                dataset_id: None,
+                as_of: None,
+                note: None,
+                profiles: Vec::new(),
+                tool_versions: BTreeMap::from([
+                    ("hash".to_string(), "0.2.0".to_string()),
+                    ("lock".to_string(), "0.5.0".to_string()),
+                ]),
+            },
+        }
+    }
+
+    fn args(prune: bool) -> UpdateArgs {
+        UpdateArgs {
+            base: "base.lock.json".into(),
+            input: None,
+            prune,
+            as_of: None,
+            note: None,
+            output: None,
+            keep_mtime: false,
+            keep_fields: vec![],
+            keep_all_fields: false,
+            no_witness: true,
+        }
+    }
+
+    #[test]
+    fn upserts_members_and_keeps_base_metadata() {
+        let updated = apply_update(
+            base(
+                vec![member("a.csv", "sha256:a"), member("b.csv", "sha256:b")],
+                vec![],
+            ),
+            incoming(
+                vec![member("b.csv", "sha256:b2"), member("c.csv", "sha256:c")],
+                vec![],
+            ),
+            &args(false),
+        );
+
+        let paths: Vec<&str> = updated.members.iter().map(|m| m.path.as_str()).collect();
+        assert_eq!(paths, ["a.csv", "b.csv", "c.csv"]);
+        assert_eq!(updated.members[1].bytes_hash, "sha256:b2");
+        assert_eq!(updated.member_count, 3);
+        assert_eq!(updated.dataset_id.as_deref(), Some("raw-dec"));
+        assert_eq!(updated.as_of.as_deref(), Some("2026-01-01"));
+        assert_eq!(updated.parent.as_deref(), Some("sha256:base"));
+        assert_eq!(updated.tool_versions["hash"], "0.2.0");
+        assert_eq!(updated.tool_versions["lock"], "0.5.0");
+    }
+
+    #[test]
+    fn prune_drops_paths_absent_from_input() {
+        let updated = apply_update(
+            base(
+                vec![member("a.csv", "sha256:a"), member("b.csv", "sha256:b")],
+                vec![skipped("old.csv")],
+            ),
+            incoming(vec![member("b.csv", "sha256:b")], vec![]),
+            &args(true),
+        );
+
+        assert_eq!(updated.members, vec![member("b.csv", "sha256:b")]);
+        assert!(updated.skipped.is_empty());
+        assert_eq!(updated.skipped_count, 0);
+    }
+
+    #[test]
+    fn incoming_record_moves_path_between_members_and_skipped() {
+        let updated = apply_update(
+            base(vec![member("a.csv", "sha256:a")], vec![skipped("b.csv")]),
+            incoming(vec![member("b.csv", "sha256:b")], vec![skipped("a.csv")]),
+            &args(false),
+        );
+
+        assert_eq!(updated.members, vec![member("b.csv", "sha256:b")]);
+        assert_eq!(updated.skipped, vec![skipped("a.csv")]);
+    }
+
+    #[test]
+    fn unchanged_members_keep_hashes_recorded_on_the_base() {
+        let with_hashes = |path: &str, bytes_hash: &str, hashes: &[(&str, &str)]| Member {
+            hashes: Some(
+                hashes
+                    .iter()
+                    .map(|(algorithm, digest)| (algorithm.to_string(), digest.to_string()))
+                    .collect(),
+            ),
+            ..member(path, bytes_hash)
+        };
+        let updated = apply_update(
+            base(
+                vec![
+                    with_hashes(
+                        "a.csv",
+                        "sha256:a",
+                        &[("sha256", "sha256:a"), ("blake3", "blake3:a")],
+                    ),
+                    with_hashes(
+                        "b.csv",
+                        "sha256:b",
+                        &[("sha256", "sha256:b"), ("blake3", "blake3:b")],
+                    ),
+                ],
+                vec![],
+            ),
+            incoming(
+                vec![
+                    with_hashes(
+                        "a.csv",
+                        "sha256:a",
+                        &[("sha256", "sha256:a"), ("sha512", "sha512:a")],
+                    ),
+                    member("b.csv", "sha256:b2"),
+                ],
+                vec![],
+            ),
+            &args(false),
+        );
+
+        let hashes = updated.members[0].hashes.as_ref().unwrap();
+        let algorithms: Vec<&str> = hashes.keys().map(String::as_str).collect();
+        assert_eq!(algorithms, ["blake3", "sha256", "sha512"]);
+        assert_eq!(updated.members[1].hashes, None);
+    }
+
+    #[test]
+    fn as_of_and_note_overrides_replace_base_values() {
+        let mut update_args = args(false);
+        update_args.as_of = Some("2026-01-02".to_string());
+        update_args.note = Some("daily".to_string());
+
+        let updated = apply_update(
+            base(vec![], vec![]),
+            incoming(vec![member("a.csv", "sha256:a")], vec![]),
+            &update_args,
+        );
+
+        assert_eq!(updated.as_of.as_deref(), Some("2026-01-02"));
+        assert_eq!(updated.note.as_deref(), Some("daily"));
+    }
+
+    #[test]
+    fn keeps_base_lockfile_version() {
+        let mut v1_base = base(vec![], vec![]);
+        v1_base.version = LOCK_V1_VERSION.to_string();
+
+        let updated = apply_update(
+            v1_base,
+            incoming(vec![member("a.csv", "sha256:a")], vec![]),
+            &args(false),
+        );
+
+        assert_eq!(updated.version, LOCK_V1_VERSION);
+    }
+}
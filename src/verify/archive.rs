@@ -0,0 +1,397 @@
+//! Member verification against a tar, tar.gz, or zip archive.
+//!
+//! Entries are streamed and hashed straight from the archive; nothing is
+//! extracted to disk. Results use the same [`MembersResult`] shape and
+//! failure reasons as verification against a `--root` directory.
+
+use std::collections::{BTreeMap, BTreeSet};
+use std::fs::File;
+use std::io::{BufReader, Read};
+use std::path::Path;
+
+use serde_json::Value;
+
+use super::members::{
+    Checked, MemberCheck, MemberFailure, MemberSkip, MembersResult, VerifyOptions, assemble,
+    expected_digests, hash_reader,
+};
+
+/// Archive container formats `verify --archive` reads.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ArchiveFormat {
+    Tar,
+    TarGz,
+    Zip,
+}
+
+impl ArchiveFormat {
+    /// Pick the format from the file name: `.tar`, `.tar.gz`/`.tgz`, `.zip`.
+    pub fn from_path(path: &Path) -> Option<Self> {
+        let name = path.file_name()?.to_string_lossy().to_ascii_lowercase();
+        if name.ends_with(".tar.gz") || name.ends_with(".tgz") {
+            Some(Self::TarGz)
+        } else if name.ends_with(".tar") {
+            Some(Self::Tar)
+        } else if name.ends_with(".zip") {
+            Some(Self::Zip)
+        } else {
+            None
+        }
+    }
+}
+
+/// Verify all members against the regular-file entries of `archive`.
+///
+/// Each entry whose normalized path names a member is size-checked and
+/// hashed in-stream with every digest the member records; members with no
+/// entry are `MISSING`. When an archive holds the same path twice, the later
+/// entry wins, as it would on extraction. With `extra_excludes`, entries that
+/// are neither members nor skipped (and match no exclude glob) are reported
+/// as `EXTRA`, in path order.
+///
+/// Returns `Err` when the archive cannot be opened or its structure cannot
+/// be read.
+pub fn verify_archive(
+    lockfile_json: &Value,
+    archive: &Path,
+    extra_excludes: Option<&[String]>,
+) -> std::io::Result<MembersResult> {
+    let format = ArchiveFormat::from_path(archive).ok_or_else(|| {
+        std::io::Error::new(
+            std::io::ErrorKind::InvalidInput,
+            "unsupported archive format",
+        )
+    })?;
+    let members = lockfile_json
+        .get("members")
+        .and_then(Value::as_array)
+        .map(Vec::as_slice)
+        .unwrap_or(&[]);
+    let by_path: BTreeMap<&str, usize> = members
+        .iter()
+        .enumerate()
+        .filter_map(|(index, member)| Some((member.get("path")?.as_str()?, index)))
+        .collect();
+    let skipped: BTreeSet<&str> = lockfile_json
+        .get("skipped")
+        .and_then(Value::as_array)
+        .into_iter()
+        .flatten()
+        .filter_map(|entry| entry.get("path").and_then(Value::as_str))
+        .collect();
+
+    let mut found: BTreeMap<usize, MemberCheck> = BTreeMap::new();
+    let mut extras: BTreeMap<String, u64> = BTreeMap::new();
+    for_each_file(archive, format, |name, size, reader| {
+        if let Some(&index) = by_path.get(name.as_str()) {
+            found.insert(index, check_entry(&members[index], size, reader));
+        } else if let Some(excludes) = extra_excludes
+            && !skipped.contains(name.as_str())
+            && !crate::glob::any_match(excludes, &name)
+        {
+            extras.insert(name, size);
+        }
+    })?;
+
+    let checks: Vec<Checked> = members
+        .iter()
+        .enumerate()
+        .map(|(index, member)| {
+            let check = found.remove(&index).unwrap_or_else(|| missing(member));
+            (check, None)
+        })
+        .collect();
+    let mut result = assemble(
+        archive.display().to_string(),
+        checks,
+        &VerifyOptions::default(),
+    );
+    if extra_excludes.is_some() {
+        result.add_extra(
+            extras
+                .into_iter()
+                .map(|(path, size)| MemberFailure {
+                    path,
+                    reason: "EXTRA".to_string(),
+                    expected: None,
+                    actual: None,
+                    expected_size: None,
+                    actual_size: Some(size),
+                })
+                .collect(),
+            // An archive is read in one stream; there are no directories to
+            // fail to list.
+            Vec::new(),
+        );
+    }
+    Ok(result)
+}
+
+fn missing(member: &Value) -> MemberCheck {
+    MemberCheck::Failed(MemberFailure {
+        path: member_str(member, "path").to_string(),
+        reason: "MISSING".to_string(),
+        expected: Some(member_str(member, "bytes_hash").to_string()),
+        actual: None,
+        expected_size: member.get("size").and_then(Value::as_u64),
+        actual_size: None,
+    })
+}
+
+fn member_str<'m>(member: &'m Value, field: &str) -> &'m str {
+    member.get(field).and_then(Value::as_str).unwrap_or("")
+}
+
+/// Check one archive entry against its member: size from the entry header
+/// first, then every recorded digest in a single pass over the contents.
+fn check_entry(member: &Value, size: u64, reader: &mut dyn Read) -> MemberCheck {
+    let member_path = member_str(member, "path");
+    let expected_hash = member_str(member, "bytes_hash");
+    let expected_size = member.get("size").and_then(Value::as_u64);
+
+    if let Some(exp_size) = expected_size
+        && size != exp_size
+    {
+        return MemberCheck::Failed(MemberFailure {
+            path: member_path.to_string(),
+            reason: "SIZE_MISMATCH".to_string(),
+            expected: Some(expected_hash.to_string()),
+            actual: None,
+            expected_size: Some(exp_size),
+            actual_size: Some(size),
+        });
+    }
+
+    // Report a failed pre-check digest first, as directory verification does.
+    let mut expected = expected_digests(member, expected_hash);
+    expected.sort_by_key(|(algorithm, _)| {
+        crate::digest::lookup(algorithm).is_some_and(|a| a.cryptographic)
+    });
+    let algorithms: Vec<&str> = expected.iter().map(|(algorithm, _)| *algorithm).collect();
+    let actual = match hash_reader(reader, &algorithms) {
+        Ok(actual) => actual,
+        Err(detail) => {
+            return MemberCheck::Skipped(MemberSkip {
+                path: member_path.to_string(),
+                reason: "IO_ERROR".to_string(),
+                detail,
+            });
+        }
+    };
+
+    match expected
+        .iter()
+        .zip(actual)
+        .find(|((_, expected), actual)| actual != expected)
+    {
+        None => MemberCheck::Verified,
+        Some(((_, expected), actual)) => MemberCheck::Failed(MemberFailure {
+            path: member_path.to_string(),
+            reason: "HASH_MISMATCH".to_string(),
+            expected: Some(expected.to_string()),
+            actual: Some(actual),
+            expected_size,
+            actual_size: Some(size),
+        }),
+    }
+}
+
+/// Call `visit(path, size, contents)` for each regular-file entry, in
+/// archive order. Directories, links, and other entry types are skipped.
+fn for_each_file(
+    archive: &Path,
+    format: ArchiveFormat,
+    mut visit: impl FnMut(String, u64, &mut dyn Read),
+) -> std::io::Result<()> {
+    let file = File::open(archive)?;
+    match format {
+        ArchiveFormat::Tar => for_each_tar_file(BufReader::new(file), &mut visit),
+        ArchiveFormat::TarGz => for_each_tar_file(
+            flate2::read::MultiGzDecoder::new(BufReader::new(file)),
+            &mut visit,
+        ),
+        ArchiveFormat::Zip => {
+            let mut zip = zip::ZipArchive::new(BufReader::new(file))?;
+            for index in 0..zip.len() {
+                let mut entry = zip.by_index(index)?;
+                if entry.is_file() {
+                    let name = normalize_entry_path(entry.name());
+                    let size = entry.size();
+                    visit(name, size, &mut entry);
+                }
+            }
+            Ok(())
+        }
+    }
+}
+
+fn for_each_tar_file(
+    reader: impl Read,
+    visit: &mut impl FnMut(String, u64, &mut dyn Read),
+) -> std::io::Result<()> {
+    let mut archive = tar::Archive::new(reader);
+    for entry in archive.entries()? {
+        let mut entry = entry?;
+        if entry.header().entry_type().is_file() {
+            let name = normalize_entry_path(&String::from_utf8_lossy(&entry.path_bytes()));
+            let size = entry.size();
+            visit(name, size, &mut entry);
+        }
+    }
+    Ok(())
+}
+
+/// Archive entry names as lockfile member paths: `/`-separated, with `.`
+/// segments and the empty segments of `./` or `//` dropped.
+fn normalize_entry_path(name: &str) -> String {
+    name.split('/')
+        .filter(|segment| !segment.is_empty() && *segment != ".")
+        .collect::<Vec<_>>()
+        .join("/")
+}
+
+#[cfg(test)]
+mod tests {
+    use std::io::Write;
+
+    use super::*;
+
+    fn sha256(data: &[u8]) -> String {
+        use sha2::{Digest, Sha256};
+        format!("sha256:{:x}", Sha256::digest(data))
+    }
+
+    fn write_tar(path: &Path, files: &[(&str, &[u8])]) {
+        let mut builder = tar::Builder::new(File::create(path).unwrap());
+        for (name, content) in files {
+            let mut header = tar::Header::new_gnu();
+            header.set_size(content.len() as u64);
+            header.set_mode(0o644);
+            header.set_cksum();
+            builder.append_data(&mut header, name, *content).unwrap();
+        }
+        builder.finish().unwrap();
+    }
+
+    fn write_zip(path: &Path, files: &[(&str, &[u8])]) {
+        let mut writer = zip::ZipWriter::new(File::create(path).unwrap());
+        for (name, content) in files {
+            writer
+                .start_file(*name, zip::write::SimpleFileOptions::default())
+                .unwrap();
+            writer.write_all(content).unwrap();
+        }
+        writer.finish().unwrap();
+    }
+
+    fn lockfile() -> Value {
+        serde_json::json!({
+            "members": [
+                { "path": "a.csv", "bytes_hash": sha256(b"alpha"), "size": 5 },
+                { "path": "b.csv", "bytes_hash": sha256(b"bravo"), "size": 5 },
+                { "path": "sub/c.csv", "bytes_hash": sha256(b"charlie"), "size": 7 },
+                { "path": "d.csv", "bytes_hash": sha256(b"delta"), "size": 5 }
+            ],
+            "skipped": [{ "path": "locked.bin" }]
+        })
+    }
+
+    fn reasons(result: &MembersResult) -> Vec<(&str, &str)> {
+        result
+            .failures
+            .iter()
+            .map(|f| (f.path.as_str(), f.reason.as_str()))
+            .collect()
+    }
+
+    const ENTRIES: &[(&str, &[u8])] = &[
+        ("./a.csv", b"alpha"),
+        ("b.csv", b"BRAVO"),
+        ("sub/c.csv", b"charlie!"),
+        ("locked.bin", b"x"),
+        ("notes.txt", b"n"),
+    ];
+
+    #[test]
+    fn tar_reports_the_same_failures_as_a_directory() {
+        let dir = tempfile::tempdir().unwrap();
+        let archive = dir.path().join("delivery.tar");
+        write_tar(&archive, ENTRIES);
+
+        let result = verify_archive(&lockfile(), &archive, None).unwrap();
+        assert_eq!(result.checked, 4);
+        assert_eq!(result.verified, 1);
+        assert_eq!(
+            reasons(&result),
+            [
+                ("b.csv", "HASH_MISMATCH"),
+                ("sub/c.csv", "SIZE_MISMATCH"),
+                ("d.csv", "MISSING")
+            ]
+        );
+        assert_eq!(result.extra, None);
+    }
+
+    #[test]
+    fn zip_and_tar_gz_are_read_in_stream() {
+        let dir = tempfile::tempdir().unwrap();
+        let zip_path = dir.path().join("delivery.zip");
+        write_zip(&zip_path, ENTRIES);
+        let tar_path = dir.path().join("delivery.tar");
+        write_tar(&tar_path, ENTRIES);
+        let gz_path = dir.path().join("delivery.TGZ");
+        let mut encoder = flate2::write::GzEncoder::new(
+            File::create(&gz_path).unwrap(),
+            flate2::Compression::fast(),
+        );
+        encoder
+            .write_all(&std::fs::read(&tar_path).unwrap())
+            .unwrap();
+        encoder.finish().unwrap();
+
+        let expected = reasons(&verify_archive(&lockfile(), &tar_path, None).unwrap()).len();
+        for archive in [zip_path, gz_path] {
+            let result = verify_archive(&lockfile(), &archive, None).unwrap();
+            assert_eq!(result.verified, 1, "{}", archive.display());
+            assert_eq!(result.failures.len(), expected);
+        }
+    }
+
+    #[test]
+    fn extra_entries_skip_listed_and_excluded_paths() {
+        let dir = tempfile::tempdir().unwrap();
+        let archive = dir.path().join("delivery.zip");
+        let mut entries = ENTRIES.to_vec();
+        entries.push(("logs/run.log", b"log"));
+        write_zip(&archive, &entries);
+
+        let excludes = ["logs/**".to_string()];
+        let result = verify_archive(&lockfile(), &archive, Some(&excludes)).unwrap();
+        assert_eq!(result.extra, Some(1));
+        let extra = result.failures.last().unwrap();
+        assert_eq!(
+            (extra.path.as_str(), extra.reason.as_str()),
+            ("notes.txt", "EXTRA")
+        );
+        assert_eq!(extra.actual_size, Some(1));
+    }
+
+    #[test]
+    fn format_follows_the_file_name() {
+        let format = |name: &str| ArchiveFormat::from_path(Path::new(name));
+        assert_eq!(format("d/delivery.tar"), Some(ArchiveFormat::Tar));
+        assert_eq!(format("delivery.tar.gz"), Some(ArchiveFormat::TarGz));
+        assert_eq!(format("delivery.tgz"), Some(ArchiveFormat::TarGz));
+        assert_eq!(format("DELIVERY.ZIP"), Some(ArchiveFormat::Zip));
+        assert_eq!(format("delivery.7z"), None);
+        assert_eq!(normalize_entry_path("./sub//c.csv"), "sub/c.csv");
+    }
+
+    #[test]
+    fn corrupt_archive_is_an_error() {
+        let dir = tempfile::tempdir().unwrap();
+        let archive = dir.path().join("delivery.zip");
+        std::fs::write(&archive, b"not a zip").unwrap();
+        assert!(verify_archive(&lockfile(), &archive, None).is_err());
+    }
+}
@@ -1,9 +1,17 @@
+use std::collections::BTreeSet;
 use std::io::Read;
-use std::path::Path;
+use std::num::NonZeroUsize;
+use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::time::SystemTime;
 
+use chrono::{DateTime, Utc};
+
+use crate::cache::HashCache;
+use crate::digest::Hasher;
+use crate::walk::walk_files;
 use serde::Serialize;
 use serde_json::Value;
-use sha2::{Digest, Sha256};
 
 // ---------------------------------------------------------------------------
 // Result types
@@ -19,6 +27,21 @@ pub struct MembersResult {
     pub skipped: usize,
     pub failures: Vec<MemberFailure>,
     pub skips: Vec<MemberSkip>,
+    /// Files under the root that the lockfile does not list. Only present
+    /// with `--detect-extra`; their `EXTRA` entries are appended to
+    /// `failures` but not counted in `failed`.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub extra: Option<usize>,
+    /// Members `--fast` accepted on matching size and mtime without
+    /// rehashing (`VERIFIED_BY_METADATA`). They are counted in `verified`;
+    /// only present with `--fast`.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub verified_by_metadata: Option<Vec<String>>,
+    /// Members whose digest came from the `--cache` hash cache rather than
+    /// from reading the file, whatever the comparison's result. Only
+    /// present with `--cache`.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub cache_hits: Option<Vec<String>>,
 }
 
 /// A member that failed verification.
@@ -44,110 +67,413 @@ pub struct MemberSkip {
 // Verification logic
 // ---------------------------------------------------------------------------
 
+/// Tuning knobs for member verification.
+#[derive(Debug, Clone, Copy)]
+pub struct VerifyOptions<'a> {
+    /// Number of worker threads hashing members concurrently.
+    pub jobs: NonZeroUsize,
+    /// Trust a recorded `mtime` that still matches, together with the size,
+    /// instead of rehashing the file.
+    pub fast: bool,
+    /// Digests to reuse for files whose identity is unchanged; new digests
+    /// are recorded back into it.
+    pub cache: Option<&'a HashCache>,
+}
+
+impl Default for VerifyOptions<'_> {
+    fn default() -> Self {
+        Self {
+            jobs: NonZeroUsize::MIN,
+            fast: false,
+            cache: None,
+        }
+    }
+}
+
+/// Outcome of checking a single member.
+pub(super) enum MemberCheck {
+    Verified,
+    VerifiedByMetadata(String),
+    Failed(MemberFailure),
+    Skipped(MemberSkip),
+}
+
+/// Verify all members in the lockfile against the filesystem, one at a time.
+pub fn verify_members(lockfile_json: &Value, root: &Path) -> MembersResult {
+    verify_members_with(lockfile_json, root, &VerifyOptions::default())
+}
+
 /// Verify all members in the lockfile against the filesystem.
 ///
 /// For each member: resolve path against root, check existence, stat size,
 /// and stream-hash using the algorithm prefix from the stored `bytes_hash`.
-pub fn verify_members(lockfile_json: &Value, root: &Path) -> MembersResult {
+/// With `options.fast`, a member whose size and recorded `mtime` both match
+/// the file is accepted without the hash step. With `options.cache`, a
+/// cached digest stands in for reading the file.
+///
+/// With `options.jobs > 1`, members are checked on a bounded pool of scoped
+/// threads pulling from a shared index. Results are reassembled in lockfile
+/// order, so `failures` and `skips` are identical for any job count.
+pub fn verify_members_with(
+    lockfile_json: &Value,
+    root: &Path,
+    options: &VerifyOptions,
+) -> MembersResult {
     let members = lockfile_json
         .get("members")
         .and_then(Value::as_array)
         .map(Vec::as_slice)
         .unwrap_or(&[]);
 
+    let checks = if options.jobs.get() == 1 || members.len() < 2 {
+        members
+            .iter()
+            .map(|member| check_member(member, root, options))
+            .collect()
+    } else {
+        check_members_parallel(members, root, options)
+    };
+
+    assemble(root.display().to_string(), checks, options)
+}
+
+/// Tally per-member checks, in lockfile order, into a [`MembersResult`].
+pub(super) fn assemble(
+    root: String,
+    checks: Vec<Checked>,
+    options: &VerifyOptions,
+) -> MembersResult {
+    let checked = checks.len();
     let mut failures = Vec::new();
     let mut skips = Vec::new();
+    let mut by_metadata = Vec::new();
+    let mut cache_hits = Vec::new();
     let mut verified = 0;
 
-    for member in members {
-        let member_path = member.get("path").and_then(Value::as_str).unwrap_or("");
-        let expected_hash = member
-            .get("bytes_hash")
-            .and_then(Value::as_str)
-            .unwrap_or("");
-        let expected_size = member.get("size").and_then(Value::as_u64);
+    for (check, cache_hit) in checks {
+        if let Some(path) = cache_hit {
+            cache_hits.push(path);
+        }
+        match check {
+            MemberCheck::Verified => verified += 1,
+            MemberCheck::VerifiedByMetadata(path) => {
+                verified += 1;
+                by_metadata.push(path);
+            }
+            MemberCheck::Failed(failure) => failures.push(failure),
+            MemberCheck::Skipped(skip) => skips.push(skip),
+        }
+    }
+
+    let failed = failures.len();
+    let skipped = skips.len();
+
+    MembersResult {
+        root,
+        checked,
+        verified,
+        failed,
+        skipped,
+        failures,
+        skips,
+        extra: None,
+        verified_by_metadata: options.fast.then_some(by_metadata),
+        cache_hits: options.cache.map(|_| cache_hits),
+    }
+}
+
+/// Walk `root` for files listed in neither `members` nor `skipped`.
+///
+/// Paths matching any of `excludes` (see [`crate::glob`]) are ignored, as
+/// are `ignore` paths — verify's own inputs, when they live under the root.
+/// Returns one `EXTRA` failure per file, in path order, and one `IO_ERROR`
+/// skip per subdirectory that could not be listed — extras could hide there.
+pub fn find_extra(
+    lockfile_json: &Value,
+    root: &Path,
+    excludes: &[String],
+    ignore: &[&Path],
+) -> std::io::Result<(Vec<MemberFailure>, Vec<MemberSkip>)> {
+    let listed: BTreeSet<&str> = ["members", "skipped"]
+        .iter()
+        .filter_map(|section| lockfile_json.get(section).and_then(Value::as_array))
+        .flatten()
+        .filter_map(|entry| entry.get("path").and_then(Value::as_str))
+        .collect();
+    let ignore: Vec<PathBuf> = ignore
+        .iter()
+        .filter_map(|path| path.canonicalize().ok())
+        .collect();
+
+    let walk = walk_files(root)?;
+    let unreadable = walk
+        .unreadable
+        .into_iter()
+        .filter(|dir| !crate::glob::any_match(excludes, &dir.relative))
+        .map(|dir| MemberSkip {
+            path: dir.relative,
+            reason: "IO_ERROR".to_string(),
+            detail: format!("cannot read directory: {}", dir.error),
+        })
+        .collect();
+    let extras = walk
+        .files
+        .into_iter()
+        .filter(|file| !listed.contains(file.relative.as_str()))
+        .filter(|file| !crate::glob::any_match(excludes, &file.relative))
+        .filter(|file| {
+            file.path
+                .canonicalize()
+                .map_or(true, |path| !ignore.contains(&path))
+        })
+        .map(|file| MemberFailure {
+            actual_size: std::fs::metadata(&file.path).ok().map(|m| m.len()),
+            path: file.relative,
+            reason: "EXTRA".to_string(),
+            expected: None,
+            actual: None,
+            expected_size: None,
+        })
+        .collect();
+    Ok((extras, unreadable))
+}
+
+impl MembersResult {
+    /// Record `--detect-extra` findings: extra files fail, and directories
+    /// that could not be searched for them are skipped.
+    pub fn add_extra(&mut self, extras: Vec<MemberFailure>, unreadable: Vec<MemberSkip>) {
+        self.extra = Some(extras.len());
+        self.failures.extend(extras);
+        self.skipped += unreadable.len();
+        self.skips.extend(unreadable);
+    }
+}
+
+/// Check members on `jobs` worker threads, returning results in member order.
+fn check_members_parallel(members: &[Value], root: &Path, options: &VerifyOptions) -> Vec<Checked> {
+    let next = AtomicUsize::new(0);
+    let workers = options.jobs.get().min(members.len());
+
+    let mut indexed: Vec<(usize, Checked)> = std::thread::scope(|scope| {
+        let handles: Vec<_> = (0..workers)
+            .map(|_| {
+                scope.spawn(|| {
+                    let mut results = Vec::new();
+                    loop {
+                        let index = next.fetch_add(1, Ordering::Relaxed);
+                        let Some(member) = members.get(index) else {
+                            break;
+                        };
+                        results.push((index, check_member(member, root, options)));
+                    }
+                    results
+                })
+            })
+            .collect();
+
+        handles
+            .into_iter()
+            .flat_map(|handle| handle.join().expect("member worker panicked"))
+            .collect()
+    });
+
+    indexed.sort_unstable_by_key(|(index, _)| *index);
+    indexed.into_iter().map(|(_, check)| check).collect()
+}
+
+/// A member's check, plus its path when the digest came from the cache.
+pub(super) type Checked = (MemberCheck, Option<String>);
+
+fn check_member(member: &Value, root: &Path, options: &VerifyOptions) -> Checked {
+    let member_path = member.get("path").and_then(Value::as_str).unwrap_or("");
+    let expected_hash = member
+        .get("bytes_hash")
+        .and_then(Value::as_str)
+        .unwrap_or("");
+    let expected_size = member.get("size").and_then(Value::as_u64);
 
-        let full_path = root.join(member_path);
+    let full_path = root.join(member_path);
 
-        // Check existence.
-        if !full_path.exists() {
-            failures.push(MemberFailure {
+    // Check existence.
+    if !full_path.exists() {
+        let failure = MemberFailure {
+            path: member_path.to_string(),
+            reason: "MISSING".to_string(),
+            expected: Some(expected_hash.to_string()),
+            actual: None,
+            expected_size,
+            actual_size: None,
+        };
+        return (MemberCheck::Failed(failure), None);
+    }
+
+    // Stat size.
+    let metadata = match std::fs::metadata(&full_path) {
+        Ok(m) => m,
+        Err(e) => {
+            let skip = MemberSkip {
                 path: member_path.to_string(),
-                reason: "MISSING".to_string(),
-                expected: Some(expected_hash.to_string()),
-                actual: None,
-                expected_size,
-                actual_size: None,
-            });
-            continue;
+                reason: "IO_ERROR".to_string(),
+                detail: e.to_string(),
+            };
+            return (MemberCheck::Skipped(skip), None);
         }
+    };
 
-        // Stat size.
-        let metadata = match std::fs::metadata(&full_path) {
-            Ok(m) => m,
-            Err(e) => {
-                skips.push(MemberSkip {
-                    path: member_path.to_string(),
-                    reason: "IO_ERROR".to_string(),
-                    detail: e.to_string(),
-                });
-                continue;
-            }
+    let actual_size = metadata.len();
+    if let Some(exp_size) = expected_size
+        && actual_size != exp_size
+    {
+        let failure = MemberFailure {
+            path: member_path.to_string(),
+            reason: "SIZE_MISMATCH".to_string(),
+            expected: Some(expected_hash.to_string()),
+            actual: None,
+            expected_size: Some(exp_size),
+            actual_size: Some(actual_size),
         };
+        return (MemberCheck::Failed(failure), None);
+    }
 
-        let actual_size = metadata.len();
-        if let Some(exp_size) = expected_size
-            && actual_size != exp_size
-        {
-            failures.push(MemberFailure {
-                path: member_path.to_string(),
-                reason: "SIZE_MISMATCH".to_string(),
-                expected: Some(expected_hash.to_string()),
-                actual: None,
-                expected_size: Some(exp_size),
-                actual_size: Some(actual_size),
-            });
+    // Size matches; with --fast an unchanged mtime stands in for the hash.
+    if options.fast
+        && let Some(recorded) = member.get("mtime").and_then(Value::as_str)
+        && metadata
+            .modified()
+            .is_ok_and(|modified| mtime_matches(recorded, modified))
+    {
+        return (
+            MemberCheck::VerifiedByMetadata(member_path.to_string()),
+            None,
+        );
+    }
+
+    // Check every recorded digest, reusing cached ones. Non-cryptographic
+    // pre-check digests (xxh3) get their own cheap read first, so a changed
+    // file fails before the expensive hashes run; everything else shares
+    // one read.
+    let (pre_checks, digests): (Vec<_>, Vec<_>) = expected_digests(member, expected_hash)
+        .into_iter()
+        .partition(|(algorithm, _)| {
+            crate::digest::lookup(algorithm).is_some_and(|a| !a.cryptographic)
+        });
+    let mut from_cache = true;
+    for stage in [pre_checks, digests] {
+        if stage.is_empty() {
             continue;
         }
-
-        // Stream-hash the file.
-        match stream_hash(&full_path, expected_hash) {
-            Ok(actual_hash) => {
-                if actual_hash == expected_hash {
-                    verified += 1;
-                } else {
-                    failures.push(MemberFailure {
-                        path: member_path.to_string(),
-                        reason: "HASH_MISMATCH".to_string(),
-                        expected: Some(expected_hash.to_string()),
-                        actual: Some(actual_hash),
-                        expected_size,
-                        actual_size: Some(actual_size),
-                    });
-                }
+        let algorithms: Vec<&str> = stage.iter().map(|(algorithm, _)| *algorithm).collect();
+        let actual = match digests_for(&full_path, &metadata, &algorithms, options.cache) {
+            Ok((actual, cached)) => {
+                from_cache &= cached;
+                actual
             }
             Err(e) => {
-                skips.push(MemberSkip {
+                let skip = MemberSkip {
                     path: member_path.to_string(),
                     reason: "IO_ERROR".to_string(),
                     detail: e,
-                });
+                };
+                return (MemberCheck::Skipped(skip), None);
             }
+        };
+        let cache_hit = from_cache.then(|| member_path.to_string());
+        if let Some(((_, expected), actual)) = stage
+            .iter()
+            .zip(actual)
+            .find(|((_, expected), actual)| actual != expected)
+        {
+            let failure = MemberFailure {
+                path: member_path.to_string(),
+                reason: "HASH_MISMATCH".to_string(),
+                expected: Some(expected.to_string()),
+                actual: Some(actual),
+                expected_size,
+                actual_size: Some(actual_size),
+            };
+            return (MemberCheck::Failed(failure), cache_hit);
         }
     }
+    (
+        MemberCheck::Verified,
+        from_cache.then(|| member_path.to_string()),
+    )
+}
 
-    let checked = members.len();
-    let failed = failures.len();
-    let skipped = skips.len();
+/// A member's recorded digests as `(algorithm, digest)`, `bytes_hash` first,
+/// followed by any other algorithms in its `hashes` map.
+pub(super) fn expected_digests<'m>(
+    member: &'m Value,
+    bytes_hash: &'m str,
+) -> Vec<(&'m str, &'m str)> {
+    let own = crate::digest::prefix(bytes_hash);
+    let mut digests = vec![(own, bytes_hash)];
+    if let Some(hashes) = member.get("hashes").and_then(Value::as_object) {
+        digests.extend(
+            hashes
+                .iter()
+                .filter(|(algorithm, _)| *algorithm != own)
+                .filter_map(|(algorithm, digest)| Some((algorithm.as_str(), digest.as_str()?))),
+        );
+    }
+    digests
+}
 
-    MembersResult {
-        root: root.display().to_string(),
-        checked,
-        verified,
-        failed,
-        skipped,
-        failures,
-        skips,
+/// Digests of `path` for each of `algorithms`, in order, and whether the
+/// cache supplied all of them. Uncached algorithms share one read of the
+/// file, and their digests are recorded back into the cache.
+fn digests_for(
+    path: &Path,
+    metadata: &std::fs::Metadata,
+    algorithms: &[&str],
+    cache: Option<&HashCache>,
+) -> Result<(Vec<String>, bool), String> {
+    let cached: Vec<Option<String>> = algorithms
+        .iter()
+        .map(|algorithm| cache.and_then(|cache| cache.lookup(metadata, algorithm)))
+        .collect();
+    let uncached: Vec<&str> = algorithms
+        .iter()
+        .zip(&cached)
+        .filter(|(_, digest)| digest.is_none())
+        .map(|(algorithm, _)| *algorithm)
+        .collect();
+    if uncached.is_empty() {
+        return Ok((cached.into_iter().flatten().collect(), true));
+    }
+
+    let fresh = stream_hashes(path, &uncached)?;
+    if let Some(cache) = cache {
+        for (algorithm, digest) in uncached.iter().zip(&fresh) {
+            cache.insert(path, metadata, algorithm, digest);
+        }
+    }
+    let mut fresh = fresh.into_iter();
+    let digests = cached
+        .into_iter()
+        .map(|digest| {
+            digest
+                .or_else(|| fresh.next())
+                .expect("one digest per algorithm")
+        })
+        .collect();
+    Ok((digests, false))
+}
+
+/// Whether a recorded RFC 3339 `mtime` names the same instant as `modified`.
+///
+/// A recorded value without fractional seconds is compared at whole-second
+/// precision, since that is all the producer kept. Anything unparseable
+/// counts as changed, so the member is rehashed.
+fn mtime_matches(recorded: &str, modified: SystemTime) -> bool {
+    let Ok(recorded) = DateTime::parse_from_rfc3339(recorded) else {
+        return false;
+    };
+    let modified = DateTime::<Utc>::from(modified);
+    if recorded.timestamp_subsec_nanos() == 0 {
+        recorded.timestamp() == modified.timestamp()
+    } else {
+        recorded == modified
     }
 }
 
@@ -155,7 +481,7 @@ pub fn verify_members(lockfile_json: &Value, root: &Path) -> MembersResult {
 ///
 /// Returns (outcome, exit_code).
 pub fn members_outcome(result: &MembersResult, strict: bool) -> (&'static str, u8) {
-    if result.failed > 0 {
+    if result.failed > 0 || result.extra.unwrap_or(0) > 0 {
         ("VERIFY_FAILED", 1)
     } else if result.skipped > 0 {
         if strict {
@@ -168,44 +494,59 @@ pub fn members_outcome(result: &MembersResult, strict: bool) -> (&'static str, u
     }
 }
 
+/// Read buffer for streaming member hashes.
+const HASH_BUFFER_SIZE: usize = 64 * 1024;
+
 /// Stream-hash a file using the algorithm indicated by the expected hash prefix.
-fn stream_hash(path: &Path, expected_hash: &str) -> Result<String, String> {
+pub(crate) fn stream_hash(path: &Path, expected_hash: &str) -> Result<String, String> {
     let prefix = expected_hash.split(':').next().unwrap_or("sha256");
+    stream_hash_with(path, prefix)
+}
 
-    let mut file = std::fs::File::open(path).map_err(|e| e.to_string())?;
-    let mut buf = [0u8; 8192];
-
-    match prefix {
-        "sha256" => {
-            let mut hasher = Sha256::new();
-            loop {
-                let n = file.read(&mut buf).map_err(|e| e.to_string())?;
-                if n == 0 {
-                    break;
-                }
-                hasher.update(&buf[..n]);
-            }
-            let digest = hasher.finalize();
-            Ok(format!("sha256:{:x}", digest))
+/// Stream-hash a file with `algorithm` (any name in [`crate::digest`]),
+/// returning the prefixed digest.
+pub(crate) fn stream_hash_with(path: &Path, prefix: &str) -> Result<String, String> {
+    let mut digests = stream_hashes(path, &[prefix])?;
+    Ok(digests.remove(0))
+}
+
+/// Stream-hash a file once with every one of `algorithms`, returning the
+/// prefixed digests in the same order.
+pub(crate) fn stream_hashes(path: &Path, algorithms: &[&str]) -> Result<Vec<String>, String> {
+    let file = std::fs::File::open(path).map_err(|e| e.to_string())?;
+    hash_reader(file, algorithms)
+}
+
+/// Hash everything `reader` yields with every one of `algorithms`, returning
+/// the prefixed digests in the same order.
+pub(crate) fn hash_reader(
+    mut reader: impl Read,
+    algorithms: &[&str],
+) -> Result<Vec<String>, String> {
+    let mut hashers = algorithms
+        .iter()
+        .map(|algorithm| {
+            Hasher::new(algorithm).ok_or_else(|| format!("unsupported algorithm: {algorithm}"))
+        })
+        .collect::<Result<Vec<_>, _>>()?;
+    let mut buf = vec![0u8; HASH_BUFFER_SIZE];
+
+    loop {
+        let n = reader.read(&mut buf).map_err(|e| e.to_string())?;
+        if n == 0 {
+            break;
         }
-        "blake3" => {
-            let mut hasher = blake3::Hasher::new();
-            loop {
-                let n = file.read(&mut buf).map_err(|e| e.to_string())?;
-                if n == 0 {
-                    break;
-                }
-                hasher.update(&buf[..n]);
-            }
-            Ok(format!("blake3:{}", hasher.finalize().to_hex()))
+        for hasher in &mut hashers {
+            hasher.update(&buf[..n]);
         }
-        _ => Err(format!("unsupported algorithm: {prefix}")),
     }
+    Ok(hashers.into_iter().map(Hasher::finish).collect())
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
+    use sha2::{Digest, Sha256};
 
     fn make_test_root(files: &[(&str, &[u8])]) -> (tempfile::TempDir, std::path::PathBuf) {
         let dir = tempfile::tempdir().unwrap();
@@ -351,6 +692,9 @@ mod tests {
                 reason: "IO_ERROR".to_string(),
                 detail: "permission denied".to_string(),
             }],
+            extra: None,
+            verified_by_metadata: None,
+            cache_hits: None,
         };
 
         let (outcome, exit_code) = members_outcome(&result, false);
@@ -372,6 +716,9 @@ mod tests {
             skipped: 0,
             failures: vec![],
             skips: vec![],
+            extra: None,
+            verified_by_metadata: None,
+            cache_hits: None,
         };
 
         let (outcome, exit_code) = members_outcome(&result, false);
@@ -396,6 +743,9 @@ mod tests {
                 actual_size: None,
             }],
             skips: vec![],
+            extra: None,
+            verified_by_metadata: None,
+            cache_hits: None,
         };
 
         let (outcome, exit_code) = members_outcome(&result, false);
@@ -418,4 +768,270 @@ mod tests {
         let result = verify_members(&lockfile, &root);
         assert_eq!(result.verified, 1);
     }
+
+    #[test]
+    fn parallel_results_match_sequential_order() {
+        let files: Vec<(String, Vec<u8>)> = (0..40)
+            .map(|i| (format!("f{i:02}.csv"), format!("content {i}").into_bytes()))
+            .collect();
+        let on_disk: Vec<(&str, &[u8])> = files
+            .iter()
+            .filter(|(name, _)| name != "f07.csv" && name != "f31.csv")
+            .map(|(name, content)| (name.as_str(), content.as_slice()))
+            .collect();
+        let (_dir, root) = make_test_root(&on_disk);
+
+        let members: Vec<Value> = files
+            .iter()
+            .enumerate()
+            .map(|(i, (name, content))| {
+                let hash = if i % 5 == 0 {
+                    hash_sha256(b"something else")
+                } else {
+                    hash_sha256(content)
+                };
+                serde_json::json!({ "path": name, "bytes_hash": hash, "size": content.len() })
+            })
+            .collect();
+        let lockfile = serde_json::json!({ "members": members });
+
+        let sequential = verify_members(&lockfile, &root);
+        let parallel = verify_members_with(
+            &lockfile,
+            &root,
+            &VerifyOptions {
+                jobs: NonZeroUsize::new(4).unwrap(),
+                ..VerifyOptions::default()
+            },
+        );
+
+        assert_eq!(sequential.failed, 10);
+        assert_eq!(
+            serde_json::to_string(&sequential).unwrap(),
+            serde_json::to_string(&parallel).unwrap()
+        );
+        let paths: Vec<&str> = parallel.failures.iter().map(|f| f.path.as_str()).collect();
+        let mut sorted = paths.clone();
+        sorted.sort_unstable();
+        assert_eq!(paths, sorted);
+    }
+
+    #[test]
+    fn more_jobs_than_members_is_fine() {
+        let content = b"solo";
+        let (_dir, root) = make_test_root(&[("a.csv", content)]);
+        let lockfile = serde_json::json!({
+            "members": [
+                { "path": "a.csv", "bytes_hash": hash_sha256(content), "size": content.len() }
+            ]
+        });
+
+        let result = verify_members_with(
+            &lockfile,
+            &root,
+            &VerifyOptions {
+                jobs: NonZeroUsize::new(16).unwrap(),
+                ..VerifyOptions::default()
+            },
+        );
+        assert_eq!(result.verified, 1);
+    }
+
+    #[test]
+    fn extra_files_are_reported_unless_listed_or_excluded() {
+        let (_dir, root) = make_test_root(&[
+            ("a.csv", b"a"),
+            ("locked.bin", b"x"),
+            ("late.csv", b"late"),
+            ("tmp/scratch.tmp", b"t"),
+            ("nested/new.csv", b"n"),
+        ]);
+        let lockfile = serde_json::json!({
+            "members": [{ "path": "a.csv", "bytes_hash": hash_sha256(b"a"), "size": 1 }],
+            "skipped": [{ "path": "locked.bin" }],
+        });
+
+        let (extras, unreadable) =
+            find_extra(&lockfile, &root, &["*.tmp".to_string()], &[]).unwrap();
+        assert!(unreadable.is_empty());
+        let paths: Vec<&str> = extras.iter().map(|f| f.path.as_str()).collect();
+        assert_eq!(paths, ["late.csv", "nested/new.csv"]);
+        assert_eq!(extras[0].reason, "EXTRA");
+        assert_eq!(extras[0].actual_size, Some(4));
+    }
+
+    #[test]
+    fn extra_ignores_verify_inputs_under_root() {
+        let (_dir, root) = make_test_root(&[("a.csv", b"a"), ("dec.lock.json", b"{}")]);
+        let lockfile = serde_json::json!({
+            "members": [{ "path": "a.csv", "bytes_hash": hash_sha256(b"a"), "size": 1 }],
+        });
+
+        let lockfile_path = root.join("dec.lock.json");
+        let (extras, _) = find_extra(&lockfile, &root, &[], &[lockfile_path.as_path()]).unwrap();
+        assert!(extras.is_empty());
+    }
+
+    #[test]
+    fn extra_files_fail_verification() {
+        let (_dir, root) = make_test_root(&[("a.csv", b"a"), ("b.csv", b"b")]);
+        let lockfile = serde_json::json!({
+            "members": [{ "path": "a.csv", "bytes_hash": hash_sha256(b"a"), "size": 1 }],
+        });
+
+        let mut result = verify_members(&lockfile, &root);
+        assert_eq!(members_outcome(&result, false), ("VERIFY_OK", 0));
+
+        let (extras, unreadable) = find_extra(&lockfile, &root, &[], &[]).unwrap();
+        result.add_extra(extras, unreadable);
+        assert_eq!(result.extra, Some(1));
+        assert_eq!(result.failed, 0);
+        assert_eq!(members_outcome(&result, false), ("VERIFY_FAILED", 1));
+    }
+
+    #[test]
+    fn unsearchable_directories_keep_extra_detection_partial() {
+        let (_dir, root) = make_test_root(&[("a.csv", b"a")]);
+        let lockfile = serde_json::json!({
+            "members": [{ "path": "a.csv", "bytes_hash": hash_sha256(b"a"), "size": 1 }],
+        });
+
+        let mut result = verify_members(&lockfile, &root);
+        let unreadable = vec![MemberSkip {
+            path: "locked".to_string(),
+            reason: "IO_ERROR".to_string(),
+            detail: "cannot read directory: Permission denied (os error 13)".to_string(),
+        }];
+        result.add_extra(Vec::new(), unreadable);
+        assert_eq!(result.extra, Some(0));
+        assert_eq!(result.skipped, 1);
+        assert_eq!(result.skips[0].path, "locked");
+        assert_eq!(members_outcome(&result, false), ("VERIFY_PARTIAL", 1));
+        assert_eq!(members_outcome(&result, true), ("VERIFY_FAILED", 1));
+    }
+
+    fn file_mtime(path: &Path) -> String {
+        let modified = std::fs::metadata(path).unwrap().modified().unwrap();
+        DateTime::<Utc>::from(modified).to_rfc3339_opts(chrono::SecondsFormat::AutoSi, true)
+    }
+
+    #[test]
+    fn mtime_matches_at_recorded_precision() {
+        let modified = DateTime::parse_from_rfc3339("2026-01-15T10:00:00.250Z")
+            .unwrap()
+            .with_timezone(&Utc)
+            .into();
+        assert!(mtime_matches("2026-01-15T10:00:00.250Z", modified));
+        assert!(mtime_matches("2026-01-15T11:00:00+01:00", modified));
+        assert!(!mtime_matches("2026-01-15T10:00:00.251Z", modified));
+        assert!(!mtime_matches("2026-01-15T10:00:01Z", modified));
+        assert!(!mtime_matches("yesterday", modified));
+    }
+
+    #[test]
+    fn fast_skips_rehash_only_for_unchanged_mtime() {
+        // Recorded hashes are wrong on purpose: a rehash would fail them.
+        let (_dir, root) = make_test_root(&[("same.csv", b"same"), ("touched.csv", b"tchd")]);
+        let lockfile = serde_json::json!({
+            "members": [
+                {
+                    "path": "same.csv",
+                    "bytes_hash": hash_sha256(b"old!"),
+                    "size": 4,
+                    "mtime": file_mtime(&root.join("same.csv")),
+                },
+                {
+                    "path": "touched.csv",
+                    "bytes_hash": hash_sha256(b"old!"),
+                    "size": 4,
+                    "mtime": "2000-01-01T00:00:00Z",
+                },
+            ]
+        });
+
+        let fast = verify_members_with(
+            &lockfile,
+            &root,
+            &VerifyOptions {
+                fast: true,
+                ..VerifyOptions::default()
+            },
+        );
+        assert_eq!(fast.verified, 1);
+        assert_eq!(
+            fast.verified_by_metadata,
+            Some(vec!["same.csv".to_string()])
+        );
+        assert_eq!(fast.failed, 1);
+        assert_eq!(fast.failures[0].path, "touched.csv");
+        assert_eq!(fast.failures[0].reason, "HASH_MISMATCH");
+
+        let full = verify_members(&lockfile, &root);
+        assert_eq!(full.failed, 2);
+        assert_eq!(full.verified_by_metadata, None);
+    }
+
+    #[test]
+    fn every_recorded_digest_is_checked() {
+        let content = b"two digests";
+        let (_dir, root) = make_test_root(&[("a.csv", content)]);
+        let lockfile = |blake3: String| {
+            serde_json::json!({
+                "members": [{
+                    "path": "a.csv",
+                    "bytes_hash": hash_sha256(content),
+                    "size": content.len(),
+                    "hashes": { "sha256": hash_sha256(content), "blake3": blake3 },
+                }]
+            })
+        };
+
+        let result = verify_members(&lockfile(hash_blake3(content)), &root);
+        assert_eq!(result.verified, 1);
+
+        let result = verify_members(&lockfile(hash_blake3(b"other")), &root);
+        assert_eq!(result.failed, 1);
+        assert_eq!(result.failures[0].reason, "HASH_MISMATCH");
+        assert_eq!(result.failures[0].expected, Some(hash_blake3(b"other")));
+        assert_eq!(result.failures[0].actual, Some(hash_blake3(content)));
+    }
+
+    #[test]
+    fn stream_hashes_matches_single_algorithm_hashing() {
+        let content = b"one pass";
+        let (_dir, root) = make_test_root(&[("a.csv", content)]);
+        let path = root.join("a.csv");
+
+        assert_eq!(
+            stream_hashes(&path, &["blake3", "sha256"]).unwrap(),
+            [hash_blake3(content), hash_sha256(content)]
+        );
+        assert!(stream_hashes(&path, &["md5"]).is_err());
+    }
+
+    #[test]
+    fn xxh3_pre_check_fails_a_changed_file_first() {
+        let (_dir, root) = make_test_root(&[("a.csv", b"changed")]);
+        let xxh3 = stream_hash_with(&root.join("a.csv"), "xxh3").unwrap();
+        let stale_xxh3 = "xxh3:0000000000000000";
+        let lockfile = |xxh3: &str| {
+            serde_json::json!({
+                "members": [{
+                    "path": "a.csv",
+                    "bytes_hash": hash_sha256(b"origin"),
+                    "size": 7,
+                    "hashes": { "sha256": hash_sha256(b"origin"), "xxh3": xxh3 },
+                }]
+            })
+        };
+
+        let result = verify_members(&lockfile(stale_xxh3), &root);
+        assert_eq!(result.failures[0].expected.as_deref(), Some(stale_xxh3));
+        assert_eq!(result.failures[0].actual, Some(xxh3.clone()));
+
+        // A matching pre-check never vouches for the file on its own.
+        let result = verify_members(&lockfile(&xxh3), &root);
+        assert_eq!(result.failed, 1);
+        assert_eq!(result.failures[0].expected, Some(hash_sha256(b"origin")));
+    }
 }
@@ -1,3 +1,4 @@
+pub mod archive;
 pub mod members;
 pub mod output;
 
@@ -7,9 +8,11 @@ use std::path::Path;
 use serde::Serialize;
 use serde_json::Value;
 
+use crate::cache::HashCache;
 use crate::cli::VerifyArgs;
-use crate::lockfile::self_hash;
-use crate::refusal::sort_value;
+use crate::lockfile::{merkle, self_hash};
+use crate::refusal::{LOCK_V1_VERSION, LOCK_VERSION, sort_value};
+use crate::signing::{self, SignatureCheck};
 
 /// Verify output schema version.
 pub const VERIFY_VERSION: &str = "lock-verify.v0";
@@ -18,7 +21,7 @@ pub const VERIFY_VERSION: &str = "lock-verify.v0";
 // Verify refusal codes
 // ---------------------------------------------------------------------------
 
-/// Refusal codes specific to the verify subcommand.
+/// Refusal codes for subcommands that read an existing lockfile.
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
 pub enum VerifyRefusalCode {
     /// Cannot read the lockfile from disk.
@@ -29,8 +32,25 @@ pub enum VerifyRefusalCode {
     UnsupportedVersion,
     /// `--root` directory does not exist.
     RootNotFound,
-    /// Unrecognized hash algorithm prefix in a member's `bytes_hash`.
+    /// Hash algorithm prefix in a member's `bytes_hash` or `hashes` is not
+    /// in the digest registry.
     UnknownAlgorithm,
+    /// Stored `lock_hash` does not match the recomputed self-hash.
+    Tampered,
+    /// Key file is malformed or inconsistent.
+    BadKey,
+    /// Signature file is malformed or uses an unsupported version or algorithm.
+    BadSignature,
+    /// Requested path is not among the lockfile's members.
+    NotMember,
+    /// Inclusion proof is malformed or uses an unsupported version.
+    BadProof,
+    /// Member files under `--root` no longer match the lockfile.
+    Drift,
+    /// Members lack a digest under the algorithm an export format needs.
+    NoDigest,
+    /// A bag's `Payload-Oxum` disagrees with its payload files.
+    OxumMismatch,
 }
 
 impl VerifyRefusalCode {
@@ -42,6 +62,14 @@ impl VerifyRefusalCode {
             Self::UnsupportedVersion => "E_UNSUPPORTED_VERSION",
             Self::RootNotFound => "E_ROOT_NOT_FOUND",
             Self::UnknownAlgorithm => "E_UNKNOWN_ALGORITHM",
+            Self::Tampered => "E_TAMPERED",
+            Self::BadKey => "E_BAD_KEY",
+            Self::BadSignature => "E_BAD_SIGNATURE",
+            Self::NotMember => "E_NOT_MEMBER",
+            Self::BadProof => "E_BAD_PROOF",
+            Self::Drift => "E_DRIFT",
+            Self::NoDigest => "E_NO_DIGEST",
+            Self::OxumMismatch => "E_OXUM_MISMATCH",
         }
     }
 }
@@ -56,116 +84,249 @@ impl Serialize for VerifyRefusalCode {
 // Refusal envelope constructors
 // ---------------------------------------------------------------------------
 
-/// Serialize a verify refusal envelope to JSON with the correct code wire format.
+/// A lockfile refusal before it is wrapped in a versioned envelope.
 ///
-/// We can't reuse `RefusalEnvelope::to_json()` directly because the code field
-/// needs to use `VerifyRefusalCode`, not `RefusalCode`. Instead, we build the
-/// JSON value manually with the correct code string.
-fn verify_refusal_json(code: VerifyRefusalCode, message: String, detail: Value) -> String {
-    let value = serde_json::json!({
-        "version": VERIFY_VERSION,
-        "outcome": "REFUSAL",
-        "refusal": {
-            "code": code.as_str(),
-            "message": message,
-            "detail": detail,
-            "next_command": null,
+/// `verify` renders these under `lock-verify.v0`; other subcommands that
+/// consume lockfiles (`diff`, `sign`, ...) render the same code, message, and detail
+/// under their own output version.
+#[derive(Debug, Clone, PartialEq)]
+pub struct LockfileRefusal {
+    pub code: VerifyRefusalCode,
+    pub message: String,
+    pub detail: Value,
+}
+
+impl LockfileRefusal {
+    pub(crate) fn new(code: VerifyRefusalCode, message: String, detail: Value) -> Self {
+        Self {
+            code,
+            message,
+            detail,
         }
-    });
-    let sorted = sort_value(value);
-    serde_json::to_string(&sorted).expect("sorted Value is always serializable")
+    }
+
+    /// Serialize the refusal envelope under the given output `version`.
+    ///
+    /// We can't reuse `RefusalEnvelope::to_json()` directly because the code
+    /// field needs to use `VerifyRefusalCode`, not `RefusalCode`. Instead, we
+    /// build the JSON value manually with the correct code string.
+    pub fn to_json(&self, version: &str) -> String {
+        let value = serde_json::json!({
+            "version": version,
+            "outcome": "REFUSAL",
+            "refusal": {
+                "code": self.code.as_str(),
+                "message": self.message,
+                "detail": self.detail,
+                "next_command": null,
+            }
+        });
+        let sorted = sort_value(value);
+        serde_json::to_string(&sorted).expect("sorted Value is always serializable")
+    }
+
+    fn io(path: &Path, error: &str) -> Self {
+        Self::new(
+            VerifyRefusalCode::Io,
+            format!("cannot read lockfile — {error}"),
+            serde_json::json!({
+                "path": path.display().to_string(),
+                "error": error,
+            }),
+        )
+    }
+
+    fn bad_lockfile_parse(error: &str) -> Self {
+        Self::new(
+            VerifyRefusalCode::BadLockfile,
+            format!("malformed lockfile JSON — {error}"),
+            serde_json::json!({
+                "error": error,
+            }),
+        )
+    }
+
+    fn bad_lockfile_missing_fields(missing: &[&str]) -> Self {
+        Self::new(
+            VerifyRefusalCode::BadLockfile,
+            "lockfile missing required fields".to_string(),
+            serde_json::json!({
+                "missing_fields": missing,
+            }),
+        )
+    }
+
+    fn bad_lockfile_absolute_path(member_index: usize, member_path: &str) -> Self {
+        Self::new(
+            VerifyRefusalCode::BadLockfile,
+            format!("member path is absolute: {member_path}"),
+            serde_json::json!({
+                "member_index": member_index,
+                "member_path": member_path,
+            }),
+        )
+    }
+
+    fn bad_lockfile_traversal(member_index: usize, member_path: &str) -> Self {
+        Self::new(
+            VerifyRefusalCode::BadLockfile,
+            format!("member path contains traversal: {member_path}"),
+            serde_json::json!({
+                "member_index": member_index,
+                "member_path": member_path,
+            }),
+        )
+    }
+
+    fn bad_lockfile_conflicting_hash(
+        member_index: usize,
+        member_path: &str,
+        algorithm: &str,
+    ) -> Self {
+        Self::new(
+            VerifyRefusalCode::BadLockfile,
+            format!("member hashes.{algorithm} disagrees with its bytes_hash: {member_path}"),
+            serde_json::json!({
+                "member_index": member_index,
+                "member_path": member_path,
+                "algorithm": algorithm,
+            }),
+        )
+    }
+
+    fn unsupported_version(version: &str) -> Self {
+        Self::new(
+            VerifyRefusalCode::UnsupportedVersion,
+            format!("unsupported lockfile version: {version}"),
+            serde_json::json!({
+                "version": version,
+            }),
+        )
+    }
+
+    fn archive_unreadable(archive: &Path, error: &str) -> Self {
+        Self::new(
+            VerifyRefusalCode::Io,
+            format!("cannot read archive — {error}"),
+            serde_json::json!({
+                "archive": archive.display().to_string(),
+                "error": error,
+            }),
+        )
+    }
+
+    pub(crate) fn root_not_found(root: &Path) -> Self {
+        Self::new(
+            VerifyRefusalCode::RootNotFound,
+            format!("root directory not found: {}", root.display()),
+            serde_json::json!({
+                "root": root.display().to_string(),
+            }),
+        )
+    }
+
+    fn unknown_algorithm(member_path: &str, algorithm: &str) -> Self {
+        Self::new(
+            VerifyRefusalCode::UnknownAlgorithm,
+            format!("unrecognized hash algorithm: {algorithm}"),
+            serde_json::json!({
+                "member_path": member_path,
+                "algorithm": algorithm,
+            }),
+        )
+    }
+
+    fn non_cryptographic_bytes_hash(
+        member_index: usize,
+        member_path: &str,
+        algorithm: &str,
+    ) -> Self {
+        Self::new(
+            VerifyRefusalCode::BadLockfile,
+            format!("{algorithm} is not cryptographic and cannot be a member's bytes_hash"),
+            serde_json::json!({
+                "member_index": member_index,
+                "member_path": member_path,
+                "algorithm": algorithm,
+            }),
+        )
+    }
+
+    fn members_root_mismatch(stored: &str, computed: &str) -> Self {
+        Self::new(
+            VerifyRefusalCode::BadLockfile,
+            "members_root does not match members".to_string(),
+            serde_json::json!({
+                "stored": stored,
+                "computed": computed,
+            }),
+        )
+    }
+
+    fn tampered(path: &Path, stored: &str, computed: &str) -> Self {
+        Self::new(
+            VerifyRefusalCode::Tampered,
+            format!(
+                "lockfile self-hash does not match its contents: {}",
+                path.display()
+            ),
+            serde_json::json!({
+                "stored": stored,
+                "computed": computed,
+            }),
+        )
+    }
+
+    /// Record which lockfile the refusal is about, for commands that read more than one.
+    fn with_lockfile(mut self, path: &Path) -> Self {
+        if let Value::Object(detail) = &mut self.detail {
+            detail.insert(
+                "lockfile".to_string(),
+                Value::String(path.display().to_string()),
+            );
+        }
+        self
+    }
 }
 
 /// E_IO: cannot read lockfile.
 pub fn refusal_io(path: &Path, error: &str) -> String {
-    verify_refusal_json(
-        VerifyRefusalCode::Io,
-        format!("cannot read lockfile — {error}"),
-        serde_json::json!({
-            "path": path.display().to_string(),
-            "error": error,
-        }),
-    )
+    LockfileRefusal::io(path, error).to_json(VERIFY_VERSION)
 }
 
 /// E_BAD_LOCKFILE: malformed JSON.
 pub fn refusal_bad_lockfile_parse(error: &str) -> String {
-    verify_refusal_json(
-        VerifyRefusalCode::BadLockfile,
-        format!("malformed lockfile JSON — {error}"),
-        serde_json::json!({
-            "error": error,
-        }),
-    )
+    LockfileRefusal::bad_lockfile_parse(error).to_json(VERIFY_VERSION)
 }
 
 /// E_BAD_LOCKFILE: missing required fields.
 pub fn refusal_bad_lockfile_missing_fields(missing: &[&str]) -> String {
-    verify_refusal_json(
-        VerifyRefusalCode::BadLockfile,
-        "lockfile missing required fields".to_string(),
-        serde_json::json!({
-            "missing_fields": missing,
-        }),
-    )
+    LockfileRefusal::bad_lockfile_missing_fields(missing).to_json(VERIFY_VERSION)
 }
 
 /// E_BAD_LOCKFILE: absolute member path.
 pub fn refusal_bad_lockfile_absolute_path(member_index: usize, member_path: &str) -> String {
-    verify_refusal_json(
-        VerifyRefusalCode::BadLockfile,
-        format!("member path is absolute: {member_path}"),
-        serde_json::json!({
-            "member_index": member_index,
-            "member_path": member_path,
-        }),
-    )
+    LockfileRefusal::bad_lockfile_absolute_path(member_index, member_path).to_json(VERIFY_VERSION)
 }
 
 /// E_BAD_LOCKFILE: path traversal.
 pub fn refusal_bad_lockfile_traversal(member_index: usize, member_path: &str) -> String {
-    verify_refusal_json(
-        VerifyRefusalCode::BadLockfile,
-        format!("member path contains traversal: {member_path}"),
-        serde_json::json!({
-            "member_index": member_index,
-            "member_path": member_path,
-        }),
-    )
+    LockfileRefusal::bad_lockfile_traversal(member_index, member_path).to_json(VERIFY_VERSION)
 }
 
 /// E_UNSUPPORTED_VERSION: lockfile version not recognized.
 pub fn refusal_unsupported_version(version: &str) -> String {
-    verify_refusal_json(
-        VerifyRefusalCode::UnsupportedVersion,
-        format!("unsupported lockfile version: {version}"),
-        serde_json::json!({
-            "version": version,
-        }),
-    )
+    LockfileRefusal::unsupported_version(version).to_json(VERIFY_VERSION)
 }
 
 /// E_ROOT_NOT_FOUND: --root directory does not exist.
 pub fn refusal_root_not_found(root: &Path) -> String {
-    verify_refusal_json(
-        VerifyRefusalCode::RootNotFound,
-        format!("root directory not found: {}", root.display()),
-        serde_json::json!({
-            "root": root.display().to_string(),
-        }),
-    )
+    LockfileRefusal::root_not_found(root).to_json(VERIFY_VERSION)
 }
 
 /// E_UNKNOWN_ALGORITHM: unrecognized hash algorithm prefix.
 pub fn refusal_unknown_algorithm(member_path: &str, algorithm: &str) -> String {
-    verify_refusal_json(
-        VerifyRefusalCode::UnknownAlgorithm,
-        format!("unrecognized hash algorithm: {algorithm}"),
-        serde_json::json!({
-            "member_path": member_path,
-            "algorithm": algorithm,
-        }),
-    )
+    LockfileRefusal::unknown_algorithm(member_path, algorithm).to_json(VERIFY_VERSION)
 }
 
 // ---------------------------------------------------------------------------
@@ -173,10 +334,7 @@ pub fn refusal_unknown_algorithm(member_path: &str, algorithm: &str) -> String {
 // ---------------------------------------------------------------------------
 
 /// Recognized lockfile versions.
-const SUPPORTED_VERSIONS: &[&str] = &["lock.v0"];
-
-/// Recognized hash algorithm prefixes.
-const SUPPORTED_ALGORITHMS: &[&str] = &["sha256", "blake3"];
+const SUPPORTED_VERSIONS: &[&str] = &[LOCK_VERSION, LOCK_V1_VERSION];
 
 /// Validation result: either the parsed JSON value or a refusal JSON string.
 pub enum ValidationResult {
@@ -186,32 +344,30 @@ pub enum ValidationResult {
     Refusal(String),
 }
 
-fn bad_lockfile_type_error(field: &str, expected: &str) -> ValidationResult {
-    ValidationResult::Refusal(refusal_bad_lockfile_parse(&format!(
-        "field '{field}' must be a {expected}"
-    )))
+fn bad_lockfile_type_error(field: &str, expected: &str) -> LockfileRefusal {
+    LockfileRefusal::bad_lockfile_parse(&format!("field '{field}' must be a {expected}"))
 }
 
 fn bad_lockfile_member_type_error(
     member_index: usize,
     field: &str,
     expected: &str,
-) -> ValidationResult {
-    ValidationResult::Refusal(refusal_bad_lockfile_parse(&format!(
+) -> LockfileRefusal {
+    LockfileRefusal::bad_lockfile_parse(&format!(
         "members[{member_index}].{field} must be a {expected}"
-    )))
+    ))
 }
 
-fn missing_member_fields_refusal(member_index: usize, fields: &[&str]) -> ValidationResult {
+fn missing_member_fields_refusal(member_index: usize, fields: &[&str]) -> LockfileRefusal {
     let missing: Vec<String> = fields
         .iter()
         .map(|field| format!("members[{member_index}].{field}"))
         .collect();
     let missing_refs: Vec<&str> = missing.iter().map(String::as_str).collect();
-    ValidationResult::Refusal(refusal_bad_lockfile_missing_fields(&missing_refs))
+    LockfileRefusal::bad_lockfile_missing_fields(&missing_refs)
 }
 
-fn is_absolute_member_path(path: &str) -> bool {
+pub(crate) fn is_absolute_member_path(path: &str) -> bool {
     if path.starts_with('/') || path.starts_with('\\') {
         return true;
     }
@@ -223,9 +379,14 @@ fn is_absolute_member_path(path: &str) -> bool {
         && matches!(bytes[2], b'/' | b'\\')
 }
 
-fn validate_member(member: &Value, member_index: usize) -> ValidationResult {
+/// Whether a member path has a `..` segment, with either separator.
+pub(crate) fn has_traversal(path: &str) -> bool {
+    path.split('/').any(|seg| seg == "..") || path.split('\\').any(|seg| seg == "..")
+}
+
+fn validate_member(member: &Value, member_index: usize) -> Result<(), LockfileRefusal> {
     let Some(member) = member.as_object() else {
-        return ValidationResult::Refusal(refusal_bad_lockfile_parse(&format!(
+        return Err(LockfileRefusal::bad_lockfile_parse(&format!(
             "members[{member_index}] must be an object"
         )));
     };
@@ -238,45 +399,91 @@ fn validate_member(member: &Value, member_index: usize) -> ValidationResult {
             missing.push("path");
             None
         }
-        Some(_) => return bad_lockfile_member_type_error(member_index, "path", "string"),
+        Some(_) => {
+            return Err(bad_lockfile_member_type_error(
+                member_index,
+                "path",
+                "string",
+            ));
+        }
     };
 
     match member.get("bytes_hash") {
         Some(Value::String(hash)) if !hash.is_empty() => {}
         Some(Value::String(_)) | Some(Value::Null) | None => missing.push("bytes_hash"),
-        Some(_) => return bad_lockfile_member_type_error(member_index, "bytes_hash", "string"),
+        Some(_) => {
+            return Err(bad_lockfile_member_type_error(
+                member_index,
+                "bytes_hash",
+                "string",
+            ));
+        }
     }
 
     match member.get("size") {
         Some(Value::Number(size)) if size.as_u64().is_some() => {}
         Some(Value::Null) | None => missing.push("size"),
-        Some(_) => return bad_lockfile_member_type_error(member_index, "size", "u64"),
+        Some(_) => return Err(bad_lockfile_member_type_error(member_index, "size", "u64")),
     }
 
     if !missing.is_empty() {
-        return missing_member_fields_refusal(member_index, &missing);
+        return Err(missing_member_fields_refusal(member_index, &missing));
     }
 
     let path = path.expect("validated above");
 
     if is_absolute_member_path(path) {
-        return ValidationResult::Refusal(refusal_bad_lockfile_absolute_path(member_index, path));
+        return Err(LockfileRefusal::bad_lockfile_absolute_path(
+            member_index,
+            path,
+        ));
     }
 
-    if path.split('/').any(|seg| seg == "..") || path.split('\\').any(|seg| seg == "..") {
-        return ValidationResult::Refusal(refusal_bad_lockfile_traversal(member_index, path));
+    if has_traversal(path) {
+        return Err(LockfileRefusal::bad_lockfile_traversal(member_index, path));
     }
 
     let hash = member
         .get("bytes_hash")
         .and_then(Value::as_str)
         .expect("validated above");
-    let prefix = hash.split(':').next().unwrap_or_default();
-    if !SUPPORTED_ALGORITHMS.contains(&prefix) {
-        return ValidationResult::Refusal(refusal_unknown_algorithm(path, prefix));
+    let prefix = crate::digest::prefix(hash);
+    match crate::digest::lookup(prefix) {
+        None => return Err(LockfileRefusal::unknown_algorithm(path, prefix)),
+        Some(algorithm) if !algorithm.cryptographic => {
+            return Err(LockfileRefusal::non_cryptographic_bytes_hash(
+                member_index,
+                path,
+                prefix,
+            ));
+        }
+        Some(_) => {}
     }
 
-    ValidationResult::Ok(Value::Null)
+    if let Some(hashes) = member.get("hashes") {
+        let hashes_type_error =
+            || bad_lockfile_member_type_error(member_index, "hashes", "map of algorithm to digest");
+        for (algorithm, digest) in hashes.as_object().ok_or_else(hashes_type_error)? {
+            let digest = digest.as_str().ok_or_else(hashes_type_error)?;
+            if crate::digest::lookup(algorithm).is_none() {
+                return Err(LockfileRefusal::unknown_algorithm(path, algorithm));
+            }
+            if digest.split(':').next() != Some(algorithm.as_str()) {
+                return Err(hashes_type_error());
+            }
+            // Verify and rehash take the `bytes_hash` algorithm from
+            // `bytes_hash` alone, so a different copy here would go unchecked.
+            if algorithm == prefix && digest != hash {
+                return Err(LockfileRefusal::bad_lockfile_conflicting_hash(
+                    member_index,
+                    path,
+                    algorithm,
+                ));
+            }
+        }
+    }
+
+    Ok(())
 }
 
 /// Validate a lockfile JSON string.
@@ -287,12 +494,18 @@ fn validate_member(member: &Value, member_index: usize) -> ValidationResult {
 /// 3. Version is supported
 /// 4. No absolute member paths
 /// 5. No `..` traversal in member paths
-/// 6. Recognized algorithm prefixes
+/// 6. Recognized algorithm prefixes, in `bytes_hash` and any `hashes` map
 pub fn validate_lockfile_json(json: &str) -> ValidationResult {
-    let value: Value = match serde_json::from_str(json) {
-        Ok(v) => v,
-        Err(e) => return ValidationResult::Refusal(refusal_bad_lockfile_parse(&e.to_string())),
-    };
+    match check_lockfile_json(json) {
+        Ok(value) => ValidationResult::Ok(value),
+        Err(refusal) => ValidationResult::Refusal(refusal.to_json(VERIFY_VERSION)),
+    }
+}
+
+/// Version-neutral form of [`validate_lockfile_json`].
+pub fn check_lockfile_json(json: &str) -> Result<Value, LockfileRefusal> {
+    let value: Value = serde_json::from_str(json)
+        .map_err(|e| LockfileRefusal::bad_lockfile_parse(&e.to_string()))?;
 
     // Check required fields.
     let mut missing = Vec::new();
@@ -306,38 +519,86 @@ pub fn validate_lockfile_json(json: &str) -> ValidationResult {
         missing.push("members");
     }
     if !missing.is_empty() {
-        return ValidationResult::Refusal(refusal_bad_lockfile_missing_fields(&missing));
+        return Err(LockfileRefusal::bad_lockfile_missing_fields(&missing));
     }
 
     // Check version.
     let version = match value.get("version") {
         Some(Value::String(version)) => version.as_str(),
-        Some(_) => return bad_lockfile_type_error("version", "string"),
+        Some(_) => return Err(bad_lockfile_type_error("version", "string")),
         None => unreachable!("checked above"),
     };
     if !SUPPORTED_VERSIONS.contains(&version) {
-        return ValidationResult::Refusal(refusal_unsupported_version(version));
+        return Err(LockfileRefusal::unsupported_version(version));
     }
 
     match value.get("lock_hash") {
         Some(Value::String(_)) => {}
-        Some(_) => return bad_lockfile_type_error("lock_hash", "string"),
+        Some(_) => return Err(bad_lockfile_type_error("lock_hash", "string")),
         None => unreachable!("checked above"),
     }
 
+    if version == LOCK_V1_VERSION {
+        match value.get("members_root") {
+            Some(Value::String(_)) => {}
+            Some(Value::Null) | None => {
+                return Err(LockfileRefusal::bad_lockfile_missing_fields(&[
+                    "members_root",
+                ]));
+            }
+            Some(_) => return Err(bad_lockfile_type_error("members_root", "string")),
+        }
+    }
+
     let members = match value.get("members") {
         Some(Value::Array(members)) => members,
-        Some(_) => return bad_lockfile_type_error("members", "array"),
+        Some(_) => return Err(bad_lockfile_type_error("members", "array")),
         None => unreachable!("checked above"),
     };
 
     for (i, member) in members.iter().enumerate() {
-        if let ValidationResult::Refusal(refusal) = validate_member(member, i) {
-            return ValidationResult::Refusal(refusal);
-        }
+        validate_member(member, i)?;
     }
 
-    ValidationResult::Ok(value)
+    Ok(value)
+}
+
+/// Check a stored `members_root` against the lockfile's `members`.
+///
+/// Only meaningful once the self-hash is valid: a tampered member already
+/// fails `lock_hash`, so a mismatch here means the lockfile was sealed with
+/// a wrong root. Lockfiles without `members_root` (`lock.v0`) pass.
+pub fn check_members_root(value: &Value) -> Result<(), LockfileRefusal> {
+    let Some(stored) = value.get("members_root").and_then(Value::as_str) else {
+        return Ok(());
+    };
+    let members = value["members"].as_array().map_or(&[][..], Vec::as_slice);
+    let computed = merkle::members_root(members);
+    if stored == computed {
+        Ok(())
+    } else {
+        Err(LockfileRefusal::members_root_mismatch(stored, &computed))
+    }
+}
+
+/// Read a lockfile, validate it, and require a valid self-hash.
+///
+/// This is the entry point for subcommands that build on an existing lockfile
+/// and must not trust a tampered one. Refusal details carry the `lockfile`
+/// path so commands reading several lockfiles can say which one failed.
+pub fn load_sealed_lockfile(path: &Path) -> Result<Value, LockfileRefusal> {
+    let json = fs::read_to_string(path)
+        .map_err(|e| LockfileRefusal::io(path, &e.to_string()).with_lockfile(path))?;
+    let value = check_lockfile_json(&json).map_err(|r| r.with_lockfile(path))?;
+    let detail = self_hash::verify_lock_hash_detail_value(&value)
+        .map_err(|e| LockfileRefusal::bad_lockfile_parse(&e.to_string()).with_lockfile(path))?;
+    if !detail.valid {
+        return Err(
+            LockfileRefusal::tampered(path, &detail.stored, &detail.computed).with_lockfile(path),
+        );
+    }
+    check_members_root(&value).map_err(|r| r.with_lockfile(path))?;
+    Ok(value)
 }
 
 // ---------------------------------------------------------------------------
@@ -352,6 +613,7 @@ struct VerifyResult {
     lockfile: String,
     lock_hash: LockHashResult,
     members: Option<Value>,
+    signature: Option<SignatureCheck>,
     tool_versions: std::collections::BTreeMap<String, String>,
 }
 
@@ -400,6 +662,35 @@ pub fn run_verify(args: &VerifyArgs) -> u8 {
         return 2;
     }
 
+    // Step 3b: Load --signature and --trusted-keys (if provided).
+    let signature_inputs = match (&args.signature, &args.trusted_keys) {
+        (Some(signature), Some(trusted_keys)) => {
+            match signing::load_signature(signature)
+                .and_then(|envelope| Ok((envelope, signing::load_trusted_keys(trusted_keys)?)))
+            {
+                Ok(inputs) => Some(inputs),
+                Err(refusal) => {
+                    let payload = refusal.to_json(VERIFY_VERSION);
+                    print!("{payload}");
+                    emit_witness(args, 2, "REFUSAL", payload.as_bytes());
+                    return 2;
+                }
+            }
+        }
+        _ => None,
+    };
+
+    // Step 3c: Load --cache (a missing file starts an empty cache).
+    let cache = match args.cache.as_deref().map(HashCache::load).transpose() {
+        Ok(cache) => cache,
+        Err(refusal) => {
+            let payload = refusal.to_json(VERIFY_VERSION);
+            print!("{payload}");
+            emit_witness(args, 2, "REFUSAL", payload.as_bytes());
+            return 2;
+        }
+    };
+
     // Step 4: Level 1 — self-hash verification.
     let detail = match self_hash::verify_lock_hash_detail_value(&lockfile_value) {
         Ok(d) => d,
@@ -418,13 +709,70 @@ pub fn run_verify(args: &VerifyArgs) -> u8 {
         valid: detail.valid,
     };
 
-    // Step 5: Level 2 — member verification (if --root and self-hash valid).
+    // Step 4b: lock.v1 — members_root must match the sealed members.
+    if lock_hash_result.valid
+        && let Err(refusal) = check_members_root(&lockfile_value)
+    {
+        let payload = refusal.to_json(VERIFY_VERSION);
+        print!("{payload}");
+        emit_witness(args, 2, "REFUSAL", payload.as_bytes());
+        return 2;
+    }
+
+    // Step 5: Level 2 — member verification (if --root or --archive and self-hash valid).
     let (members_value, outcome, exit_code) = if !lock_hash_result.valid {
         // Self-hash failed — skip member verification.
         (None, "VERIFY_FAILED", 1u8)
     } else if let Some(root) = &args.root {
         // Level 2: verify members against filesystem.
-        let members_result = members::verify_members(&lockfile_value, root);
+        let options = members::VerifyOptions {
+            jobs: args.jobs,
+            fast: args.fast,
+            cache: cache.as_ref(),
+        };
+        let mut members_result = members::verify_members_with(&lockfile_value, root, &options);
+        if let (Some(cache), Some(path)) = (&cache, &args.cache)
+            && let Err(e) = cache.save_if_dirty(path)
+        {
+            // The verification itself stands; only the next run is slower.
+            eprintln!(
+                "lock: warning: cannot write hash cache '{}': {e}",
+                path.display()
+            );
+        }
+        if args.detect_extra {
+            // verify's own inputs may sit under the root without being listed.
+            let inputs: Vec<&Path> = std::iter::once(args.lockfile.as_path())
+                .chain(args.signature.as_deref())
+                .chain(args.cache.as_deref())
+                .collect();
+            match members::find_extra(&lockfile_value, root, &args.exclude, &inputs) {
+                Ok((extras, unreadable)) => members_result.add_extra(extras, unreadable),
+                Err(e) => {
+                    let payload = refusal_io(root, &e.to_string());
+                    print!("{payload}");
+                    emit_witness(args, 2, "REFUSAL", payload.as_bytes());
+                    return 2;
+                }
+            }
+        }
+        let (outcome, exit_code) = members::members_outcome(&members_result, args.strict);
+        let members_json =
+            serde_json::to_value(&members_result).expect("MembersResult is serializable");
+        (Some(members_json), outcome, exit_code)
+    } else if let Some(archive) = &args.archive {
+        // Level 2: verify members against archive entries, in-stream.
+        let excludes = args.detect_extra.then_some(args.exclude.as_slice());
+        let members_result = match archive::verify_archive(&lockfile_value, archive, excludes) {
+            Ok(result) => result,
+            Err(e) => {
+                let payload = LockfileRefusal::archive_unreadable(archive, &e.to_string())
+                    .to_json(VERIFY_VERSION);
+                print!("{payload}");
+                emit_witness(args, 2, "REFUSAL", payload.as_bytes());
+                return 2;
+            }
+        };
         let (outcome, exit_code) = members::members_outcome(&members_result, args.strict);
         let members_json =
             serde_json::to_value(&members_result).expect("MembersResult is serializable");
@@ -433,6 +781,18 @@ pub fn run_verify(args: &VerifyArgs) -> u8 {
         (None, "VERIFY_OK", 0)
     };
 
+    // Step 5b: Signature check (only meaningful over a valid self-hash).
+    let signature_check =
+        signature_inputs
+            .filter(|_| lock_hash_result.valid)
+            .map(|(envelope, trusted)| {
+                signing::check_signature(&envelope, &lock_hash_result.stored, &trusted)
+            });
+    let (outcome, exit_code) = match &signature_check {
+        Some(check) if !check.accepted() => ("VERIFY_FAILED", 1),
+        _ => (outcome, exit_code),
+    };
+
     // Step 6: Build tool_versions.
     let mut tool_versions = std::collections::BTreeMap::new();
     tool_versions.insert("lock".to_string(), env!("CARGO_PKG_VERSION").to_string());
@@ -444,6 +804,7 @@ pub fn run_verify(args: &VerifyArgs) -> u8 {
         lockfile: args.lockfile.display().to_string(),
         lock_hash: lock_hash_result,
         members: members_value,
+        signature: signature_check,
         tool_versions,
     };
 
@@ -474,7 +835,15 @@ fn emit_witness(args: &VerifyArgs, exit_code: u8, outcome: &str, output_bytes: &
     let params = serde_json::json!({
         "subcommand": "verify",
         "root": args.root.as_ref().map(|p| p.display().to_string()),
+        "archive": args.archive.as_ref().map(|p| p.display().to_string()),
         "strict": args.strict,
+        "jobs": args.jobs,
+        "detect_extra": args.detect_extra,
+        "fast": args.fast,
+        "cache": args.cache.as_ref().map(|p| p.display().to_string()),
+        "exclude": args.exclude,
+        "signature": args.signature.as_ref().map(|p| p.display().to_string()),
+        "trusted_keys": args.trusted_keys.as_ref().map(|p| p.display().to_string()),
     });
     let inputs = serde_json::json!([
         { "path": args.lockfile.display().to_string(), "hash": null, "bytes": null }
@@ -506,6 +875,9 @@ mod tests {
             VerifyRefusalCode::UnknownAlgorithm.as_str(),
             "E_UNKNOWN_ALGORITHM"
         );
+        assert_eq!(VerifyRefusalCode::Tampered.as_str(), "E_TAMPERED");
+        assert_eq!(VerifyRefusalCode::BadKey.as_str(), "E_BAD_KEY");
+        assert_eq!(VerifyRefusalCode::BadSignature.as_str(), "E_BAD_SIGNATURE");
     }
 
     #[test]
@@ -768,6 +1140,52 @@ mod tests {
         }
     }
 
+    #[test]
+    fn validate_v1_requires_members_root() {
+        let json = serde_json::json!({
+            "version": "lock.v1",
+            "lock_hash": "sha256:abc",
+            "members": []
+        })
+        .to_string();
+        match validate_lockfile_json(&json) {
+            ValidationResult::Refusal(r) => {
+                let parsed: Value = serde_json::from_str(&r).unwrap();
+                let fields = parsed["refusal"]["detail"]["missing_fields"]
+                    .as_array()
+                    .unwrap();
+                assert!(fields.contains(&Value::String("members_root".to_string())));
+            }
+            ValidationResult::Ok(_) => panic!("expected refusal"),
+        }
+    }
+
+    #[test]
+    fn members_root_must_match_members() {
+        let members = serde_json::json!([
+            { "path": "a.csv", "bytes_hash": "sha256:aa", "size": 1 },
+            { "path": "b.csv", "bytes_hash": "sha256:bb", "size": 2 }
+        ]);
+        let root = merkle::members_root(members.as_array().unwrap());
+        let mut value = serde_json::json!({
+            "version": "lock.v1",
+            "members_root": root,
+            "members": members
+        });
+        assert!(check_members_root(&value).is_ok());
+
+        value["members"][1]["size"] = serde_json::json!(3);
+        let refusal = check_members_root(&value).unwrap_err();
+        assert_eq!(refusal.code, VerifyRefusalCode::BadLockfile);
+        assert_eq!(refusal.detail["stored"], root);
+    }
+
+    #[test]
+    fn v0_without_members_root_passes_root_check() {
+        let value = serde_json::json!({ "version": "lock.v0", "members": [] });
+        assert!(check_members_root(&value).is_ok());
+    }
+
     #[test]
     fn validate_multiple_missing_fields() {
         let json = serde_json::json!({}).to_string();
@@ -931,6 +1349,57 @@ mod tests {
         }
     }
 
+    #[test]
+    fn validate_refuses_non_cryptographic_bytes_hash() {
+        let json = serde_json::json!({
+            "version": "lock.v0",
+            "lock_hash": "sha256:abc",
+            "members": [
+                { "path": "data.csv", "bytes_hash": "xxh3:0123456789abcdef", "size": 100 }
+            ]
+        })
+        .to_string();
+        let refusal = check_lockfile_json(&json).unwrap_err();
+        assert_eq!(refusal.code, VerifyRefusalCode::BadLockfile);
+        assert_eq!(refusal.detail["algorithm"], "xxh3");
+    }
+
+    #[test]
+    fn validate_hashes_algorithms_and_prefixes() {
+        let validate = |hashes: Value| {
+            let json = serde_json::json!({
+                "version": "lock.v0",
+                "lock_hash": "sha256:abc",
+                "members": [
+                    { "path": "data.csv", "bytes_hash": "sha256:aaa", "size": 100, "hashes": hashes }
+                ]
+            })
+            .to_string();
+            check_lockfile_json(&json).map(|_| ())
+        };
+
+        assert!(
+            validate(serde_json::json!({ "sha256": "sha256:aaa", "blake3": "blake3:bbb" })).is_ok()
+        );
+        let refusal = validate(serde_json::json!({ "md5": "md5:ccc" })).unwrap_err();
+        assert_eq!(refusal.code, VerifyRefusalCode::UnknownAlgorithm);
+        assert_eq!(refusal.detail["algorithm"], "md5");
+        let refusal = validate(serde_json::json!({ "sha256": "sha256:bbb" })).unwrap_err();
+        assert_eq!(refusal.code, VerifyRefusalCode::BadLockfile);
+        assert_eq!(refusal.detail["algorithm"], "sha256");
+        assert_eq!(refusal.detail["member_index"], 0);
+        for bad in [
+            serde_json::json!({ "blake3": "sha256:aaa" }),
+            serde_json::json!({ "blake3": 1 }),
+            serde_json::json!(["blake3:bbb"]),
+        ] {
+            assert_eq!(
+                validate(bad).unwrap_err().code,
+                VerifyRefusalCode::BadLockfile
+            );
+        }
+    }
+
     #[test]
     fn validate_sha256_algorithm_accepted() {
         let json = serde_json::json!({
@@ -991,6 +1460,8 @@ mod tests {
             dataset_id: Some("test".to_string()),
             as_of: None,
             note: None,
+            parent: None,
+            members_root: None,
             created: "2026-01-01T00:00:00Z".to_string(),
             tool_versions: std::collections::BTreeMap::from([(
                 "lock".to_string(),
@@ -1003,6 +1474,9 @@ mod tests {
                 bytes_hash: "sha256:aaaa".to_string(),
                 size: 100,
                 fingerprint: None,
+                mtime: None,
+                extra: None,
+                hashes: None,
             }],
             skipped_count: 0,
             member_count: 1,
@@ -1018,9 +1492,17 @@ mod tests {
         VerifyArgs {
             lockfile,
             root: None,
+            archive: None,
             json: true,
             no_witness: true,
             strict: false,
+            jobs: std::num::NonZeroUsize::MIN,
+            detect_extra: false,
+            exclude: vec![],
+            fast: false,
+            cache: None,
+            signature: None,
+            trusted_keys: None,
         }
     }
 
@@ -1087,4 +1569,42 @@ mod tests {
         args.root = Some(std::path::PathBuf::from("/nonexistent/dir"));
         assert_eq!(run_verify(&args), 2);
     }
+
+    // -- Sealed lockfile loading --
+
+    #[test]
+    fn load_sealed_lockfile_accepts_valid_lockfile() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = make_valid_lockfile_on_disk(dir.path());
+        let value = load_sealed_lockfile(&path).expect("valid lockfile should load");
+        assert_eq!(value["dataset_id"], "test");
+    }
+
+    #[test]
+    fn load_sealed_lockfile_refuses_tampered_lockfile() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = make_valid_lockfile_on_disk(dir.path());
+        let content = std::fs::read_to_string(&path).unwrap();
+        std::fs::write(&path, content.replace("\"test\"", "\"tampered\"")).unwrap();
+
+        let refusal = load_sealed_lockfile(&path).unwrap_err();
+        assert_eq!(refusal.code, VerifyRefusalCode::Tampered);
+        assert_eq!(
+            refusal.detail["lockfile"],
+            path.display().to_string().as_str()
+        );
+        assert_ne!(refusal.detail["stored"], refusal.detail["computed"]);
+    }
+
+    #[test]
+    fn lockfile_refusal_renders_under_requested_version() {
+        let refusal = load_sealed_lockfile(Path::new("/nonexistent/file.lock.json")).unwrap_err();
+        let parsed: Value = serde_json::from_str(&refusal.to_json("lock-diff.v0")).unwrap();
+        assert_eq!(parsed["version"], "lock-diff.v0");
+        assert_eq!(parsed["refusal"]["code"], "E_IO");
+        assert_eq!(
+            parsed["refusal"]["detail"]["lockfile"],
+            "/nonexistent/file.lock.json"
+        );
+    }
 }
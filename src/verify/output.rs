@@ -22,17 +22,71 @@ fn render_ok(result: &Value, lockfile: &str) -> String {
         .and_then(|h| h.get(..15))
         .unwrap_or("?");
 
+    let mut lines = Vec::new();
     if let Some(members) = result.get("members").filter(|v| !v.is_null()) {
         let checked = members["checked"].as_u64().unwrap_or(0);
         let verified = members["verified"].as_u64().unwrap_or(0);
-        format!(
-            "\u{2713} {lockfile}: self-hash valid ({hash_prefix}...), {verified}/{checked} members verified"
-        )
+        let by_metadata = by_metadata_note(members);
+        let extra = if members["extra"].is_u64() {
+            ", no extra files"
+        } else {
+            ""
+        };
+        lines.push(format!(
+            "\u{2713} {lockfile}: self-hash valid ({hash_prefix}...), {verified}/{checked} members verified{by_metadata}{extra}"
+        ));
+    } else {
+        lines.push(format!(
+            "\u{2713} {lockfile}: self-hash valid ({hash_prefix}...)"
+        ));
+    }
+    lines.extend(signature_line(result));
+
+    lines.join("\n")
+}
+
+/// ` (N by size+mtime only, M from hash cache)` for members whose bytes
+/// were not reread; empty when every member was hashed.
+fn by_metadata_note(members: &Value) -> String {
+    let count = |field: &str| members[field].as_array().map_or(0, Vec::len);
+    let notes: Vec<String> = [
+        (count("verified_by_metadata"), "by size+mtime only"),
+        (count("cache_hits"), "from hash cache"),
+    ]
+    .into_iter()
+    .filter(|(count, _)| *count > 0)
+    .map(|(count, label)| format!("{count} {label}"))
+    .collect();
+    if notes.is_empty() {
+        String::new()
     } else {
-        format!("\u{2713} {lockfile}: self-hash valid ({hash_prefix}...)")
+        format!(" ({})", notes.join(", "))
     }
 }
 
+/// Signature that was checked, or `None` when `--signature` was not given.
+fn signature(result: &Value) -> Option<&Value> {
+    result.get("signature").filter(|v| !v.is_null())
+}
+
+/// Who signed, for display: `signer (key_id)` or just `key_id`.
+fn signed_by(signature: &Value) -> String {
+    let key_id = signature["key_id"].as_str().unwrap_or("?");
+    match signature["signer"].as_str() {
+        Some(signer) => format!("{signer} ({key_id})"),
+        None => key_id.to_string(),
+    }
+}
+
+fn signature_line(result: &Value) -> Option<String> {
+    let signature = signature(result)?;
+    let status = signature["reason"].as_str().unwrap_or("trusted");
+    Some(format!(
+        "  signature: {status}, signed by {}",
+        signed_by(signature)
+    ))
+}
+
 fn render_failed(result: &Value, lockfile: &str) -> String {
     let mut lines = Vec::new();
 
@@ -45,13 +99,20 @@ fn render_failed(result: &Value, lockfile: &str) -> String {
         lines.push(format!("\u{2717} {lockfile}: self-hash TAMPERED"));
         lines.push(format!("  stored:   {stored}"));
         lines.push(format!("  computed: {computed}"));
-    } else if let Some(members) = result.get("members").filter(|v| !v.is_null()) {
+    } else if let Some(members) = result.get("members").filter(|v| !v.is_null()).filter(|m| {
+        !m["failures"].as_array().is_none_or(Vec::is_empty) || !signature_rejected(result)
+    }) {
         // Member drift.
         let checked = members["checked"].as_u64().unwrap_or(0);
         let failed = members["failed"].as_u64().unwrap_or(0);
         let verified = members["verified"].as_u64().unwrap_or(0);
+        let by_metadata = by_metadata_note(members);
+        let extra = match members["extra"].as_u64() {
+            Some(extra) if extra > 0 => format!(", {extra} extra file(s)"),
+            _ => String::new(),
+        };
         lines.push(format!(
-            "\u{2717} {lockfile}: {failed} of {checked} members failed ({verified} verified)"
+            "\u{2717} {lockfile}: {failed} of {checked} members failed ({verified} verified{by_metadata}){extra}"
         ));
 
         if let Some(failures) = members["failures"].as_array() {
@@ -61,21 +122,37 @@ fn render_failed(result: &Value, lockfile: &str) -> String {
                 lines.push(format!("  {reason}: {path}"));
             }
         }
-    } else {
+    }
+
+    if hash_valid && signature_rejected(result) {
+        let signature = &result["signature"];
+        let reason = signature["reason"].as_str().unwrap_or("?");
+        lines.push(format!(
+            "\u{2717} {lockfile}: signature rejected ({reason})"
+        ));
+        lines.push(format!("  signed by: {}", signed_by(signature)));
+    }
+
+    if lines.is_empty() {
         lines.push(format!("\u{2717} {lockfile}: VERIFY_FAILED"));
     }
 
     lines.join("\n")
 }
 
+fn signature_rejected(result: &Value) -> bool {
+    signature(result).is_some_and(|s| !s["reason"].is_null())
+}
+
 fn render_partial(result: &Value, lockfile: &str) -> String {
     let mut lines = Vec::new();
 
     if let Some(members) = result.get("members").filter(|v| !v.is_null()) {
         let verified = members["verified"].as_u64().unwrap_or(0);
         let skipped = members["skipped"].as_u64().unwrap_or(0);
+        let by_metadata = by_metadata_note(members);
         lines.push(format!(
-            "\u{26A0} {lockfile}: {verified} verified, {skipped} skipped"
+            "\u{26A0} {lockfile}: {verified} verified{by_metadata}, {skipped} skipped"
         ));
 
         if let Some(skips) = members["skips"].as_array() {
@@ -89,6 +166,7 @@ fn render_partial(result: &Value, lockfile: &str) -> String {
     } else {
         lines.push(format!("\u{26A0} {lockfile}: VERIFY_PARTIAL"));
     }
+    lines.extend(signature_line(result));
 
     lines.join("\n")
 }
@@ -103,6 +181,71 @@ fn render_refusal(result: &Value) -> String {
 mod tests {
     use super::*;
 
+    #[test]
+    fn render_verify_failed_extra_files() {
+        let result = serde_json::json!({
+            "outcome": "VERIFY_FAILED",
+            "lockfile": "dec.lock.json",
+            "lock_hash": { "stored": "sha256:abc123", "computed": "sha256:abc123", "valid": true },
+            "members": {
+                "checked": 2,
+                "verified": 2,
+                "failed": 0,
+                "skipped": 0,
+                "extra": 1,
+                "failures": [{ "path": "late.csv", "reason": "EXTRA" }]
+            }
+        });
+        let output = render_human(&result);
+        assert!(output.contains("0 of 2 members failed (2 verified), 1 extra file(s)"));
+        assert!(output.contains("EXTRA: late.csv"));
+    }
+
+    #[test]
+    fn render_verify_ok_notes_no_extra_files() {
+        let result = serde_json::json!({
+            "outcome": "VERIFY_OK",
+            "lockfile": "dec.lock.json",
+            "lock_hash": { "stored": "sha256:abc123", "computed": "sha256:abc123", "valid": true },
+            "members": { "checked": 2, "verified": 2, "failed": 0, "skipped": 0, "extra": 0 }
+        });
+        assert!(render_human(&result).contains("2/2 members verified, no extra files"));
+    }
+
+    #[test]
+    fn render_verify_ok_counts_metadata_only_members() {
+        let result = serde_json::json!({
+            "outcome": "VERIFY_OK",
+            "lockfile": "dec.lock.json",
+            "lock_hash": { "stored": "sha256:abc123", "computed": "sha256:abc123", "valid": true },
+            "members": {
+                "checked": 3,
+                "verified": 3,
+                "failed": 0,
+                "skipped": 0,
+                "verified_by_metadata": ["a.csv", "b.csv"]
+            }
+        });
+        assert!(render_human(&result).contains("3/3 members verified (2 by size+mtime only)"));
+    }
+
+    #[test]
+    fn render_verify_ok_counts_cache_hits() {
+        let result = serde_json::json!({
+            "outcome": "VERIFY_OK",
+            "lockfile": "dec.lock.json",
+            "lock_hash": { "stored": "sha256:abc123", "computed": "sha256:abc123", "valid": true },
+            "members": {
+                "checked": 2,
+                "verified": 2,
+                "failed": 0,
+                "skipped": 0,
+                "cache_hits": ["a.csv"]
+            }
+        });
+        assert!(render_human(&result).contains("2/2 members verified (1 from hash cache)"));
+    }
+
     #[test]
     fn render_verify_ok_self_hash_only() {
         let result = serde_json::json!({
@@ -212,4 +355,64 @@ mod tests {
         assert!(output.contains("E_BAD_LOCKFILE"));
         assert!(output.contains("malformed lockfile JSON"));
     }
+
+    #[test]
+    fn render_verify_ok_with_trusted_signature() {
+        let result = serde_json::json!({
+            "outcome": "VERIFY_OK",
+            "lockfile": "dec.lock.json",
+            "lock_hash": {
+                "stored": "sha256:a1b2c3d4e5f6a1b2c3d4e5f6a1b2c3d4e5f6a1b2c3d4e5f6a1b2c3d4e5f6a1b2",
+                "computed": "sha256:a1b2c3d4e5f6a1b2c3d4e5f6a1b2c3d4e5f6a1b2c3d4e5f6a1b2c3d4e5f6a1b2",
+                "valid": true
+            },
+            "members": null,
+            "signature": {
+                "signer": "audit-team",
+                "key_id": "ed25519:0123456789abcdef",
+                "algorithm": "ed25519",
+                "valid": true,
+                "trusted": true,
+                "reason": null
+            }
+        });
+        let output = render_human(&result);
+        assert!(
+            output.contains("signature: trusted, signed by audit-team (ed25519:0123456789abcdef)")
+        );
+    }
+
+    #[test]
+    fn render_verify_failed_untrusted_signature() {
+        let result = serde_json::json!({
+            "outcome": "VERIFY_FAILED",
+            "lockfile": "dec.lock.json",
+            "lock_hash": {
+                "stored": "sha256:abc",
+                "computed": "sha256:abc",
+                "valid": true
+            },
+            "members": {
+                "checked": 2,
+                "verified": 2,
+                "failed": 0,
+                "skipped": 0,
+                "failures": [],
+                "skips": []
+            },
+            "signature": {
+                "signer": null,
+                "key_id": "ed25519:0123456789abcdef",
+                "algorithm": "ed25519",
+                "valid": true,
+                "trusted": false,
+                "reason": "KEY_UNTRUSTED"
+            }
+        });
+        let output = render_human(&result);
+        assert!(output.starts_with('\u{2717}'));
+        assert!(output.contains("signature rejected (KEY_UNTRUSTED)"));
+        assert!(output.contains("signed by: ed25519:0123456789abcdef"));
+        assert!(!output.contains("members failed"));
+    }
 }
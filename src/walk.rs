@@ -0,0 +1,162 @@
+use std::fs;
+use std::io;
+use std::path::{Path, PathBuf};
+
+/// A regular file found under a walk root.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct WalkedFile {
+    /// Path on disk (the root joined with the relative path).
+    pub path: PathBuf,
+    /// Path relative to the root, `/`-separated like lockfile member paths.
+    pub relative: String,
+}
+
+/// A subdirectory whose listing failed, so its files are unaccounted for.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct UnreadableDir {
+    /// Path on disk.
+    pub path: PathBuf,
+    /// Path relative to the root, without a trailing `/`.
+    pub relative: String,
+    pub error: String,
+}
+
+/// The result of [`walk_files`].
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct Walk {
+    /// Regular files, ordered by relative path.
+    pub files: Vec<WalkedFile>,
+    /// Subdirectories that could not be read, ordered by relative path.
+    pub unreadable: Vec<UnreadableDir>,
+}
+
+/// Recursively list regular files under `root`, ordered by relative path.
+///
+/// Symlinks to files are listed; symlinks to directories are not followed,
+/// so a link cycle cannot make the walk run forever. Errors reading `root`
+/// itself are returned; subdirectories that cannot be read (or stop being
+/// readable partway through) are reported in [`Walk::unreadable`] so callers
+/// never mistake a partial listing for a complete one.
+pub fn walk_files(root: &Path) -> io::Result<Walk> {
+    let mut walk = Walk::default();
+    let mut pending = vec![(root.to_path_buf(), String::new())];
+    let mut first = true;
+
+    while let Some((dir, prefix)) = pending.pop() {
+        let entries = match fs::read_dir(&dir) {
+            Ok(entries) => entries,
+            Err(e) if first => return Err(e),
+            Err(e) => {
+                walk.unreadable.push(UnreadableDir {
+                    path: dir,
+                    relative: prefix,
+                    error: e.to_string(),
+                });
+                continue;
+            }
+        };
+        first = false;
+
+        for entry in entries {
+            let entry = match entry {
+                Ok(entry) => entry,
+                Err(e) => {
+                    walk.unreadable.push(UnreadableDir {
+                        path: dir.clone(),
+                        relative: prefix.clone(),
+                        error: e.to_string(),
+                    });
+                    break;
+                }
+            };
+            let Ok(file_type) = entry.file_type() else {
+                continue;
+            };
+            let name = entry.file_name().to_string_lossy().into_owned();
+            let relative = if prefix.is_empty() {
+                name
+            } else {
+                format!("{prefix}/{name}")
+            };
+            let path = entry.path();
+
+            if file_type.is_dir() {
+                pending.push((path, relative));
+            } else if file_type.is_file()
+                || (file_type.is_symlink() && fs::metadata(&path).is_ok_and(|m| m.is_file()))
+            {
+                walk.files.push(WalkedFile { path, relative });
+            }
+        }
+    }
+
+    walk.files.sort_by(|a, b| a.relative.cmp(&b.relative));
+    walk.unreadable.sort_by(|a, b| a.relative.cmp(&b.relative));
+    Ok(walk)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::TempDir;
+
+    #[test]
+    fn lists_nested_files_sorted_by_relative_path() {
+        let dir = TempDir::new().unwrap();
+        fs::create_dir_all(dir.path().join("b/c")).unwrap();
+        fs::write(dir.path().join("z.csv"), "z").unwrap();
+        fs::write(dir.path().join("b/c/x.csv"), "x").unwrap();
+        fs::write(dir.path().join("a.csv"), "a").unwrap();
+
+        let files = walk_files(dir.path()).unwrap().files;
+        let relative: Vec<&str> = files.iter().map(|f| f.relative.as_str()).collect();
+        assert_eq!(relative, ["a.csv", "b/c/x.csv", "z.csv"]);
+        assert_eq!(files[1].path, dir.path().join("b/c/x.csv"));
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn reports_unreadable_subdirectories() {
+        use std::os::unix::fs::PermissionsExt;
+
+        let dir = TempDir::new().unwrap();
+        fs::create_dir_all(dir.path().join("locked")).unwrap();
+        fs::write(dir.path().join("locked/x.csv"), "x").unwrap();
+        fs::write(dir.path().join("a.csv"), "a").unwrap();
+        let locked = dir.path().join("locked");
+        fs::set_permissions(&locked, fs::Permissions::from_mode(0o000)).unwrap();
+        // Root can read any directory; there is nothing to observe then.
+        let readable = fs::read_dir(&locked).is_ok();
+
+        let walk = walk_files(dir.path());
+        fs::set_permissions(&locked, fs::Permissions::from_mode(0o755)).unwrap();
+        let walk = walk.unwrap();
+        if readable {
+            return;
+        }
+        let relative: Vec<&str> = walk.files.iter().map(|f| f.relative.as_str()).collect();
+        assert_eq!(relative, ["a.csv"]);
+        assert_eq!(walk.unreadable.len(), 1);
+        assert_eq!(walk.unreadable[0].relative, "locked");
+        assert_eq!(walk.unreadable[0].path, locked);
+    }
+
+    #[test]
+    fn missing_root_is_an_error() {
+        let dir = TempDir::new().unwrap();
+        assert!(walk_files(&dir.path().join("absent")).is_err());
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn does_not_follow_directory_symlinks() {
+        let dir = TempDir::new().unwrap();
+        fs::create_dir(dir.path().join("data")).unwrap();
+        fs::write(dir.path().join("data/a.csv"), "a").unwrap();
+        std::os::unix::fs::symlink(dir.path(), dir.path().join("data/loop")).unwrap();
+
+        let files = walk_files(dir.path()).unwrap().files;
+        let relative: Vec<&str> = files.iter().map(|f| f.relative.as_str()).collect();
+        assert_eq!(relative, ["data/a.csv"]);
+    }
+}
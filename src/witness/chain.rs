@@ -0,0 +1,470 @@
+//! Hash chaining for the witness ledger.
+//!
+//! Each record appended by `lock` carries `prev_id`, the `id` of the record
+//! on the line before it (`null` for the first record in a ledger). An `id`
+//! is BLAKE3 over the compact JSON of the record with `id` set to `""`, and
+//! `prev_id` is part of what it covers, so deleting, reordering, or editing a
+//! line shows up as a break at the next record.
+
+use std::collections::BTreeMap;
+use std::fs::File;
+use std::io::{self, BufRead, Read, Seek, SeekFrom};
+use std::path::Path;
+
+use serde::Serialize;
+use serde_json::Value;
+
+use super::segments;
+
+/// Block size used when scanning backwards for the last ledger line.
+const TAIL_BLOCK: u64 = 8192;
+
+/// Compute a record's `id`: BLAKE3 of its compact JSON with `id` set to `""`.
+pub(crate) fn record_id(record: &Value) -> io::Result<String> {
+    let mut unsigned = record.clone();
+    if let Some(object) = unsigned.as_object_mut() {
+        object.insert("id".to_string(), Value::String(String::new()));
+    }
+    let json = serde_json::to_string(&unsigned).map_err(io::Error::other)?;
+    Ok(format!("blake3:{}", blake3::hash(json.as_bytes()).to_hex()))
+}
+
+/// The last JSON record in an open ledger, skipping blank and unparseable
+/// lines the same way [`verify_ledger`] does.
+///
+/// Reads backwards from the end in fixed blocks so appends stay cheap on a
+/// large ledger.
+pub(crate) fn last_record(file: &mut File) -> io::Result<Option<Value>> {
+    let mut position = file.seek(SeekFrom::End(0))?;
+    let mut tail: Vec<u8> = Vec::new();
+
+    loop {
+        while let Some(end) = tail.iter().rposition(|byte| !byte.is_ascii_whitespace()) {
+            let start = match tail[..end].iter().rposition(|byte| *byte == b'\n') {
+                Some(newline) => newline + 1,
+                None if position == 0 => 0,
+                None => break,
+            };
+            if let Ok(record) = serde_json::from_slice::<Value>(&tail[start..=end])
+                && record.is_object()
+            {
+                return Ok(Some(record));
+            }
+            tail.truncate(start);
+        }
+        if position == 0 {
+            return Ok(None);
+        }
+
+        let step = position.min(TAIL_BLOCK);
+        position -= step;
+        file.seek(SeekFrom::Start(position))?;
+        let mut block = vec![0; step as usize];
+        file.read_exact(&mut block)?;
+        block.extend_from_slice(&tail);
+        tail = block;
+    }
+}
+
+// ---------------------------------------------------------------------------
+// Chain verification
+// ---------------------------------------------------------------------------
+
+/// One problem found while walking the ledger.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct ChainIssue {
+    /// The live ledger or segment the line is in.
+    pub file: String,
+    /// 1-based line number in that file (decompressed, for a segment).
+    pub line: usize,
+    /// `UNPARSEABLE`, `ID_MISMATCH`, `BROKEN`, `REORDERED`, or `BAD_SEGMENT`.
+    pub code: String,
+    /// For `ID_MISMATCH`, the recorded id; for chain issues, the id of the
+    /// previous record; for `BAD_SEGMENT`, the previous segment's hash or
+    /// the segment's actual last id.
+    pub expected: Option<String>,
+    /// For `ID_MISMATCH`, the recomputed id; for chain issues, `prev_id`;
+    /// for `BAD_SEGMENT`, what the header claims.
+    pub actual: Option<String>,
+    /// For `REORDERED`, the line holding the record `prev_id` names.
+    pub prev_line: Option<usize>,
+}
+
+/// Outcome of walking the whole ledger.
+#[derive(Debug, Clone, Serialize)]
+pub struct ChainReport {
+    pub outcome: String,
+    pub ledger: String,
+    /// Sealed segments walked before the live ledger.
+    pub segments: usize,
+    /// Parseable records.
+    pub records: usize,
+    /// Records carrying `prev_id`.
+    pub chained: usize,
+    /// Records written before chaining (or by tools that do not chain).
+    pub unchained: usize,
+    pub issues: Vec<ChainIssue>,
+}
+
+impl ChainReport {
+    pub fn is_intact(&self) -> bool {
+        self.issues.is_empty()
+    }
+}
+
+/// Walk the sealed segments of `ledger`, oldest first, and then the live
+/// ledger as one chain, recomputing `lock` record ids and checking each
+/// `prev_id` against the last parseable record before it.
+///
+/// Each segment header must name the hash of the segment before it and the
+/// id of its own last record; a header that does not is a `BAD_SEGMENT`.
+pub fn verify_ledger(ledger: &Path) -> io::Result<ChainReport> {
+    let live = ledger.display().to_string();
+    let mut walker = ChainWalker::new(&live);
+    let mut prev_segment_hash: Option<String> = None;
+
+    for (_, path) in segments::segment_paths(ledger)? {
+        let file = path.display().to_string();
+        let mut reader = segments::open_segment(&path)?;
+        let mut header_line = String::new();
+        reader.read_line(&mut header_line)?;
+        walker.report.segments += 1;
+
+        match segments::parse_header(header_line.trim()) {
+            Ok(header) => {
+                if header.prev_segment_hash != prev_segment_hash {
+                    walker.report.issues.push(issue(
+                        &file,
+                        1,
+                        "BAD_SEGMENT",
+                        prev_segment_hash.clone(),
+                        header.prev_segment_hash.clone(),
+                    ));
+                }
+                walker.walk(reader, &file, 1)?;
+                if header.last_id != walker.previous {
+                    walker.report.issues.push(issue(
+                        &file,
+                        1,
+                        "BAD_SEGMENT",
+                        walker.previous.clone(),
+                        header.last_id,
+                    ));
+                }
+            }
+            Err(_) => {
+                walker
+                    .report
+                    .issues
+                    .push(issue(&file, 1, "BAD_SEGMENT", None, None));
+                walker.walk(reader, &file, 1)?;
+            }
+        }
+        prev_segment_hash = Some(segments::file_hash(&path)?);
+    }
+
+    match File::open(ledger) {
+        Ok(file) => walker.walk(io::BufReader::new(file), &live, 0)?,
+        Err(e) if e.kind() == io::ErrorKind::NotFound => {}
+        Err(e) => return Err(e),
+    }
+    Ok(walker.finish())
+}
+
+/// Chain state carried from one ledger file to the next.
+///
+/// Records from other tools are linked into the chain but their ids are not
+/// recomputed, since each tool defines what its id covers.
+struct ChainWalker {
+    /// The id of the last parseable record; unparseable lines are reported
+    /// once and otherwise stepped over.
+    previous: Option<String>,
+    report: ChainReport,
+}
+
+impl ChainWalker {
+    fn new(ledger: &str) -> Self {
+        Self {
+            previous: None,
+            report: ChainReport {
+                outcome: String::new(),
+                ledger: ledger.to_string(),
+                segments: 0,
+                records: 0,
+                chained: 0,
+                unchained: 0,
+                issues: Vec::new(),
+            },
+        }
+    }
+
+    /// Walk one file's lines; `offset` is the number of lines already
+    /// consumed from it (a segment header).
+    fn walk(&mut self, reader: impl BufRead, file: &str, offset: usize) -> io::Result<()> {
+        let mut lines: Vec<(usize, Option<Value>)> = Vec::new();
+        for (index, line) in reader.lines().enumerate() {
+            let line = line?;
+            let trimmed = line.trim();
+            if trimmed.is_empty() {
+                continue;
+            }
+            let record = serde_json::from_str::<Value>(trimmed)
+                .ok()
+                .filter(Value::is_object);
+            lines.push((offset + index + 1, record));
+        }
+
+        let mut lines_by_id: BTreeMap<&str, usize> = BTreeMap::new();
+        for (line, record) in &lines {
+            if let Some(id) = record.as_ref().and_then(|r| r.get("id")?.as_str()) {
+                lines_by_id.entry(id).or_insert(*line);
+            }
+        }
+
+        let issues = &mut self.report.issues;
+        for (line, record) in &lines {
+            let Some(record) = record else {
+                issues.push(issue(file, *line, "UNPARSEABLE", None, None));
+                continue;
+            };
+            self.report.records += 1;
+            let id = record.get("id").and_then(Value::as_str);
+
+            if record.get("tool").and_then(Value::as_str) == Some("lock")
+                && let Some(id) = id
+            {
+                let recomputed = record_id(record)?;
+                if recomputed != id {
+                    issues.push(issue(
+                        file,
+                        *line,
+                        "ID_MISMATCH",
+                        Some(id.to_string()),
+                        Some(recomputed),
+                    ));
+                }
+            }
+
+            if let Some(prev_id) = record.get("prev_id") {
+                self.report.chained += 1;
+                let prev_id = prev_id.as_str();
+                if prev_id != self.previous.as_deref() {
+                    let mut found = issue(
+                        file,
+                        *line,
+                        "BROKEN",
+                        self.previous.clone(),
+                        prev_id.map(str::to_string),
+                    );
+                    if let Some(prev_line) = prev_id.and_then(|p| lines_by_id.get(p)) {
+                        found.code = "REORDERED".to_string();
+                        found.prev_line = Some(*prev_line);
+                    }
+                    issues.push(found);
+                }
+            }
+
+            self.previous = id.map(str::to_string);
+        }
+        Ok(())
+    }
+
+    fn finish(mut self) -> ChainReport {
+        let report = &mut self.report;
+        report.outcome = if report.issues.is_empty() {
+            "CHAIN_INTACT"
+        } else {
+            "CHAIN_BROKEN"
+        }
+        .to_string();
+        report.unchained = report.records - report.chained;
+        self.report
+    }
+}
+
+fn issue(
+    file: &str,
+    line: usize,
+    code: &str,
+    expected: Option<String>,
+    actual: Option<String>,
+) -> ChainIssue {
+    ChainIssue {
+        file: file.to_string(),
+        line,
+        code: code.to_string(),
+        expected,
+        actual,
+        prev_line: None,
+    }
+}
+
+/// Render a chain report as human-readable text (no trailing newline).
+pub fn render_human(report: &ChainReport) -> String {
+    let unchained = if report.unchained > 0 {
+        format!(" ({} unchained)", report.unchained)
+    } else {
+        String::new()
+    };
+    let segments = if report.segments > 0 {
+        format!(" in {} segment(s) and the live ledger", report.segments)
+    } else {
+        String::new()
+    };
+    if report.is_intact() {
+        return format!(
+            "\u{2713} {}: {} records{unchained}{segments}, chain intact",
+            report.ledger, report.records
+        );
+    }
+
+    let mut lines = vec![format!(
+        "\u{2717} {}: {} records{unchained}{segments}, {} issue(s)",
+        report.ledger,
+        report.records,
+        report.issues.len()
+    )];
+    for issue in &report.issues {
+        let detail = match issue.code.as_str() {
+            "UNPARSEABLE" => "not a JSON record".to_string(),
+            "ID_MISMATCH" => format!(
+                "id {} recomputes to {}",
+                issue.expected.as_deref().unwrap_or("?"),
+                issue.actual.as_deref().unwrap_or("?")
+            ),
+            "REORDERED" => format!(
+                "prev_id points at line {}",
+                issue.prev_line.unwrap_or_default()
+            ),
+            "BAD_SEGMENT" => format!(
+                "header says {} (expected {})",
+                issue.actual.as_deref().unwrap_or("null"),
+                issue.expected.as_deref().unwrap_or("null")
+            ),
+            _ => format!(
+                "prev_id {} (expected {})",
+                issue.actual.as_deref().unwrap_or("null"),
+                issue.expected.as_deref().unwrap_or("null")
+            ),
+        };
+        lines.push(format!(
+            "  {}:{}: {} {detail}",
+            issue.file, issue.line, issue.code
+        ));
+    }
+    lines.join("\n")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::io::Write;
+
+    /// Build a chained `lock` record linked to `prev`.
+    fn chained(outcome: &str, prev: Option<&str>) -> Value {
+        let mut record = serde_json::json!({
+            "id": "",
+            "tool": "lock",
+            "outcome": outcome,
+            "prev_id": prev,
+        });
+        record["id"] = Value::String(record_id(&record).unwrap());
+        record
+    }
+
+    fn verify(lines: &[String]) -> ChainReport {
+        let text = lines.join("\n") + "\n";
+        let mut walker = ChainWalker::new("witness.jsonl");
+        walker.walk(text.as_bytes(), "witness.jsonl", 0).unwrap();
+        walker.finish()
+    }
+
+    fn three() -> Vec<Value> {
+        let first = chained("LOCK_CREATED", None);
+        let second = chained("VERIFY_OK", first["id"].as_str());
+        let third = chained("REFUSAL", second["id"].as_str());
+        vec![first, second, third]
+    }
+
+    fn lines(records: &[Value]) -> Vec<String> {
+        records.iter().map(Value::to_string).collect()
+    }
+
+    #[test]
+    fn intact_chain_has_no_issues() {
+        let report = verify(&lines(&three()));
+        assert!(report.is_intact(), "{:?}", report.issues);
+        assert_eq!(report.records, 3);
+        assert_eq!(report.chained, 3);
+    }
+
+    #[test]
+    fn deleted_line_breaks_the_next_record() {
+        let mut records = three();
+        let removed = records.remove(1);
+        let report = verify(&lines(&records));
+        assert_eq!(report.issues.len(), 1);
+        let issue = &report.issues[0];
+        assert_eq!((issue.line, issue.code.as_str()), (2, "BROKEN"));
+        assert_eq!(issue.actual.as_deref(), removed["id"].as_str());
+    }
+
+    #[test]
+    fn swapped_lines_are_reported_as_reordered() {
+        let mut records = three();
+        records.swap(1, 2);
+        let report = verify(&lines(&records));
+        let codes: Vec<(usize, &str)> = report
+            .issues
+            .iter()
+            .map(|issue| (issue.line, issue.code.as_str()))
+            .collect();
+        assert_eq!(codes, [(2, "REORDERED"), (3, "REORDERED")]);
+        assert_eq!(report.issues[0].prev_line, Some(3));
+    }
+
+    #[test]
+    fn edited_record_fails_id_check_and_garbage_is_stepped_over() {
+        let mut records = three();
+        records[0]["outcome"] = Value::String("LOCK_PARTIAL".to_string());
+        let mut text = lines(&records);
+        text.insert(1, "{not json".to_string());
+        let report = verify(&text);
+        let codes: Vec<(usize, &str)> = report
+            .issues
+            .iter()
+            .map(|issue| (issue.line, issue.code.as_str()))
+            .collect();
+        assert_eq!(codes, [(1, "ID_MISMATCH"), (2, "UNPARSEABLE")]);
+    }
+
+    #[test]
+    fn legacy_records_are_unchained() {
+        let legacy = serde_json::json!({ "tool": "hashbytes", "id": "blake3:legacy" });
+        let first = chained("LOCK_CREATED", Some("blake3:legacy"));
+        let report = verify(&lines(&[legacy, first]));
+        assert!(report.is_intact(), "{:?}", report.issues);
+        assert_eq!((report.chained, report.unchained), (1, 1));
+    }
+
+    #[test]
+    fn last_record_reads_the_final_record() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("witness.jsonl");
+        let mut file = File::create(&path).unwrap();
+        let padding = "x".repeat(TAIL_BLOCK as usize);
+        writeln!(file, "{{\"id\":\"blake3:first\",\"pad\":\"{padding}\"}}").unwrap();
+        writeln!(file, "{{\"id\":\"blake3:last\",\"pad\":\"{padding}\"}}").unwrap();
+        writeln!(file, "not json").unwrap();
+        writeln!(file).unwrap();
+        drop(file);
+
+        let mut file = File::open(&path).unwrap();
+        let last = last_record(&mut file).unwrap().unwrap();
+        assert_eq!(last["id"], "blake3:last");
+
+        let empty = dir.path().join("empty.jsonl");
+        File::create(&empty).unwrap();
+        assert_eq!(last_record(&mut File::open(&empty).unwrap()).unwrap(), None);
+    }
+}
@@ -0,0 +1,656 @@
+//! Optional sidecar index for witness queries.
+//!
+//! `<ledger>.idx` is JSONL: a header naming the sealed segments it covers,
+//! with their sizes and header hashes, then one line per record holding the
+//! fields queries filter on (tool, outcome, ts, dataset_id, input hashes)
+//! and where the record lives. Every line written for the live ledger also
+//! carries `end`, the live ledger's length after it, and `tail`, a hash of
+//! the last [`TAIL_BYTES`] before `end`; the last line of the index says how
+//! far into the live ledger it reaches.
+//!
+//! Segments are never rewritten once sealed, and each header names the
+//! previous segment's hash, so size and header identify one. Checking them
+//! and the live tail keeps every query and append independent of how long
+//! the ledger is. Segment records also carry the gzip member they sit in,
+//! so reading one back inflates only that member.
+//!
+//! The index is only created by `lock witness reindex`. Once present it is
+//! kept current by appends and rotations, which hold the ledger lock. An
+//! index that no longer matches the ledger (another tool appended, a
+//! segment was replaced) is ignored until it is rebuilt.
+
+use std::collections::BTreeMap;
+use std::fs::{self, File};
+use std::io::{self, BufRead, BufReader, Read, Seek, SeekFrom, Write};
+use std::path::{Path, PathBuf};
+
+use serde::{Deserialize, Serialize};
+
+use super::{WitnessRecord, segments};
+use crate::cli::WitnessFilters;
+
+/// Index header version.
+pub const INDEX_VERSION: &str = "lock.witness-index.v0";
+
+/// How much of the live ledger before the indexed end is hashed.
+pub const TAIL_BYTES: u64 = 1024;
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+struct IndexHeader {
+    version: String,
+    segments: Vec<SegmentStamp>,
+}
+
+/// A sealed segment as it was when indexed.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+struct SegmentStamp {
+    file: String,
+    bytes: u64,
+    /// BLAKE3 of the segment's header line.
+    header: String,
+}
+
+/// One index line: a record entry when `offset` is set, otherwise only the
+/// `end`/`tail` mark written after a rebuild.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct IndexEntry {
+    /// Segment file name; absent for the live ledger.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    segment: Option<String>,
+    /// Byte offset of the record's line (decompressed, for a segment).
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    offset: Option<u64>,
+    /// File offset of the segment's gzip member holding the record.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    block: Option<u64>,
+    /// Decompressed offset at which that member starts.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    block_start: Option<u64>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    ts: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    tool: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    outcome: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    dataset_id: Option<String>,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    input_hashes: Vec<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    end: Option<u64>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    tail: Option<String>,
+}
+
+impl IndexEntry {
+    fn for_record(segment: Option<&str>, offset: u64, record: &WitnessRecord) -> Self {
+        Self {
+            segment: segment.map(str::to_string),
+            offset: Some(offset),
+            ts: record.ts.clone(),
+            tool: record.tool.clone(),
+            outcome: record.outcome.clone(),
+            dataset_id: record
+                .params
+                .as_ref()
+                .and_then(|params| params.get("dataset_id")?.as_str())
+                .map(str::to_string),
+            input_hashes: record
+                .inputs
+                .iter()
+                .flatten()
+                .filter_map(|input| input.get("hash")?.as_str())
+                .map(str::to_string)
+                .collect(),
+            ..Self::default()
+        }
+    }
+
+    /// A stand-in record carrying only the indexed fields, so filtering and
+    /// ordering go through exactly the code the scan path uses.
+    pub fn summary(&self) -> WitnessRecord {
+        let inputs = self
+            .input_hashes
+            .iter()
+            .map(|hash| serde_json::json!({ "hash": hash }))
+            .collect();
+        WitnessRecord {
+            id: None,
+            tool: self.tool.clone(),
+            version: None,
+            outcome: self.outcome.clone(),
+            exit_code: None,
+            ts: self.ts.clone(),
+            output_hash: None,
+            inputs: Some(inputs),
+            params: Some(serde_json::json!({ "dataset_id": self.dataset_id })),
+            binary_hash: None,
+            prev_id: None,
+            extra: serde_json::Map::new(),
+        }
+    }
+}
+
+/// A loaded index that matched the ledger when it was read.
+pub struct Index {
+    entries: Vec<IndexEntry>,
+}
+
+impl Index {
+    /// Entries whose indexed fields match `filters`, in ledger order. Filters
+    /// on fields the index lacks are left for the caller; see [`covers`].
+    pub fn matching(&self, filters: &WitnessFilters) -> Vec<&IndexEntry> {
+        let indexed = WitnessFilters {
+            tool: filters.tool.clone(),
+            since: filters.since.clone(),
+            until: filters.until.clone(),
+            outcome: filters.outcome.clone(),
+            input_hash: filters.input_hash.clone(),
+            dataset_id: filters.dataset_id.clone(),
+            ..WitnessFilters::default()
+        };
+        self.entries
+            .iter()
+            .filter(|entry| super::matches_filters(&entry.summary(), &indexed))
+            .collect()
+    }
+}
+
+/// Whether [`Index::matching`] applies every filter in `filters`, so its
+/// entries need no further checking against the full records.
+pub fn covers(filters: &WitnessFilters) -> bool {
+    // Destructured so a new filter field has to be classified here.
+    let WitnessFilters {
+        tool: _,
+        since: _,
+        until: _,
+        outcome: _,
+        input_hash: _,
+        dataset_id: _,
+        subcommand,
+        exit_code,
+        output_hash,
+        params,
+        lockfile,
+    } = filters;
+    subcommand.is_none()
+        && exit_code.is_none()
+        && output_hash.is_none()
+        && params.is_empty()
+        && lockfile.is_none()
+}
+
+/// Path of the sidecar index for `ledger`.
+pub fn index_path(ledger: &Path) -> PathBuf {
+    let mut name = ledger
+        .file_name()
+        .map(|name| name.to_os_string())
+        .unwrap_or_default();
+    name.push(".idx");
+    ledger.with_file_name(name)
+}
+
+fn file_name(path: &Path) -> String {
+    path.file_name()
+        .map(|name| name.to_string_lossy().into_owned())
+        .unwrap_or_default()
+}
+
+fn segment_stamps(ledger: &Path) -> io::Result<Vec<SegmentStamp>> {
+    segments::segment_paths(ledger)?
+        .into_iter()
+        .map(|(_, path)| {
+            let mut header = String::new();
+            segments::open_segment(&path)?.read_line(&mut header)?;
+            Ok(SegmentStamp {
+                file: file_name(&path),
+                bytes: fs::metadata(&path)?.len(),
+                header: format!(
+                    "blake3:{}",
+                    blake3::hash(header.trim_end().as_bytes()).to_hex()
+                ),
+            })
+        })
+        .collect()
+}
+
+fn to_line(value: &impl Serialize) -> io::Result<String> {
+    serde_json::to_string(value).map_err(io::Error::other)
+}
+
+/// BLAKE3 of the last [`TAIL_BYTES`] of `file` before `end`.
+fn tail_hash(file: &mut File, end: u64) -> io::Result<String> {
+    let start = end.saturating_sub(TAIL_BYTES);
+    file.seek(SeekFrom::Start(start))?;
+    let mut tail = Vec::new();
+    Read::by_ref(file)
+        .take(end - start)
+        .read_to_end(&mut tail)?;
+    Ok(format!("blake3:{}", blake3::hash(&tail).to_hex()))
+}
+
+/// Call `visit` with the byte offset and parsed record of every line that
+/// the scan path would yield.
+fn walk_lines(
+    mut reader: impl BufRead,
+    mut offset: u64,
+    mut visit: impl FnMut(u64, WitnessRecord),
+) -> io::Result<()> {
+    let mut line = String::new();
+    loop {
+        line.clear();
+        let read = reader.read_line(&mut line)?;
+        if read == 0 {
+            return Ok(());
+        }
+        let trimmed = line.trim();
+        if !trimmed.is_empty()
+            && let Ok(record) = serde_json::from_str::<WitnessRecord>(trimmed)
+        {
+            visit(offset, record);
+        }
+        offset += read as u64;
+    }
+}
+
+/// Rebuild the index from the segments and the live ledger. Returns the
+/// number of records indexed.
+///
+/// The caller must hold the ledger's exclusive lock.
+pub fn rebuild(ledger: &Path) -> io::Result<usize> {
+    let header = IndexHeader {
+        version: INDEX_VERSION.to_string(),
+        segments: segment_stamps(ledger)?,
+    };
+    let mut entries = Vec::new();
+
+    for (_, path) in segments::segment_paths(ledger)? {
+        let name = file_name(&path);
+        let mut walked = Ok(());
+        segments::for_each_block(&path, |block| {
+            if walked.is_err() {
+                return;
+            }
+            // The segment header is the first line of the first member.
+            let mut bytes = &block.bytes[..];
+            let mut offset = block.start;
+            if block.start == 0 {
+                let header = bytes
+                    .iter()
+                    .position(|&b| b == b'\n')
+                    .map_or(bytes.len(), |at| at + 1);
+                bytes = &bytes[header..];
+                offset = header as u64;
+            }
+            walked = walk_lines(bytes, offset, |offset, record| {
+                entries.push(IndexEntry {
+                    block: Some(block.at),
+                    block_start: Some(block.start),
+                    ..IndexEntry::for_record(Some(&name), offset, &record)
+                });
+            });
+        })?;
+        walked?;
+    }
+
+    let (end, tail) = match File::open(ledger) {
+        Ok(mut file) => {
+            walk_lines(BufReader::new(&file), 0, |offset, record| {
+                entries.push(IndexEntry::for_record(None, offset, &record));
+            })?;
+            let end = file.metadata()?.len();
+            (end, tail_hash(&mut file, end)?)
+        }
+        Err(e) if e.kind() == io::ErrorKind::NotFound => {
+            (0, format!("blake3:{}", blake3::hash(b"").to_hex()))
+        }
+        Err(e) => return Err(e),
+    };
+
+    let path = index_path(ledger);
+    let partial = path.with_extension("idx.partial");
+    {
+        let mut out = io::BufWriter::new(File::create(&partial)?);
+        writeln!(out, "{}", to_line(&header)?)?;
+        for entry in &entries {
+            writeln!(out, "{}", to_line(entry)?)?;
+        }
+        let mark = IndexEntry {
+            end: Some(end),
+            tail: Some(tail),
+            ..IndexEntry::default()
+        };
+        writeln!(out, "{}", to_line(&mark)?)?;
+        out.into_inner().map_err(io::Error::other)?.sync_all()?;
+    }
+    fs::rename(&partial, &path)?;
+    Ok(entries.len())
+}
+
+/// Rebuild the index if one exists; used after a rotation.
+pub fn rebuild_if_present(ledger: &Path) -> io::Result<()> {
+    if index_path(ledger).exists() {
+        rebuild(ledger)?;
+    }
+    Ok(())
+}
+
+/// Record a line just appended at `start` of the live ledger, if an index
+/// exists and covered the ledger up to `start`.
+///
+/// The caller must hold the ledger's exclusive lock.
+pub fn record_appended(ledger: &Path, file: &mut File, start: u64, line: &str) -> io::Result<()> {
+    let path = index_path(ledger);
+    let last = match last_line(&path)? {
+        Some(last) => last,
+        None => return Ok(()),
+    };
+    if last.end != Some(start) {
+        return Ok(());
+    }
+    let Ok(record) = serde_json::from_str::<WitnessRecord>(line) else {
+        return Ok(());
+    };
+
+    if last.tail.as_deref() != Some(&*tail_hash(file, start)?) {
+        return Ok(());
+    }
+    let end = file.metadata()?.len();
+    let entry = IndexEntry {
+        end: Some(end),
+        tail: Some(tail_hash(file, end)?),
+        ..IndexEntry::for_record(None, start, &record)
+    };
+    let mut index = fs::OpenOptions::new().append(true).open(&path)?;
+    writeln!(index, "{}", to_line(&entry)?)?;
+    Ok(())
+}
+
+/// The last line of an index file, or `None` when there is no index.
+fn last_line(path: &Path) -> io::Result<Option<IndexEntry>> {
+    let mut file = match File::open(path) {
+        Ok(file) => file,
+        Err(e) if e.kind() == io::ErrorKind::NotFound => return Ok(None),
+        Err(e) => return Err(e),
+    };
+    let len = file.metadata()?.len();
+    let start = len.saturating_sub(4096);
+    file.seek(SeekFrom::Start(start))?;
+    let mut tail = Vec::new();
+    file.read_to_end(&mut tail)?;
+    Ok(String::from_utf8_lossy(&tail)
+        .lines()
+        .rev()
+        .find(|line| !line.trim().is_empty())
+        .and_then(|line| serde_json::from_str(line).ok()))
+}
+
+/// Load the index if it exists and still describes `ledger` exactly.
+///
+/// The caller should hold at least a shared lock on the ledger.
+pub fn load_fresh(ledger: &Path) -> io::Result<Option<Index>> {
+    let file = match File::open(index_path(ledger)) {
+        Ok(file) => file,
+        Err(e) if e.kind() == io::ErrorKind::NotFound => return Ok(None),
+        Err(e) => return Err(e),
+    };
+    let mut lines = BufReader::new(file).lines();
+    let Some(header) = lines
+        .next()
+        .transpose()?
+        .and_then(|line| serde_json::from_str::<IndexHeader>(&line).ok())
+    else {
+        return Ok(None);
+    };
+    if header.version != INDEX_VERSION || header.segments != segment_stamps(ledger)? {
+        return Ok(None);
+    }
+
+    let mut entries = Vec::new();
+    let mut last = None;
+    for line in lines {
+        let Ok(entry) = serde_json::from_str::<IndexEntry>(&line?) else {
+            return Ok(None);
+        };
+        if entry.end.is_some() {
+            last = Some((entry.end, entry.tail.clone()));
+        }
+        if entry.offset.is_some() {
+            entries.push(entry);
+        }
+    }
+
+    let Some((Some(end), Some(tail))) = last else {
+        return Ok(None);
+    };
+    let fresh = match File::open(ledger) {
+        Ok(mut live) => live.metadata()?.len() == end && tail_hash(&mut live, end)? == tail,
+        Err(e) if e.kind() == io::ErrorKind::NotFound => end == 0,
+        Err(e) => return Err(e),
+    };
+    Ok(fresh.then_some(Index { entries }))
+}
+
+/// Where a record's line can be read from: the live ledger, or one gzip
+/// member of a segment.
+type Source<'a> = (Option<&'a str>, u64, u64);
+
+fn source(entry: &IndexEntry) -> Source<'_> {
+    (
+        entry.segment.as_deref(),
+        entry.block.unwrap_or(0),
+        entry.block_start.unwrap_or(0),
+    )
+}
+
+/// Read the full records behind `entries`, returned in the same order.
+pub fn load_records(ledger: &Path, entries: &[&IndexEntry]) -> io::Result<Vec<WitnessRecord>> {
+    let mut wanted: BTreeMap<Source<'_>, BTreeMap<u64, Option<WitnessRecord>>> = BTreeMap::new();
+    for entry in entries {
+        if let Some(offset) = entry.offset {
+            wanted
+                .entry(source(entry))
+                .or_default()
+                .insert(offset, None);
+        }
+    }
+
+    for ((segment, block, block_start), offsets) in &mut wanted {
+        match segment {
+            Some(name) => {
+                let reader = segments::open_block(&ledger.with_file_name(name), *block)?;
+                walk_lines(reader, *block_start, |offset, record| {
+                    if let Some(slot) = offsets.get_mut(&offset) {
+                        *slot = Some(record);
+                    }
+                })?;
+            }
+            None => {
+                let mut live = File::open(ledger)?;
+                for (offset, slot) in offsets.iter_mut() {
+                    live.seek(SeekFrom::Start(*offset))?;
+                    let mut line = String::new();
+                    BufReader::new(&live).read_line(&mut line)?;
+                    *slot = serde_json::from_str(line.trim()).ok();
+                }
+            }
+        }
+    }
+
+    entries
+        .iter()
+        .map(|entry| {
+            entry
+                .offset
+                .and_then(|offset| wanted.get_mut(&source(entry))?.get_mut(&offset)?.clone())
+                .ok_or_else(|| {
+                    io::Error::new(
+                        io::ErrorKind::InvalidData,
+                        "witness index points at a missing record",
+                    )
+                })
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::Value;
+    use std::fs::OpenOptions;
+
+    fn write_ledger(path: &Path, lines: &[&str]) {
+        fs::write(path, lines.join("\n") + "\n").unwrap();
+    }
+
+    fn index_lines(path: &Path) -> Vec<Value> {
+        fs::read_to_string(index_path(path))
+            .unwrap()
+            .lines()
+            .map(|line| serde_json::from_str(line).unwrap())
+            .collect()
+    }
+
+    #[test]
+    fn rebuild_indexes_scan_visible_records_with_offsets() {
+        let dir = tempfile::tempdir().unwrap();
+        let ledger = dir.path().join("witness.jsonl");
+        let first = r#"{"tool":"lock","outcome":"LOCK_CREATED","ts":"2026-01-01T00:00:00Z","params":{"dataset_id":"raw"},"inputs":[{"path":"a","hash":"blake3:aa"}]}"#;
+        write_ledger(&ledger, &[first, "not json", r#"{"tool":"hash"}"#]);
+
+        assert_eq!(rebuild(&ledger).unwrap(), 2);
+        let lines = index_lines(&ledger);
+        assert_eq!(lines[0]["version"], INDEX_VERSION);
+        assert_eq!(lines[1]["offset"], 0);
+        assert_eq!(lines[1]["dataset_id"], "raw");
+        assert_eq!(lines[1]["input_hashes"], serde_json::json!(["blake3:aa"]));
+        assert_eq!(lines[2]["offset"], first.len() + 1 + "not json".len() + 1);
+        assert_eq!(lines[3]["end"], fs::metadata(&ledger).unwrap().len());
+
+        let index = load_fresh(&ledger).unwrap().expect("fresh index");
+        let filters = WitnessFilters {
+            input_hash: Some("aa".to_string()),
+            ..Default::default()
+        };
+        let matched = index.matching(&filters);
+        assert_eq!(matched.len(), 1);
+        let records = load_records(&ledger, &matched).unwrap();
+        assert_eq!(records[0].outcome.as_deref(), Some("LOCK_CREATED"));
+    }
+
+    #[test]
+    fn appends_keep_the_index_fresh_and_foreign_writes_make_it_stale() {
+        let dir = tempfile::tempdir().unwrap();
+        let ledger = dir.path().join("witness.jsonl");
+        write_ledger(&ledger, &[r#"{"tool":"lock","outcome":"LOCK_CREATED"}"#]);
+        rebuild(&ledger).unwrap();
+
+        let mut file = OpenOptions::new()
+            .read(true)
+            .append(true)
+            .open(&ledger)
+            .unwrap();
+        let start = file.metadata().unwrap().len();
+        let line = r#"{"tool":"lock","outcome":"REFUSAL"}"#;
+        writeln!(file, "{line}").unwrap();
+        record_appended(&ledger, &mut file, start, line).unwrap();
+
+        let index = load_fresh(&ledger).unwrap().expect("fresh after append");
+        assert_eq!(index.entries.len(), 2);
+        assert_eq!(index.entries[1].offset, Some(start));
+
+        writeln!(file, "{{\"tool\":\"hash\"}}").unwrap();
+        assert!(load_fresh(&ledger).unwrap().is_none());
+
+        let start = file.metadata().unwrap().len();
+        writeln!(file, "{line}").unwrap();
+        record_appended(&ledger, &mut file, start, line).unwrap();
+        assert!(load_fresh(&ledger).unwrap().is_none());
+    }
+
+    #[test]
+    fn edits_near_the_indexed_end_or_to_segments_make_it_stale() {
+        let dir = tempfile::tempdir().unwrap();
+        let ledger = dir.path().join("witness.jsonl");
+        let first = r#"{"tool":"lock","outcome":"LOCK_CREATED"}"#;
+        let last = r#"{"tool":"lock","outcome":"REFUSAL"}"#;
+        write_ledger(&ledger, &[first]);
+        let mut file = OpenOptions::new()
+            .read(true)
+            .write(true)
+            .open(&ledger)
+            .unwrap();
+        segments::rotate_locked(&mut file, &ledger).unwrap();
+        write_ledger(&ledger, &[first, last]);
+        rebuild(&ledger).unwrap();
+        assert!(load_fresh(&ledger).unwrap().is_some());
+
+        let edited = fs::read_to_string(&ledger)
+            .unwrap()
+            .replacen("REFUSAL", "FAILURE", 1);
+        fs::write(&ledger, &edited).unwrap();
+        assert!(load_fresh(&ledger).unwrap().is_none());
+        let before = index_lines(&ledger);
+        record_appended(&ledger, &mut file, edited.len() as u64, first).unwrap();
+        assert_eq!(index_lines(&ledger), before);
+
+        // A different segment under the same name has a different header,
+        // since it seals at another time and chains to other content.
+        rebuild(&ledger).unwrap();
+        let (_, segment) = segments::segment_paths(&ledger).unwrap().remove(0);
+        let stamps = segment_stamps(&ledger).unwrap();
+        let mut header = segments::read_header(&segment).unwrap();
+        header.sealed_at = "2000-01-01T00:00:00Z".to_string();
+        let mut encoder = flate2::write::GzEncoder::new(
+            File::create(&segment).unwrap(),
+            flate2::Compression::default(),
+        );
+        writeln!(encoder, "{}", serde_json::to_string(&header).unwrap()).unwrap();
+        writeln!(encoder, "{first}").unwrap();
+        encoder.finish().unwrap();
+        assert_ne!(segment_stamps(&ledger).unwrap(), stamps);
+        assert!(load_fresh(&ledger).unwrap().is_none());
+    }
+
+    #[test]
+    fn records_read_back_by_block_match_a_scan() {
+        let dir = tempfile::tempdir().unwrap();
+        let ledger = dir.path().join("witness.jsonl");
+        let note = "x".repeat(1000);
+        let lines: Vec<String> = (0..3 * segments::BLOCK_BYTES / 1000)
+            .map(|n| format!(r#"{{"tool":"lock","outcome":"O{n}","note":"{note}"}}"#))
+            .collect();
+        fs::write(&ledger, lines.join("\n") + "\n").unwrap();
+        let mut file = OpenOptions::new()
+            .read(true)
+            .write(true)
+            .open(&ledger)
+            .unwrap();
+        segments::rotate_locked(&mut file, &ledger).unwrap();
+        write_ledger(&ledger, &[r#"{"tool":"lock","outcome":"LIVE"}"#]);
+
+        assert_eq!(rebuild(&ledger).unwrap(), lines.len() + 1);
+        let index = load_fresh(&ledger).unwrap().expect("fresh index");
+        let blocks: std::collections::BTreeSet<_> = index
+            .entries
+            .iter()
+            .filter_map(|entry| entry.block)
+            .collect();
+        assert!(blocks.len() > 2, "{blocks:?}");
+
+        let picked: Vec<&IndexEntry> = index.entries.iter().rev().step_by(7).collect();
+        let records = load_records(&ledger, &picked).unwrap();
+        let mut scanned = Vec::new();
+        super::super::scan_records(&ledger, |record| scanned.push(record)).unwrap();
+        assert_eq!(scanned.len(), index.entries.len());
+        for (entry, record) in picked.iter().zip(&records) {
+            assert_eq!(record.outcome, entry.outcome);
+            assert!(
+                scanned
+                    .iter()
+                    .any(|scanned| scanned.id == record.id && scanned.outcome == record.outcome)
+            );
+        }
+        assert_eq!(records[0].outcome.as_deref(), Some("LIVE"));
+    }
+}
@@ -1,3 +1,4 @@
+use std::cmp::Ordering;
 use std::env;
 use std::ffi::OsString;
 use std::fs::{self, OpenOptions};
@@ -11,6 +12,10 @@ use serde_json::Value;
 
 use crate::cli::WitnessFilters;
 
+mod chain;
+mod index;
+mod segments;
+
 #[cfg(test)]
 static TEST_ENV_OVERRIDE_LOCK: std::sync::Mutex<()> = std::sync::Mutex::new(());
 
@@ -338,26 +343,164 @@ pub struct WitnessRecord {
     pub params: Option<Value>,
     #[serde(default)]
     pub binary_hash: Option<String>,
+    /// `id` of the preceding ledger record; absent on records written before
+    /// chaining.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub prev_id: Option<String>,
     /// Capture any additional fields.
     #[serde(flatten)]
     pub extra: serde_json::Map<String, Value>,
 }
 
-/// Read all witness records from the ledger file.
+/// Read all witness records from the ledger and its sealed segments.
 ///
 /// Returns an empty vec if the file does not exist.
 /// Returns an error only for I/O failures other than not-found.
 pub fn read_ledger(path: &std::path::Path) -> io::Result<Vec<WitnessRecord>> {
-    let file = match fs::File::open(path) {
-        Ok(f) => f,
-        Err(e) if e.kind() == io::ErrorKind::NotFound => return Ok(Vec::new()),
+    let mut records = Vec::new();
+    for_each_record(path, |record| records.push(record))?;
+    Ok(records)
+}
+
+/// Stream every witness record, oldest segment first and the live ledger
+/// last, without holding the whole ledger in memory.
+///
+/// A shared lock on the live ledger is held throughout, so a concurrent
+/// rotation is seen either entirely or not at all.
+pub fn for_each_record(path: &std::path::Path, visit: impl FnMut(WitnessRecord)) -> io::Result<()> {
+    with_shared_lock(path, || scan_records(path, visit))
+}
+
+/// Run `read` under a shared lock on the live ledger, if it exists.
+fn with_shared_lock<T>(path: &Path, read: impl FnOnce() -> io::Result<T>) -> io::Result<T> {
+    let live = match fs::File::open(path) {
+        Ok(f) => Some(f),
+        Err(e) if e.kind() == io::ErrorKind::NotFound => None,
         Err(e) => return Err(e),
     };
+    if let Some(file) = &live {
+        file.lock_shared()?;
+    }
+    let result = read();
+    if let Some(file) = live {
+        file.unlock()?;
+    }
+    result
+}
 
-    let reader = io::BufReader::new(file);
-    let mut records = Vec::new();
+fn scan_records(path: &Path, mut visit: impl FnMut(WitnessRecord)) -> io::Result<()> {
+    for (_, segment) in segments::segment_paths(path)? {
+        let mut lines = segments::open_segment(&segment)?.lines();
+        // The first line is the segment header, not a record.
+        lines.next().transpose()?;
+        visit_lines(lines, &mut visit)?;
+    }
+    match fs::File::open(path) {
+        Ok(file) => visit_lines(io::BufReader::new(file).lines(), &mut visit),
+        Err(e) if e.kind() == io::ErrorKind::NotFound => Ok(()),
+        Err(e) => Err(e),
+    }
+}
+
+/// Which slice of the sorted matches `query` returns.
+#[derive(Debug, Clone, Copy)]
+struct Page {
+    /// Oldest first instead of most recent first.
+    ascending: bool,
+    offset: usize,
+    limit: usize,
+}
 
-    for line in reader.lines() {
+impl Page {
+    /// Sort by timestamp in the page's direction, then cut the page out.
+    fn apply<T>(&self, mut items: Vec<T>, record: impl Fn(&T) -> &WitnessRecord) -> Vec<T> {
+        items.sort_by(|a, b| {
+            let ordering = compare_ts(record(a), record(b));
+            if self.ascending {
+                ordering
+            } else {
+                ordering.reverse()
+            }
+        });
+        items
+            .into_iter()
+            .skip(self.offset)
+            .take(self.limit)
+            .collect()
+    }
+}
+
+/// The page of matching records that `query` prints.
+///
+/// Uses the sidecar index when it is present and fresh; otherwise scans the
+/// ledger. Both paths return the same records in the same order.
+fn query_records(
+    path: &Path,
+    filters: &WitnessFilters,
+    page: Page,
+) -> io::Result<Vec<WitnessRecord>> {
+    with_shared_lock(path, || {
+        if let Some(index) = index::load_fresh(path)? {
+            let candidates = index.matching(filters);
+            if !index::covers(filters) {
+                // Some filters look past the indexed fields: read the
+                // candidates back and finish filtering on the full records.
+                let records = index::load_records(path, &candidates)?
+                    .into_iter()
+                    .filter(|record| matches_filters(record, filters))
+                    .collect();
+                return Ok(page.apply(records, |record| record));
+            }
+            // Sort on the indexed timestamps exactly as the scan sorts records,
+            // then read back only the records on the page.
+            let summaries = candidates
+                .into_iter()
+                .map(|entry| (entry.summary(), entry))
+                .collect();
+            let entries: Vec<_> = page
+                .apply(summaries, |(summary, _)| summary)
+                .into_iter()
+                .map(|(_, entry)| entry)
+                .collect();
+            return index::load_records(path, &entries);
+        }
+
+        let mut matched: Vec<WitnessRecord> = Vec::new();
+        scan_records(path, |record| {
+            if matches_filters(&record, filters) {
+                matched.push(record);
+            }
+        })?;
+        Ok(page.apply(matched, |record| record))
+    })
+}
+
+/// Number of matching records, from the index when it is fresh.
+fn count_records(path: &Path, filters: &WitnessFilters) -> io::Result<usize> {
+    with_shared_lock(path, || {
+        if let Some(index) = index::load_fresh(path)? {
+            let candidates = index.matching(filters);
+            if index::covers(filters) {
+                return Ok(candidates.len());
+            }
+            return Ok(index::load_records(path, &candidates)?
+                .iter()
+                .filter(|record| matches_filters(record, filters))
+                .count());
+        }
+        let mut count = 0usize;
+        scan_records(path, |record| {
+            count += usize::from(matches_filters(&record, filters));
+        })?;
+        Ok(count)
+    })
+}
+
+fn visit_lines(
+    lines: impl Iterator<Item = io::Result<String>>,
+    visit: &mut impl FnMut(WitnessRecord),
+) -> io::Result<()> {
+    for line in lines {
         let line = line?;
         let trimmed = line.trim();
         if trimmed.is_empty() {
@@ -365,11 +508,10 @@ pub fn read_ledger(path: &std::path::Path) -> io::Result<Vec<WitnessRecord>> {
         }
         // Skip unparseable lines silently (ledger may contain records from other tools).
         if let Ok(record) = serde_json::from_str::<WitnessRecord>(trimmed) {
-            records.push(record);
+            visit(record);
         }
     }
-
-    Ok(records)
+    Ok(())
 }
 
 /// Apply filters to a set of witness records.
@@ -417,6 +559,52 @@ fn matches_filters(record: &WitnessRecord, filters: &WitnessFilters) -> bool {
         }
     }
 
+    // Dataset, subcommand, exit code, and output hash filters.
+    let param = |key: &str| record.params.as_ref().and_then(|params| params.get(key));
+    if let Some(dataset_id) = &filters.dataset_id
+        && param("dataset_id").and_then(Value::as_str) != Some(dataset_id.as_str())
+    {
+        return false;
+    }
+    if let Some(subcommand) = &filters.subcommand
+        && record_subcommand(record) != Some(subcommand.as_str())
+    {
+        return false;
+    }
+    if filters.exit_code.is_some() && record.exit_code != filters.exit_code {
+        return false;
+    }
+    if let Some(output_hash) = &filters.output_hash
+        && record.output_hash.as_deref() != Some(output_hash.as_str())
+    {
+        return false;
+    }
+
+    // `--param KEY=VALUE`: VALUE as JSON, or else as a plain string.
+    for (key, expected) in &filters.params {
+        let Some(actual) = param(key) else {
+            return false;
+        };
+        let as_json = serde_json::from_str::<Value>(expected).ok();
+        if actual.as_str() != Some(expected.as_str()) && as_json.as_ref() != Some(actual) {
+            return false;
+        }
+    }
+
+    // Lockfile filter: an input path ending in the given components.
+    if let Some(lockfile) = &filters.lockfile {
+        let wanted = without_cur_dir(lockfile);
+        let has_match = record.inputs.iter().flatten().any(|input| {
+            input
+                .get("path")
+                .and_then(Value::as_str)
+                .is_some_and(|path| without_cur_dir(Path::new(path)).ends_with(&wanted))
+        });
+        if !has_match {
+            return false;
+        }
+    }
+
     // Since filter (RFC3339 instant comparison).
     if let Some(since) = &filters.since {
         let Some(since_ts) = parse_rfc3339_timestamp(since) else {
@@ -446,10 +634,39 @@ fn matches_filters(record: &WitnessRecord, filters: &WitnessFilters) -> bool {
     true
 }
 
+/// The lock subcommand that wrote a record: `params.subcommand`, or `lock`
+/// for the default run, which records none.
+fn record_subcommand(record: &WitnessRecord) -> Option<&str> {
+    match record
+        .params
+        .as_ref()
+        .and_then(|params| params.get("subcommand"))
+    {
+        Some(subcommand) => subcommand.as_str(),
+        None => (record.tool.as_deref() == Some(TOOL_NAME)).then_some(TOOL_NAME),
+    }
+}
+
+fn without_cur_dir(path: &Path) -> PathBuf {
+    path.components()
+        .filter(|component| *component != std::path::Component::CurDir)
+        .collect()
+}
+
 fn parse_rfc3339_timestamp(value: &str) -> Option<DateTime<FixedOffset>> {
     DateTime::parse_from_rfc3339(value).ok()
 }
 
+/// Order records by parsed instant, falling back to string comparison.
+fn compare_ts(a: &WitnessRecord, b: &WitnessRecord) -> Ordering {
+    let a_parsed = a.ts.as_deref().and_then(parse_rfc3339_timestamp);
+    let b_parsed = b.ts.as_deref().and_then(parse_rfc3339_timestamp);
+    match (a_parsed, b_parsed) {
+        (Some(a_ts), Some(b_ts)) => a_ts.cmp(&b_ts),
+        _ => a.ts.cmp(&b.ts),
+    }
+}
+
 // ---------------------------------------------------------------------------
 // Witness append (called from orchestration after stdout output)
 // ---------------------------------------------------------------------------
@@ -463,7 +680,8 @@ fn parse_rfc3339_timestamp(value: &str) -> Option<DateTime<FixedOffset>> {
 /// `inputs` is the inputs array as a JSON value.
 ///
 /// This function computes `output_hash` as BLAKE3 of those bytes, builds the witness
-/// record, and appends it as a single JSONL line.
+/// record linked by `prev_id` to the last record in the ledger, and appends it as a
+/// single JSONL line.
 ///
 /// Witness failures are non-fatal: errors are printed to stderr but do not
 /// change the domain exit code.
@@ -513,6 +731,16 @@ fn append_witness_record_to(
         .append(true)
         .open(ledger_path)?;
     file.lock_exclusive()?;
+    if segments::finish_interrupted_rotation(&mut file, ledger_path)? {
+        index::rebuild_if_present(ledger_path)?;
+    }
+
+    // Chain to the last record, read under the same lock as the append. A
+    // freshly rotated ledger chains to the newest segment's last record.
+    let prev_id = match chain::last_record(&mut file)? {
+        Some(last) => last.get("id").and_then(Value::as_str).map(str::to_string),
+        None => segments::newest_last_id(ledger_path)?,
+    };
 
     // Compute output_hash (BLAKE3 of stdout bytes).
     let output_hash = format!("blake3:{}", blake3::hash(output_bytes).to_hex());
@@ -531,18 +759,23 @@ fn append_witness_record_to(
         "exit_code": exit_code,
         "output_hash": output_hash,
         "ts": ts,
+        "prev_id": prev_id,
     });
 
     // Compute record id as BLAKE3 of the record with id="".
-    let pre_id_json = serde_json::to_string(&record).map_err(io::Error::other)?;
-    let record_id = format!("blake3:{}", blake3::hash(pre_id_json.as_bytes()).to_hex());
-    record["id"] = Value::String(record_id);
+    record["id"] = Value::String(chain::record_id(&record)?);
 
     // Serialize final record (compact, single line).
     let line = serde_json::to_string(&record).map_err(io::Error::other)?;
 
-    // Append to ledger.
+    // Append to ledger, sealing it into a segment once it grows too large.
+    let start = file.metadata()?.len();
     writeln!(file, "{line}")?;
+    index::record_appended(ledger_path, &mut file, start, &line)?;
+    if file.metadata()?.len() > segments::AUTO_ROTATE_BYTES {
+        segments::rotate_locked(&mut file, ledger_path)?;
+        index::rebuild_if_present(ledger_path)?;
+    }
     file.unlock()?;
 
     Ok(())
@@ -558,7 +791,13 @@ fn append_witness_record_to(
 /// - `0`: matches found
 /// - `1`: no matches
 /// - `2`: error
-pub fn dispatch_query(filters: &WitnessFilters, limit: usize, json_output: bool) -> u8 {
+pub fn dispatch_query(
+    filters: &WitnessFilters,
+    limit: usize,
+    offset: usize,
+    ascending: bool,
+    json_output: bool,
+) -> u8 {
     let path = match resolve_ledger_path_for_query() {
         Ok(path) => path,
         Err(e) => {
@@ -566,27 +805,19 @@ pub fn dispatch_query(filters: &WitnessFilters, limit: usize, json_output: bool)
             return 2;
         }
     };
-    let records = match read_ledger(&path) {
-        Ok(r) => r,
+    let page = Page {
+        ascending,
+        offset,
+        limit,
+    };
+    let matched = match query_records(&path, filters, page) {
+        Ok(matched) => matched,
         Err(e) => {
             eprintln!("lock: witness ledger error: {e}");
             return 2;
         }
     };
 
-    let mut matched: Vec<&WitnessRecord> = apply_filters(&records, filters);
-
-    // Most recent first (by parsed instant, falling back to string comparison).
-    matched.sort_by(|a, b| {
-        let a_parsed = a.ts.as_deref().and_then(parse_rfc3339_timestamp);
-        let b_parsed = b.ts.as_deref().and_then(parse_rfc3339_timestamp);
-        match (b_parsed, a_parsed) {
-            (Some(b_ts), Some(a_ts)) => b_ts.cmp(&a_ts),
-            _ => b.ts.cmp(&a.ts),
-        }
-    });
-    matched.truncate(limit);
-
     if matched.is_empty() {
         if json_output {
             println!("[]");
@@ -627,32 +858,29 @@ pub fn dispatch_last(json_output: bool) -> u8 {
             return 2;
         }
     };
-    let records = match read_ledger(&path) {
-        Ok(r) => r,
-        Err(e) => {
-            eprintln!("lock: witness ledger error: {e}");
-            return 2;
-        }
-    };
-
     // Find the most recent record by timestamp (or last in file order).
-    let last = records.iter().max_by(|a, b| {
-        let a_parsed = a.ts.as_deref().and_then(parse_rfc3339_timestamp);
-        let b_parsed = b.ts.as_deref().and_then(parse_rfc3339_timestamp);
-        match (a_parsed, b_parsed) {
-            (Some(a_ts), Some(b_ts)) => a_ts.cmp(&b_ts),
-            _ => a.ts.cmp(&b.ts),
+    let mut last: Option<WitnessRecord> = None;
+    let streamed = for_each_record(&path, |record| {
+        if last
+            .as_ref()
+            .is_none_or(|best| compare_ts(&record, best).is_ge())
+        {
+            last = Some(record);
         }
     });
+    if let Err(e) = streamed {
+        eprintln!("lock: witness ledger error: {e}");
+        return 2;
+    }
 
     match last {
         Some(record) => {
             if json_output {
-                if let Ok(out) = serde_json::to_string_pretty(record) {
+                if let Ok(out) = serde_json::to_string_pretty(&record) {
                     println!("{out}");
                 }
             } else {
-                print_record_human(record);
+                print_record_human(&record);
             }
             0
         }
@@ -679,16 +907,14 @@ pub fn dispatch_count(filters: &WitnessFilters, json_output: bool) -> u8 {
             return 2;
         }
     };
-    let records = match read_ledger(&path) {
-        Ok(r) => r,
+    let count = match count_records(&path, filters) {
+        Ok(count) => count,
         Err(e) => {
             eprintln!("lock: witness ledger error: {e}");
             return 2;
         }
     };
 
-    let count = apply_filters(&records, filters).len();
-
     if json_output {
         println!("{}", serde_json::json!({ "count": count }));
     } else {
@@ -698,6 +924,297 @@ pub fn dispatch_count(filters: &WitnessFilters, json_output: bool) -> u8 {
     0
 }
 
+/// Records in the ledger at `path` whose `output_hash` equals
+/// `output_hash`, in ledger order.
+pub fn find_by_output_hash(path: &Path, output_hash: &str) -> io::Result<Vec<WitnessRecord>> {
+    let mut matched = Vec::new();
+    for_each_record(path, |record| {
+        if record.output_hash.as_deref() == Some(output_hash) {
+            matched.push(record);
+        }
+    })?;
+    Ok(matched)
+}
+
+/// Execute `lock witness find-output` — find the runs that emitted an artifact.
+///
+/// The artifact is hashed with BLAKE3 exactly as `output_hash` was computed
+/// from stdout, so a lockfile written with `--output` or redirected from
+/// stdout matches the run that produced it.
+///
+/// Exit codes:
+/// - `0`: one or more records produced this artifact
+/// - `1`: the artifact was never witnessed by this ledger
+/// - `2`: error
+pub fn dispatch_find_output(artifact: &Path, json_output: bool) -> u8 {
+    let output_hash = match hash_artifact(artifact) {
+        Ok(hash) => hash,
+        Err(e) => {
+            eprintln!("lock: cannot read '{}': {e}", artifact.display());
+            return 2;
+        }
+    };
+    let path = match resolve_ledger_path_for_query() {
+        Ok(path) => path,
+        Err(e) => {
+            eprintln!("lock: witness ledger error: {e}");
+            return 2;
+        }
+    };
+    let matched = match find_by_output_hash(&path, &output_hash) {
+        Ok(matched) => matched,
+        Err(e) => {
+            eprintln!("lock: witness ledger error: {e}");
+            return 2;
+        }
+    };
+
+    if matched.is_empty() {
+        if json_output {
+            println!("[]");
+        } else {
+            eprintln!("no witness record with output_hash {output_hash}");
+        }
+        return 1;
+    }
+
+    if json_output {
+        if let Ok(out) = serde_json::to_string_pretty(&matched) {
+            println!("{out}");
+        }
+    } else {
+        for r in &matched {
+            print_record_human(r);
+            print_record_detail(r);
+        }
+    }
+
+    0
+}
+
+fn hash_artifact(path: &Path) -> io::Result<String> {
+    let mut file = fs::File::open(path)?;
+    let mut hasher = blake3::Hasher::new();
+    io::copy(&mut file, &mut hasher)?;
+    Ok(format!("blake3:{}", hasher.finalize().to_hex()))
+}
+
+/// Print a record's params and inputs, indented under its summary line.
+fn print_record_detail(record: &WitnessRecord) {
+    if let Some(params) = &record.params {
+        println!("  params: {params}");
+    }
+    for input in record.inputs.iter().flatten() {
+        let path = input.get("path").and_then(Value::as_str).unwrap_or("?");
+        match input.get("hash").and_then(Value::as_str) {
+            Some(hash) => println!("  input: {path} ({hash})"),
+            None => println!("  input: {path}"),
+        }
+    }
+}
+
+/// Execute `lock witness verify` — walk the hash chain through every sealed
+/// segment and the live ledger.
+///
+/// Exit codes:
+/// - `0`: chain intact (including an empty or missing ledger)
+/// - `1`: breaks, reorderings, id mismatches, or unparseable lines found
+/// - `2`: error
+pub fn dispatch_verify(json_output: bool) -> u8 {
+    let path = match resolve_ledger_path_for_query() {
+        Ok(path) => path,
+        Err(e) => {
+            eprintln!("lock: witness ledger error: {e}");
+            return 2;
+        }
+    };
+    let report = match chain::verify_ledger(&path) {
+        Ok(report) => report,
+        Err(e) => {
+            eprintln!("lock: witness ledger error: {e}");
+            return 2;
+        }
+    };
+
+    if json_output {
+        if let Ok(out) = serde_json::to_string_pretty(&report) {
+            println!("{out}");
+        }
+    } else {
+        println!("{}", chain::render_human(&report));
+    }
+
+    if report.is_intact() { 0 } else { 1 }
+}
+
+/// Execute `lock witness rotate` — seal the live ledger into a segment.
+///
+/// With no thresholds the ledger is rotated whenever it has records; with
+/// `max_bytes` or `max_age_days`, only once it reaches that size or its
+/// oldest record is that many days old.
+///
+/// Exit codes:
+/// - `0`: a segment was written
+/// - `1`: nothing to rotate (empty ledger or below thresholds)
+/// - `2`: error
+pub fn dispatch_rotate(max_bytes: Option<u64>, max_age_days: Option<u64>, json_output: bool) -> u8 {
+    let path = match resolve_ledger_path_for_query() {
+        Ok(path) => path,
+        Err(e) => {
+            eprintln!("lock: witness ledger error: {e}");
+            return 2;
+        }
+    };
+    let rotated = match rotate_ledger(&path, max_bytes, max_age_days) {
+        Ok(rotated) => rotated,
+        Err(e) => {
+            eprintln!("lock: witness ledger error: {e}");
+            return 2;
+        }
+    };
+
+    if json_output {
+        let outcome = if rotated.is_some() {
+            "ROTATED"
+        } else {
+            "NOT_ROTATED"
+        };
+        let out = serde_json::json!({
+            "outcome": outcome,
+            "ledger": path.display().to_string(),
+            "segment": rotated.as_ref().map(|r| &r.segment),
+            "records": rotated.as_ref().map_or(0, |r| r.records),
+            "segment_hash": rotated.as_ref().map(|r| &r.segment_hash),
+        });
+        if let Ok(out) = serde_json::to_string_pretty(&out) {
+            println!("{out}");
+        }
+    }
+
+    match rotated {
+        Some(rotated) => {
+            if !json_output {
+                println!(
+                    "\u{2713} rotated {} records into {} ({})",
+                    rotated.records, rotated.segment, rotated.segment_hash
+                );
+            }
+            0
+        }
+        None => {
+            if !json_output {
+                eprintln!("nothing to rotate in {}", path.display());
+            }
+            1
+        }
+    }
+}
+
+fn rotate_ledger(
+    path: &Path,
+    max_bytes: Option<u64>,
+    max_age_days: Option<u64>,
+) -> io::Result<Option<segments::Rotated>> {
+    let mut file = match OpenOptions::new().read(true).append(true).open(path) {
+        Ok(file) => file,
+        Err(e) if e.kind() == io::ErrorKind::NotFound => return Ok(None),
+        Err(e) => return Err(e),
+    };
+    file.lock_exclusive()?;
+    if segments::finish_interrupted_rotation(&mut file, path)? {
+        index::rebuild_if_present(path)?;
+    }
+
+    let due = match (max_bytes, max_age_days) {
+        (None, None) => true,
+        _ => {
+            let too_big =
+                max_bytes.is_some_and(|max| file.metadata().is_ok_and(|m| m.len() >= max));
+            let too_old = max_age_days.is_some_and(|days| {
+                let cutoff = Utc::now() - chrono::Duration::days(days as i64);
+                oldest_ts(path).is_some_and(|ts| ts <= cutoff)
+            });
+            too_big || too_old
+        }
+    };
+    let rotated = if due {
+        segments::rotate_locked(&mut file, path)?
+    } else {
+        None
+    };
+    if rotated.is_some() {
+        index::rebuild_if_present(path)?;
+    }
+    file.unlock()?;
+    Ok(rotated)
+}
+
+/// Execute `lock witness reindex` — rebuild the sidecar query index.
+///
+/// Exit codes:
+/// - `0`: index written
+/// - `2`: error
+pub fn dispatch_reindex(json_output: bool) -> u8 {
+    let path = match resolve_ledger_path_for_append() {
+        Ok(path) => path,
+        Err(e) => {
+            eprintln!("lock: witness ledger error: {e}");
+            return 2;
+        }
+    };
+    let records = match reindex_ledger(&path) {
+        Ok(records) => records,
+        Err(e) => {
+            eprintln!("lock: witness ledger error: {e}");
+            return 2;
+        }
+    };
+
+    let index = index::index_path(&path);
+    if json_output {
+        let out = serde_json::json!({
+            "outcome": "REINDEXED",
+            "ledger": path.display().to_string(),
+            "index": index.display().to_string(),
+            "records": records,
+        });
+        if let Ok(out) = serde_json::to_string_pretty(&out) {
+            println!("{out}");
+        }
+    } else {
+        println!(
+            "\u{2713} indexed {records} records into {}",
+            index.display()
+        );
+    }
+    0
+}
+
+fn reindex_ledger(path: &Path) -> io::Result<usize> {
+    if let Some(parent) = path.parent() {
+        fs::create_dir_all(parent)?;
+    }
+    let file = OpenOptions::new()
+        .create(true)
+        .read(true)
+        .append(true)
+        .open(path)?;
+    file.lock_exclusive()?;
+    let records = index::rebuild(path);
+    file.unlock()?;
+    records
+}
+
+/// Timestamp of the first record in the live ledger.
+fn oldest_ts(path: &Path) -> Option<DateTime<FixedOffset>> {
+    let file = fs::File::open(path).ok()?;
+    io::BufReader::new(file)
+        .lines()
+        .map_while(Result::ok)
+        .find_map(|line| serde_json::from_str::<WitnessRecord>(line.trim()).ok())
+        .and_then(|record| record.ts.as_deref().and_then(parse_rfc3339_timestamp))
+}
+
 /// Print a witness record in human-readable format to stdout.
 fn print_record_human(record: &WitnessRecord) {
     let tool = record.tool.as_deref().unwrap_or("?");
@@ -730,6 +1247,7 @@ mod tests {
             inputs: None,
             params: None,
             binary_hash: None,
+            prev_id: None,
             extra: serde_json::Map::new(),
         }
     }
@@ -853,6 +1371,31 @@ mod tests {
         assert!(notice.contains("canonical_preferred"));
     }
 
+    #[test]
+    fn find_by_output_hash_matches_exact_hash_only() {
+        let mut first = make_record("lock", "LOCK_CREATED", "2026-01-01T00:00:00Z");
+        first.output_hash = Some("blake3:aa".to_string());
+        let mut second = make_record("lock", "VERIFY_OK", "2026-01-02T00:00:00Z");
+        second.output_hash = Some("blake3:aabb".to_string());
+        let records = [
+            first,
+            second,
+            make_record("lock", "REFUSAL", "2026-01-03T00:00:00Z"),
+        ];
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("witness.jsonl");
+        let lines: Vec<String> = records
+            .iter()
+            .map(|record| serde_json::to_string(record).unwrap())
+            .collect();
+        std::fs::write(&path, lines.join("\n") + "\n").unwrap();
+
+        let matched = find_by_output_hash(&path, "blake3:aa").unwrap();
+        assert_eq!(matched.len(), 1);
+        assert_eq!(matched[0].outcome.as_deref(), Some("LOCK_CREATED"));
+        assert!(find_by_output_hash(&path, "blake3:cc").unwrap().is_empty());
+    }
+
     #[test]
     fn read_ledger_returns_empty_for_missing_file() {
         let records = read_ledger(std::path::Path::new("/nonexistent/witness.jsonl")).unwrap();
@@ -930,6 +1473,112 @@ not json
         assert_eq!(matched.len(), 2);
     }
 
+    #[test]
+    fn filter_by_subcommand_treats_the_default_run_as_lock() {
+        let mut verify = make_record("lock", "VERIFY_OK", "2026-01-02T00:00:00Z");
+        verify.params = Some(serde_json::json!({ "subcommand": "verify" }));
+        let mut created = make_record("lock", "LOCK_CREATED", "2026-01-01T00:00:00Z");
+        created.params = Some(serde_json::json!({ "dataset_id": "raw" }));
+        let records = vec![
+            created,
+            verify,
+            make_record("shape", "COMPATIBLE", "2026-01-03T00:00:00Z"),
+        ];
+
+        let outcomes = |subcommand: &str| {
+            let filters = WitnessFilters {
+                subcommand: Some(subcommand.to_string()),
+                ..Default::default()
+            };
+            apply_filters(&records, &filters)
+                .iter()
+                .map(|r| r.outcome.as_deref().unwrap())
+                .collect::<Vec<_>>()
+        };
+        assert_eq!(outcomes("lock"), ["LOCK_CREATED"]);
+        assert_eq!(outcomes("verify"), ["VERIFY_OK"]);
+    }
+
+    #[test]
+    fn filter_by_params_dataset_exit_code_and_output_hash() {
+        let mut strict = make_record("lock", "VERIFY_FAILED", "2026-01-01T00:00:00Z");
+        strict.exit_code = Some(1);
+        strict.output_hash = Some("blake3:aa".to_string());
+        strict.params = Some(serde_json::json!({
+            "subcommand": "verify",
+            "strict": true,
+            "jobs": 4,
+            "root": "/data/dec",
+            "dataset_id": "raw-dec",
+        }));
+        let mut loose = make_record("lock", "VERIFY_OK", "2026-01-02T00:00:00Z");
+        loose.params = Some(serde_json::json!({ "subcommand": "verify", "strict": false }));
+        let records = vec![strict, loose];
+
+        let count = |filters: WitnessFilters| apply_filters(&records, &filters).len();
+        let param = |key: &str, value: &str| WitnessFilters {
+            params: vec![(key.to_string(), value.to_string())],
+            ..Default::default()
+        };
+        assert_eq!(count(param("strict", "true")), 1);
+        assert_eq!(count(param("jobs", "4")), 1);
+        assert_eq!(count(param("root", "/data/dec")), 1);
+        assert_eq!(count(param("root", "\"/data/dec\"")), 1);
+        assert_eq!(count(param("cache", "null")), 0);
+        assert_eq!(
+            count(WitnessFilters {
+                dataset_id: Some("raw-dec".to_string()),
+                ..Default::default()
+            }),
+            1
+        );
+        assert_eq!(
+            count(WitnessFilters {
+                exit_code: Some(0),
+                ..Default::default()
+            }),
+            1
+        );
+        assert_eq!(
+            count(WitnessFilters {
+                output_hash: Some("blake3:a".to_string()),
+                ..Default::default()
+            }),
+            0
+        );
+        assert_eq!(
+            count(WitnessFilters {
+                output_hash: Some("blake3:aa".to_string()),
+                exit_code: Some(1),
+                ..Default::default()
+            }),
+            1
+        );
+    }
+
+    #[test]
+    fn filter_by_lockfile_matches_trailing_path_components() {
+        let mut record = make_record("lock", "VERIFY_OK", "2026-01-01T00:00:00Z");
+        record.inputs = Some(vec![serde_json::json!({
+            "path": "/data/out/dec.lock.json",
+            "hash": null,
+        })]);
+        let records = vec![record];
+
+        let count = |lockfile: &str| {
+            let filters = WitnessFilters {
+                lockfile: Some(PathBuf::from(lockfile)),
+                ..Default::default()
+            };
+            apply_filters(&records, &filters).len()
+        };
+        assert_eq!(count("dec.lock.json"), 1);
+        assert_eq!(count("./out/dec.lock.json"), 1);
+        assert_eq!(count("/data/out/dec.lock.json"), 1);
+        assert_eq!(count("c.lock.json"), 0);
+        assert_eq!(count("other/dec.lock.json"), 0);
+    }
+
     #[test]
     fn filter_by_since() {
         let records = vec![
@@ -1176,6 +1825,147 @@ not json
         assert_eq!(second["outcome"], "LOCK_PARTIAL");
         assert_eq!(second["exit_code"], 1);
         assert_ne!(second["id"], first["id"]);
+        assert_eq!(first["prev_id"], Value::Null);
+        assert_eq!(second["prev_id"], first["id"]);
+    }
+
+    #[test]
+    fn rotation_keeps_reads_and_chain_across_segments() {
+        let dir = tempfile::tempdir().unwrap();
+        let ledger_path = dir.path().join("witness.jsonl");
+        let append = |outcome: &str| {
+            append_witness_record_to(
+                outcome,
+                0,
+                outcome.as_bytes(),
+                default_params(),
+                default_inputs(),
+                &ledger_path,
+            )
+            .unwrap();
+        };
+
+        append("LOCK_CREATED");
+        append("VERIFY_OK");
+        assert!(
+            rotate_ledger(&ledger_path, Some(u64::MAX), None)
+                .unwrap()
+                .is_none()
+        );
+        let rotated = rotate_ledger(&ledger_path, None, None).unwrap().unwrap();
+        assert_eq!(rotated.records, 2);
+        append("LOCK_PARTIAL");
+
+        let records = read_ledger(&ledger_path).unwrap();
+        let outcomes: Vec<&str> = records
+            .iter()
+            .map(|r| r.outcome.as_deref().unwrap())
+            .collect();
+        assert_eq!(outcomes, ["LOCK_CREATED", "VERIFY_OK", "LOCK_PARTIAL"]);
+        assert_eq!(records[2].prev_id, records[1].id);
+
+        let report = chain::verify_ledger(&ledger_path).unwrap();
+        assert!(report.is_intact(), "{:?}", report.issues);
+        assert_eq!((report.segments, report.records), (1, 3));
+
+        std::fs::remove_file(&rotated.segment).unwrap();
+        let report = chain::verify_ledger(&ledger_path).unwrap();
+        assert_eq!(report.issues.len(), 1);
+        assert_eq!(report.issues[0].code, "BROKEN");
+    }
+
+    #[test]
+    fn indexed_queries_match_the_scan_and_survive_staleness() {
+        let dir = tempfile::tempdir().unwrap();
+        let ledger_path = dir.path().join("witness.jsonl");
+        let append = |outcome: &str, dataset: &str, hash: &str| {
+            append_witness_record_to(
+                outcome,
+                0,
+                outcome.as_bytes(),
+                serde_json::json!({ "dataset_id": dataset }),
+                serde_json::json!([{ "path": "in", "hash": hash }]),
+                &ledger_path,
+            )
+            .unwrap();
+        };
+        let scan = |filters: &WitnessFilters, page: Page| {
+            let matched = apply_filters(&read_ledger(&ledger_path).unwrap(), filters)
+                .into_iter()
+                .cloned()
+                .collect::<Vec<_>>();
+            page.apply(matched, |record| record)
+        };
+        let filter_sets = [
+            WitnessFilters::default(),
+            WitnessFilters {
+                outcome: Some("VERIFY_OK".to_string()),
+                ..Default::default()
+            },
+            WitnessFilters {
+                input_hash: Some("beef".to_string()),
+                tool: Some("lock".to_string()),
+                ..Default::default()
+            },
+            WitnessFilters {
+                since: Some("2000-01-01T00:00:00Z".to_string()),
+                dataset_id: Some("raw".to_string()),
+                ..Default::default()
+            },
+            WitnessFilters {
+                outcome: Some("VERIFY_OK".to_string()),
+                params: vec![("dataset_id".to_string(), "clean".to_string())],
+                exit_code: Some(0),
+                ..Default::default()
+            },
+        ];
+        let pages = [(false, 0, 1), (false, 1, 100), (true, 0, 2), (true, 3, 100)].map(
+            |(ascending, offset, limit)| Page {
+                ascending,
+                offset,
+                limit,
+            },
+        );
+        let assert_same = |fresh: bool| {
+            assert_eq!(index::load_fresh(&ledger_path).unwrap().is_some(), fresh);
+            for filters in &filter_sets {
+                for page in pages {
+                    let ids = |records: Vec<WitnessRecord>| {
+                        records.into_iter().map(|r| r.id).collect::<Vec<_>>()
+                    };
+                    assert_eq!(
+                        ids(query_records(&ledger_path, filters, page).unwrap()),
+                        ids(scan(filters, page)),
+                        "{filters:?} {page:?}"
+                    );
+                }
+                let everything = Page {
+                    ascending: true,
+                    offset: 0,
+                    limit: usize::MAX,
+                };
+                assert_eq!(
+                    count_records(&ledger_path, filters).unwrap(),
+                    scan(filters, everything).len()
+                );
+            }
+        };
+
+        append("LOCK_CREATED", "raw", "blake3:beef");
+        assert_eq!(reindex_ledger(&ledger_path).unwrap(), 1);
+        append("VERIFY_OK", "raw", "blake3:cafe");
+        rotate_ledger(&ledger_path, None, None).unwrap().unwrap();
+        append("VERIFY_OK", "clean", "blake3:beef");
+        append("LOCK_PARTIAL", "clean", "blake3:0beef");
+        assert_same(true);
+
+        let mut file = OpenOptions::new().append(true).open(&ledger_path).unwrap();
+        writeln!(file, "{{\"tool\":\"shape\",\"outcome\":\"VERIFY_OK\"}}").unwrap();
+        append("VERIFY_OK", "raw", "blake3:beef");
+        assert_same(false);
+
+        assert_eq!(reindex_ledger(&ledger_path).unwrap(), 6);
+        assert_same(true);
     }
 
     #[test]
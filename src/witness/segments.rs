@@ -0,0 +1,440 @@
+//! Rotation of the witness ledger into sealed, compressed segments.
+//!
+//! A segment sits next to the live ledger as `<ledger>.<NNNNNN>.gz`. Its
+//! first line is a [`SegmentHeader`] naming the BLAKE3 of the previous
+//! segment file, so the segments form a hash chain of their own; the
+//! remaining lines are the rotated ledger lines, byte for byte. The header
+//! also records the `id` of the segment's last record, which is what the
+//! next append to the emptied live ledger chains to.
+//!
+//! The file is a multi-member gzip: the header line is one member and the
+//! ledger lines follow in members of about [`BLOCK_BYTES`], each ending on a
+//! line boundary, so one record can be read without inflating the rest.
+//! Segments written as a single member read the same way.
+
+use std::fs::{self, File};
+use std::io::{self, BufRead, BufReader, Read, Seek, SeekFrom, Write};
+use std::path::{Path, PathBuf};
+
+use chrono::{SecondsFormat, Utc};
+use flate2::Compression;
+use flate2::bufread::GzDecoder;
+use flate2::read::MultiGzDecoder;
+use flate2::write::GzEncoder;
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+
+/// Segment header version.
+pub const SEGMENT_VERSION: &str = "lock.witness-segment.v0";
+
+/// Live ledger size past which an append rotates it automatically.
+pub const AUTO_ROTATE_BYTES: u64 = 64 * 1024 * 1024;
+
+/// Uncompressed ledger bytes per gzip member of a segment.
+pub const BLOCK_BYTES: usize = 64 * 1024;
+
+/// First line of every segment.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct SegmentHeader {
+    pub version: String,
+    /// 1-based sequence number, matching the file name.
+    pub segment: u64,
+    /// BLAKE3 of the previous segment file as stored; `null` for the first.
+    pub prev_segment_hash: Option<String>,
+    /// Non-blank ledger lines in the segment.
+    pub records: usize,
+    pub first_ts: Option<String>,
+    pub last_ts: Option<String>,
+    /// `id` of the last parseable record, carried over as the next `prev_id`.
+    pub last_id: Option<String>,
+    pub sealed_at: String,
+}
+
+/// A segment that was just written by [`rotate_locked`].
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct Rotated {
+    pub segment: String,
+    pub records: usize,
+    pub segment_hash: String,
+}
+
+/// Segment files for `ledger`, oldest first, with their sequence numbers.
+pub fn segment_paths(ledger: &Path) -> io::Result<Vec<(u64, PathBuf)>> {
+    let Some(name) = ledger.file_name().and_then(|name| name.to_str()) else {
+        return Ok(Vec::new());
+    };
+    let dir = match ledger.parent() {
+        Some(parent) if !parent.as_os_str().is_empty() => parent,
+        _ => Path::new("."),
+    };
+    let entries = match fs::read_dir(dir) {
+        Ok(entries) => entries,
+        Err(e) if e.kind() == io::ErrorKind::NotFound => return Ok(Vec::new()),
+        Err(e) => return Err(e),
+    };
+
+    let mut segments = Vec::new();
+    for entry in entries {
+        let entry = entry?;
+        let file_name = entry.file_name();
+        let Some(sequence) = file_name
+            .to_str()
+            .and_then(|file_name| file_name.strip_prefix(name))
+            .and_then(|rest| rest.strip_prefix('.'))
+            .and_then(|rest| rest.strip_suffix(".gz"))
+            .filter(|digits| !digits.is_empty() && digits.bytes().all(|b| b.is_ascii_digit()))
+            .and_then(|digits| digits.parse::<u64>().ok())
+        else {
+            continue;
+        };
+        segments.push((sequence, entry.path()));
+    }
+    segments.sort();
+    Ok(segments)
+}
+
+/// Open a segment for line-by-line reading, header first.
+pub fn open_segment(path: &Path) -> io::Result<impl BufRead + use<>> {
+    Ok(BufReader::new(MultiGzDecoder::new(File::open(path)?)))
+}
+
+/// One gzip member of a segment.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Block {
+    /// Byte offset of the member in the segment file.
+    pub at: u64,
+    /// Offset of the member's first byte in the decompressed segment.
+    pub start: u64,
+    pub bytes: Vec<u8>,
+}
+
+/// Call `visit` with every gzip member of a segment, in order.
+pub fn for_each_block(path: &Path, mut visit: impl FnMut(Block)) -> io::Result<()> {
+    let mut reader = BufReader::new(File::open(path)?);
+    let mut start = 0;
+    loop {
+        let at = reader.stream_position()?;
+        if reader.fill_buf()?.is_empty() {
+            return Ok(());
+        }
+        let mut bytes = Vec::new();
+        GzDecoder::new(&mut reader).read_to_end(&mut bytes)?;
+        let len = bytes.len() as u64;
+        visit(Block { at, start, bytes });
+        start += len;
+    }
+}
+
+/// Read the single gzip member that starts `at` bytes into a segment.
+pub fn open_block(path: &Path, at: u64) -> io::Result<impl BufRead + use<>> {
+    let mut file = File::open(path)?;
+    file.seek(SeekFrom::Start(at))?;
+    Ok(BufReader::new(GzDecoder::new(BufReader::new(file))))
+}
+
+fn write_member(out: &mut impl Write, bytes: &[u8]) -> io::Result<()> {
+    let mut encoder = GzEncoder::new(out, Compression::default());
+    encoder.write_all(bytes)?;
+    encoder.finish()?;
+    Ok(())
+}
+
+/// Parse a segment's header line.
+pub fn parse_header(line: &str) -> io::Result<SegmentHeader> {
+    serde_json::from_str::<SegmentHeader>(line)
+        .ok()
+        .filter(|header| header.version == SEGMENT_VERSION)
+        .ok_or_else(|| io::Error::new(io::ErrorKind::InvalidData, "not a witness segment header"))
+}
+
+/// Read only the header of a segment.
+pub fn read_header(path: &Path) -> io::Result<SegmentHeader> {
+    let mut line = String::new();
+    open_segment(path)?.read_line(&mut line)?;
+    parse_header(line.trim())
+}
+
+/// BLAKE3 of a segment file as stored on disk.
+pub fn file_hash(path: &Path) -> io::Result<String> {
+    let mut hasher = blake3::Hasher::new();
+    io::copy(&mut File::open(path)?, &mut hasher)?;
+    Ok(format!("blake3:{}", hasher.finalize().to_hex()))
+}
+
+/// `last_id` of the newest segment, or `None` when there are no segments.
+pub fn newest_last_id(ledger: &Path) -> io::Result<Option<String>> {
+    match segment_paths(ledger)?.last() {
+        Some((_, path)) => Ok(read_header(path)?.last_id),
+        None => Ok(None),
+    }
+}
+
+/// Seal the contents of the live ledger into the next segment and empty it.
+///
+/// `file` must be the live ledger, opened for reading and writing and held
+/// under an exclusive lock for the whole call, so no append lands between
+/// the copy and the truncate. Returns `None` when the ledger has no lines.
+/// A crash between the two leaves the sealed lines in the live ledger too;
+/// [`finish_interrupted_rotation`] drops them before the next append.
+pub fn rotate_locked(file: &mut File, ledger: &Path) -> io::Result<Option<Rotated>> {
+    let mut contents = String::new();
+    file.seek(SeekFrom::Start(0))?;
+    file.read_to_string(&mut contents)?;
+    let lines: Vec<&str> = contents
+        .lines()
+        .filter(|line| !line.trim().is_empty())
+        .collect();
+    if lines.is_empty() {
+        return Ok(None);
+    }
+
+    let existing = segment_paths(ledger)?;
+    let (sequence, prev_segment_hash) = match existing.last() {
+        Some((sequence, path)) => (sequence + 1, Some(file_hash(path)?)),
+        None => (1, None),
+    };
+
+    let records: Vec<Value> = lines
+        .iter()
+        .filter_map(|line| serde_json::from_str::<Value>(line).ok())
+        .filter(Value::is_object)
+        .collect();
+    let ts = |record: &Value| record.get("ts").and_then(Value::as_str).map(str::to_string);
+    let header = SegmentHeader {
+        version: SEGMENT_VERSION.to_string(),
+        segment: sequence,
+        prev_segment_hash,
+        records: lines.len(),
+        first_ts: records.first().and_then(ts),
+        last_ts: records.last().and_then(ts),
+        last_id: records
+            .last()
+            .and_then(|record| record.get("id")?.as_str())
+            .map(str::to_string),
+        sealed_at: Utc::now().to_rfc3339_opts(SecondsFormat::Secs, true),
+    };
+
+    let path = segment_path(ledger, sequence);
+    let partial = path.with_extension("gz.partial");
+    {
+        let mut out = io::BufWriter::new(File::create(&partial)?);
+        let header_line = serde_json::to_string(&header).map_err(io::Error::other)?;
+        write_member(&mut out, format!("{header_line}\n").as_bytes())?;
+        let mut block = String::new();
+        for line in &lines {
+            block.push_str(line);
+            block.push('\n');
+            if block.len() >= BLOCK_BYTES {
+                write_member(&mut out, block.as_bytes())?;
+                block.clear();
+            }
+        }
+        if !block.is_empty() {
+            write_member(&mut out, block.as_bytes())?;
+        }
+        out.into_inner().map_err(io::Error::other)?.sync_all()?;
+    }
+    fs::rename(&partial, &path)?;
+    file.set_len(0)?;
+
+    Ok(Some(Rotated {
+        segment: path.display().to_string(),
+        records: lines.len(),
+        segment_hash: file_hash(&path)?,
+    }))
+}
+
+/// Empty a live ledger whose lines a rotation already sealed but did not
+/// truncate. That is the case exactly when its last record is the one the
+/// newest segment names as `last_id`: every append makes a record with a
+/// new `id`, so a ledger that grew afterwards ends with a different one.
+/// Returns whether the ledger was emptied.
+///
+/// Same locking contract as [`rotate_locked`].
+pub fn finish_interrupted_rotation(file: &mut File, ledger: &Path) -> io::Result<bool> {
+    let Some(last) = super::chain::last_record(file)? else {
+        return Ok(false);
+    };
+    let Some(id) = last.get("id").and_then(Value::as_str) else {
+        return Ok(false);
+    };
+    if newest_last_id(ledger)?.as_deref() != Some(id) {
+        return Ok(false);
+    }
+    file.set_len(0)?;
+    file.sync_all()?;
+    Ok(true)
+}
+
+fn segment_path(ledger: &Path, sequence: u64) -> PathBuf {
+    let mut name = ledger
+        .file_name()
+        .map(|name| name.to_os_string())
+        .unwrap_or_default();
+    name.push(format!(".{sequence:06}.gz"));
+    ledger.with_file_name(name)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::fs::OpenOptions;
+
+    fn open_live(path: &Path) -> File {
+        OpenOptions::new()
+            .create(true)
+            .read(true)
+            .append(true)
+            .open(path)
+            .unwrap()
+    }
+
+    fn segment_lines(path: &Path) -> Vec<String> {
+        open_segment(path)
+            .unwrap()
+            .lines()
+            .collect::<io::Result<_>>()
+            .unwrap()
+    }
+
+    #[test]
+    fn rotation_seals_lines_and_links_segments() {
+        let dir = tempfile::tempdir().unwrap();
+        let ledger = dir.path().join("witness.jsonl");
+        fs::write(
+            &ledger,
+            "{\"id\":\"blake3:a\",\"ts\":\"2026-01-01T00:00:00Z\"}\n\n",
+        )
+        .unwrap();
+
+        let mut file = open_live(&ledger);
+        let first = rotate_locked(&mut file, &ledger).unwrap().unwrap();
+        assert_eq!(first.records, 1);
+        assert_eq!(fs::metadata(&ledger).unwrap().len(), 0);
+        assert_eq!(rotate_locked(&mut file, &ledger).unwrap(), None);
+
+        writeln!(file, "{{\"id\":\"blake3:b\"}}").unwrap();
+        writeln!(file, "not json").unwrap();
+        let second = rotate_locked(&mut file, &ledger).unwrap().unwrap();
+
+        let segments = segment_paths(&ledger).unwrap();
+        assert_eq!(
+            segments,
+            [
+                (1, dir.path().join("witness.jsonl.000001.gz")),
+                (2, dir.path().join("witness.jsonl.000002.gz")),
+            ]
+        );
+        let header = read_header(&segments[0].1).unwrap();
+        assert_eq!(header.prev_segment_hash, None);
+        assert_eq!(header.last_id.as_deref(), Some("blake3:a"));
+        assert_eq!(header.first_ts.as_deref(), Some("2026-01-01T00:00:00Z"));
+
+        let header = read_header(&segments[1].1).unwrap();
+        assert_eq!(
+            header.prev_segment_hash.as_deref(),
+            Some(first.segment_hash.as_str())
+        );
+        assert_eq!(header.records, 2);
+        assert_eq!(header.last_id.as_deref(), Some("blake3:b"));
+        assert_eq!(second.segment_hash, file_hash(&segments[1].1).unwrap());
+        assert_eq!(
+            &segment_lines(&segments[1].1)[1..],
+            ["{\"id\":\"blake3:b\"}", "not json"]
+        );
+        assert_eq!(
+            newest_last_id(&ledger).unwrap().as_deref(),
+            Some("blake3:b")
+        );
+    }
+
+    #[test]
+    fn interrupted_rotation_is_finished_once() {
+        let dir = tempfile::tempdir().unwrap();
+        let ledger = dir.path().join("witness.jsonl");
+        let sealed = "{\"id\":\"blake3:a\"}\n{\"id\":\"blake3:b\",\"prev_id\":\"blake3:a\"}\n";
+        fs::write(&ledger, sealed).unwrap();
+        let mut file = open_live(&ledger);
+        rotate_locked(&mut file, &ledger).unwrap().unwrap();
+        assert!(!finish_interrupted_rotation(&mut file, &ledger).unwrap());
+
+        // As if the process died after the rename, before the truncate.
+        fs::write(&ledger, sealed).unwrap();
+        assert!(finish_interrupted_rotation(&mut file, &ledger).unwrap());
+        assert_eq!(fs::metadata(&ledger).unwrap().len(), 0);
+        assert!(!finish_interrupted_rotation(&mut file, &ledger).unwrap());
+
+        writeln!(file, "{{\"id\":\"blake3:c\",\"prev_id\":\"blake3:b\"}}").unwrap();
+        assert!(!finish_interrupted_rotation(&mut file, &ledger).unwrap());
+        assert_eq!(fs::read_to_string(&ledger).unwrap().lines().count(), 1);
+    }
+
+    #[test]
+    fn large_segments_are_split_into_readable_blocks() {
+        let dir = tempfile::tempdir().unwrap();
+        let ledger = dir.path().join("witness.jsonl");
+        let line = format!("{{\"note\":\"{}\"}}", "x".repeat(1000));
+        let count = 3 * BLOCK_BYTES / line.len();
+        fs::write(&ledger, format!("{line}\n").repeat(count)).unwrap();
+        let mut file = open_live(&ledger);
+        rotate_locked(&mut file, &ledger).unwrap().unwrap();
+        let (_, segment) = segment_paths(&ledger).unwrap().remove(0);
+
+        let mut blocks = Vec::new();
+        for_each_block(&segment, |block| blocks.push(block)).unwrap();
+        assert!(blocks.len() >= 4, "{} blocks", blocks.len());
+        assert_eq!(blocks[0].start, 0);
+        assert!(blocks.iter().all(|block| block.bytes.ends_with(b"\n")));
+        assert_eq!(segment_lines(&segment).len(), count + 1);
+
+        let last = blocks.last().unwrap();
+        let mut first_line = String::new();
+        open_block(&segment, last.at)
+            .unwrap()
+            .read_line(&mut first_line)
+            .unwrap();
+        assert_eq!(first_line.trim_end(), line);
+    }
+
+    #[test]
+    fn single_member_segments_still_read() {
+        let dir = tempfile::tempdir().unwrap();
+        let ledger = dir.path().join("witness.jsonl");
+        let segment = segment_path(&ledger, 1);
+        let mut encoder = GzEncoder::new(File::create(&segment).unwrap(), Compression::default());
+        writeln!(encoder, "{{\"version\":\"{SEGMENT_VERSION}\"}}").unwrap();
+        writeln!(encoder, "{{\"id\":\"blake3:a\"}}").unwrap();
+        encoder.finish().unwrap();
+
+        assert_eq!(segment_lines(&segment).len(), 2);
+        let mut blocks = Vec::new();
+        for_each_block(&segment, |block| blocks.push(block)).unwrap();
+        assert_eq!(blocks.len(), 1);
+        assert_eq!(blocks[0].at, 0);
+    }
+
+    #[test]
+    fn segment_paths_ignore_unrelated_files() {
+        let dir = tempfile::tempdir().unwrap();
+        let ledger = dir.path().join("witness.jsonl");
+        for name in [
+            "witness.jsonl.000010.gz",
+            "witness.jsonl.000002.gz",
+            "witness.jsonl.gz",
+            "witness.jsonl.x1.gz",
+            "other.jsonl.000001.gz",
+        ] {
+            fs::write(dir.path().join(name), b"").unwrap();
+        }
+        let sequences: Vec<u64> = segment_paths(&ledger)
+            .unwrap()
+            .into_iter()
+            .map(|(sequence, _)| sequence)
+            .collect();
+        assert_eq!(sequences, [2, 10]);
+        assert_eq!(
+            newest_last_id(&dir.path().join("none.jsonl")).unwrap(),
+            None
+        );
+    }
+}
@@ -0,0 +1,168 @@
+#![cfg(unix)]
+
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::process::Output;
+use std::time::{Duration, SystemTime};
+
+use serde_json::Value;
+use tempfile::TempDir;
+
+mod support;
+
+fn run_lock(args: &[&str]) -> Output {
+    let mut cmd = support::lock_command("cache-e2e");
+    cmd.args(args);
+    cmd.output().expect("run lock binary")
+}
+
+/// Write `content` with an mtime old enough for the cache to accept it.
+fn write_old(path: &Path, content: &[u8]) {
+    fs::write(path, content).unwrap();
+    fs::File::options()
+        .write(true)
+        .open(path)
+        .unwrap()
+        .set_modified(SystemTime::now() - Duration::from_secs(60))
+        .unwrap();
+}
+
+/// A scanned two-file tree. Returns (dir, data root, lockfile, cache path).
+fn fixture() -> (TempDir, PathBuf, PathBuf, PathBuf) {
+    let dir = TempDir::new().unwrap();
+    let root = dir.path().join("data");
+    fs::create_dir_all(&root).unwrap();
+    write_old(&root.join("a.csv"), b"alpha");
+    write_old(&root.join("b.csv"), b"bravo");
+
+    let lockfile = dir.path().join("dec.lock.json");
+    let output = run_lock(&[
+        "scan",
+        root.to_str().unwrap(),
+        "--output",
+        lockfile.to_str().unwrap(),
+        "--no-witness",
+    ]);
+    assert_eq!(output.status.code(), Some(0));
+
+    let cache = dir.path().join("hash.cache");
+    (dir, root, lockfile, cache)
+}
+
+fn verify_with_cache(lockfile: &Path, root: &Path, cache: &Path) -> (Option<i32>, Value) {
+    let output = run_lock(&[
+        "verify",
+        lockfile.to_str().unwrap(),
+        "--root",
+        root.to_str().unwrap(),
+        "--cache",
+        cache.to_str().unwrap(),
+        "--json",
+        "--no-witness",
+    ]);
+    let report = serde_json::from_slice(&output.stdout).unwrap();
+    (output.status.code(), report)
+}
+
+#[test]
+fn second_verify_reports_cache_hits() {
+    let (_dir, root, lockfile, cache) = fixture();
+
+    let (code, first) = verify_with_cache(&lockfile, &root, &cache);
+    assert_eq!(code, Some(0));
+    assert_eq!(first["members"]["cache_hits"], serde_json::json!([]));
+    assert!(cache.exists());
+
+    let (code, second) = verify_with_cache(&lockfile, &root, &cache);
+    assert_eq!(code, Some(0));
+    assert_eq!(second["members"]["verified"], 2);
+    assert_eq!(
+        second["members"]["cache_hits"],
+        serde_json::json!(["a.csv", "b.csv"])
+    );
+}
+
+#[test]
+fn changed_file_is_rehashed_not_served_from_cache() {
+    let (_dir, root, lockfile, cache) = fixture();
+    verify_with_cache(&lockfile, &root, &cache);
+
+    write_old(&root.join("b.csv"), b"BRAVO");
+    let (code, report) = verify_with_cache(&lockfile, &root, &cache);
+    assert_eq!(code, Some(1));
+    assert_eq!(
+        report["members"]["cache_hits"],
+        serde_json::json!(["a.csv"])
+    );
+    assert_eq!(report["members"]["failures"][0]["path"], "b.csv");
+    assert_eq!(report["members"]["failures"][0]["reason"], "HASH_MISMATCH");
+}
+
+#[test]
+fn corrupt_cache_refuses() {
+    let (_dir, root, lockfile, cache) = fixture();
+    fs::write(&cache, "{").unwrap();
+
+    let (code, report) = verify_with_cache(&lockfile, &root, &cache);
+    assert_eq!(code, Some(2));
+    assert_eq!(report["refusal"]["code"], "E_IO");
+}
+
+#[test]
+fn cache_actions_refuse_corrupt_cache_as_json() {
+    let (_dir, _root, _lockfile, cache) = fixture();
+    fs::write(&cache, "{").unwrap();
+    let cache_arg = cache.to_str().unwrap();
+
+    for action in ["stats", "prune"] {
+        let output = run_lock(&["cache", action, cache_arg, "--json"]);
+        assert_eq!(output.status.code(), Some(2), "{action}");
+        let refusal: Value = serde_json::from_slice(&output.stdout).unwrap();
+        assert_eq!(refusal["version"], "lock-cache.v0");
+        assert_eq!(refusal["outcome"], "REFUSAL");
+        assert_eq!(refusal["refusal"]["code"], "E_IO");
+        assert_eq!(refusal["refusal"]["detail"]["path"], cache_arg);
+    }
+
+    let output = run_lock(&["cache", "stats", cache_arg]);
+    assert_eq!(output.status.code(), Some(2));
+    assert!(output.stdout.is_empty());
+    assert!(String::from_utf8_lossy(&output.stderr).contains("cannot read hash cache"));
+}
+
+#[test]
+fn cache_stats_prune_and_clear() {
+    let (_dir, root, lockfile, cache) = fixture();
+    verify_with_cache(&lockfile, &root, &cache);
+    let cache_arg = cache.to_str().unwrap();
+
+    let stats = run_lock(&["cache", "stats", cache_arg, "--json"]);
+    assert_eq!(stats.status.code(), Some(0));
+    let stats: Value = serde_json::from_slice(&stats.stdout).unwrap();
+    assert_eq!(stats["version"], "lock-cache.v0");
+    assert_eq!(stats["entries"], 2);
+    assert_eq!(stats["by_algorithm"]["sha256"], 2);
+
+    fs::remove_file(root.join("a.csv")).unwrap();
+    let prune = run_lock(&["cache", "prune", cache_arg, "--json"]);
+    assert_eq!(prune.status.code(), Some(0));
+    let prune: Value = serde_json::from_slice(&prune.stdout).unwrap();
+    assert_eq!(prune["version"], "lock-cache.v0");
+    assert_eq!(prune["removed"], 1);
+    assert_eq!(prune["entries"], 1);
+
+    let clear = run_lock(&["cache", "clear", cache_arg, "--json"]);
+    assert_eq!(clear.status.code(), Some(0));
+    let clear: Value = serde_json::from_slice(&clear.stdout).unwrap();
+    assert_eq!(clear["version"], "lock-cache.v0");
+    assert_eq!(clear["cleared"], true);
+    assert!(!cache.exists());
+
+    let clear = run_lock(&["cache", "clear", cache_arg]);
+    assert_eq!(clear.status.code(), Some(0));
+    assert!(String::from_utf8_lossy(&clear.stdout).contains("already empty"));
+
+    let stats = run_lock(&["cache", "stats", cache_arg, "--json"]);
+    let stats: Value = serde_json::from_slice(&stats.stdout).unwrap();
+    assert_eq!(stats["entries"], 0);
+}
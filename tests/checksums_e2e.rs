@@ -0,0 +1,398 @@
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::process::Output;
+
+use serde_json::Value;
+use tempfile::TempDir;
+
+mod support;
+
+fn run_lock(args: &[&str]) -> Output {
+    let mut cmd = support::lock_command("checksums-e2e");
+    cmd.args(args);
+    cmd.output().expect("run lock binary")
+}
+
+fn read_json(path: &Path) -> Value {
+    serde_json::from_str(&fs::read_to_string(path).unwrap()).unwrap()
+}
+
+/// A scanned two-file tree. Returns (dir, data root, lockfile).
+fn fixture() -> (TempDir, PathBuf, PathBuf) {
+    let dir = TempDir::new().unwrap();
+    let root = dir.path().join("data");
+    fs::create_dir_all(root.join("sub")).unwrap();
+    fs::write(root.join("a.csv"), b"alpha").unwrap();
+    fs::write(root.join("sub/b.csv"), b"bravo").unwrap();
+
+    let lockfile = dir.path().join("dec.lock.json");
+    let output = run_lock(&[
+        "scan",
+        root.to_str().unwrap(),
+        "--output",
+        lockfile.to_str().unwrap(),
+        "--no-witness",
+    ]);
+    assert_eq!(output.status.code(), Some(0));
+    (dir, root, lockfile)
+}
+
+fn export(lockfile: &Path, format: &str) -> Output {
+    run_lock(&[
+        "export",
+        lockfile.to_str().unwrap(),
+        "--format",
+        format,
+        "--no-witness",
+    ])
+}
+
+#[test]
+fn sha256sum_export_imports_back_to_the_same_members() {
+    let (dir, root, lockfile) = fixture();
+    let output = export(&lockfile, "sha256sum");
+    assert_eq!(output.status.code(), Some(0));
+    let manifest = String::from_utf8(output.stdout).unwrap();
+    let lines: Vec<&str> = manifest.lines().collect();
+    assert_eq!(lines.len(), 2);
+    assert!(lines[0].ends_with("  a.csv"), "{manifest}");
+    assert!(lines[1].ends_with("  sub/b.csv"), "{manifest}");
+
+    let manifest_path = dir.path().join("SHA256SUMS");
+    fs::write(&manifest_path, &manifest).unwrap();
+    let imported_path = dir.path().join("imported.lock.json");
+    let output = run_lock(&[
+        "import",
+        manifest_path.to_str().unwrap(),
+        "--format",
+        "sha256sum",
+        "--root",
+        root.to_str().unwrap(),
+        "--output",
+        imported_path.to_str().unwrap(),
+        "--no-witness",
+    ]);
+    assert_eq!(output.status.code(), Some(0));
+
+    let imported = read_json(&imported_path);
+    let scanned = read_json(&lockfile);
+    assert_eq!(imported["version"], "lock.v0");
+    assert_eq!(imported["members"], scanned["members"]);
+
+    let output = run_lock(&[
+        "verify",
+        imported_path.to_str().unwrap(),
+        "--root",
+        root.to_str().unwrap(),
+        "--no-witness",
+    ]);
+    assert_eq!(output.status.code(), Some(0));
+}
+
+#[test]
+fn import_skips_listed_files_missing_from_root() {
+    let (dir, root, lockfile) = fixture();
+    let manifest_path = dir.path().join("SHA256SUMS");
+    fs::write(&manifest_path, export(&lockfile, "sha256sum").stdout).unwrap();
+    fs::remove_file(root.join("sub/b.csv")).unwrap();
+
+    let output = run_lock(&[
+        "import",
+        manifest_path.to_str().unwrap(),
+        "--format",
+        "sha256sum",
+        "--root",
+        root.to_str().unwrap(),
+        "--no-witness",
+    ]);
+    assert_eq!(output.status.code(), Some(1));
+    let imported: Value = serde_json::from_slice(&output.stdout).unwrap();
+    assert_eq!(imported["member_count"], 1);
+    assert_eq!(imported["skipped"][0]["path"], "sub/b.csv");
+}
+
+#[test]
+fn import_refuses_files_that_do_not_match_their_digests() {
+    let (dir, root, lockfile) = fixture();
+    let manifest_path = dir.path().join("SHA256SUMS");
+    fs::write(&manifest_path, export(&lockfile, "sha256sum").stdout).unwrap();
+    fs::write(root.join("sub/b.csv"), b"BRAVO").unwrap();
+
+    let output = run_lock(&[
+        "import",
+        manifest_path.to_str().unwrap(),
+        "--format",
+        "sha256sum",
+        "--root",
+        root.to_str().unwrap(),
+        "--no-witness",
+    ]);
+    assert_eq!(output.status.code(), Some(2));
+    let refusal: Value = serde_json::from_slice(&output.stdout).unwrap();
+    assert_eq!(refusal["refusal"]["code"], "E_DRIFT");
+    assert_eq!(
+        refusal["refusal"]["detail"]["drifted"],
+        serde_json::json!([{ "path": "sub/b.csv", "reason": "HASH_MISMATCH" }])
+    );
+}
+
+#[test]
+fn import_refuses_malformed_lines() {
+    let (dir, root, _lockfile) = fixture();
+    let manifest_path = dir.path().join("SHA256SUMS");
+    fs::write(&manifest_path, "not a checksum line\n").unwrap();
+
+    let output = run_lock(&[
+        "import",
+        manifest_path.to_str().unwrap(),
+        "--format",
+        "sha256sum",
+        "--root",
+        root.to_str().unwrap(),
+        "--no-witness",
+    ]);
+    assert_eq!(output.status.code(), Some(2));
+    let refusal: Value = serde_json::from_slice(&output.stdout).unwrap();
+    assert_eq!(refusal["refusal"]["code"], "E_BAD_INPUT");
+    assert_eq!(refusal["refusal"]["detail"]["line"], 1);
+}
+
+#[test]
+fn import_refuses_paths_outside_root_and_duplicates() {
+    let (dir, root, _lockfile) = fixture();
+    let manifest_path = dir.path().join("SHA256SUMS");
+    let hex = "0".repeat(64);
+    for (manifest, line) in [
+        (format!("{hex}  /etc/hostname\n"), 1),
+        (format!("{hex}  a.csv\n{hex}  ../dec.lock.json\n"), 2),
+        (format!("{hex}  sub/../../dec.lock.json\n"), 1),
+        (
+            format!("{hex}  a.csv\n{hex}  sub/b.csv\n{hex}  ./a.csv\n"),
+            3,
+        ),
+    ] {
+        fs::write(&manifest_path, &manifest).unwrap();
+        let output = run_lock(&[
+            "import",
+            manifest_path.to_str().unwrap(),
+            "--format",
+            "sha256sum",
+            "--root",
+            root.to_str().unwrap(),
+            "--no-witness",
+        ]);
+        assert_eq!(output.status.code(), Some(2), "{manifest}");
+        let refusal: Value = serde_json::from_slice(&output.stdout).unwrap();
+        assert_eq!(refusal["refusal"]["code"], "E_BAD_INPUT", "{manifest}");
+        assert_eq!(refusal["refusal"]["detail"]["line"], line, "{manifest}");
+    }
+}
+
+#[test]
+fn export_refuses_tampered_lockfile_and_missing_digests() {
+    let (_dir, _root, lockfile) = fixture();
+
+    let output = export(&lockfile, "b3sum");
+    assert_eq!(output.status.code(), Some(2));
+    let refusal: Value = serde_json::from_slice(&output.stdout).unwrap();
+    assert_eq!(refusal["refusal"]["code"], "E_NO_DIGEST");
+    assert_eq!(
+        refusal["refusal"]["detail"]["paths"],
+        serde_json::json!(["a.csv", "sub/b.csv"])
+    );
+
+    let mut tampered = read_json(&lockfile);
+    tampered["members"][0]["size"] = 6.into();
+    fs::write(&lockfile, tampered.to_string()).unwrap();
+    let output = export(&lockfile, "csv");
+    assert_eq!(output.status.code(), Some(2));
+    let refusal: Value = serde_json::from_slice(&output.stdout).unwrap();
+    assert_eq!(refusal["refusal"]["code"], "E_TAMPERED");
+}
+
+#[test]
+fn bsd_csv_and_jsonl_list_every_member() {
+    let (_dir, _root, lockfile) = fixture();
+    let scanned = read_json(&lockfile);
+    let hash_a = scanned["members"][0]["bytes_hash"].as_str().unwrap();
+
+    let bsd = String::from_utf8(export(&lockfile, "bsd").stdout).unwrap();
+    assert_eq!(
+        bsd.lines().next().unwrap(),
+        format!("SHA256 (a.csv) = {}", &hash_a["sha256:".len()..])
+    );
+
+    let csv = String::from_utf8(export(&lockfile, "csv").stdout).unwrap();
+    assert_eq!(
+        csv.lines().collect::<Vec<_>>(),
+        [
+            "path,size,bytes_hash".to_string(),
+            format!("a.csv,5,{hash_a}"),
+            format!(
+                "sub/b.csv,5,{}",
+                scanned["members"][1]["bytes_hash"].as_str().unwrap()
+            ),
+        ]
+    );
+
+    let jsonl = String::from_utf8(export(&lockfile, "jsonl").stdout).unwrap();
+    let first: Value = serde_json::from_str(jsonl.lines().next().unwrap()).unwrap();
+    assert_eq!(
+        first,
+        serde_json::json!({ "path": "a.csv", "size": 5, "bytes_hash": hash_a })
+    );
+}
+
+/// Export `lockfile` as a bag under `dir/bag`, with the payload copied in.
+fn export_bag(dir: &Path, root: &Path, lockfile: &Path) -> PathBuf {
+    let bag = dir.join("bag");
+    let output = run_lock(&[
+        "export",
+        lockfile.to_str().unwrap(),
+        "--format",
+        "bagit",
+        "--root",
+        bag.to_str().unwrap(),
+        "--no-witness",
+    ]);
+    assert_eq!(output.status.code(), Some(0));
+    assert!(output.stdout.is_empty());
+    fs::create_dir_all(bag.join("data/sub")).unwrap();
+    fs::copy(root.join("a.csv"), bag.join("data/a.csv")).unwrap();
+    fs::copy(root.join("sub/b.csv"), bag.join("data/sub/b.csv")).unwrap();
+    bag
+}
+
+fn import_bag(bag: &Path) -> Output {
+    run_lock(&[
+        "import",
+        bag.to_str().unwrap(),
+        "--format",
+        "bagit",
+        "--no-witness",
+    ])
+}
+
+#[test]
+fn bagit_export_imports_back_with_metadata() {
+    let dir = TempDir::new().unwrap();
+    let root = dir.path().join("data");
+    fs::create_dir_all(root.join("sub")).unwrap();
+    fs::write(root.join("a.csv"), b"alpha").unwrap();
+    fs::write(root.join("sub/b.csv"), b"bravo").unwrap();
+    let lockfile = dir.path().join("dec.lock.json");
+    let output = run_lock(&[
+        "scan",
+        root.to_str().unwrap(),
+        "--dataset-id",
+        "raw-dec",
+        "--note",
+        "December delivery",
+        "--output",
+        lockfile.to_str().unwrap(),
+        "--no-witness",
+    ]);
+    assert_eq!(output.status.code(), Some(0));
+
+    let bag = export_bag(dir.path(), &root, &lockfile);
+    let manifest = fs::read_to_string(bag.join("manifest-sha256.txt")).unwrap();
+    assert!(manifest.contains("  data/sub/b.csv\n"), "{manifest}");
+    let info = fs::read_to_string(bag.join("bag-info.txt")).unwrap();
+    assert!(info.contains("External-Identifier: raw-dec\n"), "{info}");
+    assert!(info.contains("Payload-Oxum: 10.2\n"), "{info}");
+    assert!(bag.join("tagmanifest-sha256.txt").exists());
+
+    let output = import_bag(&bag);
+    assert_eq!(output.status.code(), Some(0));
+    let imported: Value = serde_json::from_slice(&output.stdout).unwrap();
+    let scanned = read_json(&lockfile);
+    assert_eq!(imported["members"], scanned["members"]);
+    assert_eq!(imported["dataset_id"], "raw-dec");
+    assert_eq!(imported["note"], "December delivery");
+}
+
+#[test]
+fn bagit_import_refuses_payload_oxum_mismatch() {
+    let (dir, root, lockfile) = fixture();
+    let bag = export_bag(dir.path(), &root, &lockfile);
+    fs::write(bag.join("data/a.csv"), b"alpha!").unwrap();
+
+    let output = import_bag(&bag);
+    assert_eq!(output.status.code(), Some(2));
+    let refusal: Value = serde_json::from_slice(&output.stdout).unwrap();
+    assert_eq!(refusal["refusal"]["code"], "E_OXUM_MISMATCH");
+    assert_eq!(refusal["refusal"]["detail"]["declared"], "10.2");
+    assert_eq!(refusal["refusal"]["detail"]["actual"], "11.2");
+
+    fs::remove_file(bag.join("bagit.txt")).unwrap();
+    let output = import_bag(&bag);
+    assert_eq!(output.status.code(), Some(2));
+    let refusal: Value = serde_json::from_slice(&output.stdout).unwrap();
+    assert_eq!(refusal["refusal"]["code"], "E_BAD_INPUT");
+}
+
+#[test]
+fn bagit_import_counts_only_payload_files_present_against_oxum() {
+    let (dir, root, lockfile) = fixture();
+    let bag = export_bag(dir.path(), &root, &lockfile);
+    fs::remove_file(bag.join("data/sub/b.csv")).unwrap();
+
+    let output = import_bag(&bag);
+    assert_eq!(output.status.code(), Some(2));
+    let refusal: Value = serde_json::from_slice(&output.stdout).unwrap();
+    assert_eq!(refusal["refusal"]["code"], "E_OXUM_MISMATCH");
+    assert_eq!(refusal["refusal"]["detail"]["declared"], "10.2");
+    assert_eq!(refusal["refusal"]["detail"]["actual"], "5.1");
+}
+
+#[test]
+fn bagit_import_refuses_payload_that_does_not_match_its_digests() {
+    let (dir, root, lockfile) = fixture();
+    let bag = export_bag(dir.path(), &root, &lockfile);
+    fs::write(bag.join("data/a.csv"), b"ALPHA").unwrap();
+
+    let output = import_bag(&bag);
+    assert_eq!(output.status.code(), Some(2));
+    let refusal: Value = serde_json::from_slice(&output.stdout).unwrap();
+    assert_eq!(refusal["refusal"]["code"], "E_DRIFT");
+    assert_eq!(refusal["refusal"]["detail"]["drifted"][0]["path"], "a.csv");
+}
+
+#[test]
+fn bagit_import_refuses_root() {
+    let (dir, root, lockfile) = fixture();
+    let bag = export_bag(dir.path(), &root, &lockfile);
+
+    let output = run_lock(&[
+        "import",
+        bag.to_str().unwrap(),
+        "--format",
+        "bagit",
+        "--root",
+        root.to_str().unwrap(),
+        "--no-witness",
+    ]);
+    assert_eq!(output.status.code(), Some(2));
+    let refusal: Value = serde_json::from_slice(&output.stdout).unwrap();
+    assert_eq!(refusal["refusal"]["code"], "E_BAD_INPUT");
+}
+
+#[test]
+fn bagit_import_refuses_manifest_paths_outside_data() {
+    let (dir, root, lockfile) = fixture();
+    let bag = export_bag(dir.path(), &root, &lockfile);
+    let manifest_path = bag.join("manifest-sha256.txt");
+    let manifest = fs::read_to_string(&manifest_path).unwrap();
+    let hex = manifest.split_once("  ").unwrap().0.to_owned();
+
+    for extra in [
+        format!("{hex}  data/../../dec.lock.json\n"),
+        format!("{hex}  data/a.csv\n"),
+    ] {
+        fs::write(&manifest_path, format!("{manifest}{extra}")).unwrap();
+        let output = import_bag(&bag);
+        assert_eq!(output.status.code(), Some(2), "{extra}");
+        let refusal: Value = serde_json::from_slice(&output.stdout).unwrap();
+        assert_eq!(refusal["refusal"]["code"], "E_BAD_INPUT", "{extra}");
+    }
+}
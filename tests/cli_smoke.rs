@@ -197,6 +197,223 @@ fn smoke_witness_query_input_hash_filter_works_for_real_runs() {
     assert_eq!(items[0]["outcome"], "LOCK_CREATED");
 }
 
+#[test]
+fn smoke_witness_find_output_ties_lockfile_to_its_run() {
+    let dir = tempfile::tempdir().unwrap();
+    let ledger = dir.path().join("witness.jsonl");
+    let input = write_manifest(
+        &dir,
+        "created.jsonl",
+        r#"{"version":"hash.v0","relative_path":"a.csv","bytes_hash":"sha256:aaaaaaaa","size":10}
+"#,
+    );
+    let lockfile = dir.path().join("dec.lock.json");
+    let output = run_lock(
+        &[
+            input.to_str().unwrap(),
+            "--dataset-id",
+            "raw-dec",
+            "--output",
+            lockfile.to_str().unwrap(),
+        ],
+        Some(&ledger),
+    );
+    assert_eq!(output.status.code(), Some(0));
+
+    let output = run_lock(
+        &[
+            "witness",
+            "find-output",
+            lockfile.to_str().unwrap(),
+            "--json",
+        ],
+        Some(&ledger),
+    );
+    assert_eq!(output.status.code(), Some(0));
+    let parsed: Value = serde_json::from_slice(&output.stdout).unwrap();
+    let items = parsed.as_array().unwrap();
+    assert_eq!(items.len(), 1);
+    assert_eq!(items[0]["outcome"], "LOCK_CREATED");
+    assert_eq!(items[0]["params"]["dataset_id"], "raw-dec");
+    assert_eq!(items[0]["inputs"][0]["path"], input.to_str().unwrap());
+
+    let output = run_lock(
+        &["witness", "find-output", input.to_str().unwrap(), "--json"],
+        Some(&ledger),
+    );
+    assert_eq!(output.status.code(), Some(1));
+    let parsed: Value = serde_json::from_slice(&output.stdout).unwrap();
+    assert_eq!(parsed, serde_json::json!([]));
+}
+
+#[test]
+fn smoke_witness_query_filters_verify_runs_and_pages() {
+    let dir = tempfile::tempdir().unwrap();
+    let ledger = dir.path().join("witness.jsonl");
+    let input = write_manifest(
+        &dir,
+        "created.jsonl",
+        r#"{"version":"hash.v0","relative_path":"a.csv","bytes_hash":"sha256:aaaaaaaa","size":10}
+"#,
+    );
+    let lockfile = dir.path().join("dec.lock.json");
+    let output = run_lock(
+        &[
+            input.to_str().unwrap(),
+            "--dataset-id",
+            "raw-dec",
+            "--output",
+            lockfile.to_str().unwrap(),
+        ],
+        Some(&ledger),
+    );
+    assert_eq!(output.status.code(), Some(0));
+    let root = dir.path().to_str().unwrap();
+    for _ in 0..2 {
+        run_lock(
+            &["verify", lockfile.to_str().unwrap(), "--root", root],
+            Some(&ledger),
+        );
+    }
+
+    let query = |args: &[&str]| {
+        let mut full = vec!["witness", "query", "--json"];
+        full.extend_from_slice(args);
+        let output = run_lock(&full, Some(&ledger));
+        serde_json::from_slice::<Value>(&output.stdout).unwrap()
+    };
+    let verify_runs = query(&["--subcommand", "verify", "--lockfile", "dec.lock.json"]);
+    assert_eq!(verify_runs.as_array().unwrap().len(), 2);
+    let root_param = format!("root={root}");
+    assert_eq!(query(&["--param", &root_param]), verify_runs);
+
+    let created = query(&["--dataset-id", "raw-dec"]);
+    assert_eq!(created.as_array().unwrap().len(), 1);
+    assert_eq!(created[0]["outcome"], "LOCK_CREATED");
+    assert_eq!(query(&["--subcommand", "lock"]), created);
+
+    let exit_code = verify_runs[0]["exit_code"].to_string();
+    assert_eq!(query(&["--exit-code", &exit_code]), verify_runs);
+
+    let oldest = query(&["--order", "asc", "--limit", "1"]);
+    assert_eq!(oldest, created);
+    let rest = query(&["--order", "asc", "--offset", "1"]);
+    assert_eq!(rest.as_array().unwrap().len(), 2);
+    assert_eq!(rest[0]["params"]["subcommand"], "verify");
+    assert_eq!(query(&["--offset", "3"]), serde_json::json!([]));
+}
+
+#[test]
+fn smoke_witness_rotate_keeps_history_queryable() {
+    let dir = tempfile::tempdir().unwrap();
+    let ledger = dir.path().join("witness.jsonl");
+    let input = write_manifest(
+        &dir,
+        "created.jsonl",
+        r#"{"version":"hash.v0","relative_path":"a.csv","bytes_hash":"sha256:aaaaaaaa","size":10}
+"#,
+    );
+    let run = || {
+        let output = run_lock(&[input.to_str().unwrap()], Some(&ledger));
+        assert_eq!(output.status.code(), Some(0));
+    };
+    run();
+    run();
+
+    let output = run_lock(&["witness", "rotate", "--json"], Some(&ledger));
+    assert_eq!(output.status.code(), Some(0));
+    let rotated: Value = serde_json::from_slice(&output.stdout).unwrap();
+    assert_eq!(rotated["outcome"], "ROTATED");
+    assert_eq!(rotated["records"], 2);
+    assert!(dir.path().join("witness.jsonl.000001.gz").is_file());
+    assert_eq!(fs::read_to_string(&ledger).unwrap(), "");
+
+    let output = run_lock(&["witness", "rotate"], Some(&ledger));
+    assert_eq!(output.status.code(), Some(1));
+
+    run();
+    let output = run_lock(&["witness", "count", "--json"], Some(&ledger));
+    let parsed: Value = serde_json::from_slice(&output.stdout).unwrap();
+    assert_eq!(parsed["count"], 3);
+
+    let output = run_lock(&["witness", "verify", "--json"], Some(&ledger));
+    assert_eq!(output.status.code(), Some(0));
+    let report: Value = serde_json::from_slice(&output.stdout).unwrap();
+    assert_eq!(report["segments"], 1);
+    assert_eq!(report["chained"], 3);
+}
+
+#[test]
+fn smoke_witness_reindex_keeps_query_results() {
+    let dir = tempfile::tempdir().unwrap();
+    let ledger = dir.path().join("witness.jsonl");
+    let input = write_manifest(
+        &dir,
+        "created.jsonl",
+        r#"{"version":"hash.v0","relative_path":"a.csv","bytes_hash":"sha256:aaaaaaaa","size":10}
+"#,
+    );
+    let run = || {
+        let output = run_lock(&[input.to_str().unwrap()], Some(&ledger));
+        assert_eq!(output.status.code(), Some(0));
+    };
+    let query = || {
+        let output = run_lock(&["witness", "query", "--json"], Some(&ledger));
+        assert_eq!(output.status.code(), Some(0));
+        serde_json::from_slice::<Value>(&output.stdout).unwrap()
+    };
+    run();
+    run();
+    let scanned = query();
+
+    let output = run_lock(&["witness", "reindex", "--json"], Some(&ledger));
+    assert_eq!(output.status.code(), Some(0));
+    let reindexed: Value = serde_json::from_slice(&output.stdout).unwrap();
+    assert_eq!(reindexed["outcome"], "REINDEXED");
+    assert_eq!(reindexed["records"], 2);
+    assert!(dir.path().join("witness.jsonl.idx").is_file());
+    assert_eq!(query(), scanned);
+
+    run();
+    let indexed = query();
+    assert_eq!(indexed.as_array().unwrap().len(), 3);
+    fs::remove_file(dir.path().join("witness.jsonl.idx")).unwrap();
+    assert_eq!(query(), indexed);
+}
+
+#[test]
+fn smoke_witness_verify_detects_deleted_record() {
+    let dir = tempfile::tempdir().unwrap();
+    let ledger = dir.path().join("witness.jsonl");
+    let input = write_manifest(
+        &dir,
+        "created.jsonl",
+        r#"{"version":"hash.v0","relative_path":"a.csv","bytes_hash":"sha256:aaaaaaaa","size":10}
+"#,
+    );
+    for _ in 0..3 {
+        let output = run_lock(&[input.to_str().unwrap()], Some(&ledger));
+        assert_eq!(output.status.code(), Some(0));
+    }
+
+    let output = run_lock(&["witness", "verify", "--json"], Some(&ledger));
+    assert_eq!(output.status.code(), Some(0));
+    let report: Value = serde_json::from_slice(&output.stdout).unwrap();
+    assert_eq!(report["outcome"], "CHAIN_INTACT");
+    assert_eq!(report["chained"], 3);
+
+    let content = fs::read_to_string(&ledger).unwrap();
+    let lines: Vec<&str> = content.lines().collect();
+    fs::write(&ledger, format!("{}\n{}\n", lines[0], lines[2])).unwrap();
+
+    let output = run_lock(&["witness", "verify", "--json"], Some(&ledger));
+    assert_eq!(output.status.code(), Some(1));
+    let report: Value = serde_json::from_slice(&output.stdout).unwrap();
+    assert_eq!(report["outcome"], "CHAIN_BROKEN");
+    assert_eq!(report["issues"][0]["line"], 2);
+    assert_eq!(report["issues"][0]["code"], "BROKEN");
+}
+
 #[test]
 fn smoke_default_witness_migrates_legacy_home_ledger_before_append() {
     let dir = tempfile::tempdir().unwrap();
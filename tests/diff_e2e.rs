@@ -0,0 +1,187 @@
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::process::Output;
+
+use serde_json::Value;
+use sha2::{Digest, Sha256};
+use tempfile::TempDir;
+
+mod support;
+
+fn run_lock(args: &[&str], ledger_path: Option<&Path>) -> Output {
+    let mut cmd = support::lock_command("diff-e2e");
+    cmd.args(args);
+    if let Some(path) = ledger_path {
+        cmd.env("EPISTEMIC_WITNESS", path);
+    }
+    cmd.output().expect("run lock binary")
+}
+
+fn validate_diff_schema(instance: &Value) {
+    let schema: Value =
+        serde_json::from_str(include_str!("../schemas/lock-diff-v0.schema.json")).unwrap();
+    let validator = jsonschema::validator_for(&schema).expect("schema should compile");
+    let errors: Vec<String> = validator
+        .iter_errors(instance)
+        .map(|error| format!("{error} at {}", error.instance_path()))
+        .collect();
+    assert!(errors.is_empty(), "schema errors:\n{}", errors.join("\n"));
+}
+
+/// Lock a set of (path, content) records and write the lockfile to `dir/name`.
+fn lock_files(dir: &TempDir, name: &str, files: &[(&str, &[u8])]) -> PathBuf {
+    let manifest: Vec<String> = files
+        .iter()
+        .map(|(path, content)| {
+            serde_json::json!({
+                "version": "hash.v0",
+                "relative_path": path,
+                "bytes_hash": format!("sha256:{:x}", Sha256::digest(content)),
+                "size": content.len(),
+                "tool_versions": { "hash": "0.1.0" }
+            })
+            .to_string()
+        })
+        .collect();
+    let manifest_path = dir.path().join(format!("{name}.jsonl"));
+    fs::write(&manifest_path, manifest.join("\n") + "\n").unwrap();
+
+    let lockfile_path = dir.path().join(name);
+    let output = run_lock(
+        &[
+            manifest_path.to_str().unwrap(),
+            "--dataset-id",
+            "raw-dec",
+            "--output",
+            lockfile_path.to_str().unwrap(),
+            "--no-witness",
+        ],
+        None,
+    );
+    assert_eq!(output.status.code(), Some(0));
+    lockfile_path
+}
+
+#[test]
+fn diff_identical_lockfiles_exits_0() {
+    let dir = tempfile::tempdir().unwrap();
+    let old = lock_files(&dir, "old.lock.json", &[("a.csv", b"hello")]);
+    let new = lock_files(&dir, "new.lock.json", &[("a.csv", b"hello")]);
+
+    let output = run_lock(
+        &[
+            "diff",
+            old.to_str().unwrap(),
+            new.to_str().unwrap(),
+            "--json",
+            "--no-witness",
+        ],
+        None,
+    );
+    assert_eq!(output.status.code(), Some(0));
+    let report: Value = serde_json::from_slice(&output.stdout).unwrap();
+    assert_eq!(report["outcome"], "DIFF_IDENTICAL");
+    validate_diff_schema(&report);
+}
+
+#[test]
+fn diff_changed_lockfiles_exits_1_with_member_changes() {
+    let dir = tempfile::tempdir().unwrap();
+    let old = lock_files(
+        &dir,
+        "old.lock.json",
+        &[("a.csv", b"hello"), ("b.csv", b"bye")],
+    );
+    let new = lock_files(
+        &dir,
+        "new.lock.json",
+        &[("a.csv", b"hello, world"), ("c.csv", b"new")],
+    );
+
+    let output = run_lock(
+        &[
+            "diff",
+            old.to_str().unwrap(),
+            new.to_str().unwrap(),
+            "--json",
+            "--no-witness",
+        ],
+        None,
+    );
+    assert_eq!(output.status.code(), Some(1));
+    let report: Value = serde_json::from_slice(&output.stdout).unwrap();
+    assert_eq!(report["outcome"], "DIFF_CHANGED");
+    assert_eq!(report["members"]["added"][0]["path"], "c.csv");
+    assert_eq!(report["members"]["removed"][0]["path"], "b.csv");
+    assert_eq!(report["members"]["changed"][0]["path"], "a.csv");
+    assert_eq!(report["members"]["changed"][0]["size_delta"], 7);
+    validate_diff_schema(&report);
+}
+
+#[test]
+fn diff_tampered_lockfile_refuses_with_exit_2() {
+    let dir = tempfile::tempdir().unwrap();
+    let old = lock_files(&dir, "old.lock.json", &[("a.csv", b"hello")]);
+    let new = lock_files(&dir, "new.lock.json", &[("a.csv", b"hello")]);
+    let content = fs::read_to_string(&new).unwrap();
+    fs::write(&new, content.replace("raw-dec", "raw-jan")).unwrap();
+
+    let output = run_lock(
+        &[
+            "diff",
+            old.to_str().unwrap(),
+            new.to_str().unwrap(),
+            "--no-witness",
+        ],
+        None,
+    );
+    assert_eq!(output.status.code(), Some(2));
+    let refusal: Value = serde_json::from_slice(&output.stdout).unwrap();
+    assert_eq!(refusal["version"], "lock-diff.v0");
+    assert_eq!(refusal["refusal"]["code"], "E_TAMPERED");
+    assert_eq!(
+        refusal["refusal"]["detail"]["lockfile"],
+        new.to_str().unwrap()
+    );
+}
+
+#[test]
+fn diff_human_output_lists_changes() {
+    let dir = tempfile::tempdir().unwrap();
+    let old = lock_files(&dir, "old.lock.json", &[("a.csv", b"hello")]);
+    let new = lock_files(&dir, "new.lock.json", &[("b.csv", b"hello")]);
+
+    let output = run_lock(
+        &[
+            "diff",
+            old.to_str().unwrap(),
+            new.to_str().unwrap(),
+            "--no-witness",
+        ],
+        None,
+    );
+    assert_eq!(output.status.code(), Some(1));
+    let stdout = String::from_utf8(output.stdout).unwrap();
+    assert!(stdout.contains("+1 -1 ~0 members"));
+    assert!(stdout.contains("+ b.csv"));
+    assert!(stdout.contains("- a.csv"));
+}
+
+#[test]
+fn diff_appends_witness_record_with_subcommand() {
+    let dir = tempfile::tempdir().unwrap();
+    let ledger = dir.path().join("witness.jsonl");
+    let old = lock_files(&dir, "old.lock.json", &[("a.csv", b"hello")]);
+
+    let output = run_lock(
+        &["diff", old.to_str().unwrap(), old.to_str().unwrap()],
+        Some(&ledger),
+    );
+    assert_eq!(output.status.code(), Some(0));
+
+    let content = fs::read_to_string(&ledger).expect("ledger should exist");
+    let record: Value = serde_json::from_str(content.lines().last().unwrap()).unwrap();
+    assert_eq!(record["params"]["subcommand"], "diff");
+    assert_eq!(record["outcome"], "DIFF_IDENTICAL");
+    assert_eq!(record["inputs"].as_array().unwrap().len(), 2);
+}
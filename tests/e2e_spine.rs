@@ -50,6 +50,8 @@ fn run_spine(
         dataset_id: metadata.dataset_id,
         as_of: metadata.as_of,
         note: metadata.note,
+        parent: None,
+        members_root: None,
         created: "2026-01-15T10:30:00Z".to_owned(),
         tool_versions: metadata.tool_versions,
         profiles: metadata.profiles,
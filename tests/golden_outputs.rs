@@ -12,6 +12,8 @@ fn fixture_lockfile() -> Lockfile {
         dataset_id: Some("dataset-golden".to_owned()),
         as_of: Some("2026-01-31T00:00:00Z".to_owned()),
         note: Some("fixture note".to_owned()),
+        parent: None,
+        members_root: None,
         created: "2026-02-01T00:00:00Z".to_owned(),
         tool_versions: BTreeMap::from([
             ("fingerprint".to_owned(), "0.1.0".to_owned()),
@@ -43,12 +45,18 @@ fn fixture_lockfile() -> Lockfile {
                             .to_owned(),
                     ),
                 }),
+                mtime: None,
+                extra: None,
+                hashes: None,
             },
             Member {
                 path: "b.csv".to_owned(),
                 bytes_hash: "sha256:bbbbbbbb".to_owned(),
                 size: 20,
                 fingerprint: None,
+                mtime: None,
+                extra: None,
+                hashes: None,
             },
         ],
         skipped_count: 1,
@@ -55,6 +55,8 @@ fn run_pipeline(
         dataset_id: metadata.dataset_id,
         as_of: metadata.as_of,
         note: metadata.note,
+        parent: None,
+        members_root: None,
         created: "2026-01-15T10:00:00Z".to_owned(),
         tool_versions: metadata.tool_versions,
         profiles: metadata.profiles,
@@ -491,6 +493,8 @@ fn self_hash_round_trip_with_all_field_types() {
         dataset_id: Some("test-ds".to_owned()),
         as_of: Some("2026-01-01T00:00:00Z".to_owned()),
         note: Some("test note".to_owned()),
+        parent: None,
+        members_root: None,
         created: "2026-01-15T10:00:00Z".to_owned(),
         tool_versions,
         profiles: vec![],
@@ -514,12 +518,18 @@ fn self_hash_round_trip_with_all_field_types() {
                     matched: true,
                     content_hash: Some("blake3:cccc".to_owned()),
                 }),
+                mtime: None,
+                extra: None,
+                hashes: None,
             },
             lockfile::Member {
                 path: "beta.csv".to_owned(),
                 bytes_hash: "sha256:bbbb".to_owned(),
                 size: 200,
                 fingerprint: None,
+                mtime: None,
+                extra: None,
+                hashes: None,
             },
         ],
         skipped_count: 1,
@@ -609,6 +619,11 @@ fn run_lock_exit_0_for_valid_input() {
         as_of: None,
         note: None,
         output: None,
+        parent: None,
+        lock_version: "lock.v0".to_string(),
+        keep_mtime: false,
+        keep_fields: vec![],
+        keep_all_fields: false,
         no_witness: true,
         describe: false,
         schema: false,
@@ -640,6 +655,11 @@ fn run_lock_exit_1_for_partial_input() {
         as_of: None,
         note: None,
         output: None,
+        parent: None,
+        lock_version: "lock.v0".to_string(),
+        keep_mtime: false,
+        keep_fields: vec![],
+        keep_all_fields: false,
         no_witness: true,
         describe: false,
         schema: false,
@@ -659,6 +679,11 @@ fn run_lock_exit_2_for_missing_file() {
         as_of: None,
         note: None,
         output: None,
+        parent: None,
+        lock_version: "lock.v0".to_string(),
+        keep_mtime: false,
+        keep_fields: vec![],
+        keep_all_fields: false,
         no_witness: true,
         describe: false,
         schema: false,
@@ -682,6 +707,11 @@ fn run_lock_exit_2_for_empty_file() {
         as_of: None,
         note: None,
         output: None,
+        parent: None,
+        lock_version: "lock.v0".to_string(),
+        keep_mtime: false,
+        keep_fields: vec![],
+        keep_all_fields: false,
         no_witness: true,
         describe: false,
         schema: false,
@@ -709,6 +739,11 @@ fn run_lock_exit_2_for_bad_version() {
         as_of: None,
         note: None,
         output: None,
+        parent: None,
+        lock_version: "lock.v0".to_string(),
+        keep_mtime: false,
+        keep_fields: vec![],
+        keep_all_fields: false,
         no_witness: true,
         describe: false,
         schema: false,
@@ -736,6 +771,11 @@ fn run_lock_exit_2_for_missing_hash() {
         as_of: None,
         note: None,
         output: None,
+        parent: None,
+        lock_version: "lock.v0".to_string(),
+        keep_mtime: false,
+        keep_fields: vec![],
+        keep_all_fields: false,
         no_witness: true,
         describe: false,
         schema: false,
@@ -762,6 +802,11 @@ fn run_lock_exit_2_for_invalid_json() {
         as_of: None,
         note: None,
         output: None,
+        parent: None,
+        lock_version: "lock.v0".to_string(),
+        keep_mtime: false,
+        keep_fields: vec![],
+        keep_all_fields: false,
         no_witness: true,
         describe: false,
         schema: false,
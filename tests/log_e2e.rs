@@ -0,0 +1,270 @@
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::process::Output;
+
+use serde_json::Value;
+use sha2::{Digest, Sha256};
+use tempfile::TempDir;
+
+mod support;
+
+fn run_lock(args: &[&str], ledger_path: Option<&Path>) -> Output {
+    let mut cmd = support::lock_command("log-e2e");
+    cmd.args(args);
+    if let Some(path) = ledger_path {
+        cmd.env("EPISTEMIC_WITNESS", path);
+    }
+    cmd.output().expect("run lock binary")
+}
+
+fn validate_log_schema(instance: &Value) {
+    let schema: Value =
+        serde_json::from_str(include_str!("../schemas/lock-log-v0.schema.json")).unwrap();
+    let validator = jsonschema::validator_for(&schema).expect("schema should compile");
+    let errors: Vec<String> = validator
+        .iter_errors(instance)
+        .map(|error| format!("{error} at {}", error.instance_path()))
+        .collect();
+    assert!(errors.is_empty(), "schema errors:\n{}", errors.join("\n"));
+}
+
+/// Write a hash.v0 manifest for (path, content) records to `dir/name`.
+fn write_manifest(dir: &Path, name: &str, files: &[(&str, &[u8])]) -> PathBuf {
+    let manifest: Vec<String> = files
+        .iter()
+        .map(|(path, content)| {
+            serde_json::json!({
+                "version": "hash.v0",
+                "relative_path": path,
+                "bytes_hash": format!("sha256:{:x}", Sha256::digest(content)),
+                "size": content.len(),
+                "tool_versions": { "hash": "0.1.0" }
+            })
+            .to_string()
+        })
+        .collect();
+    let manifest_path = dir.join(name);
+    fs::write(&manifest_path, manifest.join("\n") + "\n").unwrap();
+    manifest_path
+}
+
+/// Lock `files` into `locks/<name>.lock.json`, optionally with `--parent`.
+fn lock_into(
+    dir: &TempDir,
+    name: &str,
+    files: &[(&str, &[u8])],
+    parent: Option<&Path>,
+) -> (PathBuf, Value) {
+    let locks = dir.path().join("locks");
+    fs::create_dir_all(&locks).unwrap();
+    let manifest = write_manifest(dir.path(), &format!("{name}.jsonl"), files);
+    let lockfile_path = locks.join(format!("{name}.lock.json"));
+
+    let mut args = vec![
+        manifest.to_str().unwrap().to_string(),
+        "--dataset-id".to_string(),
+        "raw".to_string(),
+        "--output".to_string(),
+        lockfile_path.to_str().unwrap().to_string(),
+        "--no-witness".to_string(),
+    ];
+    if let Some(parent) = parent {
+        args.push("--parent".to_string());
+        args.push(parent.to_str().unwrap().to_string());
+    }
+    let args: Vec<&str> = args.iter().map(String::as_str).collect();
+    let output = run_lock(&args, None);
+    assert_eq!(
+        output.status.code(),
+        Some(0),
+        "stdout: {}",
+        String::from_utf8_lossy(&output.stdout)
+    );
+
+    let lockfile = serde_json::from_str(&fs::read_to_string(&lockfile_path).unwrap()).unwrap();
+    (lockfile_path, lockfile)
+}
+
+fn three_generations(dir: &TempDir) -> [(PathBuf, Value); 3] {
+    let oct = lock_into(dir, "oct", &[("a.csv", b"a")], None);
+    let nov = lock_into(
+        dir,
+        "nov",
+        &[("a.csv", b"a2"), ("b.csv", b"b")],
+        Some(&oct.0),
+    );
+    let dec = lock_into(dir, "dec", &[("b.csv", b"b")], Some(&nov.0));
+    [oct, nov, dec]
+}
+
+#[test]
+fn parent_flag_records_parent_lock_hash() {
+    let dir = TempDir::new().unwrap();
+    let (oct_path, oct) = lock_into(&dir, "oct", &[("a.csv", b"a")], None);
+    let (_, nov) = lock_into(&dir, "nov", &[("a.csv", b"a")], Some(&oct_path));
+
+    assert!(oct.get("parent").is_none());
+    assert_eq!(nov["parent"], oct["lock_hash"]);
+}
+
+#[test]
+fn tampered_parent_is_refused() {
+    let dir = TempDir::new().unwrap();
+    let (oct_path, _) = lock_into(&dir, "oct", &[("a.csv", b"a")], None);
+    let tampered = fs::read_to_string(&oct_path)
+        .unwrap()
+        .replace("\"raw\"", "\"raw-tampered\"");
+    fs::write(&oct_path, tampered).unwrap();
+
+    let manifest = write_manifest(dir.path(), "nov.jsonl", &[("a.csv", b"a")]);
+    let output = run_lock(
+        &[
+            manifest.to_str().unwrap(),
+            "--parent",
+            oct_path.to_str().unwrap(),
+            "--no-witness",
+        ],
+        None,
+    );
+
+    assert_eq!(output.status.code(), Some(2));
+    let refusal: Value = serde_json::from_slice(&output.stdout).unwrap();
+    assert_eq!(refusal["outcome"], "REFUSAL");
+    assert_eq!(refusal["refusal"]["code"], "E_TAMPERED");
+}
+
+#[test]
+fn log_walks_chain_to_root_with_member_counts() {
+    let dir = TempDir::new().unwrap();
+    let [oct, nov, dec] = three_generations(&dir);
+    // Files that are not lockfiles are ignored.
+    fs::write(dir.path().join("locks/notes.json"), "{\"hello\": 1}").unwrap();
+
+    let output = run_lock(
+        &[
+            "log",
+            dec.0.to_str().unwrap(),
+            "--search-dir",
+            dir.path().to_str().unwrap(),
+            "--json",
+            "--no-witness",
+        ],
+        None,
+    );
+
+    assert_eq!(output.status.code(), Some(0));
+    let report: Value = serde_json::from_slice(&output.stdout).unwrap();
+    validate_log_schema(&report);
+    assert_eq!(report["outcome"], "LOG_COMPLETE");
+    assert_eq!(report["missing_parent"], Value::Null);
+
+    let entries = report["entries"].as_array().unwrap();
+    assert_eq!(entries.len(), 3);
+    assert_eq!(entries[0]["lock_hash"], dec.1["lock_hash"]);
+    assert_eq!(entries[1]["lock_hash"], nov.1["lock_hash"]);
+    assert_eq!(entries[2]["lock_hash"], oct.1["lock_hash"]);
+    assert_eq!(
+        entries[0]["changes"],
+        serde_json::json!({ "added": 0, "removed": 1, "changed": 0 })
+    );
+    assert_eq!(
+        entries[1]["changes"],
+        serde_json::json!({ "added": 1, "removed": 0, "changed": 1 })
+    );
+    assert_eq!(entries[2]["changes"], Value::Null);
+    assert_eq!(entries[2]["path"], oct.0.display().to_string());
+}
+
+#[test]
+fn log_reports_missing_parent_as_broken() {
+    let dir = TempDir::new().unwrap();
+    let [_, nov, dec] = three_generations(&dir);
+    fs::remove_file(&nov.0).unwrap();
+
+    let output = run_lock(
+        &[
+            "log",
+            dec.0.to_str().unwrap(),
+            "--search-dir",
+            dir.path().to_str().unwrap(),
+            "--json",
+            "--no-witness",
+        ],
+        None,
+    );
+
+    assert_eq!(output.status.code(), Some(1));
+    let report: Value = serde_json::from_slice(&output.stdout).unwrap();
+    validate_log_schema(&report);
+    assert_eq!(report["outcome"], "LOG_BROKEN");
+    assert_eq!(report["missing_parent"], nov.1["lock_hash"]);
+    assert_eq!(report["entries"].as_array().unwrap().len(), 1);
+}
+
+#[test]
+fn log_human_output_lists_history() {
+    let dir = TempDir::new().unwrap();
+    let [_, _, dec] = three_generations(&dir);
+
+    let output = run_lock(
+        &[
+            "log",
+            dec.0.to_str().unwrap(),
+            "--search-dir",
+            dir.path().to_str().unwrap(),
+            "--no-witness",
+        ],
+        None,
+    );
+
+    assert_eq!(output.status.code(), Some(0));
+    let stdout = String::from_utf8(output.stdout).unwrap();
+    assert!(stdout.contains("3 lockfile(s) back to root"), "{stdout}");
+    assert!(stdout.contains("(1 members, +0 -1 ~0)"), "{stdout}");
+    assert!(stdout.contains("(1 members, root)"), "{stdout}");
+}
+
+#[test]
+fn log_refuses_missing_search_dir() {
+    let dir = TempDir::new().unwrap();
+    let (oct_path, _) = lock_into(&dir, "oct", &[("a.csv", b"a")], None);
+
+    let output = run_lock(
+        &[
+            "log",
+            oct_path.to_str().unwrap(),
+            "--search-dir",
+            dir.path().join("absent").to_str().unwrap(),
+            "--no-witness",
+        ],
+        None,
+    );
+
+    assert_eq!(output.status.code(), Some(2));
+    let refusal: Value = serde_json::from_slice(&output.stdout).unwrap();
+    assert_eq!(refusal["version"], "lock-log.v0");
+    assert_eq!(refusal["refusal"]["code"], "E_IO");
+}
+
+#[test]
+fn log_appends_witness_record() {
+    let dir = TempDir::new().unwrap();
+    let (oct_path, _) = lock_into(&dir, "oct", &[("a.csv", b"a")], None);
+    let ledger = dir.path().join("witness.jsonl");
+
+    let output = run_lock(
+        &[
+            "log",
+            oct_path.to_str().unwrap(),
+            "--search-dir",
+            dir.path().to_str().unwrap(),
+        ],
+        Some(&ledger),
+    );
+
+    assert_eq!(output.status.code(), Some(0));
+    let record: Value =
+        serde_json::from_str(fs::read_to_string(&ledger).unwrap().lines().next().unwrap()).unwrap();
+    assert_eq!(record["outcome"], "LOG_COMPLETE");
+    assert_eq!(record["params"]["subcommand"], "log");
+}
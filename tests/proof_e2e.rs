@@ -0,0 +1,371 @@
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::process::Output;
+
+use serde_json::Value;
+use sha2::{Digest, Sha256};
+use tempfile::TempDir;
+
+mod support;
+
+fn run_lock(args: &[&str], ledger_path: Option<&Path>) -> Output {
+    let mut cmd = support::lock_command("proof-e2e");
+    cmd.args(args);
+    if let Some(path) = ledger_path {
+        cmd.env("EPISTEMIC_WITNESS", path);
+    }
+    cmd.output().expect("run lock binary")
+}
+
+fn validate_schema(schema: &str, instance: &Value) {
+    let schema: Value = serde_json::from_str(schema).unwrap();
+    let validator = jsonschema::validator_for(&schema).expect("schema should compile");
+    let errors: Vec<String> = validator
+        .iter_errors(instance)
+        .map(|error| format!("{error} at {}", error.instance_path()))
+        .collect();
+    assert!(errors.is_empty(), "schema errors:\n{}", errors.join("\n"));
+}
+
+const FILES: &[(&str, &[u8])] = &[
+    ("a.csv", b"a"),
+    ("b.csv", b"bb"),
+    ("tape.csv", b"id,amount\n1,100\n"),
+    ("z.csv", b"z"),
+    ("zz.csv", b"zz"),
+];
+
+/// Lock `FILES` into `dir/dec.lock.json` with the given `--lock-version`.
+fn lock_files(dir: &TempDir, lock_version: &str) -> (PathBuf, Value) {
+    lock_contents(dir, lock_version, FILES)
+}
+
+fn lock_contents(dir: &TempDir, lock_version: &str, files: &[(&str, &[u8])]) -> (PathBuf, Value) {
+    let manifest: Vec<String> = files
+        .iter()
+        .map(|(path, content)| {
+            serde_json::json!({
+                "version": "hash.v0",
+                "relative_path": path,
+                "bytes_hash": format!("sha256:{:x}", Sha256::digest(content)),
+                "size": content.len(),
+                "tool_versions": { "hash": "0.1.0" }
+            })
+            .to_string()
+        })
+        .collect();
+    let manifest_path = dir.path().join("manifest.jsonl");
+    fs::write(&manifest_path, manifest.join("\n") + "\n").unwrap();
+    let lockfile_path = dir.path().join("dec.lock.json");
+
+    let output = run_lock(
+        &[
+            manifest_path.to_str().unwrap(),
+            "--lock-version",
+            lock_version,
+            "--output",
+            lockfile_path.to_str().unwrap(),
+            "--no-witness",
+        ],
+        None,
+    );
+    assert_eq!(output.status.code(), Some(0));
+
+    let lockfile = serde_json::from_str(&fs::read_to_string(&lockfile_path).unwrap()).unwrap();
+    (lockfile_path, lockfile)
+}
+
+/// Prove `path` and write the proof to `dir/<path>.proof.json`.
+fn prove(dir: &TempDir, lockfile: &Path, path: &str) -> (PathBuf, Value) {
+    let proof_path = dir.path().join(format!("{path}.proof.json"));
+    let output = run_lock(
+        &[
+            "prove",
+            lockfile.to_str().unwrap(),
+            "--path",
+            path,
+            "--output",
+            proof_path.to_str().unwrap(),
+            "--no-witness",
+        ],
+        None,
+    );
+    assert_eq!(
+        output.status.code(),
+        Some(0),
+        "stdout: {}",
+        String::from_utf8_lossy(&output.stdout)
+    );
+    let proof = serde_json::from_str(&fs::read_to_string(&proof_path).unwrap()).unwrap();
+    (proof_path, proof)
+}
+
+fn verify_proof(proof: &Path) -> (Option<i32>, Value) {
+    verify_proof_with(proof, &[])
+}
+
+fn verify_proof_with(proof: &Path, extra: &[&str]) -> (Option<i32>, Value) {
+    let mut args = vec![
+        "verify-proof",
+        proof.to_str().unwrap(),
+        "--json",
+        "--no-witness",
+    ];
+    args.extend_from_slice(extra);
+    let output = run_lock(&args, None);
+    let report = serde_json::from_slice(&output.stdout).unwrap();
+    (output.status.code(), report)
+}
+
+#[test]
+fn v1_proof_matches_lockfile_members_root() {
+    let dir = TempDir::new().unwrap();
+    let (lockfile_path, lockfile) = lock_files(&dir, "lock.v1");
+    let (proof_path, proof) = prove(&dir, &lockfile_path, "tape.csv");
+
+    validate_schema(include_str!("../schemas/lock-proof-v0.schema.json"), &proof);
+    assert_eq!(proof["lock_hash"], lockfile["lock_hash"]);
+    assert_eq!(proof["members_root"], lockfile["members_root"]);
+    assert_eq!(proof["member"], lockfile["members"][2]);
+    assert_eq!(proof["tree_size"], 5);
+    assert_eq!(proof["root_committed"], true);
+
+    // A committed root is still only the proof's own claim until pinned.
+    let (code, report) = verify_proof(&proof_path);
+    validate_schema(
+        include_str!("../schemas/lock-proof-verify-v0.schema.json"),
+        &report,
+    );
+    assert_eq!(code, Some(1));
+    assert_eq!(report["outcome"], "PROOF_UNCOMMITTED");
+    assert_eq!(report["root"]["computed"], lockfile["members_root"]);
+
+    let (code, report) = verify_proof_with(
+        &proof_path,
+        &["--lockfile", lockfile_path.to_str().unwrap()],
+    );
+    validate_schema(
+        include_str!("../schemas/lock-proof-verify-v0.schema.json"),
+        &report,
+    );
+    assert_eq!(code, Some(0));
+    assert_eq!(report["outcome"], "PROOF_VALID");
+    assert_eq!(report["pinned"]["lock_hash"], lockfile["lock_hash"]);
+    assert_eq!(report["file"], Value::Null);
+}
+
+#[test]
+fn proof_is_deterministic() {
+    let dir = TempDir::new().unwrap();
+    let (lockfile_path, _) = lock_files(&dir, "lock.v1");
+    let (first, _) = prove(&dir, &lockfile_path, "a.csv");
+    let first = fs::read(first).unwrap();
+    let (second, _) = prove(&dir, &lockfile_path, "a.csv");
+    assert_eq!(first, fs::read(second).unwrap());
+}
+
+#[test]
+fn v0_proof_is_uncommitted_until_pinned_to_its_lockfile() {
+    let dir = TempDir::new().unwrap();
+    let (lockfile_path, _) = lock_files(&dir, "lock.v0");
+    let (proof_path, proof) = prove(&dir, &lockfile_path, "zz.csv");
+    assert_eq!(proof["lock_version"], "lock.v0");
+    assert_eq!(proof["root_committed"], false);
+
+    let (code, report) = verify_proof(&proof_path);
+    validate_schema(
+        include_str!("../schemas/lock-proof-verify-v0.schema.json"),
+        &report,
+    );
+    assert_eq!(code, Some(1));
+    assert_eq!(report["outcome"], "PROOF_UNCOMMITTED");
+    assert_eq!(report["root"]["valid"], true);
+    assert_eq!(report["root_committed"], false);
+
+    let (code, report) = verify_proof_with(
+        &proof_path,
+        &["--lockfile", lockfile_path.to_str().unwrap()],
+    );
+    validate_schema(
+        include_str!("../schemas/lock-proof-verify-v0.schema.json"),
+        &report,
+    );
+    assert_eq!(code, Some(0));
+    assert_eq!(report["outcome"], "PROOF_VALID");
+    assert_eq!(report["pinned"]["members_root"], proof["members_root"]);
+    assert_eq!(report["pinned"]["valid"], true);
+}
+
+#[test]
+fn self_consistent_forged_proof_fails_against_a_pinned_root() {
+    let dir = TempDir::new().unwrap();
+    let (lockfile_path, lockfile) = lock_files(&dir, "lock.v1");
+
+    // A proof taken from another lockfile and relabelled with the real
+    // lock_hash still recomputes its own members_root.
+    let other = TempDir::new().unwrap();
+    let mut files = FILES.to_vec();
+    files[2] = ("tape.csv", &b"id,amount\n1,999\n"[..]);
+    let (other_lockfile, _) = lock_contents(&other, "lock.v1", &files);
+    let (proof_path, mut proof) = prove(&other, &other_lockfile, "tape.csv");
+    proof["lock_hash"] = lockfile["lock_hash"].clone();
+    fs::write(&proof_path, proof.to_string()).unwrap();
+
+    // It claims a committed root, which counts for nothing unpinned.
+    assert_eq!(proof["root_committed"], true);
+    let (code, report) = verify_proof(&proof_path);
+    assert_eq!(code, Some(1), "{report}");
+    assert_eq!(report["outcome"], "PROOF_UNCOMMITTED");
+
+    for pin in [
+        vec!["--lockfile", lockfile_path.to_str().unwrap()],
+        vec!["--members-root", lockfile["members_root"].as_str().unwrap()],
+    ] {
+        let (code, report) = verify_proof_with(&proof_path, &pin);
+        validate_schema(
+            include_str!("../schemas/lock-proof-verify-v0.schema.json"),
+            &report,
+        );
+        assert_eq!(code, Some(1), "{pin:?}");
+        assert_eq!(report["outcome"], "PROOF_INVALID");
+        assert_eq!(report["root"]["valid"], true);
+        assert_eq!(report["pinned"]["valid"], false);
+        assert_eq!(report["pinned"]["members_root"], lockfile["members_root"]);
+    }
+}
+
+#[test]
+fn file_flag_checks_content_against_member() {
+    let dir = TempDir::new().unwrap();
+    let (lockfile_path, _) = lock_files(&dir, "lock.v1");
+    let (proof_path, _) = prove(&dir, &lockfile_path, "tape.csv");
+
+    let file = dir.path().join("tape.csv");
+    let args = [
+        "--lockfile",
+        lockfile_path.to_str().unwrap(),
+        "--file",
+        file.to_str().unwrap(),
+    ];
+    fs::write(&file, b"id,amount\n1,100\n").unwrap();
+    let (code, report) = verify_proof_with(&proof_path, &args);
+    assert_eq!(code, Some(0));
+    assert_eq!(report["file"]["valid"], true);
+
+    fs::write(&file, b"id,amount\n1,900\n").unwrap();
+    let (code, report) = verify_proof_with(&proof_path, &args);
+    validate_schema(
+        include_str!("../schemas/lock-proof-verify-v0.schema.json"),
+        &report,
+    );
+    assert_eq!(code, Some(1));
+    assert_eq!(report["outcome"], "PROOF_INVALID");
+    assert_eq!(report["root"]["valid"], true);
+    assert_eq!(report["file"]["reason"], "HASH_MISMATCH");
+
+    fs::write(&file, b"short").unwrap();
+    let (code, report) = verify_proof_with(&proof_path, &args);
+    assert_eq!(code, Some(1));
+    assert_eq!(report["file"]["reason"], "SIZE_MISMATCH");
+    assert_eq!(report["file"]["actual"], Value::Null);
+}
+
+#[test]
+fn altered_member_record_is_invalid() {
+    let dir = TempDir::new().unwrap();
+    let (lockfile_path, _) = lock_files(&dir, "lock.v1");
+    let (proof_path, mut proof) = prove(&dir, &lockfile_path, "tape.csv");
+
+    // Substituting another file's hash must not still reach the root.
+    proof["member"]["bytes_hash"] = serde_json::json!(format!("sha256:{:x}", Sha256::digest(b"x")));
+    fs::write(&proof_path, proof.to_string()).unwrap();
+
+    let (code, report) = verify_proof(&proof_path);
+    assert_eq!(code, Some(1));
+    assert_eq!(report["outcome"], "PROOF_INVALID");
+    assert_eq!(report["root"]["valid"], false);
+}
+
+#[test]
+fn prove_refuses_non_member_path() {
+    let dir = TempDir::new().unwrap();
+    let (lockfile_path, _) = lock_files(&dir, "lock.v1");
+
+    let output = run_lock(
+        &[
+            "prove",
+            lockfile_path.to_str().unwrap(),
+            "--path",
+            "absent.csv",
+            "--no-witness",
+        ],
+        None,
+    );
+    assert_eq!(output.status.code(), Some(2));
+    let refusal: Value = serde_json::from_slice(&output.stdout).unwrap();
+    assert_eq!(refusal["version"], "lock-proof.v0");
+    assert_eq!(refusal["refusal"]["code"], "E_NOT_MEMBER");
+    assert_eq!(refusal["refusal"]["detail"]["skipped"], false);
+}
+
+#[test]
+fn prove_refuses_tampered_lockfile() {
+    let dir = TempDir::new().unwrap();
+    let (lockfile_path, _) = lock_files(&dir, "lock.v1");
+    let tampered = fs::read_to_string(&lockfile_path)
+        .unwrap()
+        .replace("\"size\":2", "\"size\":3");
+    fs::write(&lockfile_path, tampered).unwrap();
+
+    let output = run_lock(
+        &[
+            "prove",
+            lockfile_path.to_str().unwrap(),
+            "--path",
+            "b.csv",
+            "--no-witness",
+        ],
+        None,
+    );
+    assert_eq!(output.status.code(), Some(2));
+    let refusal: Value = serde_json::from_slice(&output.stdout).unwrap();
+    assert_eq!(refusal["refusal"]["code"], "E_TAMPERED");
+}
+
+#[test]
+fn verify_proof_refuses_malformed_proof() {
+    let dir = TempDir::new().unwrap();
+    let proof_path = dir.path().join("bad.proof.json");
+    fs::write(&proof_path, "{\"version\":\"lock-proof.v0\"}").unwrap();
+
+    let (code, refusal) = verify_proof(&proof_path);
+    assert_eq!(code, Some(2));
+    assert_eq!(refusal["version"], "lock-proof-verify.v0");
+    assert_eq!(refusal["refusal"]["code"], "E_BAD_PROOF");
+}
+
+#[test]
+fn verify_proof_appends_witness_record() {
+    let dir = TempDir::new().unwrap();
+    let (lockfile_path, _) = lock_files(&dir, "lock.v1");
+    let (proof_path, _) = prove(&dir, &lockfile_path, "a.csv");
+    let ledger = dir.path().join("witness.jsonl");
+
+    let output = run_lock(
+        &[
+            "verify-proof",
+            proof_path.to_str().unwrap(),
+            "--lockfile",
+            lockfile_path.to_str().unwrap(),
+        ],
+        Some(&ledger),
+    );
+    assert_eq!(output.status.code(), Some(0));
+    let stdout = String::from_utf8(output.stdout).unwrap();
+    assert!(stdout.contains("a.csv: included in sha256:"), "{stdout}");
+
+    let record: Value =
+        serde_json::from_str(fs::read_to_string(&ledger).unwrap().lines().next().unwrap()).unwrap();
+    assert_eq!(record["outcome"], "PROOF_VALID");
+    assert_eq!(record["params"]["subcommand"], "verify-proof");
+}
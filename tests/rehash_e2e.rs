@@ -0,0 +1,182 @@
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::process::Output;
+
+use serde_json::Value;
+use sha2::{Digest, Sha256};
+use tempfile::TempDir;
+
+mod support;
+
+fn run_lock(args: &[&str]) -> Output {
+    let mut cmd = support::lock_command("rehash-e2e");
+    cmd.args(args);
+    cmd.output().expect("run lock binary")
+}
+
+fn validate_schema(schema: &str, instance: &Value) {
+    let schema: Value = serde_json::from_str(schema).unwrap();
+    let validator = jsonschema::validator_for(&schema).expect("schema should compile");
+    let errors: Vec<String> = validator
+        .iter_errors(instance)
+        .map(|error| format!("{error} at {}", error.instance_path()))
+        .collect();
+    assert!(errors.is_empty(), "schema errors:\n{}", errors.join("\n"));
+}
+
+fn read_json(path: &Path) -> Value {
+    serde_json::from_str(&fs::read_to_string(path).unwrap()).unwrap()
+}
+
+/// A scanned `lock.v1` two-file tree. Returns (dir, data root, lockfile).
+fn fixture() -> (TempDir, PathBuf, PathBuf) {
+    let dir = TempDir::new().unwrap();
+    let root = dir.path().join("data");
+    fs::create_dir_all(&root).unwrap();
+    fs::write(root.join("a.csv"), b"alpha").unwrap();
+    fs::write(root.join("b.csv"), b"bravo").unwrap();
+
+    let lockfile = dir.path().join("dec.lock.json");
+    let output = run_lock(&[
+        "scan",
+        root.to_str().unwrap(),
+        "--lock-version",
+        "lock.v1",
+        "--output",
+        lockfile.to_str().unwrap(),
+        "--no-witness",
+    ]);
+    assert_eq!(output.status.code(), Some(0));
+    (dir, root, lockfile)
+}
+
+fn rehash(lockfile: &Path, root: &Path, output: &Path) -> Output {
+    run_lock(&[
+        "rehash",
+        lockfile.to_str().unwrap(),
+        "--root",
+        root.to_str().unwrap(),
+        "--add",
+        "blake3",
+        "--output",
+        output.to_str().unwrap(),
+        "--no-witness",
+    ])
+}
+
+fn verify(lockfile: &Path, root: &Path) -> (Option<i32>, Value) {
+    let output = run_lock(&[
+        "verify",
+        lockfile.to_str().unwrap(),
+        "--root",
+        root.to_str().unwrap(),
+        "--json",
+        "--no-witness",
+    ]);
+    (
+        output.status.code(),
+        serde_json::from_slice(&output.stdout).unwrap(),
+    )
+}
+
+#[test]
+fn rehash_adds_blake3_and_verify_checks_it() {
+    let (dir, root, lockfile) = fixture();
+    let rehashed_path = dir.path().join("dec.rehashed.lock.json");
+
+    let output = rehash(&lockfile, &root, &rehashed_path);
+    assert_eq!(output.status.code(), Some(0));
+
+    let base = read_json(&lockfile);
+    let rehashed = read_json(&rehashed_path);
+    validate_schema(include_str!("../schemas/lock-v1.schema.json"), &rehashed);
+    assert_eq!(rehashed["parent"], base["lock_hash"]);
+    assert_ne!(rehashed["members_root"], base["members_root"]);
+    let hashes = &rehashed["members"][0]["hashes"];
+    assert_eq!(hashes["sha256"], base["members"][0]["bytes_hash"]);
+    assert_eq!(
+        hashes["blake3"],
+        format!("blake3:{}", blake3::hash(b"alpha").to_hex())
+    );
+
+    let (code, report) = verify(&rehashed_path, &root);
+    assert_eq!(code, Some(0));
+    assert_eq!(report["members"]["verified"], 2);
+
+    fs::write(root.join("b.csv"), b"BRAVO").unwrap();
+    let (code, report) = verify(&rehashed_path, &root);
+    assert_eq!(code, Some(1));
+    assert_eq!(report["members"]["failures"][0]["path"], "b.csv");
+    assert_eq!(report["members"]["failures"][0]["reason"], "HASH_MISMATCH");
+}
+
+#[test]
+fn rehash_refuses_drifted_tree() {
+    let (dir, root, lockfile) = fixture();
+    fs::write(root.join("a.csv"), b"ALPHA").unwrap();
+    fs::remove_file(root.join("b.csv")).unwrap();
+    let rehashed_path = dir.path().join("dec.rehashed.lock.json");
+
+    let output = rehash(&lockfile, &root, &rehashed_path);
+    assert_eq!(output.status.code(), Some(2));
+    let refusal = read_json(&rehashed_path);
+    assert_eq!(refusal["refusal"]["code"], "E_DRIFT");
+    assert_eq!(
+        refusal["refusal"]["detail"]["drifted"],
+        serde_json::json!([
+            { "path": "a.csv", "reason": "HASH_MISMATCH" },
+            { "path": "b.csv", "reason": "MISSING" },
+        ])
+    );
+}
+
+#[test]
+fn upstream_hashes_are_sealed_and_checked() {
+    let dir = TempDir::new().unwrap();
+    let root = dir.path().join("data");
+    fs::create_dir_all(&root).unwrap();
+    fs::write(root.join("a.csv"), b"alpha").unwrap();
+
+    let record = |blake3: String| {
+        serde_json::json!({
+            "version": "hash.v0",
+            "relative_path": "a.csv",
+            "bytes_hash": format!("sha256:{:x}", Sha256::digest(b"alpha")),
+            "size": 5,
+            "hashes": { "blake3": blake3 },
+            "tool_versions": { "hash": "0.1.0" }
+        })
+        .to_string()
+            + "\n"
+    };
+    let lock = |name: &str, blake3: String| {
+        let input = dir.path().join(format!("{name}.jsonl"));
+        fs::write(&input, record(blake3)).unwrap();
+        let lockfile = dir.path().join(format!("{name}.lock.json"));
+        let output = run_lock(&[
+            input.to_str().unwrap(),
+            "--output",
+            lockfile.to_str().unwrap(),
+            "--no-witness",
+        ]);
+        assert_eq!(output.status.code(), Some(0));
+        lockfile
+    };
+
+    let good = lock("good", blake3::hash(b"alpha").to_hex().to_string());
+    let sealed = read_json(&good);
+    validate_schema(include_str!("../schemas/lock-v0.schema.json"), &sealed);
+    assert_eq!(
+        sealed["members"][0]["hashes"]["blake3"],
+        format!("blake3:{}", blake3::hash(b"alpha").to_hex())
+    );
+    assert_eq!(verify(&good, &root).0, Some(0));
+
+    let wrong = lock("wrong", blake3::hash(b"other").to_hex().to_string());
+    let (code, report) = verify(&wrong, &root);
+    assert_eq!(code, Some(1));
+    assert_eq!(
+        report["members"]["failures"][0]["expected"],
+        format!("blake3:{}", blake3::hash(b"other").to_hex())
+    );
+}
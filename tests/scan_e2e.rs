@@ -0,0 +1,295 @@
+use std::fs;
+use std::path::Path;
+use std::process::Output;
+
+use serde_json::Value;
+use sha2::{Digest, Sha256};
+use tempfile::TempDir;
+
+mod support;
+
+fn run_lock(args: &[&str], ledger_path: Option<&Path>) -> Output {
+    let mut cmd = support::lock_command("scan-e2e");
+    cmd.args(args);
+    if let Some(path) = ledger_path {
+        cmd.env("EPISTEMIC_WITNESS", path);
+    }
+    cmd.output().expect("run lock binary")
+}
+
+fn write_tree(root: &Path, files: &[(&str, &[u8])]) {
+    for (path, content) in files {
+        let path = root.join(path);
+        fs::create_dir_all(path.parent().unwrap()).unwrap();
+        fs::write(path, content).unwrap();
+    }
+}
+
+/// Drop fields that legitimately differ between two runs over the same files.
+fn without_run_fields(mut lockfile: Value) -> Value {
+    let object = lockfile.as_object_mut().unwrap();
+    for key in ["created", "lock_hash", "tool_versions"] {
+        object.remove(key);
+    }
+    lockfile
+}
+
+#[test]
+fn scan_matches_pipeline_lockfile() {
+    let dir = TempDir::new().unwrap();
+    let root = dir.path().join("data");
+    let files: &[(&str, &[u8])] = &[
+        ("tape.csv", b"id,amount\n1,100\n"),
+        ("model/m.xlsx", b"xlsx bytes"),
+        ("a.txt", b""),
+    ];
+    write_tree(&root, files);
+
+    let manifest: Vec<String> = files
+        .iter()
+        .map(|(path, content)| {
+            serde_json::json!({
+                "version": "hash.v0",
+                "relative_path": path,
+                "bytes_hash": format!("sha256:{:x}", Sha256::digest(content)),
+                "size": content.len(),
+                "tool_versions": { "hash": "0.1.0" }
+            })
+            .to_string()
+        })
+        .collect();
+    let manifest_path = dir.path().join("manifest.jsonl");
+    fs::write(&manifest_path, manifest.join("\n") + "\n").unwrap();
+
+    let pipeline = run_lock(
+        &[
+            manifest_path.to_str().unwrap(),
+            "--dataset-id",
+            "raw-dec",
+            "--no-witness",
+        ],
+        None,
+    );
+    let scanned = run_lock(
+        &[
+            "scan",
+            root.to_str().unwrap(),
+            "--dataset-id",
+            "raw-dec",
+            "--no-witness",
+        ],
+        None,
+    );
+    assert_eq!(pipeline.status.code(), Some(0));
+    assert_eq!(scanned.status.code(), Some(0));
+
+    let pipeline: Value = serde_json::from_slice(&pipeline.stdout).unwrap();
+    let scanned: Value = serde_json::from_slice(&scanned.stdout).unwrap();
+    assert_eq!(
+        scanned["tool_versions"],
+        serde_json::json!({ "lock": env!("CARGO_PKG_VERSION") })
+    );
+    assert_eq!(without_run_fields(scanned), without_run_fields(pipeline));
+}
+
+#[test]
+fn scan_output_verifies_against_the_directory() {
+    let dir = TempDir::new().unwrap();
+    let root = dir.path().join("data");
+    write_tree(&root, &[("a.csv", b"a"), ("nested/b.csv", b"bb")]);
+    // Written inside the root: must not lock itself, on this run or a re-run.
+    let lockfile = root.join("dec.lock.json");
+
+    for _ in 0..2 {
+        let output = run_lock(
+            &[
+                "scan",
+                root.to_str().unwrap(),
+                "--algorithm",
+                "blake3",
+                "--output",
+                lockfile.to_str().unwrap(),
+                "--no-witness",
+            ],
+            None,
+        );
+        assert_eq!(output.status.code(), Some(0));
+    }
+
+    let sealed: Value = serde_json::from_str(&fs::read_to_string(&lockfile).unwrap()).unwrap();
+    assert_eq!(sealed["member_count"], 2);
+    assert!(
+        sealed["members"][0]["bytes_hash"]
+            .as_str()
+            .unwrap()
+            .starts_with("blake3:")
+    );
+
+    let output = run_lock(
+        &[
+            "verify",
+            lockfile.to_str().unwrap(),
+            "--root",
+            root.to_str().unwrap(),
+            "--detect-extra",
+            "--json",
+            "--no-witness",
+        ],
+        None,
+    );
+    assert_eq!(output.status.code(), Some(0));
+}
+
+#[test]
+fn scan_exclude_and_empty_refusal() {
+    let dir = TempDir::new().unwrap();
+    write_tree(dir.path(), &[("keep.csv", b"k"), ("tmp/x.tmp", b"x")]);
+
+    let output = run_lock(
+        &[
+            "scan",
+            dir.path().to_str().unwrap(),
+            "--exclude",
+            "*.tmp",
+            "--no-witness",
+        ],
+        None,
+    );
+    assert_eq!(output.status.code(), Some(0));
+    let lockfile: Value = serde_json::from_slice(&output.stdout).unwrap();
+    assert_eq!(lockfile["members"].as_array().unwrap().len(), 1);
+    assert_eq!(lockfile["members"][0]["path"], "keep.csv");
+
+    let output = run_lock(
+        &[
+            "scan",
+            dir.path().to_str().unwrap(),
+            "--exclude",
+            "**",
+            "--no-witness",
+        ],
+        None,
+    );
+    assert_eq!(output.status.code(), Some(2));
+    let refusal: Value = serde_json::from_slice(&output.stdout).unwrap();
+    assert_eq!(refusal["refusal"]["code"], "E_EMPTY");
+}
+
+#[test]
+fn scan_missing_directory_refuses() {
+    let dir = TempDir::new().unwrap();
+    let output = run_lock(
+        &[
+            "scan",
+            dir.path().join("absent").to_str().unwrap(),
+            "--no-witness",
+        ],
+        None,
+    );
+    assert_eq!(output.status.code(), Some(2));
+    let refusal: Value = serde_json::from_slice(&output.stdout).unwrap();
+    assert_eq!(refusal["version"], "lock.v0");
+    assert_eq!(refusal["refusal"]["code"], "E_IO");
+
+    let output = run_lock(
+        &[
+            "scan",
+            dir.path().join("absent").to_str().unwrap(),
+            "--lock-version",
+            "lock.v1",
+            "--no-witness",
+        ],
+        None,
+    );
+    assert_eq!(output.status.code(), Some(2));
+    let refusal: Value = serde_json::from_slice(&output.stdout).unwrap();
+    assert_eq!(refusal["version"], "lock.v1");
+}
+
+#[test]
+fn scan_appends_witness_record() {
+    let dir = TempDir::new().unwrap();
+    let root = dir.path().join("data");
+    write_tree(&root, &[("a.csv", b"a")]);
+    let ledger = dir.path().join("witness.jsonl");
+
+    let output = run_lock(&["scan", root.to_str().unwrap()], Some(&ledger));
+    assert_eq!(output.status.code(), Some(0));
+
+    let record: Value =
+        serde_json::from_str(fs::read_to_string(&ledger).unwrap().lines().next().unwrap()).unwrap();
+    assert_eq!(record["outcome"], "LOCK_CREATED");
+    assert_eq!(record["params"]["subcommand"], "scan");
+    assert_eq!(record["inputs"][0]["path"], root.display().to_string());
+
+    let lockfile = dir.path().join("dec.lock.json");
+    fs::write(&lockfile, &output.stdout).unwrap();
+    let output = run_lock(
+        &[
+            "scan",
+            root.to_str().unwrap(),
+            "--parent",
+            lockfile.to_str().unwrap(),
+        ],
+        Some(&ledger),
+    );
+    assert_eq!(output.status.code(), Some(0));
+    let parent: Value = serde_json::from_slice(&fs::read(&lockfile).unwrap()).unwrap();
+    let record: Value =
+        serde_json::from_str(fs::read_to_string(&ledger).unwrap().lines().last().unwrap()).unwrap();
+    assert_eq!(record["inputs"][1]["path"], lockfile.display().to_string());
+    assert_eq!(record["inputs"][1]["hash"], parent["lock_hash"]);
+}
+
+#[cfg(unix)]
+#[test]
+fn scan_and_detect_extra_report_unreadable_subdirectories() {
+    use std::os::unix::fs::PermissionsExt;
+
+    let dir = TempDir::new().unwrap();
+    let root = dir.path().join("data");
+    write_tree(&root, &[("a.csv", b"a"), ("locked/b.csv", b"b")]);
+    let lockfile = dir.path().join("dec.lock.json");
+    let locked = root.join("locked");
+    fs::set_permissions(&locked, fs::Permissions::from_mode(0o000)).unwrap();
+    if fs::read_dir(&locked).is_ok() {
+        // Running as root: permissions cannot make the directory unreadable.
+        fs::set_permissions(&locked, fs::Permissions::from_mode(0o755)).unwrap();
+        return;
+    }
+
+    let scanned = run_lock(
+        &[
+            "scan",
+            root.to_str().unwrap(),
+            "--output",
+            lockfile.to_str().unwrap(),
+            "--no-witness",
+        ],
+        None,
+    );
+    let verified = run_lock(
+        &[
+            "verify",
+            lockfile.to_str().unwrap(),
+            "--root",
+            root.to_str().unwrap(),
+            "--detect-extra",
+            "--json",
+            "--no-witness",
+        ],
+        None,
+    );
+    fs::set_permissions(&locked, fs::Permissions::from_mode(0o755)).unwrap();
+
+    assert_eq!(scanned.status.code(), Some(1));
+    let created: Value = serde_json::from_str(&fs::read_to_string(&lockfile).unwrap()).unwrap();
+    assert_eq!(created["skipped"][0]["path"], "locked");
+    assert_eq!(created["skipped"][0]["warnings"][0]["code"], "E_IO");
+
+    assert_eq!(verified.status.code(), Some(1));
+    let report: Value = serde_json::from_slice(&verified.stdout).unwrap();
+    assert_eq!(report["outcome"], "VERIFY_PARTIAL");
+    assert_eq!(report["members"]["skips"][0]["path"], "locked");
+    assert_eq!(report["members"]["skips"][0]["reason"], "IO_ERROR");
+}
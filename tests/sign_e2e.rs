@@ -0,0 +1,311 @@
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::process::Output;
+
+use serde_json::Value;
+use tempfile::TempDir;
+
+mod support;
+
+fn run_lock(args: &[&str]) -> Output {
+    support::lock_command("sign-e2e")
+        .args(args)
+        .output()
+        .expect("run lock binary")
+}
+
+fn validate_signature_schema(instance: &Value) {
+    let schema: Value =
+        serde_json::from_str(include_str!("../schemas/lock-signature-v0.schema.json")).unwrap();
+    let validator = jsonschema::validator_for(&schema).expect("schema should compile");
+    let errors: Vec<String> = validator
+        .iter_errors(instance)
+        .map(|error| format!("{error} at {}", error.instance_path()))
+        .collect();
+    assert!(errors.is_empty(), "schema errors:\n{}", errors.join("\n"));
+}
+
+fn make_lockfile(dir: &TempDir) -> PathBuf {
+    let manifest = dir.path().join("manifest.jsonl");
+    fs::write(
+        &manifest,
+        r#"{"version":"hash.v0","relative_path":"a.csv","bytes_hash":"sha256:aaaa","size":4}
+"#,
+    )
+    .unwrap();
+    let lockfile = dir.path().join("dec.lock.json");
+    let output = run_lock(&[
+        manifest.to_str().unwrap(),
+        "--dataset-id",
+        "raw-dec",
+        "--output",
+        lockfile.to_str().unwrap(),
+        "--no-witness",
+    ]);
+    assert_eq!(output.status.code(), Some(0));
+    lockfile
+}
+
+fn keygen(dir: &Path, name: &str) -> PathBuf {
+    let key = dir.join(name);
+    let output = run_lock(&[
+        "keygen",
+        "--output",
+        key.to_str().unwrap(),
+        "--signer",
+        "audit-team",
+    ]);
+    assert_eq!(
+        output.status.code(),
+        Some(0),
+        "keygen failed: {}",
+        String::from_utf8_lossy(&output.stdout)
+    );
+    key
+}
+
+#[test]
+fn keygen_writes_secret_and_public_key_files() {
+    let dir = tempfile::tempdir().unwrap();
+    let key = keygen(dir.path(), "auditor.key");
+
+    let secret: Value = serde_json::from_str(&fs::read_to_string(&key).unwrap()).unwrap();
+    let public: Value =
+        serde_json::from_str(&fs::read_to_string(dir.path().join("auditor.key.pub")).unwrap())
+            .unwrap();
+    assert_eq!(secret["version"], "lock-key.v0");
+    assert!(secret["secret_key"].is_string());
+    assert!(public.get("secret_key").is_none());
+    assert_eq!(secret["key_id"], public["key_id"]);
+    assert_eq!(public["signer"], "audit-team");
+
+    #[cfg(unix)]
+    {
+        use std::os::unix::fs::PermissionsExt;
+        let mode = fs::metadata(&key).unwrap().permissions().mode();
+        assert_eq!(mode & 0o777, 0o600);
+    }
+}
+
+#[test]
+fn keygen_refuses_to_overwrite_existing_key() {
+    let dir = tempfile::tempdir().unwrap();
+    let key = keygen(dir.path(), "auditor.key");
+    let before = fs::read(&key).unwrap();
+
+    let output = run_lock(&["keygen", "--output", key.to_str().unwrap()]);
+    assert_eq!(output.status.code(), Some(2));
+    let refusal: Value = serde_json::from_slice(&output.stdout).unwrap();
+    assert_eq!(refusal["refusal"]["code"], "E_IO");
+    assert_eq!(fs::read(&key).unwrap(), before);
+}
+
+#[cfg(unix)]
+#[test]
+fn keygen_leaves_no_secret_key_when_public_key_write_fails() {
+    let dir = tempfile::tempdir().unwrap();
+    let key = dir.path().join("auditor.key");
+    // A dangling symlink passes the exists() check but fails create_new.
+    std::os::unix::fs::symlink(
+        dir.path().join("nowhere"),
+        dir.path().join("auditor.key.pub"),
+    )
+    .unwrap();
+
+    let output = run_lock(&["keygen", "--output", key.to_str().unwrap()]);
+    assert_eq!(output.status.code(), Some(2));
+    let refusal: Value = serde_json::from_slice(&output.stdout).unwrap();
+    assert_eq!(refusal["refusal"]["code"], "E_IO");
+    assert!(!key.exists());
+    assert!(!dir.path().join("nowhere").exists());
+}
+
+#[test]
+fn sign_emits_envelope_over_lock_hash() {
+    let dir = tempfile::tempdir().unwrap();
+    let lockfile = make_lockfile(&dir);
+    let key = keygen(dir.path(), "auditor.key");
+    let sig = dir.path().join("dec.lock.sig");
+
+    let output = run_lock(&[
+        "sign",
+        lockfile.to_str().unwrap(),
+        "--key",
+        key.to_str().unwrap(),
+        "--output",
+        sig.to_str().unwrap(),
+        "--no-witness",
+    ]);
+    assert_eq!(output.status.code(), Some(0));
+    assert!(output.stdout.is_empty());
+
+    let envelope: Value = serde_json::from_str(&fs::read_to_string(&sig).unwrap()).unwrap();
+    let lock: Value = serde_json::from_str(&fs::read_to_string(&lockfile).unwrap()).unwrap();
+    assert_eq!(envelope["lock_hash"], lock["lock_hash"]);
+    assert_eq!(envelope["signer"], "audit-team");
+    validate_signature_schema(&envelope);
+}
+
+#[test]
+fn sign_refuses_tampered_lockfile() {
+    let dir = tempfile::tempdir().unwrap();
+    let lockfile = make_lockfile(&dir);
+    let key = keygen(dir.path(), "auditor.key");
+    let content = fs::read_to_string(&lockfile).unwrap();
+    fs::write(&lockfile, content.replace("raw-dec", "raw-jan")).unwrap();
+
+    let output = run_lock(&[
+        "sign",
+        lockfile.to_str().unwrap(),
+        "--key",
+        key.to_str().unwrap(),
+        "--no-witness",
+    ]);
+    assert_eq!(output.status.code(), Some(2));
+    let refusal: Value = serde_json::from_slice(&output.stdout).unwrap();
+    assert_eq!(refusal["version"], "lock-signature.v0");
+    assert_eq!(refusal["refusal"]["code"], "E_TAMPERED");
+}
+
+#[test]
+fn sign_refuses_public_key_file() {
+    let dir = tempfile::tempdir().unwrap();
+    let lockfile = make_lockfile(&dir);
+    keygen(dir.path(), "auditor.key");
+
+    let output = run_lock(&[
+        "sign",
+        lockfile.to_str().unwrap(),
+        "--key",
+        dir.path().join("auditor.key.pub").to_str().unwrap(),
+        "--no-witness",
+    ]);
+    assert_eq!(output.status.code(), Some(2));
+    let refusal: Value = serde_json::from_slice(&output.stdout).unwrap();
+    assert_eq!(refusal["refusal"]["code"], "E_BAD_KEY");
+}
+
+fn validate_verify_schema(instance: &Value) {
+    let schema: Value =
+        serde_json::from_str(include_str!("../schemas/lock-verify-v0.schema.json")).unwrap();
+    let validator = jsonschema::validator_for(&schema).expect("schema should compile");
+    let errors: Vec<String> = validator
+        .iter_errors(instance)
+        .map(|error| format!("{error} at {}", error.instance_path()))
+        .collect();
+    assert!(errors.is_empty(), "schema errors:\n{}", errors.join("\n"));
+}
+
+/// Sign `lockfile` with `key`, returning the signature path.
+fn sign(lockfile: &Path, key: &Path) -> PathBuf {
+    let sig = lockfile.with_extension("sig");
+    let output = run_lock(&[
+        "sign",
+        lockfile.to_str().unwrap(),
+        "--key",
+        key.to_str().unwrap(),
+        "--output",
+        sig.to_str().unwrap(),
+        "--no-witness",
+    ]);
+    assert_eq!(output.status.code(), Some(0));
+    sig
+}
+
+/// Trusted key directory holding the public half of each given key.
+fn trust(dir: &Path, keys: &[&Path]) -> PathBuf {
+    let trusted = dir.join("trusted");
+    fs::create_dir(&trusted).unwrap();
+    for key in keys {
+        let public = PathBuf::from(format!("{}.pub", key.display()));
+        fs::copy(&public, trusted.join(public.file_name().unwrap())).unwrap();
+    }
+    trusted
+}
+
+fn verify_signed(lockfile: &Path, sig: &Path, trusted: &Path) -> Output {
+    run_lock(&[
+        "verify",
+        lockfile.to_str().unwrap(),
+        "--signature",
+        sig.to_str().unwrap(),
+        "--trusted-keys",
+        trusted.to_str().unwrap(),
+        "--json",
+        "--no-witness",
+    ])
+}
+
+#[test]
+fn verify_accepts_trusted_signature() {
+    let dir = tempfile::tempdir().unwrap();
+    let lockfile = make_lockfile(&dir);
+    let key = keygen(dir.path(), "auditor.key");
+    let sig = sign(&lockfile, &key);
+    let trusted = trust(dir.path(), &[&key]);
+
+    let output = verify_signed(&lockfile, &sig, &trusted);
+    assert_eq!(output.status.code(), Some(0));
+    let report: Value = serde_json::from_slice(&output.stdout).unwrap();
+    assert_eq!(report["outcome"], "VERIFY_OK");
+    assert_eq!(report["signature"]["signer"], "audit-team");
+    assert_eq!(report["signature"]["valid"], true);
+    assert_eq!(report["signature"]["trusted"], true);
+    assert!(report["signature"]["reason"].is_null());
+    validate_verify_schema(&report);
+}
+
+#[test]
+fn verify_rejects_untrusted_signature() {
+    let dir = tempfile::tempdir().unwrap();
+    let lockfile = make_lockfile(&dir);
+    let key = keygen(dir.path(), "auditor.key");
+    let other = keygen(dir.path(), "other.key");
+    let sig = sign(&lockfile, &key);
+    let trusted = trust(dir.path(), &[&other]);
+
+    let output = verify_signed(&lockfile, &sig, &trusted);
+    assert_eq!(output.status.code(), Some(1));
+    let report: Value = serde_json::from_slice(&output.stdout).unwrap();
+    assert_eq!(report["outcome"], "VERIFY_FAILED");
+    assert_eq!(report["lock_hash"]["valid"], true);
+    assert_eq!(report["signature"]["valid"], true);
+    assert_eq!(report["signature"]["trusted"], false);
+    assert_eq!(report["signature"]["reason"], "KEY_UNTRUSTED");
+    validate_verify_schema(&report);
+}
+
+#[test]
+fn verify_rejects_signature_for_another_lockfile() {
+    let dir = tempfile::tempdir().unwrap();
+    let lockfile = make_lockfile(&dir);
+    let key = keygen(dir.path(), "auditor.key");
+    let sig = sign(&lockfile, &key);
+    let trusted = trust(dir.path(), &[&key]);
+
+    let mut envelope: Value = serde_json::from_str(&fs::read_to_string(&sig).unwrap()).unwrap();
+    envelope["lock_hash"] = Value::String(format!("sha256:{}", "0".repeat(64)));
+    fs::write(&sig, envelope.to_string()).unwrap();
+
+    let output = verify_signed(&lockfile, &sig, &trusted);
+    assert_eq!(output.status.code(), Some(1));
+    let report: Value = serde_json::from_slice(&output.stdout).unwrap();
+    assert_eq!(report["signature"]["valid"], false);
+    assert_eq!(report["signature"]["reason"], "LOCK_HASH_MISMATCH");
+}
+
+#[test]
+fn verify_refuses_malformed_signature_file() {
+    let dir = tempfile::tempdir().unwrap();
+    let lockfile = make_lockfile(&dir);
+    let key = keygen(dir.path(), "auditor.key");
+    let trusted = trust(dir.path(), &[&key]);
+    let sig = dir.path().join("bad.sig");
+    fs::write(&sig, "not json").unwrap();
+
+    let output = verify_signed(&lockfile, &sig, &trusted);
+    assert_eq!(output.status.code(), Some(2));
+    let refusal: Value = serde_json::from_slice(&output.stdout).unwrap();
+    assert_eq!(refusal["version"], "lock-verify.v0");
+    assert_eq!(refusal["refusal"]["code"], "E_BAD_SIGNATURE");
+}
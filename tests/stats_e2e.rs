@@ -0,0 +1,117 @@
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::process::Output;
+
+use serde_json::Value;
+use tempfile::TempDir;
+
+mod support;
+
+fn run_lock(args: &[&str]) -> Output {
+    let mut cmd = support::lock_command("stats-e2e");
+    cmd.args(args);
+    cmd.output().expect("run lock binary")
+}
+
+fn validate_stats_schema(instance: &Value) {
+    let schema: Value =
+        serde_json::from_str(include_str!("../schemas/lock-stats-v0.schema.json")).unwrap();
+    let validator = jsonschema::validator_for(&schema).expect("schema should compile");
+    let errors: Vec<String> = validator
+        .iter_errors(instance)
+        .map(|error| format!("{error} at {}", error.instance_path()))
+        .collect();
+    assert!(errors.is_empty(), "schema errors:\n{}", errors.join("\n"));
+}
+
+/// Scan a small tree and return (dir, lockfile).
+fn fixture() -> (TempDir, PathBuf) {
+    let dir = TempDir::new().unwrap();
+    let root = dir.path().join("data");
+    fs::create_dir_all(root.join("loans")).unwrap();
+    fs::write(root.join("README"), b"").unwrap();
+    fs::write(root.join("loans/a.csv"), b"alpha").unwrap();
+    fs::write(root.join("loans/b.CSV"), vec![b'x'; 2048]).unwrap();
+
+    let lockfile = dir.path().join("dec.lock.json");
+    let output = run_lock(&[
+        "scan",
+        root.to_str().unwrap(),
+        "--output",
+        lockfile.to_str().unwrap(),
+        "--no-witness",
+    ]);
+    assert_eq!(output.status.code(), Some(0));
+    (dir, lockfile)
+}
+
+fn stats(lockfile: &Path, json: bool) -> Output {
+    let mut args = vec!["stats", lockfile.to_str().unwrap(), "--no-witness"];
+    if json {
+        args.push("--json");
+    }
+    run_lock(&args)
+}
+
+#[test]
+fn stats_json_summarizes_members() {
+    let (_dir, lockfile) = fixture();
+    let output = stats(&lockfile, true);
+    assert_eq!(output.status.code(), Some(0));
+    let result: Value = serde_json::from_slice(&output.stdout).unwrap();
+    validate_stats_schema(&result);
+
+    assert_eq!(result["outcome"], "STATS");
+    assert_eq!(result["member_count"], 3);
+    assert_eq!(result["skipped_count"], 0);
+    assert_eq!(result["total_bytes"], 2053);
+    assert_eq!(result["size_histogram"][0]["count"], 1);
+    assert_eq!(result["size_histogram"][1]["count"], 1);
+    assert_eq!(result["size_histogram"][2]["count"], 1);
+    assert_eq!(
+        result["by_extension"]["csv"],
+        serde_json::json!({ "count": 2, "bytes": 2053 })
+    );
+    assert_eq!(result["by_extension"]["(none)"]["count"], 1);
+    assert_eq!(result["by_top_level"]["loans"]["count"], 2);
+    assert_eq!(result["by_top_level"]["."]["count"], 1);
+    assert_eq!(
+        result["algorithms"]["sha256"],
+        serde_json::json!({ "bytes_hash": 3, "hashes": 0 })
+    );
+    assert_eq!(result["unfingerprinted"], 3);
+}
+
+#[test]
+fn stats_human_output_leads_with_totals() {
+    let (_dir, lockfile) = fixture();
+    let output = stats(&lockfile, false);
+    assert_eq!(output.status.code(), Some(0));
+    let text = String::from_utf8(output.stdout).unwrap();
+    assert!(
+        text.starts_with(&format!(
+            "\u{2713} {}: 3 members (2.0 KiB), 0 skipped",
+            lockfile.display()
+        )),
+        "{text}"
+    );
+    assert!(
+        text.contains("  extensions: (none) 1 (0 B), csv 2 (2.0 KiB)"),
+        "{text}"
+    );
+}
+
+#[test]
+fn stats_refuses_tampered_lockfile() {
+    let (_dir, lockfile) = fixture();
+    let mut tampered: Value =
+        serde_json::from_str(&fs::read_to_string(&lockfile).unwrap()).unwrap();
+    tampered["members"][0]["size"] = 7.into();
+    fs::write(&lockfile, tampered.to_string()).unwrap();
+
+    let output = stats(&lockfile, true);
+    assert_eq!(output.status.code(), Some(2));
+    let refusal: Value = serde_json::from_slice(&output.stdout).unwrap();
+    assert_eq!(refusal["version"], "lock-stats.v0");
+    assert_eq!(refusal["refusal"]["code"], "E_TAMPERED");
+}
@@ -0,0 +1,276 @@
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::process::Output;
+
+use serde_json::Value;
+use sha2::{Digest, Sha256};
+use tempfile::TempDir;
+
+mod support;
+
+fn run_lock(args: &[&str], ledger_path: Option<&Path>) -> Output {
+    let mut cmd = support::lock_command("update-e2e");
+    cmd.args(args);
+    if let Some(path) = ledger_path {
+        cmd.env("EPISTEMIC_WITNESS", path);
+    }
+    cmd.output().expect("run lock binary")
+}
+
+fn validate_lock_schema(instance: &Value) {
+    let schema: Value =
+        serde_json::from_str(include_str!("../schemas/lock-v0.schema.json")).unwrap();
+    let validator = jsonschema::validator_for(&schema).expect("schema should compile");
+    let errors: Vec<String> = validator
+        .iter_errors(instance)
+        .map(|error| format!("{error} at {}", error.instance_path()))
+        .collect();
+    assert!(errors.is_empty(), "schema errors:\n{}", errors.join("\n"));
+}
+
+/// Write a hash.v0 manifest for (path, content) records to `dir/name`.
+fn write_manifest(dir: &TempDir, name: &str, files: &[(&str, &[u8])]) -> PathBuf {
+    let manifest: Vec<String> = files
+        .iter()
+        .map(|(path, content)| {
+            serde_json::json!({
+                "version": "hash.v0",
+                "relative_path": path,
+                "bytes_hash": format!("sha256:{:x}", Sha256::digest(content)),
+                "size": content.len(),
+                "tool_versions": { "hash": "0.1.0" }
+            })
+            .to_string()
+        })
+        .collect();
+    let manifest_path = dir.path().join(name);
+    fs::write(&manifest_path, manifest.join("\n") + "\n").unwrap();
+    manifest_path
+}
+
+fn lock_base(dir: &TempDir, files: &[(&str, &[u8])]) -> (PathBuf, Value) {
+    let manifest = write_manifest(dir, "base.jsonl", files);
+    let lockfile_path = dir.path().join("base.lock.json");
+    let output = run_lock(
+        &[
+            manifest.to_str().unwrap(),
+            "--dataset-id",
+            "raw-dec",
+            "--as-of",
+            "2026-01-01",
+            "--output",
+            lockfile_path.to_str().unwrap(),
+            "--no-witness",
+        ],
+        None,
+    );
+    assert_eq!(output.status.code(), Some(0));
+    let lockfile = serde_json::from_str(&fs::read_to_string(&lockfile_path).unwrap()).unwrap();
+    (lockfile_path, lockfile)
+}
+
+fn member_paths(lockfile: &Value) -> Vec<&str> {
+    lockfile["members"]
+        .as_array()
+        .unwrap()
+        .iter()
+        .map(|member| member["path"].as_str().unwrap())
+        .collect()
+}
+
+#[test]
+fn update_upserts_records_and_links_parent() {
+    let dir = tempfile::tempdir().unwrap();
+    let (base_path, base) = lock_base(&dir, &[("a.csv", b"a"), ("b.csv", b"b")]);
+    let input = write_manifest(&dir, "day2.jsonl", &[("b.csv", b"b2"), ("c.csv", b"c")]);
+
+    let output = run_lock(
+        &[
+            "update",
+            base_path.to_str().unwrap(),
+            input.to_str().unwrap(),
+            "--no-witness",
+        ],
+        None,
+    );
+    assert_eq!(output.status.code(), Some(0));
+    let updated: Value = serde_json::from_slice(&output.stdout).unwrap();
+
+    assert_eq!(member_paths(&updated), ["a.csv", "b.csv", "c.csv"]);
+    assert_eq!(updated["member_count"], 3);
+    assert_eq!(updated["dataset_id"], "raw-dec");
+    assert_eq!(updated["as_of"], "2026-01-01");
+    assert_eq!(updated["parent"], base["lock_hash"]);
+    assert_ne!(updated["lock_hash"], base["lock_hash"]);
+    validate_lock_schema(&updated);
+
+    let updated_path = dir.path().join("updated.lock.json");
+    fs::write(&updated_path, &output.stdout).unwrap();
+    let verify = run_lock(
+        &["verify", updated_path.to_str().unwrap(), "--no-witness"],
+        None,
+    );
+    assert_eq!(verify.status.code(), Some(0));
+}
+
+#[test]
+fn keep_fields_are_sealed_into_member_extra() {
+    let dir = tempfile::tempdir().unwrap();
+    let record = |path: &str, mime: &str| {
+        serde_json::json!({
+            "version": "hash.v0",
+            "relative_path": path,
+            "bytes_hash": format!("sha256:{:x}", Sha256::digest(path)),
+            "size": path.len(),
+            "mime_guess": mime,
+            "owner": "ingest",
+            "tool_versions": { "hash": "0.1.0" }
+        })
+        .to_string()
+    };
+    let base_input = dir.path().join("base.jsonl");
+    fs::write(&base_input, record("a.csv", "text/csv") + "\n").unwrap();
+    let base_path = dir.path().join("base.lock.json");
+
+    let output = run_lock(
+        &[
+            base_input.to_str().unwrap(),
+            "--keep-fields",
+            "mime_guess",
+            "--output",
+            base_path.to_str().unwrap(),
+            "--no-witness",
+        ],
+        None,
+    );
+    assert_eq!(output.status.code(), Some(0));
+    let base: Value = serde_json::from_str(&fs::read_to_string(&base_path).unwrap()).unwrap();
+    assert_eq!(
+        base["members"][0]["extra"],
+        serde_json::json!({ "mime_guess": "text/csv" })
+    );
+    validate_lock_schema(&base);
+
+    let day2 = dir.path().join("day2.jsonl");
+    fs::write(&day2, record("b.json", "application/json") + "\n").unwrap();
+    let output = run_lock(
+        &[
+            "update",
+            base_path.to_str().unwrap(),
+            day2.to_str().unwrap(),
+            "--keep-all-fields",
+            "--no-witness",
+        ],
+        None,
+    );
+    assert_eq!(output.status.code(), Some(0));
+    let updated: Value = serde_json::from_slice(&output.stdout).unwrap();
+    assert_eq!(updated["members"][0]["extra"], base["members"][0]["extra"]);
+    assert_eq!(
+        updated["members"][1]["extra"],
+        serde_json::json!({ "mime_guess": "application/json", "owner": "ingest" })
+    );
+    validate_lock_schema(&updated);
+
+    // extra is covered by lock_hash.
+    let tampered = fs::read_to_string(&base_path)
+        .unwrap()
+        .replace("text/csv", "text/tsv");
+    fs::write(&base_path, tampered).unwrap();
+    let verify = run_lock(
+        &["verify", base_path.to_str().unwrap(), "--no-witness"],
+        None,
+    );
+    assert_eq!(verify.status.code(), Some(1));
+}
+
+#[test]
+fn update_with_prune_reads_stdin_and_drops_absent_paths() {
+    let dir = tempfile::tempdir().unwrap();
+    let (base_path, _) = lock_base(&dir, &[("a.csv", b"a"), ("b.csv", b"b")]);
+    let input = write_manifest(&dir, "day2.jsonl", &[("b.csv", b"b")]);
+
+    let output = support::lock_command("update-e2e")
+        .args([
+            "update",
+            base_path.to_str().unwrap(),
+            "--prune",
+            "--no-witness",
+        ])
+        .stdin(fs::File::open(&input).unwrap())
+        .output()
+        .unwrap();
+    assert_eq!(output.status.code(), Some(0));
+    let updated: Value = serde_json::from_slice(&output.stdout).unwrap();
+    assert_eq!(member_paths(&updated), ["b.csv"]);
+}
+
+#[test]
+fn update_refuses_tampered_base() {
+    let dir = tempfile::tempdir().unwrap();
+    let (base_path, _) = lock_base(&dir, &[("a.csv", b"a")]);
+    let content = fs::read_to_string(&base_path).unwrap();
+    fs::write(&base_path, content.replace("raw-dec", "raw-jan")).unwrap();
+    let input = write_manifest(&dir, "day2.jsonl", &[("b.csv", b"b")]);
+
+    let output = run_lock(
+        &[
+            "update",
+            base_path.to_str().unwrap(),
+            input.to_str().unwrap(),
+            "--no-witness",
+        ],
+        None,
+    );
+    assert_eq!(output.status.code(), Some(2));
+    let refusal: Value = serde_json::from_slice(&output.stdout).unwrap();
+    assert_eq!(refusal["version"], "lock.v0");
+    assert_eq!(refusal["refusal"]["code"], "E_TAMPERED");
+}
+
+#[test]
+fn update_refuses_bad_input_like_lock() {
+    let dir = tempfile::tempdir().unwrap();
+    let (base_path, _) = lock_base(&dir, &[("a.csv", b"a")]);
+    let input = dir.path().join("bad.jsonl");
+    fs::write(&input, "not-json\n").unwrap();
+
+    let output = run_lock(
+        &[
+            "update",
+            base_path.to_str().unwrap(),
+            input.to_str().unwrap(),
+            "--no-witness",
+        ],
+        None,
+    );
+    assert_eq!(output.status.code(), Some(2));
+    let refusal: Value = serde_json::from_slice(&output.stdout).unwrap();
+    assert_eq!(refusal["refusal"]["code"], "E_BAD_INPUT");
+}
+
+#[test]
+fn update_appends_witness_record_with_subcommand() {
+    let dir = tempfile::tempdir().unwrap();
+    let ledger = dir.path().join("witness.jsonl");
+    let (base_path, base) = lock_base(&dir, &[("a.csv", b"a")]);
+    let input = write_manifest(&dir, "day2.jsonl", &[("b.csv", b"b")]);
+
+    let output = run_lock(
+        &[
+            "update",
+            base_path.to_str().unwrap(),
+            input.to_str().unwrap(),
+        ],
+        Some(&ledger),
+    );
+    assert_eq!(output.status.code(), Some(0));
+
+    let content = fs::read_to_string(&ledger).expect("ledger should exist");
+    let record: Value = serde_json::from_str(content.lines().last().unwrap()).unwrap();
+    assert_eq!(record["params"]["subcommand"], "update");
+    assert_eq!(record["outcome"], "LOCK_CREATED");
+    assert_eq!(record["inputs"].as_array().unwrap().len(), 2);
+    assert_eq!(record["inputs"][0]["hash"], base["lock_hash"]);
+    assert!(record["inputs"][1]["hash"].is_string());
+}
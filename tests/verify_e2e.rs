@@ -197,6 +197,255 @@ fn verify_level2_deleted_file_exits_1_missing() {
     assert_eq!(failures[0]["reason"], "MISSING");
 }
 
+#[test]
+fn verify_detect_extra_flags_untracked_files() {
+    let (dir, manifest_path, data_root) =
+        create_fixture(&[("a.csv", b"alpha"), ("sub/b.csv", b"beta")]);
+    let (lockfile_path, _) = create_lockfile(&manifest_path, dir.path());
+
+    // Files dropped in after locking; the lockfile itself also moves under the root.
+    fs::write(data_root.join("late.csv"), b"late").unwrap();
+    fs::write(data_root.join("sub/scratch.tmp"), b"tmp").unwrap();
+    let lockfile_in_root = data_root.join("dec.lock.json");
+    fs::rename(&lockfile_path, &lockfile_in_root).unwrap();
+
+    let output = run_lock(
+        &[
+            "verify",
+            lockfile_in_root.to_str().unwrap(),
+            "--root",
+            data_root.to_str().unwrap(),
+            "--detect-extra",
+            "--exclude",
+            "*.tmp",
+            "--json",
+            "--no-witness",
+        ],
+        None,
+    );
+    assert_eq!(output.status.code(), Some(1));
+
+    let parsed: Value = serde_json::from_slice(&output.stdout).unwrap();
+    let schema: Value =
+        serde_json::from_str(include_str!("../schemas/lock-verify-v0.schema.json")).unwrap();
+    let validator = jsonschema::validator_for(&schema).expect("schema should compile");
+    assert!(validator.is_valid(&parsed), "{parsed}");
+
+    assert_eq!(parsed["outcome"], "VERIFY_FAILED");
+    assert_eq!(parsed["members"]["verified"], 2);
+    assert_eq!(parsed["members"]["failed"], 0);
+    assert_eq!(parsed["members"]["extra"], 1);
+    let failures = parsed["members"]["failures"].as_array().unwrap();
+    assert_eq!(failures.len(), 1);
+    assert_eq!(failures[0]["path"], "late.csv");
+    assert_eq!(failures[0]["reason"], "EXTRA");
+}
+
+#[test]
+fn verify_detect_extra_exact_set_is_ok() {
+    let (dir, manifest_path, data_root) = create_fixture(&[("a.csv", b"alpha")]);
+    let (lockfile_path, _) = create_lockfile(&manifest_path, dir.path());
+
+    let output = run_lock(
+        &[
+            "verify",
+            lockfile_path.to_str().unwrap(),
+            "--root",
+            data_root.to_str().unwrap(),
+            "--detect-extra",
+            "--no-witness",
+        ],
+        None,
+    );
+    assert_eq!(output.status.code(), Some(0));
+    let stdout = String::from_utf8(output.stdout).unwrap();
+    assert!(
+        stdout.contains("1/1 members verified, no extra files"),
+        "{stdout}"
+    );
+}
+
+#[test]
+fn verify_archive_checks_tar_entries_without_extracting() {
+    let (dir, manifest_path, _data_root) =
+        create_fixture(&[("a.csv", b"alpha"), ("sub/b.csv", b"beta")]);
+    let (lockfile_path, _) = create_lockfile(&manifest_path, dir.path());
+
+    let archive_path = dir.path().join("delivery.tar");
+    let mut builder = tar::Builder::new(fs::File::create(&archive_path).unwrap());
+    for (name, content) in [
+        ("./a.csv", &b"alpha"[..]),
+        ("sub/b.csv", b"BETA"),
+        ("README.txt", b"notes"),
+    ] {
+        let mut header = tar::Header::new_gnu();
+        header.set_size(content.len() as u64);
+        header.set_mode(0o644);
+        header.set_cksum();
+        builder.append_data(&mut header, name, content).unwrap();
+    }
+    builder.finish().unwrap();
+    drop(builder);
+
+    let output = run_lock(
+        &[
+            "verify",
+            lockfile_path.to_str().unwrap(),
+            "--archive",
+            archive_path.to_str().unwrap(),
+            "--detect-extra",
+            "--json",
+            "--no-witness",
+        ],
+        None,
+    );
+    assert_eq!(output.status.code(), Some(1));
+
+    let parsed: Value = serde_json::from_slice(&output.stdout).unwrap();
+    let schema: Value =
+        serde_json::from_str(include_str!("../schemas/lock-verify-v0.schema.json")).unwrap();
+    let validator = jsonschema::validator_for(&schema).expect("schema should compile");
+    assert!(validator.is_valid(&parsed), "{parsed}");
+
+    assert_eq!(parsed["members"]["root"], archive_path.to_str().unwrap());
+    assert_eq!(parsed["members"]["verified"], 1);
+    assert_eq!(parsed["members"]["failed"], 1);
+    assert_eq!(parsed["members"]["extra"], 1);
+    let failures = parsed["members"]["failures"].as_array().unwrap();
+    assert_eq!(failures[0]["path"], "sub/b.csv");
+    assert_eq!(failures[0]["reason"], "HASH_MISMATCH");
+    assert_eq!(failures[1]["path"], "README.txt");
+    assert_eq!(failures[1]["reason"], "EXTRA");
+}
+
+#[test]
+fn verify_unreadable_archive_exits_2() {
+    let (dir, manifest_path, _data_root) = create_fixture(&[("a.csv", b"alpha")]);
+    let (lockfile_path, _) = create_lockfile(&manifest_path, dir.path());
+    let archive_path = dir.path().join("delivery.zip");
+    fs::write(&archive_path, b"not a zip").unwrap();
+
+    let output = run_lock(
+        &[
+            "verify",
+            lockfile_path.to_str().unwrap(),
+            "--archive",
+            archive_path.to_str().unwrap(),
+            "--json",
+            "--no-witness",
+        ],
+        None,
+    );
+    assert_eq!(output.status.code(), Some(2));
+    let parsed: Value = serde_json::from_slice(&output.stdout).unwrap();
+    assert_eq!(parsed["refusal"]["code"], "E_IO");
+    assert_eq!(
+        parsed["refusal"]["detail"]["archive"],
+        archive_path.to_str().unwrap()
+    );
+}
+
+#[test]
+fn verify_jobs_output_matches_sequential() {
+    let names: Vec<String> = (0..24).map(|i| format!("m{i:02}.csv")).collect();
+    let files: Vec<(&str, &[u8])> = names
+        .iter()
+        .map(|name| (name.as_str(), name.as_bytes()))
+        .collect();
+    let (_dir, manifest_path, data_root) = create_fixture(&files);
+    let (lockfile_path, _) = create_lockfile(&manifest_path, _dir.path());
+
+    fs::remove_file(data_root.join("m03.csv")).unwrap();
+    fs::write(data_root.join("m17.csv"), "m17.csx").unwrap();
+
+    let verify = |jobs: &str| {
+        run_lock(
+            &[
+                "verify",
+                lockfile_path.to_str().unwrap(),
+                "--root",
+                data_root.to_str().unwrap(),
+                "--jobs",
+                jobs,
+                "--json",
+                "--no-witness",
+            ],
+            None,
+        )
+    };
+    let sequential = verify("1");
+    let parallel = verify("8");
+    assert_eq!(sequential.status.code(), Some(1));
+    assert_eq!(parallel.status.code(), Some(1));
+    assert_eq!(sequential.stdout, parallel.stdout);
+
+    let parsed: Value = serde_json::from_slice(&parallel.stdout).unwrap();
+    let failures = parsed["members"]["failures"].as_array().unwrap();
+    assert_eq!(failures[0]["path"], "m03.csv");
+    assert_eq!(failures[1]["path"], "m17.csv");
+}
+
+#[test]
+fn verify_fast_trusts_unchanged_mtime_and_rehashes_the_rest() {
+    let dir = tempfile::tempdir().unwrap();
+    let data_root = dir.path().join("data");
+    fs::create_dir_all(&data_root).unwrap();
+    fs::write(data_root.join("a.csv"), "alpha").unwrap();
+    fs::write(data_root.join("b.csv"), "bravo").unwrap();
+    let lockfile_path = dir.path().join("dec.lock.json");
+
+    let output = run_lock(
+        &[
+            "scan",
+            data_root.to_str().unwrap(),
+            "--keep-mtime",
+            "--output",
+            lockfile_path.to_str().unwrap(),
+            "--no-witness",
+        ],
+        None,
+    );
+    assert_eq!(output.status.code(), Some(0));
+
+    // Same size, new content, and an mtime that no longer matches the lock.
+    let touched = fs::File::create(data_root.join("b.csv")).unwrap();
+    std::io::Write::write_all(&mut &touched, b"BRAVO").unwrap();
+    touched
+        .set_modified(std::time::SystemTime::UNIX_EPOCH)
+        .unwrap();
+    drop(touched);
+
+    let verify = |fast: bool| {
+        let mut args = vec![
+            "verify",
+            lockfile_path.to_str().unwrap(),
+            "--root",
+            data_root.to_str().unwrap(),
+            "--json",
+            "--no-witness",
+        ];
+        if fast {
+            args.push("--fast");
+        }
+        let output = run_lock(&args, None);
+        assert_eq!(output.status.code(), Some(1));
+        serde_json::from_slice::<Value>(&output.stdout).unwrap()
+    };
+
+    let fast = verify(true);
+    assert_eq!(fast["members"]["verified"], 1);
+    assert_eq!(
+        fast["members"]["verified_by_metadata"],
+        serde_json::json!(["a.csv"])
+    );
+    assert_eq!(fast["members"]["failures"][0]["path"], "b.csv");
+    assert_eq!(fast["members"]["failures"][0]["reason"], "HASH_MISMATCH");
+
+    let full = verify(false);
+    assert_eq!(full["members"]["verified"], 1);
+    assert!(full["members"].get("verified_by_metadata").is_none());
+}
+
 // ---------------------------------------------------------------------------
 // Tampered lockfile
 // ---------------------------------------------------------------------------
@@ -233,6 +482,87 @@ fn verify_tampered_lockfile_exits_1_hash_invalid_members_null() {
     );
 }
 
+// ---------------------------------------------------------------------------
+// lock.v1 members_root
+// ---------------------------------------------------------------------------
+
+fn create_v1_lockfile(manifest_path: &Path, dir: &Path) -> (PathBuf, Value) {
+    let output = run_lock(
+        &[
+            manifest_path.to_str().unwrap(),
+            "--lock-version",
+            "lock.v1",
+            "--no-witness",
+        ],
+        None,
+    );
+    assert_eq!(output.status.code(), Some(0));
+
+    let lockfile_json: Value = serde_json::from_slice(&output.stdout).unwrap();
+    let lockfile_path = dir.join("test.lock.json");
+    fs::write(&lockfile_path, &output.stdout).unwrap();
+    (lockfile_path, lockfile_json)
+}
+
+#[test]
+fn verify_lock_v1_lockfile_with_members_root() {
+    let (dir, manifest_path, data_root) = create_fixture(&[
+        ("a.csv", b"alpha"),
+        ("b.csv", b"beta"),
+        ("c/d.csv", b"gamma"),
+    ]);
+    let (lockfile_path, lockfile) = create_v1_lockfile(&manifest_path, dir.path());
+
+    assert_eq!(lockfile["version"], "lock.v1");
+    let schema: Value =
+        serde_json::from_str(include_str!("../schemas/lock-v1.schema.json")).unwrap();
+    let validator = jsonschema::validator_for(&schema).expect("schema should compile");
+    assert!(validator.is_valid(&lockfile));
+    assert_eq!(
+        lockfile["members_root"].as_str().unwrap(),
+        lock::lockfile::merkle::members_root(lockfile["members"].as_array().unwrap())
+    );
+
+    let output = run_lock(
+        &[
+            "verify",
+            lockfile_path.to_str().unwrap(),
+            "--root",
+            data_root.to_str().unwrap(),
+            "--json",
+            "--no-witness",
+        ],
+        None,
+    );
+    assert_eq!(output.status.code(), Some(0));
+    let parsed: Value = serde_json::from_slice(&output.stdout).unwrap();
+    assert_eq!(parsed["outcome"], "VERIFY_OK");
+}
+
+#[test]
+fn verify_lock_v1_resealed_with_wrong_members_root_is_refused() {
+    let (dir, manifest_path, _data_root) = create_fixture(&[("a.csv", b"alpha")]);
+    let (lockfile_path, mut lockfile) = create_v1_lockfile(&manifest_path, dir.path());
+
+    // Swap the root and re-seal, so only the members_root check can catch it.
+    lockfile["members_root"] = Value::String(sha256_hex(b"not the members"));
+    let detail = lock::lockfile::self_hash::verify_lock_hash_detail_value(&lockfile).unwrap();
+    lockfile["lock_hash"] = Value::String(detail.computed);
+    fs::write(&lockfile_path, serde_json::to_string(&lockfile).unwrap()).unwrap();
+
+    let output = run_lock(
+        &["verify", lockfile_path.to_str().unwrap(), "--no-witness"],
+        None,
+    );
+    assert_eq!(output.status.code(), Some(2));
+    let parsed: Value = serde_json::from_slice(&output.stdout).unwrap();
+    assert_eq!(parsed["refusal"]["code"], "E_BAD_LOCKFILE");
+    assert_eq!(
+        parsed["refusal"]["message"],
+        "members_root does not match members"
+    );
+}
+
 // ---------------------------------------------------------------------------
 // Refusals (exit 2)
 // ---------------------------------------------------------------------------